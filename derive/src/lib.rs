@@ -0,0 +1,317 @@
+//! `#[derive(FromJson, ToJson)]` for `jsonparser`, on structs with named
+//! fields. Fields take `#[json(...)]` options:
+//!
+//! - `rename = "key"` reads and writes the field under another key.
+//! - `default` fills in a missing field with `Default::default()`, and
+//!   `default = "path"` with what calling `path()` returns.
+//! - `flatten` reads the field from the struct's own object, and writes its
+//!   members into it.
+//!
+//! A missing field without a default decodes as if it were `null`, so
+//! `Option`s can be left out.
+
+use proc_macro::{Delimiter, Ident, Literal, Spacing, Span, TokenStream, TokenTree};
+
+#[proc_macro_derive(FromJson, attributes(json))]
+pub fn derive_from_json(input: TokenStream) -> TokenStream {
+    expand(input, from_json)
+}
+
+#[proc_macro_derive(ToJson, attributes(json))]
+pub fn derive_to_json(input: TokenStream) -> TokenStream {
+    expand(input, to_json)
+}
+
+struct Struct {
+    name: Ident,
+    fields: Vec<Field>,
+}
+
+struct Field {
+    name: Ident,
+    ty: String,
+    // The key as a string literal, ready to paste into the generated code.
+    key: String,
+    fallback: Option<Fallback>,
+    flatten: bool,
+}
+
+enum Fallback {
+    Default,
+    Path(String),
+}
+
+struct Error {
+    span: Span,
+    message: &'static str,
+}
+
+impl Error {
+    fn new(span: Span, message: &'static str) -> Self {
+        Self { span, message }
+    }
+
+    fn into_compile_error(self) -> TokenStream {
+        let tokens: TokenStream = format!("::core::compile_error!({:?});", self.message)
+            .parse()
+            .unwrap();
+        tokens
+            .into_iter()
+            .map(|mut token| {
+                token.set_span(self.span);
+                token
+            })
+            .collect()
+    }
+}
+
+fn expand(input: TokenStream, generate: fn(&Struct) -> String) -> TokenStream {
+    match parse(input) {
+        Ok(item) => generate(&item).parse().unwrap(),
+        Err(e) => e.into_compile_error(),
+    }
+}
+
+fn parse(input: TokenStream) -> Result<Struct, Error> {
+    let mut tokens = input.into_iter().peekable();
+    loop {
+        match tokens.next() {
+            Some(TokenTree::Punct(p)) if p.as_char() == '#' => {
+                tokens.next();
+            }
+            Some(TokenTree::Ident(i)) if i.to_string() == "pub" => {
+                if matches!(tokens.peek(), Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis)
+                {
+                    tokens.next();
+                }
+            }
+            Some(TokenTree::Ident(i)) if i.to_string() == "struct" => break,
+            Some(token) => return Err(Error::new(token.span(), NAMED_FIELDS)),
+            None => return Err(Error::new(Span::call_site(), NAMED_FIELDS)),
+        }
+    }
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(name)) => name,
+        _ => return Err(Error::new(Span::call_site(), NAMED_FIELDS)),
+    };
+    match tokens.next() {
+        Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace => Ok(Struct {
+            name,
+            fields: fields(g.stream())?,
+        }),
+        Some(TokenTree::Punct(p)) if p.as_char() == '<' => {
+            Err(Error::new(p.span(), "generic structs aren't supported"))
+        }
+        Some(token) => Err(Error::new(token.span(), NAMED_FIELDS)),
+        None => Err(Error::new(name.span(), NAMED_FIELDS)),
+    }
+}
+
+const NAMED_FIELDS: &str = "only structs with named fields can be derived";
+
+fn fields(stream: TokenStream) -> Result<Vec<Field>, Error> {
+    let mut fields = Vec::new();
+    let mut tokens = stream.into_iter().peekable();
+    while tokens.peek().is_some() {
+        let mut fallback = None;
+        let mut rename = None;
+        let mut flatten = false;
+        let name = loop {
+            match tokens.next() {
+                Some(TokenTree::Punct(p)) if p.as_char() == '#' => {
+                    if let Some(TokenTree::Group(g)) = tokens.next() {
+                        attribute(g.stream(), &mut rename, &mut fallback, &mut flatten)?;
+                    }
+                }
+                Some(TokenTree::Ident(i)) if i.to_string() == "pub" => {
+                    if matches!(tokens.peek(), Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis)
+                    {
+                        tokens.next();
+                    }
+                }
+                Some(TokenTree::Ident(name)) => break name,
+                Some(token) => return Err(Error::new(token.span(), "expected a field name")),
+                None => return Err(Error::new(Span::call_site(), "expected a field name")),
+            }
+        };
+        match tokens.next() {
+            Some(TokenTree::Punct(p)) if p.as_char() == ':' => {}
+            _ => return Err(Error::new(name.span(), "expected `:` after the field name")),
+        }
+        // The type runs up to a comma outside of angle brackets, which
+        // aren't groups, so they're counted; the `>` of a `->` doesn't close
+        // one.
+        let mut ty = TokenStream::new();
+        let mut depth = 0usize;
+        let mut arrow = false;
+        for token in tokens.by_ref() {
+            if let TokenTree::Punct(p) = &token {
+                match p.as_char() {
+                    ',' if depth == 0 => break,
+                    '<' => depth += 1,
+                    '>' if !arrow => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                arrow = p.as_char() == '-' && p.spacing() == Spacing::Joint;
+            } else {
+                arrow = false;
+            }
+            ty.extend([token]);
+        }
+        if flatten && (rename.is_some() || fallback.is_some()) {
+            return Err(Error::new(
+                name.span(),
+                "`flatten` can't be combined with `rename` or `default`",
+            ));
+        }
+        let key = rename.unwrap_or_else(|| {
+            let name = name.to_string();
+            Literal::string(name.trim_start_matches("r#")).to_string()
+        });
+        fields.push(Field {
+            name,
+            ty: ty.to_string(),
+            key,
+            fallback,
+            flatten,
+        });
+    }
+    Ok(fields)
+}
+
+// Reads the options out of a field attribute, if it's a `json` one.
+fn attribute(
+    stream: TokenStream,
+    rename: &mut Option<String>,
+    fallback: &mut Option<Fallback>,
+    flatten: &mut bool,
+) -> Result<(), Error> {
+    let mut tokens = stream.into_iter();
+    match tokens.next() {
+        Some(TokenTree::Ident(i)) if i.to_string() == "json" => {}
+        _ => return Ok(()),
+    }
+    let options = match tokens.next() {
+        Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis => g.stream(),
+        Some(token) => return Err(Error::new(token.span(), "expected `json(...)`")),
+        None => return Err(Error::new(Span::call_site(), "expected `json(...)`")),
+    };
+    let mut tokens = options.into_iter();
+    while let Some(token) = tokens.next() {
+        let TokenTree::Ident(option) = token else {
+            return Err(Error::new(token.span(), UNKNOWN_OPTION));
+        };
+        let value = match tokens.next() {
+            Some(TokenTree::Punct(p)) if p.as_char() == '=' => {
+                let value = match tokens.next() {
+                    Some(TokenTree::Literal(lit)) if lit.to_string().starts_with('"') => lit,
+                    _ => return Err(Error::new(p.span(), "expected a string after `=`")),
+                };
+                match tokens.next() {
+                    None => {}
+                    Some(TokenTree::Punct(p)) if p.as_char() == ',' => {}
+                    Some(token) => return Err(Error::new(token.span(), "expected `,`")),
+                }
+                Some(value)
+            }
+            Some(TokenTree::Punct(p)) if p.as_char() == ',' => None,
+            None => None,
+            Some(token) => return Err(Error::new(token.span(), "expected `=` or `,`")),
+        };
+        match (option.to_string().as_str(), value) {
+            ("rename", Some(key)) => *rename = Some(key.to_string()),
+            ("default", None) => *fallback = Some(Fallback::Default),
+            ("default", Some(path)) => {
+                let path = path.to_string();
+                *fallback = Some(Fallback::Path(path[1..path.len() - 1].to_owned()));
+            }
+            ("flatten", None) => *flatten = true,
+            _ => return Err(Error::new(option.span(), UNKNOWN_OPTION)),
+        }
+    }
+    Ok(())
+}
+
+const UNKNOWN_OPTION: &str =
+    "expected `rename = \"...\"`, `default`, `default = \"...\"` or `flatten`";
+
+fn from_json(item: &Struct) -> String {
+    let mut fields = String::new();
+    for field in &item.fields {
+        let Field { name, ty, key, .. } = field;
+        let decode = if field.flatten {
+            format!("<{ty} as ::jsonparser::FromJson>::from_json(value, arena)?")
+        } else {
+            let missing = match &field.fallback {
+                Some(Fallback::Default) => "::std::default::Default::default()".to_owned(),
+                Some(Fallback::Path(path)) => format!("{path}()"),
+                None => format!(
+                    "<{ty} as ::jsonparser::FromJson>::from_json(&::jsonparser::JsonValue::Null, arena)
+                        .map_err(|_| ::jsonparser::DecodeError::MissingField {{
+                            field: {key},
+                            pointer: ::std::string::String::new(),
+                        }})?"
+                ),
+            };
+            format!(
+                "match obj.get({key}) {{
+                    ::std::option::Option::Some(&id) => <{ty} as ::jsonparser::FromJson>::from_json(arena.fetch(id), arena)
+                        .map_err(|e| e.within({key}))?,
+                    ::std::option::Option::None => {missing},
+                }}"
+            )
+        };
+        fields += &format!("{name}: {decode},");
+    }
+    format!(
+        "impl ::jsonparser::FromJson for {name} {{
+            #[allow(unused_variables)]
+            fn from_json(
+                value: &::jsonparser::JsonValue,
+                arena: &::jsonparser::Allocator<::jsonparser::JsonValue>,
+            ) -> ::std::result::Result<Self, ::jsonparser::DecodeError> {{
+                let ::jsonparser::JsonValue::Object(obj) = value else {{
+                    return ::std::result::Result::Err(::jsonparser::DecodeError::expected(\"an object\", value));
+                }};
+                ::std::result::Result::Ok(Self {{ {fields} }})
+            }}
+        }}",
+        name = item.name,
+    )
+}
+
+fn to_json(item: &Struct) -> String {
+    let mut members = String::new();
+    for Field {
+        name, key, flatten, ..
+    } in &item.fields
+    {
+        members += &if *flatten {
+            format!(
+                "if let ::jsonparser::JsonValue::Object(inner) = ::jsonparser::ToJson::to_json(&self.{name}, arena) {{
+                    members.extend(inner);
+                }}"
+            )
+        } else {
+            format!(
+                "let value = ::jsonparser::ToJson::to_json(&self.{name}, arena);
+                members.push((::jsonparser::Key::from({key}), arena.alloc(value)));"
+            )
+        };
+    }
+    format!(
+        "impl ::jsonparser::ToJson for {name} {{
+            #[allow(unused_mut, unused_variables)]
+            fn to_json(
+                &self,
+                arena: &mut ::jsonparser::Allocator<::jsonparser::JsonValue>,
+            ) -> ::jsonparser::JsonValue {{
+                let mut members: ::std::vec::Vec<(::jsonparser::Key, ::jsonparser::Id<::jsonparser::JsonValue>)> =
+                    ::std::vec::Vec::new();
+                {members}
+                ::jsonparser::JsonValue::Object(members.into_iter().collect())
+            }}
+        }}",
+        name = item.name,
+    )
+}
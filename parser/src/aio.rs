@@ -0,0 +1,150 @@
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{
+    doc::Document,
+    error::ParseError,
+    lines::{io_error, parse_line},
+    options::ParseOptions,
+    par::Par,
+};
+
+/// A reader that can be waited on, with the same method as
+/// `futures::io::AsyncRead` so readers from any runtime can be wrapped in a
+/// few lines.
+pub trait AsyncRead {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>>;
+}
+
+impl<R: AsyncRead + Unpin + ?Sized> AsyncRead for &mut R {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut **self).poll_read(cx, buf)
+    }
+}
+
+impl AsyncRead for &[u8] {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(io::Read::read(&mut *self, buf))
+    }
+}
+
+fn read<'a, R: AsyncRead + Unpin>(
+    reader: &'a mut R,
+    buf: &'a mut [u8],
+) -> impl Future<Output = io::Result<usize>> + 'a {
+    std::future::poll_fn(move |cx| Pin::new(&mut *reader).poll_read(cx, buf))
+}
+
+// Reads all of `reader`, then parses it like `Par::parse_bytes`. Reading
+// stops a byte past `max_document_size` and leaves the parser to report it.
+pub(crate) async fn parse<R: AsyncRead + Unpin>(
+    mut reader: R,
+    opts: ParseOptions,
+) -> Result<Document, ParseError> {
+    let mut src = Vec::new();
+    let mut chunk = [0; 1 << 13];
+    while opts.max_document_size.is_none_or(|max| src.len() <= max) {
+        match read(&mut reader, &mut chunk).await {
+            Ok(0) => break,
+            Ok(n) => src.extend_from_slice(&chunk[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => {
+                let line = 1 + src.iter().filter(|&&b| b == b'\n').count();
+                return Err(io_error(e, src.len(), line));
+            }
+        }
+    }
+    Par::parse_bytes(&src, 1 << 4, opts)
+}
+
+/// The async counterpart of `Lines`: one document per line of `reader`,
+/// skipping blank ones. `poll_next` has the signature of
+/// `futures::Stream::poll_next`.
+pub struct AsyncLines<R> {
+    reader: R,
+    opts: ParseOptions,
+    buf: Vec<u8>,
+    // How much of `buf` is known not to hold a newline.
+    searched: usize,
+    // Where `buf` starts, and its line number counting from 1.
+    offset: usize,
+    line: usize,
+    eof: bool,
+    done: bool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncLines<R> {
+    pub fn new(reader: R, opts: ParseOptions) -> Self {
+        Self {
+            reader,
+            opts,
+            buf: Vec::new(),
+            searched: 0,
+            offset: 0,
+            line: 1,
+            eof: false,
+            done: false,
+        }
+    }
+
+    pub async fn next(&mut self) -> Option<Result<Document, ParseError>> {
+        std::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+
+    pub fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Document, ParseError>>> {
+        let this = self.get_mut();
+        let mut chunk = [0; 1 << 12];
+        while !this.done {
+            let newline = this.buf[this.searched..].iter().position(|&b| b == b'\n');
+            if newline.is_some() || this.eof {
+                let end = newline.map_or(this.buf.len(), |i| this.searched + i + 1);
+                let (offset, line) = (this.offset, this.line);
+                let doc = parse_line(&this.buf[..end], offset, line, this.opts);
+                this.buf.drain(..end);
+                this.searched = 0;
+                this.offset += end;
+                this.line += 1;
+                this.done = this.eof && this.buf.is_empty();
+                match doc {
+                    Some(doc) => return Poll::Ready(Some(doc)),
+                    None => continue,
+                }
+            }
+            this.searched = this.buf.len();
+            match Pin::new(&mut this.reader).poll_read(cx, &mut chunk) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(0)) => this.eof = true,
+                Poll::Ready(Ok(n)) => this.buf.extend_from_slice(&chunk[..n]),
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted => {}
+                Poll::Ready(Err(e)) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(io_error(
+                        e,
+                        this.offset + this.buf.len(),
+                        this.line,
+                    ))));
+                }
+            }
+        }
+        Poll::Ready(None)
+    }
+}
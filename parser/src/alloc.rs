@@ -0,0 +1,256 @@
+use std::{fmt, marker::PhantomData};
+
+const STALE: &str = "id from another arena, or from before the arena was reset";
+const MISSING: &str = "id past the end of the arena";
+
+/// Where an `Allocator` keeps its values. `Vec` is the default; a slab, a
+/// bump arena or storage with its own allocation policy can stand in by
+/// implementing this. An `Id` is the index a value was pushed at, so `get`
+/// must find values by the order they came in.
+pub trait Storage<T> {
+    fn push(&mut self, value: T);
+
+    fn get(&self, index: usize) -> Option<&T>;
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every value, ready to be pushed to again.
+    fn clear(&mut self);
+}
+
+impl<T> Storage<T> for Vec<T> {
+    fn push(&mut self, value: T) {
+        Vec::push(self, value)
+    }
+
+    fn get(&self, index: usize) -> Option<&T> {
+        <[T]>::get(self, index)
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        <[T]>::get_mut(self, index)
+    }
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+}
+
+// So a parse can fill storage it doesn't own.
+impl<T, S: Storage<T> + ?Sized> Storage<T> for &mut S {
+    fn push(&mut self, value: T) {
+        (**self).push(value)
+    }
+
+    fn get(&self, index: usize) -> Option<&T> {
+        (**self).get(index)
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        (**self).get_mut(index)
+    }
+
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn clear(&mut self) {
+        (**self).clear()
+    }
+}
+
+pub struct Allocator<T, S = Vec<T>> {
+    storage: S,
+    tag: Tag,
+    marker: PhantomData<T>,
+}
+
+// A clone's ids are the same as the original's, so it keeps the tag.
+impl<T, S: Clone> Clone for Allocator<T, S> {
+    fn clone(&self) -> Self {
+        Allocator {
+            storage: self.storage.clone(),
+            tag: self.tag,
+            marker: PhantomData,
+        }
+    }
+}
+
+// In debug builds every arena, and every arena again after a reset, gets
+// its own tag, which its ids carry, so an id looked up in the wrong arena
+// or after a reset panics instead of finding some other value. Release
+// builds keep no tags.
+#[cfg(debug_assertions)]
+type Tag = u32;
+#[cfg(not(debug_assertions))]
+type Tag = ();
+
+// The tag of ids made by `Id::new`, which any arena accepts.
+#[cfg(debug_assertions)]
+const UNTAGGED: Tag = 0;
+#[cfg(not(debug_assertions))]
+const UNTAGGED: Tag = ();
+
+#[cfg(debug_assertions)]
+fn fresh_tag() -> Tag {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    static NEXT: AtomicU32 = AtomicU32::new(1);
+    // The counter wraps after `u32::MAX` arenas, past `UNTAGGED`.
+    loop {
+        let tag = NEXT.fetch_add(1, Ordering::Relaxed);
+        if tag != UNTAGGED {
+            return tag;
+        }
+    }
+}
+
+#[cfg(not(debug_assertions))]
+fn fresh_tag() -> Tag {}
+
+// An id holds its index plus one, so `Option<Id>` fits in the same space.
+// With the `id32` feature that is four bytes rather than eight.
+#[cfg(not(feature = "id32"))]
+type Index = std::num::NonZeroUsize;
+#[cfg(feature = "id32")]
+type Index = std::num::NonZeroU32;
+
+/// How many values an arena can hold before ids run out.
+#[cfg(not(feature = "id32"))]
+pub(crate) const MAX_IDS: usize = usize::MAX - 1;
+#[cfg(feature = "id32")]
+pub(crate) const MAX_IDS: usize = u32::MAX as usize - 1;
+
+pub struct Id<T>(Index, Tag, PhantomData<T>);
+
+impl<T> Id<T> {
+    /// Panics past `MAX_IDS`: `usize::MAX - 1`, or `u32::MAX - 1` with the
+    /// `id32` feature. An id made here belongs to no arena in particular, so
+    /// it skips the debug build check of `Allocator::fetch`: any arena takes
+    /// it, before or after a reset, and gives whatever is at that index.
+    pub fn new(id: usize) -> Self {
+        Self::tagged(id, UNTAGGED)
+    }
+
+    // The conversions are to the same type without `id32`.
+    #[allow(clippy::useless_conversion)]
+    fn tagged(id: usize, tag: Tag) -> Self {
+        let index = id
+            .checked_add(1)
+            .and_then(|i| i.try_into().ok())
+            .and_then(Index::new)
+            .expect("too many values for an id");
+        Self(index, tag, PhantomData)
+    }
+
+    // Where the value is in its arena, for keeping more about it on the side.
+    #[allow(clippy::unnecessary_cast)]
+    pub(crate) fn index(self) -> usize {
+        self.0.get() as usize - 1
+    }
+}
+
+impl<T> fmt::Debug for Id<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Id").field(&self.index()).finish()
+    }
+}
+
+impl<T> Clone for Id<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Id<T> {}
+
+impl<T> Allocator<T> {
+    /// `size` is only the initial capacity; the arena grows as needed.
+    pub fn make(size: usize) -> Self {
+        Self::with_storage(Vec::with_capacity(size))
+    }
+}
+
+impl<T, S: Storage<T>> Allocator<T, S> {
+    /// An arena keeping its values in `storage`, which should be empty.
+    pub fn with_storage(storage: S) -> Self {
+        Self {
+            storage,
+            tag: fresh_tag(),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    pub fn into_storage(self) -> S {
+        self.storage
+    }
+
+    // The same arena, borrowed, for a parse to fill in place.
+    pub(crate) fn by_ref(&mut self) -> Allocator<T, &mut S> {
+        Allocator {
+            storage: &mut self.storage,
+            tag: self.tag,
+            marker: PhantomData,
+        }
+    }
+
+    // Tags are `()` in release builds.
+    #[allow(clippy::unit_arg)]
+    pub fn alloc(&mut self, el: T) -> Id<T> {
+        let id = Id::tagged(self.storage.len(), self.tag);
+        self.storage.push(el);
+        id
+    }
+
+    /// Panics if there is no value at `id`. Debug builds also panic if `id`
+    /// is from another arena or from before the last reset, unless it came
+    /// from `Id::new`; release builds don't know, and give whatever value
+    /// is at that index.
+    pub fn fetch(&self, id: Id<T>) -> &T {
+        self.check(id);
+        self.storage.get(id.index()).expect(MISSING)
+    }
+
+    pub fn fetch_mut(&mut self, id: Id<T>) -> &mut T {
+        self.check(id);
+        self.storage.get_mut(id.index()).expect(MISSING)
+    }
+
+    fn check(&self, id: Id<T>) {
+        #[allow(clippy::unit_cmp)]
+        let tagged = id.1 == UNTAGGED || id.1 == self.tag;
+        assert!(tagged, "{STALE}");
+    }
+
+    pub fn len(&self) -> usize {
+        self.storage.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        (0..self.len()).filter_map(|id| self.storage.get(id))
+    }
+
+    /// Drops every element but keeps the capacity, to fill the arena again
+    /// without reallocating. Ids handed out before are dangling afterwards.
+    pub fn reset(&mut self) {
+        self.storage.clear();
+        self.tag = fresh_tag();
+    }
+}
@@ -0,0 +1,77 @@
+// Base64 for binary values in formats that have them, which JSON can only
+// hold as text.
+
+#[cfg(any(feature = "cbor", feature = "base64"))]
+const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+const URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// `bytes` in the URL-safe alphabet without padding, as RFC 8949 turns byte
+/// strings into JSON.
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+pub(crate) fn encode_url(bytes: &[u8]) -> String {
+    encode(bytes, URL, false)
+}
+
+/// `bytes` in the standard alphabet, padded.
+#[cfg(any(feature = "cbor", feature = "base64"))]
+pub(crate) fn encode_standard(bytes: &[u8]) -> String {
+    encode(bytes, STANDARD, true)
+}
+
+fn encode(bytes: &[u8], alphabet: &[u8; 64], pad: bool) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(alphabet[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+        if pad {
+            for _ in chunk.len()..3 {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// The bytes of `text` in either alphabet, padded or not, or `None` if it
+/// isn't base64. Bits left over after the last byte have to be zero, so
+/// each string of bytes has one spelling in each alphabet.
+#[cfg(feature = "base64")]
+pub(crate) fn decode(text: &str) -> Option<Vec<u8>> {
+    let unpadded = text.trim_end_matches('=');
+    let padding = text.len() - unpadded.len();
+    if padding > 2 || padding > 0 && !text.len().is_multiple_of(4) || unpadded.len() % 4 == 1 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(unpadded.len() / 4 * 3 + 2);
+    for chunk in unpadded.as_bytes().chunks(4) {
+        let mut n = 0;
+        for (i, &c) in chunk.iter().enumerate() {
+            n |= sextet(c)? << (18 - 6 * i);
+        }
+        let len = chunk.len() - 1;
+        if n & ((1 << (24 - 8 * len)) - 1) != 0 {
+            return None;
+        }
+        out.extend((0..len).map(|i| (n >> (16 - 8 * i)) as u8));
+    }
+    Some(out)
+}
+
+#[cfg(feature = "base64")]
+fn sextet(c: u8) -> Option<u32> {
+    let n = match c {
+        b'A'..=b'Z' => c - b'A',
+        b'a'..=b'z' => c - b'a' + 26,
+        b'0'..=b'9' => c - b'0' + 52,
+        b'+' | b'-' => 62,
+        b'/' | b'_' => 63,
+        _ => return None,
+    };
+    Some(n as u32)
+}
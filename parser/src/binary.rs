@@ -0,0 +1,178 @@
+// What reading MessagePack and CBOR have in common: taking the input apart
+// byte by byte, and building the document from the values read, which
+// arrive in order with each container before its entries.
+
+use crate::{
+    alloc::Id,
+    doc::Document,
+    error::BinaryError,
+    key::{Interner, Key},
+    map::Map,
+    value::{JsonValue, Number},
+};
+
+pub(crate) struct Reader<'s> {
+    pub(crate) src: &'s [u8],
+    pub(crate) pos: usize,
+}
+
+impl<'s> Reader<'s> {
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'s [u8], BinaryError> {
+        let bytes = self
+            .src
+            .get(self.pos..self.pos.saturating_add(len))
+            .ok_or(BinaryError {
+                offset: self.src.len(),
+                message: "unexpected end of input",
+            })?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub(crate) fn byte(&mut self) -> Result<u8, BinaryError> {
+        Ok(self.take(1)?[0])
+    }
+
+    // A big-endian unsigned integer `width` bytes wide.
+    pub(crate) fn uint(&mut self, width: usize) -> Result<u64, BinaryError> {
+        Ok(self.take(width)?.iter().fold(0, |n, &b| n << 8 | b as u64))
+    }
+
+    // Room for `len` entries, where each takes a byte at least, so a bad
+    // length can't reserve more than the input could fill.
+    fn capacity(&self, len: usize) -> usize {
+        len.min(self.src.len() - self.pos)
+    }
+}
+
+// A container being read, with how many entries are still to come, if it
+// said.
+enum Frame {
+    List(Vec<Id<JsonValue>>, Option<usize>),
+    // The key waits for its value.
+    Object(Map<Key, Id<JsonValue>>, Option<usize>, Option<String>),
+}
+
+pub(crate) struct Builder {
+    doc: Document,
+    stack: Vec<Frame>,
+    root: Option<JsonValue>,
+    keys: Interner,
+}
+
+impl Builder {
+    pub(crate) fn new() -> Self {
+        Self {
+            doc: Document::default(),
+            stack: Vec::new(),
+            root: None,
+            keys: Interner::default(),
+        }
+    }
+
+    /// Whether the whole value has been read.
+    pub(crate) fn done(&self) -> bool {
+        self.root.is_some()
+    }
+
+    pub(crate) fn list(
+        &mut self,
+        len: Option<usize>,
+        reader: &Reader<'_>,
+        offset: usize,
+    ) -> Result<(), BinaryError> {
+        match len {
+            Some(0) => self.value(JsonValue::List(Vec::new()), offset),
+            _ => {
+                let capacity = reader.capacity(len.unwrap_or_default());
+                self.stack
+                    .push(Frame::List(Vec::with_capacity(capacity), len));
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn object(&mut self, len: Option<usize>, offset: usize) -> Result<(), BinaryError> {
+        match len {
+            Some(0) => self.value(JsonValue::Object(Map::new()), offset),
+            _ => {
+                self.stack.push(Frame::Object(Map::new(), len, None));
+                Ok(())
+            }
+        }
+    }
+
+    /// Ends the innermost container, which didn't say how long it was.
+    #[cfg(feature = "cbor")]
+    pub(crate) fn end(&mut self, offset: usize) -> Result<(), BinaryError> {
+        let fail = |message| BinaryError { offset, message };
+        let value = match self.stack.pop() {
+            Some(Frame::List(ids, None)) => JsonValue::List(ids),
+            Some(Frame::Object(obj, None, None)) => JsonValue::Object(obj),
+            Some(Frame::Object(_, None, Some(_))) => return Err(fail("a map key without a value")),
+            _ => return Err(fail("a break outside of an indefinite-length container")),
+        };
+        self.value(value, offset)
+    }
+
+    /// Hands `value`, read at `offset`, to its container, and every
+    /// container it fills to its own.
+    pub(crate) fn value(&mut self, mut value: JsonValue, offset: usize) -> Result<(), BinaryError> {
+        loop {
+            let left = match self.stack.last_mut() {
+                None => {
+                    self.root = Some(value);
+                    return Ok(());
+                }
+                Some(Frame::Object(_, _, key @ None)) => {
+                    *key = Some(match value {
+                        JsonValue::String(s) => s,
+                        JsonValue::Number(Number::I64(n)) => n.to_string(),
+                        JsonValue::Number(Number::U64(n)) => n.to_string(),
+                        _ => {
+                            return Err(BinaryError {
+                                offset,
+                                message: "a map key that isn't a string or an integer",
+                            })
+                        }
+                    });
+                    return Ok(());
+                }
+                Some(Frame::Object(obj, left, key)) => {
+                    let key = key.take().unwrap_or_default();
+                    obj.insert(self.keys.intern(&key), self.doc.alloc(value));
+                    left
+                }
+                Some(Frame::List(ids, left)) => {
+                    ids.push(self.doc.alloc(value));
+                    left
+                }
+            };
+            match left {
+                Some(1) => {}
+                Some(left) => {
+                    *left -= 1;
+                    return Ok(());
+                }
+                None => return Ok(()),
+            }
+            value = match self.stack.pop() {
+                Some(Frame::List(ids, _)) => JsonValue::List(ids),
+                Some(Frame::Object(obj, ..)) => JsonValue::Object(obj),
+                None => unreachable!(),
+            };
+        }
+    }
+
+    /// The document, once nothing is left in `reader` after the value.
+    pub(crate) fn finish(mut self, reader: &Reader<'_>) -> Result<Document, BinaryError> {
+        if reader.pos < reader.src.len() {
+            return Err(BinaryError {
+                offset: reader.pos,
+                message: "more after the value",
+            });
+        }
+        *self.doc.root_mut() = self.root.unwrap_or_default();
+        Ok(self.doc)
+    }
+}
@@ -0,0 +1,177 @@
+use std::borrow::Cow;
+
+use crate::{
+    error::ParseError,
+    map::Map,
+    options::{DuplicateKeys, ParseOptions},
+    owned::OwnedValue,
+    pull::{JsonPullParser, Step},
+    value::Number,
+};
+
+/// A value parsed by `parse_borrowed`, whose strings and keys point into
+/// the source unless they had escapes to decode. It owns its children, like
+/// `OwnedValue`, so a tree lives as long as its source and no longer.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum BorrowedValue<'json> {
+    String(Cow<'json, str>),
+    Number(Number),
+    Bool(bool),
+    Object(Map<Cow<'json, str>, BorrowedValue<'json>>),
+    List(Vec<BorrowedValue<'json>>),
+    #[default]
+    Null,
+}
+
+// A container being parsed, each object with the key its next value goes
+// under.
+enum Frame<'json> {
+    List(Vec<BorrowedValue<'json>>),
+    Object(Map<Cow<'json, str>, BorrowedValue<'json>>, Cow<'json, str>),
+}
+
+// A container being copied into an `OwnedValue`, with the children still to
+// go.
+enum IntoOwned<'json> {
+    List(Vec<OwnedValue>, std::vec::IntoIter<BorrowedValue<'json>>),
+    Object(
+        Map<String, OwnedValue>,
+        <Map<Cow<'json, str>, BorrowedValue<'json>> as IntoIterator>::IntoIter,
+        String,
+    ),
+}
+
+impl<'json> BorrowedValue<'json> {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            BorrowedValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The member `key`, if this is an object that has it.
+    pub fn get(&self, key: &str) -> Option<&BorrowedValue<'json>> {
+        match self {
+            BorrowedValue::Object(obj) => obj.get(key),
+            _ => None,
+        }
+    }
+
+    /// Copies every string that still borrows from the source, so the tree
+    /// can outlive it.
+    pub fn into_owned(self) -> OwnedValue {
+        let mut stack = Vec::new();
+        let mut next = self;
+        loop {
+            let mut value = match next {
+                BorrowedValue::List(list) => {
+                    stack.push(IntoOwned::List(
+                        Vec::with_capacity(list.len()),
+                        list.into_iter(),
+                    ));
+                    None
+                }
+                BorrowedValue::Object(obj) => {
+                    stack.push(IntoOwned::Object(
+                        Map::new(),
+                        obj.into_iter(),
+                        String::new(),
+                    ));
+                    None
+                }
+                BorrowedValue::String(s) => Some(OwnedValue::String(s.into_owned())),
+                BorrowedValue::Number(n) => Some(OwnedValue::Number(n)),
+                BorrowedValue::Bool(b) => Some(OwnedValue::Bool(b)),
+                BorrowedValue::Null => Some(OwnedValue::Null),
+            };
+            // Hand finished values to their parents until one has another
+            // child to copy.
+            next = loop {
+                let Some(frame) = stack.last_mut() else {
+                    return value.unwrap_or_default();
+                };
+                let child = match frame {
+                    IntoOwned::List(values, rest) => {
+                        values.extend(value.take());
+                        rest.next()
+                    }
+                    IntoOwned::Object(values, rest, key) => {
+                        if let Some(value) = value.take() {
+                            values.insert(std::mem::take(key), value);
+                        }
+                        rest.next().map(|(k, child)| {
+                            *key = k.into_owned();
+                            child
+                        })
+                    }
+                };
+                match child {
+                    Some(child) => break child,
+                    None => {
+                        value = Some(match stack.pop() {
+                            Some(IntoOwned::List(values, _)) => OwnedValue::List(values),
+                            Some(IntoOwned::Object(values, ..)) => OwnedValue::Object(values),
+                            None => unreachable!(),
+                        });
+                    }
+                }
+            };
+        }
+    }
+}
+
+pub(crate) fn parse<'json>(
+    src: &'json str,
+    opts: ParseOptions,
+) -> Result<BorrowedValue<'json>, ParseError> {
+    let mut par = JsonPullParser::borrowing(src, opts);
+    let mut stack = Vec::new();
+    let mut root = BorrowedValue::Null;
+    while let Some(step) = par.step_in(src)? {
+        let value = match step {
+            Step::StartObject => {
+                stack.push(Frame::Object(Map::new(), Cow::Borrowed("")));
+                continue;
+            }
+            Step::StartArray => {
+                stack.push(Frame::List(Vec::new()));
+                continue;
+            }
+            Step::Key => {
+                let key = par.text(src)?;
+                let Some(Frame::Object(obj, next)) = stack.last_mut() else {
+                    unreachable!("keys only come inside objects");
+                };
+                if opts.duplicate_keys == DuplicateKeys::Error && obj.contains_key(&*key) {
+                    return Err(ParseError::DuplicateKey {
+                        key: key.into_owned(),
+                        span: par.span(),
+                    });
+                }
+                *next = key;
+                continue;
+            }
+            Step::EndObject | Step::EndArray => match stack.pop() {
+                Some(Frame::Object(obj, _)) => BorrowedValue::Object(obj),
+                Some(Frame::List(list)) => BorrowedValue::List(list),
+                None => unreachable!(),
+            },
+            Step::String => BorrowedValue::String(par.text(src)?),
+            Step::Number(n) => BorrowedValue::Number(n),
+            Step::Bool(b) => BorrowedValue::Bool(b),
+            Step::Null => BorrowedValue::Null,
+        };
+        match stack.last_mut() {
+            None => root = value,
+            Some(Frame::List(list)) => list.push(value),
+            Some(Frame::Object(obj, key)) => {
+                let key = std::mem::take(key);
+                let first_wins = opts.duplicate_keys == DuplicateKeys::FirstWins;
+                if !(first_wins && obj.contains_key(&*key)) {
+                    obj.insert(key, value);
+                }
+            }
+        }
+    }
+    Ok(root)
+}
@@ -0,0 +1,116 @@
+use crate::{
+    alloc::{Allocator, Id},
+    doc::Document,
+    key::Key,
+    map::Map,
+    value::JsonValue,
+};
+
+/// Builds an object straight into a document's arena. Values given to
+/// `field` that hold children must have them in the same arena.
+pub struct ObjectBuilder<'doc> {
+    mem: &'doc mut Allocator<JsonValue>,
+    members: Map<Key, Id<JsonValue>>,
+}
+
+/// Builds a list straight into a document's arena, like `ObjectBuilder`.
+pub struct ArrayBuilder<'doc> {
+    mem: &'doc mut Allocator<JsonValue>,
+    elements: Vec<Id<JsonValue>>,
+}
+
+impl<'doc> ObjectBuilder<'doc> {
+    pub fn field(mut self, key: impl Into<Key>, value: impl Into<JsonValue>) -> Self {
+        let id = self.mem.alloc(value.into());
+        self.members.insert(key.into(), id);
+        self
+    }
+
+    pub fn object(
+        self,
+        key: impl Into<Key>,
+        build: impl FnOnce(ObjectBuilder<'_>) -> ObjectBuilder<'_>,
+    ) -> Self {
+        let value = build(ObjectBuilder::new(self.mem)).build();
+        self.field(key, value)
+    }
+
+    pub fn array(
+        self,
+        key: impl Into<Key>,
+        build: impl FnOnce(ArrayBuilder<'_>) -> ArrayBuilder<'_>,
+    ) -> Self {
+        let value = build(ArrayBuilder::new(self.mem)).build();
+        self.field(key, value)
+    }
+
+    /// The object, whose members are already in the arena.
+    pub fn build(self) -> JsonValue {
+        JsonValue::Object(self.members)
+    }
+
+    fn new(mem: &'doc mut Allocator<JsonValue>) -> Self {
+        Self {
+            mem,
+            members: Map::new(),
+        }
+    }
+}
+
+impl<'doc> ArrayBuilder<'doc> {
+    pub fn push(mut self, value: impl Into<JsonValue>) -> Self {
+        let id = self.mem.alloc(value.into());
+        self.elements.push(id);
+        self
+    }
+
+    pub fn object(self, build: impl FnOnce(ObjectBuilder<'_>) -> ObjectBuilder<'_>) -> Self {
+        let value = build(ObjectBuilder::new(self.mem)).build();
+        self.push(value)
+    }
+
+    pub fn array(self, build: impl FnOnce(ArrayBuilder<'_>) -> ArrayBuilder<'_>) -> Self {
+        let value = build(ArrayBuilder::new(self.mem)).build();
+        self.push(value)
+    }
+
+    /// The list, whose elements are already in the arena.
+    pub fn build(self) -> JsonValue {
+        JsonValue::List(self.elements)
+    }
+
+    fn new(mem: &'doc mut Allocator<JsonValue>) -> Self {
+        Self {
+            mem,
+            elements: Vec::new(),
+        }
+    }
+}
+
+impl Document {
+    /// A new document with `build`'s object as its root.
+    pub fn object(build: impl FnOnce(ObjectBuilder<'_>) -> ObjectBuilder<'_>) -> Document {
+        let mut doc = Document::default();
+        let root = build(doc.object_builder()).build();
+        *doc.root_mut() = root;
+        doc
+    }
+
+    /// A new document with `build`'s list as its root.
+    pub fn array(build: impl FnOnce(ArrayBuilder<'_>) -> ArrayBuilder<'_>) -> Document {
+        let mut doc = Document::default();
+        let root = build(doc.array_builder()).build();
+        *doc.root_mut() = root;
+        doc
+    }
+
+    /// An object to build in this document's arena, for adding with
+    /// `set_pointer` or `ListMut::push`.
+    pub fn object_builder(&mut self) -> ObjectBuilder<'_> {
+        ObjectBuilder::new(self.arena_mut())
+    }
+
+    pub fn array_builder(&mut self) -> ArrayBuilder<'_> {
+        ArrayBuilder::new(self.arena_mut())
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt::{self, Write};
+
+use crate::{
+    alloc::Id,
+    doc::{Document, ValueRef},
+    error::CanonicalError,
+    key::Key,
+    pointer,
+    value::JsonValue,
+};
+
+impl Document {
+    /// A 64-bit FNV-1a digest of the document's canonical form, which is the
+    /// same whatever the whitespace, key order or number spelling (`1.0` and
+    /// `1e0` are both `1`). Stable across runs and platforms. NaN and the
+    /// infinities, which have no canonical form, hash as JavaScript prints
+    /// them, and `Raw` values by their text.
+    pub fn canonical_hash(&self) -> u64 {
+        self.value().canonical_hash()
+    }
+
+    /// The document as RFC 8785 (JCS) canonical JSON, for hashing or
+    /// signing. Fails on NaN and the infinities, which JCS rejects, and on
+    /// `Raw` values, whose text was never parsed to put in canonical form.
+    pub fn to_canonical_string(&self) -> Result<String, CanonicalError> {
+        self.value().to_canonical_string()
+    }
+}
+
+impl ValueRef<'_> {
+    pub fn to_canonical_string(&self) -> Result<String, CanonicalError> {
+        let mut out = String::new();
+        match write(*self, false, &mut out) {
+            Ok(()) => Ok(out),
+            Err(fmt::Error) => {
+                let (pointer, value) = find(*self, |value| match value {
+                    JsonValue::Number(n) => !n.as_f64().is_finite(),
+                    JsonValue::Raw(_) => true,
+                    _ => false,
+                })
+                .expect("only non-finite numbers and raw values fail");
+                let message = match value {
+                    JsonValue::Raw(_) => "a raw value",
+                    _ => "a non-finite number",
+                };
+                Err(CanonicalError { pointer, message })
+            }
+        }
+    }
+
+    pub fn canonical_hash(&self) -> u64 {
+        salted_hash(*self, "")
+    }
+}
+
+// The FNV-1a digest of `salt` followed by `value`'s canonical form.
+pub(crate) fn salted_hash(value: ValueRef<'_>, salt: &str) -> u64 {
+    let mut hasher = Fnv(0xcbf2_9ce4_8422_2325);
+    hasher.write_str(salt).expect("hashing can't fail");
+    write(value, true, &mut hasher).expect("hashing can't fail");
+    hasher.0
+}
+
+// The canonical form as a key to compare values by, NaN and raw text and
+// all.
+pub(crate) fn text(value: ValueRef<'_>) -> String {
+    let mut out = String::new();
+    write(value, true, &mut out).expect("writing to a string can't fail");
+    out
+}
+
+// The JSON Pointer to the first value in `value` that `hit` picks, in the
+// map's member order, and that value.
+pub(crate) fn find<'doc>(
+    value: ValueRef<'doc>,
+    hit: impl Fn(&JsonValue) -> bool,
+) -> Option<(String, &'doc JsonValue)> {
+    let mut stack = vec![(String::new(), value)];
+    while let Some((path, value)) = stack.pop() {
+        let start = stack.len();
+        match value.value() {
+            found if hit(found) => return Some((path, found)),
+            JsonValue::Object(obj) => stack.extend(
+                obj.iter()
+                    .map(|(k, &id)| (format!("{path}/{}", pointer::escape(k)), value.child(id))),
+            ),
+            JsonValue::List(list) => stack.extend(
+                list.iter()
+                    .enumerate()
+                    .map(|(i, &id)| (format!("{path}/{i}"), value.child(id))),
+            ),
+            _ => {}
+        }
+        stack[start..].reverse();
+    }
+    None
+}
+
+struct Fnv(u64);
+
+impl Write for Fnv {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &b in s.as_bytes() {
+            self.0 = (self.0 ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3);
+        }
+        Ok(())
+    }
+}
+
+enum Frame<'doc> {
+    List(std::slice::Iter<'doc, Id<JsonValue>>),
+    Object(std::vec::IntoIter<(&'doc Key, Id<JsonValue>)>),
+}
+
+// Writes `value` as RFC 8785 (JCS) canonical JSON: no whitespace, keys
+// sorted by UTF-16 code units, numbers as ECMAScript prints doubles. JCS
+// has no spelling for NaN or the infinities, and a `Raw` value's text isn't
+// canonical, so they fail; when `lenient`, the numbers are written the way
+// JavaScript prints them and the text as it is.
+fn write(value: ValueRef<'_>, lenient: bool, out: &mut impl Write) -> fmt::Result {
+    let doc = value.document();
+    let mut stack = Vec::new();
+    let mut next = value.value();
+    loop {
+        match next {
+            JsonValue::Object(obj) => {
+                let mut members: Vec<_> = obj.iter().map(|(k, &id)| (k, id)).collect();
+                members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+                out.write_char('{')?;
+                stack.push((Frame::Object(members.into_iter()), true));
+            }
+            JsonValue::List(list) => {
+                out.write_char('[')?;
+                stack.push((Frame::List(list.iter()), true));
+            }
+            JsonValue::String(s) => string(s, false, out)?,
+            JsonValue::Number(n) if !lenient && !n.as_f64().is_finite() => return Err(fmt::Error),
+            JsonValue::Number(n) => number(n.as_f64(), out)?,
+            JsonValue::Bool(b) => write!(out, "{b}")?,
+            JsonValue::Raw(_) if !lenient => return Err(fmt::Error),
+            JsonValue::Raw(text) => out.write_str(text)?,
+            JsonValue::Null => out.write_str("null")?,
+        }
+        // Close every container that's done, up to the next value.
+        loop {
+            let Some((frame, first)) = stack.last_mut() else {
+                return Ok(());
+            };
+            let id = match frame {
+                Frame::List(iter) => iter.next().map(|&id| (None, id)),
+                Frame::Object(iter) => iter.next().map(|(key, id)| (Some(key), id)),
+            };
+            let Some((key, id)) = id else {
+                out.write_char(match frame {
+                    Frame::List(_) => ']',
+                    Frame::Object(_) => '}',
+                })?;
+                stack.pop();
+                continue;
+            };
+            if !std::mem::take(first) {
+                out.write_char(',')?;
+            }
+            if let Some(key) = key {
+                string(key, false, out)?;
+                out.write_char(':')?;
+            }
+            next = doc.get(id);
+            break;
+        }
+    }
+}
+
+// Escapes what JSON requires, control characters as `\u00xx`, and with
+// `ascii` everything outside of ASCII too, as UTF-16 `\uxxxx` units.
+pub(crate) fn string(s: &str, ascii: bool, out: &mut impl Write) -> fmt::Result {
+    out.write_char('"')?;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        let escape = match c {
+            '"' => "\\\"",
+            '\\' => "\\\\",
+            '\u{8}' => "\\b",
+            '\t' => "\\t",
+            '\n' => "\\n",
+            '\u{c}' => "\\f",
+            '\r' => "\\r",
+            '\0'..='\u{1f}' => "",
+            c if ascii && !c.is_ascii() => "",
+            _ => continue,
+        };
+        out.write_str(&s[start..i])?;
+        match escape {
+            "" => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    write!(out, "\\u{unit:04x}")?;
+                }
+            }
+            _ => out.write_str(escape)?,
+        }
+        start = i + c.len_utf8();
+    }
+    out.write_str(&s[start..])?;
+    out.write_char('"')
+}
+
+// ECMAScript's Number.prototype.toString: the shortest digits that round
+// trip, in plain notation from 1e-6 up to 1e21 and in exponent notation
+// outside of that.
+pub(crate) fn number(n: f64, out: &mut impl Write) -> fmt::Result {
+    if n.is_nan() {
+        return out.write_str("NaN");
+    }
+    if n.is_infinite() {
+        return out.write_str(if n > 0.0 { "Infinity" } else { "-Infinity" });
+    }
+    if n == 0.0 {
+        return out.write_char('0');
+    }
+    if n < 0.0 {
+        out.write_char('-')?;
+    }
+    // `{:e}` gives the shortest round-trip digits as `d.ddde±x`.
+    let sci = format!("{:e}", n.abs());
+    let (mantissa, exp) = sci.split_once('e').expect("`{:e}` has an exponent");
+    let digits: String = mantissa.chars().filter(|&c| c != '.').collect();
+    let k = digits.len() as i32;
+    // The value is 0.digits × 10^point.
+    let point = exp.parse::<i32>().expect("`{:e}` exponent is an integer") + 1;
+    if k <= point && point <= 21 {
+        out.write_str(&digits)?;
+        (k..point).try_for_each(|_| out.write_char('0'))
+    } else if 0 < point && point <= 21 {
+        let (int, frac) = digits.split_at(point as usize);
+        write!(out, "{int}.{frac}")
+    } else if -6 < point && point <= 0 {
+        out.write_str("0.")?;
+        (point..0).try_for_each(|_| out.write_char('0'))?;
+        out.write_str(&digits)
+    } else {
+        let (first, rest) = digits.split_at(1);
+        out.write_str(first)?;
+        if !rest.is_empty() {
+            write!(out, ".{rest}")?;
+        }
+        let sign = if point > 0 { '+' } else { '-' };
+        write!(out, "e{sign}{}", (point - 1).abs())
+    }
+}
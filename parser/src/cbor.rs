@@ -0,0 +1,229 @@
+use crate::{
+    base64,
+    binary::{Builder, Reader},
+    doc::{Document, ValueRef},
+    error::BinaryError,
+    value::{JsonValue, Number},
+};
+
+// Something still to write: a member's key goes before its value.
+enum Item<'doc> {
+    Key(&'doc str),
+    Value(ValueRef<'doc>),
+}
+
+impl Document {
+    /// The document as CBOR, in RFC 8949's preferred serialization: each
+    /// integer and length in its shortest form, and floats in single
+    /// precision when that loses nothing. A `Raw` value, which CBOR has no
+    /// room for, is a string of its JSON text.
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut stack = vec![Item::Value(self.value())];
+        while let Some(item) = stack.pop() {
+            let value = match item {
+                Item::Key(key) => {
+                    head(3, key.len() as u64, &mut out);
+                    out.extend(key.as_bytes());
+                    continue;
+                }
+                Item::Value(value) => value,
+            };
+            let start = stack.len();
+            match value.value() {
+                JsonValue::Null => out.push(0xf6),
+                JsonValue::Bool(b) => out.push(0xf4 | *b as u8),
+                JsonValue::Number(n) => number(n, &mut out),
+                JsonValue::String(s) | JsonValue::Raw(s) => {
+                    head(3, s.len() as u64, &mut out);
+                    out.extend(s.as_bytes());
+                }
+                JsonValue::List(list) => {
+                    head(4, list.len() as u64, &mut out);
+                    stack.extend(list.iter().map(|&id| Item::Value(value.child(id))));
+                }
+                JsonValue::Object(obj) => {
+                    head(5, obj.len() as u64, &mut out);
+                    for (key, &id) in obj {
+                        stack.push(Item::Key(key));
+                        stack.push(Item::Value(value.child(id)));
+                    }
+                }
+            }
+            stack[start..].reverse();
+        }
+        out
+    }
+}
+
+// The initial byte of `major` type with its argument.
+fn head(major: u8, arg: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    match arg {
+        0..=23 => out.push(major | arg as u8),
+        24..=0xff => out.extend([major | 24, arg as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend((arg as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend((arg as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend(arg.to_be_bytes());
+        }
+    }
+}
+
+fn number(n: &Number, out: &mut Vec<u8>) {
+    match (n.as_u64(), n.as_i64()) {
+        (Some(n), _) => head(0, n, out),
+        // -1 - m for a negative n.
+        (None, Some(n)) => head(1, !n as u64, out),
+        (None, None) => {
+            let f = n.as_f64();
+            let single = f as f32;
+            if single as f64 == f {
+                out.push(0xfa);
+                out.extend(single.to_bits().to_be_bytes());
+            } else {
+                out.push(0xfb);
+                out.extend(f.to_bits().to_be_bytes());
+            }
+        }
+    }
+}
+
+pub(crate) fn parse(src: &[u8]) -> Result<Document, BinaryError> {
+    let mut reader = Reader { src, pos: 0 };
+    let mut builder = Builder::new();
+    // The tag on the item being read, for byte strings to be converted by.
+    let mut tag = None;
+    while !builder.done() {
+        let offset = reader.pos;
+        let initial = reader.byte()?;
+        let fail = |message| BinaryError { offset, message };
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        let arg = match info {
+            0..=23 => Some(info as u64),
+            24..=27 => Some(reader.uint(1 << (info - 24))?),
+            31 => None,
+            _ => return Err(fail("a reserved additional information value")),
+        };
+        let item_tag = match major {
+            6 => None,
+            _ => tag.take(),
+        };
+        let value = match (major, arg) {
+            (0, Some(n)) => JsonValue::Number(n.into()),
+            (1, Some(n)) => match i64::try_from(n) {
+                Ok(n) => JsonValue::Number(Number::I64(-1 - n)),
+                // Below `i64::MIN`.
+                Err(_) => JsonValue::Number(Number::F64(-1.0 - n as f64)),
+            },
+            (2, _) => {
+                let bytes = string(&mut reader, 2, arg)?;
+                JsonValue::String(match item_tag {
+                    Some(3) => format!("~{}", base64::encode_url(&bytes)),
+                    Some(22) => base64::encode_standard(&bytes),
+                    Some(23) => bytes.iter().map(|b| format!("{b:02x}")).collect(),
+                    _ => base64::encode_url(&bytes),
+                })
+            }
+            (3, _) => {
+                let bytes = string(&mut reader, 3, arg)?;
+                let s =
+                    String::from_utf8(bytes).map_err(|_| fail("a text string that isn't UTF-8"))?;
+                JsonValue::String(s)
+            }
+            (4, len) => {
+                let len = len.map(|len| usize::try_from(len).unwrap_or(usize::MAX));
+                builder.list(len, &reader, offset)?;
+                continue;
+            }
+            (5, len) => {
+                let len = len.map(|len| usize::try_from(len).unwrap_or(usize::MAX));
+                builder.object(len, offset)?;
+                continue;
+            }
+            // Other tags leave their content as it is.
+            (6, Some(n)) => {
+                tag = Some(n);
+                continue;
+            }
+            (7, _) => match info {
+                20 => JsonValue::Bool(false),
+                21 => JsonValue::Bool(true),
+                // undefined, and simple values JSON has nothing for.
+                0..=24 => JsonValue::Null,
+                25..=27 => {
+                    let bits = arg.unwrap_or_default();
+                    let f = match info {
+                        25 => half(bits as u16),
+                        26 => f32::from_bits(bits as u32) as f64,
+                        _ => f64::from_bits(bits),
+                    };
+                    match f.is_finite() {
+                        true => JsonValue::Number(Number::F64(f)),
+                        false => JsonValue::Null,
+                    }
+                }
+                _ => {
+                    builder.end(offset)?;
+                    continue;
+                }
+            },
+            _ => return Err(fail("a length where there can't be one")),
+        };
+        builder.value(value, offset)?;
+    }
+    builder.finish(&reader)
+}
+
+// The bytes of a byte or text string of `major` type, whose chunks follow
+// if it has no length.
+fn string(reader: &mut Reader<'_>, major: u8, len: Option<u64>) -> Result<Vec<u8>, BinaryError> {
+    if let Some(len) = len {
+        return Ok(reader
+            .take(usize::try_from(len).unwrap_or(usize::MAX))?
+            .to_vec());
+    }
+    let mut bytes = Vec::new();
+    loop {
+        let offset = reader.pos;
+        let initial = reader.byte()?;
+        let len = match (initial >> 5, initial & 0x1f) {
+            (7, 31) => return Ok(bytes),
+            (chunk, info @ 0..=23) if chunk == major => info as usize,
+            (chunk, info @ 24..=27) if chunk == major => {
+                let len = reader.uint(1 << (info - 24))?;
+                usize::try_from(len).unwrap_or(usize::MAX)
+            }
+            _ => {
+                return Err(BinaryError {
+                    offset,
+                    message: "a chunk of an indefinite-length string that isn't a string of its type with a length",
+                })
+            }
+        };
+        bytes.extend(reader.take(len)?);
+    }
+}
+
+// An IEEE 754 half-precision float.
+fn half(bits: u16) -> f64 {
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = (bits & 0x3ff) as f64;
+    let magnitude = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f64.powi(exponent as i32 - 15),
+    };
+    match bits >> 15 {
+        0 => magnitude,
+        _ => -magnitude,
+    }
+}
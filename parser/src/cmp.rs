@@ -0,0 +1,122 @@
+use std::cmp::Ordering;
+
+use crate::{
+    doc::{Document, ValueRef},
+    value::{JsonValue, Number},
+};
+
+impl Document {
+    /// A total order over values of this document, for sorting mixed lists:
+    /// null < bool < number < string < list < object. Numbers compare by
+    /// value, an integer before an equal float and NaN last; strings by code
+    /// point; lists element by element; objects by their members sorted by
+    /// key, key before value. `Raw` values come after everything else, in
+    /// the order of their text.
+    pub fn cmp_values(&self, a: &JsonValue, b: &JsonValue) -> Ordering {
+        self.value_ref(a).cmp_with(self.value_ref(b))
+    }
+}
+
+enum Work<'a, 'b> {
+    Values(ValueRef<'a>, ValueRef<'b>),
+    // Already compared, but only decides if everything before it is equal.
+    Decided(Ordering),
+}
+
+impl ValueRef<'_> {
+    /// `Document::cmp_values` for values that may come from different
+    /// documents.
+    pub fn cmp_with(self, other: ValueRef<'_>) -> Ordering {
+        let mut stack = vec![Work::Values(self, other)];
+        while let Some(work) = stack.pop() {
+            let (a, b) = match work {
+                Work::Values(a, b) => (a, b),
+                Work::Decided(Ordering::Equal) => continue,
+                Work::Decided(order) => return order,
+            };
+            let order = match (a.value(), b.value()) {
+                (JsonValue::List(x), JsonValue::List(y)) => {
+                    stack.push(Work::Decided(x.len().cmp(&y.len())));
+                    let pairs = x
+                        .iter()
+                        .zip(y)
+                        .map(|(&i, &j)| Work::Values(a.child(i), b.child(j)));
+                    let start = stack.len();
+                    stack.extend(pairs);
+                    stack[start..].reverse();
+                    continue;
+                }
+                (JsonValue::Object(x), JsonValue::Object(y)) => {
+                    let mut x: Vec<_> = x.iter().collect();
+                    let mut y: Vec<_> = y.iter().collect();
+                    x.sort_by_key(|&(k, _)| k);
+                    y.sort_by_key(|&(k, _)| k);
+                    stack.push(Work::Decided(x.len().cmp(&y.len())));
+                    let start = stack.len();
+                    for ((k, &i), (l, &j)) in x.into_iter().zip(y) {
+                        stack.push(Work::Decided(k.cmp(l)));
+                        stack.push(Work::Values(a.child(i), b.child(j)));
+                    }
+                    stack[start..].reverse();
+                    continue;
+                }
+                (JsonValue::Number(x), JsonValue::Number(y)) => numbers(x, y),
+                (JsonValue::String(x), JsonValue::String(y)) => x.cmp(y),
+                (JsonValue::Bool(x), JsonValue::Bool(y)) => x.cmp(y),
+                (JsonValue::Raw(x), JsonValue::Raw(y)) => x.cmp(y),
+                (x, y) => rank(x).cmp(&rank(y)),
+            };
+            if order != Ordering::Equal {
+                return order;
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+fn rank(value: &JsonValue) -> u8 {
+    match value {
+        JsonValue::Null => 0,
+        JsonValue::Bool(_) => 1,
+        JsonValue::Number(_) => 2,
+        JsonValue::String(_) => 3,
+        JsonValue::List(_) => 4,
+        JsonValue::Object(_) => 5,
+        JsonValue::Raw(_) => 6,
+    }
+}
+
+pub(crate) fn numbers(x: &Number, y: &Number) -> Ordering {
+    match (integer(x), integer(y)) {
+        (Some(i), Some(j)) => i.cmp(&j),
+        (Some(i), None) => int_float(i, y.as_f64()),
+        (None, Some(j)) => int_float(j, x.as_f64()).reverse(),
+        (None, None) => {
+            let (f, g) = (x.as_f64(), y.as_f64());
+            f.partial_cmp(&g)
+                .unwrap_or_else(|| f.is_nan().cmp(&g.is_nan()))
+        }
+    }
+}
+
+fn integer(n: &Number) -> Option<i128> {
+    match *n {
+        Number::I64(n) => Some(n.into()),
+        Number::U64(n) => Some(n.into()),
+        _ => None,
+    }
+}
+
+// Exact even where `i` has no `f64` of its own.
+fn int_float(i: i128, f: f64) -> Ordering {
+    if f.is_nan() {
+        return Ordering::Less;
+    }
+    // Every i64 and u64 fits well inside ±2^100.
+    let t = f.trunc().clamp(-1e30, 1e30);
+    match i.cmp(&(t as i128)) {
+        Ordering::Equal if f < t => Ordering::Greater,
+        Ordering::Equal => Ordering::Less,
+        order => order,
+    }
+}
@@ -0,0 +1,321 @@
+use std::{collections::HashMap, hash::BuildHasher};
+
+use crate::{
+    alloc::{Allocator, Id},
+    doc::{Document, ValueRef},
+    error::{DecodeError, TypeError},
+    key::Key,
+    value::{JsonValue, Number},
+};
+
+/// Types that can be read out of a parsed value, whose children are in
+/// `arena`.
+pub trait FromJson: Sized {
+    fn from_json(value: &JsonValue, arena: &Allocator<JsonValue>) -> Result<Self, DecodeError>;
+}
+
+/// Types that can be written as a value, with any children going in
+/// `arena`.
+pub trait ToJson {
+    fn to_json(&self, arena: &mut Allocator<JsonValue>) -> JsonValue;
+}
+
+impl Document {
+    pub fn decode<T: FromJson>(&self) -> Result<T, DecodeError> {
+        T::from_json(self.root(), self.arena())
+    }
+
+    pub fn encode<T: ToJson + ?Sized>(value: &T) -> Document {
+        let mut doc = Document::default();
+        let root = value.to_json(doc.arena_mut());
+        *doc.root_mut() = root;
+        doc
+    }
+}
+
+impl DecodeError {
+    /// A `Type` error for finding `found` where `expected` should be.
+    pub fn expected(expected: &'static str, found: &JsonValue) -> Self {
+        TypeError::new(expected, found.type_name()).into()
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &JsonValue, _: &Allocator<JsonValue>) -> Result<Self, DecodeError> {
+        value
+            .as_bool()
+            .ok_or_else(|| DecodeError::expected("a bool", value))
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &JsonValue, _: &Allocator<JsonValue>) -> Result<Self, DecodeError> {
+        value
+            .as_str()
+            .map(str::to_owned)
+            .ok_or_else(|| DecodeError::expected("a string", value))
+    }
+}
+
+impl FromJson for Number {
+    fn from_json(value: &JsonValue, _: &Allocator<JsonValue>) -> Result<Self, DecodeError> {
+        value
+            .as_number()
+            .cloned()
+            .ok_or_else(|| DecodeError::expected("a number", value))
+    }
+}
+
+impl FromJson for f64 {
+    fn from_json(value: &JsonValue, _: &Allocator<JsonValue>) -> Result<Self, DecodeError> {
+        value
+            .as_f64()
+            .ok_or_else(|| DecodeError::expected("a number", value))
+    }
+}
+
+impl FromJson for f32 {
+    fn from_json(value: &JsonValue, _: &Allocator<JsonValue>) -> Result<Self, DecodeError> {
+        Ok(value
+            .as_f64()
+            .ok_or_else(|| DecodeError::expected("a number", value))? as f32)
+    }
+}
+
+// Integers have to be integers in the source and fit the type.
+macro_rules! integers {
+    ($($ty:ident $expected:literal)*) => {$(
+        impl FromJson for $ty {
+            fn from_json(value: &JsonValue, _: &Allocator<JsonValue>) -> Result<Self, DecodeError> {
+                let n = value.as_number().and_then(|n| match n {
+                    Number::U64(n) => $ty::try_from(*n).ok(),
+                    n => $ty::try_from(n.as_i64()?).ok(),
+                });
+                n.ok_or_else(|| DecodeError::expected($expected, value))
+            }
+        }
+
+        impl ToJson for $ty {
+            fn to_json(&self, _: &mut Allocator<JsonValue>) -> JsonValue {
+                match i64::try_from(*self) {
+                    Ok(n) => JsonValue::Number(Number::I64(n)),
+                    Err(_) => JsonValue::Number(Number::U64(*self as u64)),
+                }
+            }
+        }
+    )*};
+}
+
+integers! {
+    i8 "an i8"
+    i16 "an i16"
+    i32 "an i32"
+    i64 "an i64"
+    isize "an isize"
+    u8 "a u8"
+    u16 "a u16"
+    u32 "a u32"
+    u64 "a u64"
+    usize "a usize"
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &JsonValue, arena: &Allocator<JsonValue>) -> Result<Self, DecodeError> {
+        match value {
+            JsonValue::Null => Ok(None),
+            value => T::from_json(value, arena).map(Some),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Box<T> {
+    fn from_json(value: &JsonValue, arena: &Allocator<JsonValue>) -> Result<Self, DecodeError> {
+        T::from_json(value, arena).map(Box::new)
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &JsonValue, arena: &Allocator<JsonValue>) -> Result<Self, DecodeError> {
+        let list = value
+            .as_array()
+            .ok_or_else(|| DecodeError::expected("a list", value))?;
+        list.iter()
+            .enumerate()
+            .map(|(i, &id)| T::from_json(arena.fetch(id), arena).map_err(|e| e.within(i)))
+            .collect()
+    }
+}
+
+impl<T: FromJson, S: BuildHasher + Default> FromJson for HashMap<String, T, S> {
+    fn from_json(value: &JsonValue, arena: &Allocator<JsonValue>) -> Result<Self, DecodeError> {
+        let obj = value
+            .as_object()
+            .ok_or_else(|| DecodeError::expected("an object", value))?;
+        obj.iter()
+            .map(|(k, &id)| {
+                let v = T::from_json(arena.fetch(id), arena).map_err(|e| e.within(k))?;
+                Ok((k.to_string(), v))
+            })
+            .collect()
+    }
+}
+
+// Tuples are lists of exactly their length.
+macro_rules! tuples {
+    ($($len:literal => ($($ty:ident $id:ident $i:tt),*))*) => {$(
+        impl<$($ty: FromJson),*> FromJson for ($($ty,)*) {
+            fn from_json(value: &JsonValue, arena: &Allocator<JsonValue>) -> Result<Self, DecodeError> {
+                match value.as_array() {
+                    Some(&[$($id),*]) => Ok(($(
+                        $ty::from_json(arena.fetch($id), arena).map_err(|e| e.within($i))?,
+                    )*)),
+                    _ => Err(DecodeError::expected(concat!("a list of ", $len), value)),
+                }
+            }
+        }
+
+        impl<$($ty: ToJson),*> ToJson for ($($ty,)*) {
+            fn to_json(&self, arena: &mut Allocator<JsonValue>) -> JsonValue {
+                let list = vec![$({
+                    let value = self.$i.to_json(arena);
+                    arena.alloc(value)
+                }),*];
+                JsonValue::List(list)
+            }
+        }
+    )*};
+}
+
+tuples! {
+    1 => (A a 0)
+    2 => (A a 0, B b 1)
+    3 => (A a 0, B b 1, C c 2)
+    4 => (A a 0, B b 1, C c 2, D d 3)
+    5 => (A a 0, B b 1, C c 2, D d 3, E e 4)
+    6 => (A a 0, B b 1, C c 2, D d 3, E e 4, F f 5)
+}
+
+impl ToJson for bool {
+    fn to_json(&self, _: &mut Allocator<JsonValue>) -> JsonValue {
+        JsonValue::Bool(*self)
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self, _: &mut Allocator<JsonValue>) -> JsonValue {
+        JsonValue::String(self.to_owned())
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self, arena: &mut Allocator<JsonValue>) -> JsonValue {
+        self.as_str().to_json(arena)
+    }
+}
+
+impl ToJson for Number {
+    // `Number` is only `Copy` without `arbitrary-precision`.
+    #[allow(clippy::clone_on_copy)]
+    fn to_json(&self, _: &mut Allocator<JsonValue>) -> JsonValue {
+        JsonValue::Number(self.clone())
+    }
+}
+
+impl ToJson for f64 {
+    fn to_json(&self, _: &mut Allocator<JsonValue>) -> JsonValue {
+        JsonValue::Number(Number::F64(*self))
+    }
+}
+
+impl ToJson for f32 {
+    fn to_json(&self, _: &mut Allocator<JsonValue>) -> JsonValue {
+        JsonValue::Number(Number::F64((*self).into()))
+    }
+}
+
+/// Copies the value and everything below it, for moving values between
+/// documents.
+impl ToJson for ValueRef<'_> {
+    fn to_json(&self, arena: &mut Allocator<JsonValue>) -> JsonValue {
+        // Children get a slot straight away, filled in when they come off
+        // the stack.
+        let mut stack = Vec::new();
+        let root = shallow_copy(*self, arena, &mut stack);
+        while let Some((value, id)) = stack.pop() {
+            *arena.fetch_mut(id) = shallow_copy(value, arena, &mut stack);
+        }
+        root
+    }
+}
+
+fn shallow_copy<'doc>(
+    value: ValueRef<'doc>,
+    arena: &mut Allocator<JsonValue>,
+    stack: &mut Vec<(ValueRef<'doc>, Id<JsonValue>)>,
+) -> JsonValue {
+    let mut slot = |child: Id<JsonValue>| {
+        let id = arena.alloc(JsonValue::Null);
+        stack.push((value.child(child), id));
+        id
+    };
+    match value.value() {
+        JsonValue::List(list) => JsonValue::List(list.iter().map(|&id| slot(id)).collect()),
+        JsonValue::Object(obj) => {
+            JsonValue::Object(obj.iter().map(|(k, &id)| (k.clone(), slot(id))).collect())
+        }
+        scalar => scalar.clone(),
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self, arena: &mut Allocator<JsonValue>) -> JsonValue {
+        (**self).to_json(arena)
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for Box<T> {
+    fn to_json(&self, arena: &mut Allocator<JsonValue>) -> JsonValue {
+        (**self).to_json(arena)
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self, arena: &mut Allocator<JsonValue>) -> JsonValue {
+        match self {
+            Some(value) => value.to_json(arena),
+            None => JsonValue::Null,
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self, arena: &mut Allocator<JsonValue>) -> JsonValue {
+        let list = self
+            .iter()
+            .map(|element| {
+                let value = element.to_json(arena);
+                arena.alloc(value)
+            })
+            .collect();
+        JsonValue::List(list)
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self, arena: &mut Allocator<JsonValue>) -> JsonValue {
+        self.as_slice().to_json(arena)
+    }
+}
+
+impl<T: ToJson, S> ToJson for HashMap<String, T, S> {
+    fn to_json(&self, arena: &mut Allocator<JsonValue>) -> JsonValue {
+        let obj = self
+            .iter()
+            .map(|(k, v)| {
+                let value = v.to_json(arena);
+                (Key::from(k), arena.alloc(value))
+            })
+            .collect();
+        JsonValue::Object(obj)
+    }
+}
@@ -0,0 +1,254 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt::Write,
+};
+
+use crate::{
+    doc::Document,
+    schema::{self, Shape},
+};
+
+/// How `codegen` writes Rust types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodegenOptions {
+    /// The name of the type for the whole document.
+    pub root_name: String,
+    /// Derive serde's `Deserialize` and `Serialize`, with `serde_json::Value`
+    /// for values of no one type, instead of this crate's `FromJson` and
+    /// `ToJson` with `OwnedValue`.
+    pub serde: bool,
+}
+
+/// A root called `Root`, with this crate's derives.
+impl Default for CodegenOptions {
+    fn default() -> Self {
+        Self {
+            root_name: "Root".into(),
+            serde: false,
+        }
+    }
+}
+
+// Names a type can't take without shadowing one the definitions use.
+const RESERVED: [&str; 7] = [
+    "Vec",
+    "Option",
+    "String",
+    "Box",
+    "Self",
+    "OwnedValue",
+    "Value",
+];
+
+const KEYWORDS: [&str; 47] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+// Keywords that can't be raw identifiers either.
+const UNRAWABLE: [&str; 5] = ["crate", "self", "Self", "super", "extern"];
+
+struct Generator<'a> {
+    shapes: &'a [Shape],
+    opts: &'a CodegenOptions,
+    names: HashSet<String>,
+    // Objects still to write, with their type names.
+    queue: VecDeque<(usize, String)>,
+}
+
+pub(crate) fn generate(samples: &[Document], opts: &CodegenOptions) -> String {
+    let shapes = schema::shapes(samples);
+    let mut gen = Generator {
+        shapes: &shapes,
+        opts,
+        names: RESERVED.iter().map(|&name| name.to_owned()).collect(),
+        queue: VecDeque::new(),
+    };
+    let root = type_name(&opts.root_name);
+    let mut out = String::new();
+    gen.names.insert(root.clone());
+    let ty = gen.field_type(0, &root, true);
+    if ty != root {
+        writeln!(out, "pub type {root} = {ty};").unwrap();
+    }
+    while let Some((index, name)) = gen.queue.pop_front() {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        gen.write_struct(index, &name, &mut out);
+    }
+    out
+}
+
+impl Generator<'_> {
+    fn write_struct(&mut self, index: usize, name: &str, out: &mut String) {
+        let (derives, attribute) = match self.opts.serde {
+            true => ("serde::Deserialize, serde::Serialize", "serde"),
+            false => ("jsonparser::FromJson, jsonparser::ToJson", "json"),
+        };
+        writeln!(out, "#[derive(Debug, Clone, PartialEq, {derives})]").unwrap();
+        writeln!(out, "pub struct {name} {{").unwrap();
+        let shape = &self.shapes[index];
+        let mut fields = HashSet::new();
+        for (key, &(child, seen)) in &shape.members {
+            let mut field = field_name(key);
+            let base = field.clone();
+            let mut n = 1;
+            while !fields.insert(field.clone()) {
+                n += 1;
+                field = format!("{base}_{n}");
+            }
+            if field != *key {
+                writeln!(out, "    #[{attribute}(rename = {key:?})]").unwrap();
+            }
+            let ty = self.field_type(child, &type_name(key), seen == shape.objects);
+            writeln!(out, "    pub {field}: {ty},").unwrap();
+        }
+        out.push_str("}\n");
+    }
+
+    // The type for values of `index`, where objects are named after `name`;
+    // `required` if every object had the member.
+    fn field_type(&mut self, index: usize, name: &str, required: bool) -> String {
+        let mut name = name.to_owned();
+        let (mut prefix, mut suffix) = (String::new(), String::new());
+        if !required {
+            prefix.push_str("Option<");
+            suffix.push('>');
+        }
+        let mut index = Some(index);
+        // Lists of lists go round again for their items.
+        loop {
+            let Some(shape) = index.map(|i| &self.shapes[i]) else {
+                return format!("{prefix}{}{suffix}", self.any());
+            };
+            let kinds = [
+                shape.boolean,
+                shape.integer || shape.number,
+                shape.string,
+                shape.list,
+                shape.objects > 0,
+            ];
+            let ty = match kinds.iter().filter(|&&seen| seen).count() {
+                1 => match kinds.iter().position(|&seen| seen) {
+                    Some(0) => "bool".to_owned(),
+                    Some(1) if shape.number => "f64".to_owned(),
+                    Some(1) => "i64".to_owned(),
+                    Some(2) => "String".to_owned(),
+                    Some(3) => {
+                        if shape.null {
+                            prefix.push_str("Option<");
+                            suffix.insert(0, '>');
+                        }
+                        prefix.push_str("Vec<");
+                        suffix.insert(0, '>');
+                        index = shape.items;
+                        name = singular(&name);
+                        continue;
+                    }
+                    _ => self.object(index.unwrap_or_default(), &name),
+                },
+                // Null alone, or a mix.
+                _ => return format!("{prefix}{}{suffix}", self.any()),
+            };
+            return match shape.null {
+                true => format!("{prefix}Option<{ty}>{suffix}"),
+                false => format!("{prefix}{ty}{suffix}"),
+            };
+        }
+    }
+
+    fn object(&mut self, index: usize, name: &str) -> String {
+        // The root's name was taken for it at the start.
+        if index == 0 {
+            self.queue.push_back((index, name.to_owned()));
+            return name.to_owned();
+        }
+        let mut unique = name.to_owned();
+        let mut n = 1;
+        while !self.names.insert(unique.clone()) {
+            n += 1;
+            unique = format!("{name}{n}");
+        }
+        self.queue.push_back((index, unique.clone()));
+        unique
+    }
+
+    fn any(&self) -> &'static str {
+        match self.opts.serde {
+            true => "serde_json::Value",
+            false => "jsonparser::OwnedValue",
+        }
+    }
+}
+
+// The words of a key: `userID`, `user_id` and `user-id` all have "user" and
+// "id".
+fn words(key: &str) -> Vec<String> {
+    let chars: Vec<char> = key.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        let starts = c.is_ascii_uppercase()
+            && (prev.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit())
+                || prev.is_some_and(|p| p.is_ascii_uppercase())
+                    && next.is_some_and(|n| n.is_ascii_lowercase()));
+        if starts && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c.to_ascii_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn field_name(key: &str) -> String {
+    let mut name = words(key).join("_");
+    if name.is_empty() {
+        name.push_str("field");
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    if UNRAWABLE.contains(&name.as_str()) {
+        name.push('_');
+    } else if KEYWORDS.contains(&name.as_str()) {
+        name.insert_str(0, "r#");
+    }
+    name
+}
+
+fn type_name(key: &str) -> String {
+    let mut name: String = words(key)
+        .iter()
+        .map(|word| word[..1].to_ascii_uppercase() + &word[1..])
+        .collect();
+    if name.is_empty() {
+        name.push_str("Type");
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, 'T');
+    }
+    name
+}
+
+// The name for an element of a list named `name`.
+fn singular(name: &str) -> String {
+    match name.strip_suffix('s') {
+        Some(stem) if !stem.is_empty() && !stem.ends_with('s') => stem.to_owned(),
+        _ => format!("{name}Item"),
+    }
+}
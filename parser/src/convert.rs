@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::TypeError,
+    owned::OwnedValue,
+    value::{JsonValue, Number},
+};
+
+impl JsonValue {
+    // For `TypeError`s.
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            JsonValue::String(_) => "a string",
+            JsonValue::Number(_) => "a number",
+            JsonValue::Bool(_) => "a bool",
+            JsonValue::Object(_) => "an object",
+            JsonValue::List(_) => "a list",
+            JsonValue::Raw(_) => "a raw value",
+            JsonValue::Null => "null",
+        }
+    }
+}
+
+impl OwnedValue {
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            OwnedValue::String(_) => "a string",
+            OwnedValue::Number(_) => "a number",
+            OwnedValue::Bool(_) => "a bool",
+            OwnedValue::Object(_) => "an object",
+            OwnedValue::List(_) => "a list",
+            OwnedValue::Raw(_) => "a raw value",
+            OwnedValue::Null => "null",
+        }
+    }
+}
+
+// Scalars convert to both value types and back. Containers only convert
+// to and from `OwnedValue`, since a `JsonValue` needs an arena for them.
+macro_rules! scalars {
+    ($($value:ident)*) => {$(
+        impl From<&str> for $value {
+            fn from(s: &str) -> Self {
+                $value::String(s.to_owned())
+            }
+        }
+
+        impl From<String> for $value {
+            fn from(s: String) -> Self {
+                $value::String(s)
+            }
+        }
+
+        impl From<bool> for $value {
+            fn from(b: bool) -> Self {
+                $value::Bool(b)
+            }
+        }
+
+        impl From<Number> for $value {
+            fn from(n: Number) -> Self {
+                $value::Number(n)
+            }
+        }
+
+        impl From<i64> for $value {
+            fn from(n: i64) -> Self {
+                $value::Number(n.into())
+            }
+        }
+
+        // What untyped integer literals default to.
+        impl From<i32> for $value {
+            fn from(n: i32) -> Self {
+                $value::Number(i64::from(n).into())
+            }
+        }
+
+        impl From<u64> for $value {
+            fn from(n: u64) -> Self {
+                $value::Number(n.into())
+            }
+        }
+
+        impl From<f64> for $value {
+            fn from(n: f64) -> Self {
+                $value::Number(n.into())
+            }
+        }
+
+        /// `None` is `null`.
+        impl<T: Into<$value>> From<Option<T>> for $value {
+            fn from(value: Option<T>) -> Self {
+                value.map_or($value::Null, Into::into)
+            }
+        }
+
+        impl TryFrom<$value> for String {
+            type Error = TypeError;
+
+            fn try_from(value: $value) -> Result<Self, TypeError> {
+                match value {
+                    $value::String(s) => Ok(s),
+                    other => Err(TypeError::new("a string", other.type_name())),
+                }
+            }
+        }
+
+        impl TryFrom<$value> for bool {
+            type Error = TypeError;
+
+            fn try_from(value: $value) -> Result<Self, TypeError> {
+                match value {
+                    $value::Bool(b) => Ok(b),
+                    other => Err(TypeError::new("a bool", other.type_name())),
+                }
+            }
+        }
+
+        impl TryFrom<$value> for Number {
+            type Error = TypeError;
+
+            fn try_from(value: $value) -> Result<Self, TypeError> {
+                match value {
+                    $value::Number(n) => Ok(n),
+                    other => Err(TypeError::new("a number", other.type_name())),
+                }
+            }
+        }
+
+        /// Integers only, and only those that fit.
+        impl TryFrom<$value> for i64 {
+            type Error = TypeError;
+
+            fn try_from(value: $value) -> Result<Self, TypeError> {
+                match &value {
+                    $value::Number(n) => n.as_i64(),
+                    _ => None,
+                }
+                .ok_or_else(|| TypeError::new("an i64", value.type_name()))
+            }
+        }
+
+        impl TryFrom<$value> for u64 {
+            type Error = TypeError;
+
+            fn try_from(value: $value) -> Result<Self, TypeError> {
+                match &value {
+                    $value::Number(n) => n.as_u64(),
+                    _ => None,
+                }
+                .ok_or_else(|| TypeError::new("a u64", value.type_name()))
+            }
+        }
+
+        /// Any number, rounding integers beyond 2^53.
+        impl TryFrom<$value> for f64 {
+            type Error = TypeError;
+
+            fn try_from(value: $value) -> Result<Self, TypeError> {
+                match value {
+                    $value::Number(n) => Ok(n.as_f64()),
+                    other => Err(TypeError::new("a number", other.type_name())),
+                }
+            }
+        }
+    )*};
+}
+
+scalars!(JsonValue OwnedValue);
+
+impl<T: Into<OwnedValue>> From<Vec<T>> for OwnedValue {
+    fn from(list: Vec<T>) -> Self {
+        OwnedValue::List(list.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<OwnedValue>> From<HashMap<String, T>> for OwnedValue {
+    fn from(map: HashMap<String, T>) -> Self {
+        OwnedValue::Object(map.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+}
+
+#[cfg(feature = "preserve-order")]
+impl<T: Into<OwnedValue>> From<crate::map::Map<String, T>> for OwnedValue {
+    fn from(map: crate::map::Map<String, T>) -> Self {
+        OwnedValue::Object(map.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+}
+
+impl<T: TryFrom<OwnedValue, Error = TypeError>> TryFrom<OwnedValue> for Vec<T> {
+    type Error = TypeError;
+
+    fn try_from(value: OwnedValue) -> Result<Self, TypeError> {
+        match value {
+            OwnedValue::List(list) => list.into_iter().map(T::try_from).collect(),
+            other => Err(TypeError::new("a list", other.type_name())),
+        }
+    }
+}
+
+impl<T: TryFrom<OwnedValue, Error = TypeError>> TryFrom<OwnedValue> for HashMap<String, T> {
+    type Error = TypeError;
+
+    fn try_from(value: OwnedValue) -> Result<Self, TypeError> {
+        match value {
+            OwnedValue::Object(obj) => obj
+                .into_iter()
+                .map(|(k, v)| Ok((k, T::try_from(v)?)))
+                .collect(),
+            other => Err(TypeError::new("an object", other.type_name())),
+        }
+    }
+}
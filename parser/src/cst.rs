@@ -0,0 +1,683 @@
+use std::{fmt, ops::Range, rc::Rc};
+
+use crate::{
+    canonical,
+    codec::ToJson,
+    doc::Document,
+    error::ParseError,
+    lex::{Lex, Token},
+    options::ParseOptions,
+    par::Par,
+    pointer,
+};
+
+/// What a `GreenNode` or `GreenToken` is. Nodes are `Root`, `Object`,
+/// `Array` and `Member`; everything else is a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CstKind {
+    /// The whole input: the value and the whitespace and comments around it.
+    Root,
+    Object,
+    Array,
+    /// A key, its colon and its value, with whatever lies between them.
+    Member,
+    Whitespace,
+    Comment,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Comma,
+    Colon,
+    String,
+    Number,
+    True,
+    False,
+    Null,
+    /// An unquoted JSON5 key.
+    Ident,
+}
+
+impl CstKind {
+    pub fn is_trivia(self) -> bool {
+        matches!(self, CstKind::Whitespace | CstKind::Comment)
+    }
+}
+
+/// A token exactly as it's written, quotes and escapes included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GreenToken {
+    kind: CstKind,
+    text: String,
+}
+
+impl GreenToken {
+    pub fn new(kind: CstKind, text: impl Into<String>) -> Self {
+        Self {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    pub fn kind(&self) -> CstKind {
+        self.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum GreenElement {
+    Node(Rc<GreenNode>),
+    Token(Rc<GreenToken>),
+}
+
+impl GreenElement {
+    pub fn kind(&self) -> CstKind {
+        match self {
+            GreenElement::Node(node) => node.kind,
+            GreenElement::Token(token) => token.kind,
+        }
+    }
+
+    /// The length of its text, in bytes.
+    pub fn width(&self) -> usize {
+        match self {
+            GreenElement::Node(node) => node.width,
+            GreenElement::Token(token) => token.text.len(),
+        }
+    }
+}
+
+/// An immutable piece of the tree that knows only its own text, so it can
+/// be shared between trees: an edit rebuilds the nodes above the change and
+/// reuses everything else.
+#[derive(Clone)]
+pub struct GreenNode {
+    kind: CstKind,
+    width: usize,
+    children: Vec<GreenElement>,
+}
+
+impl GreenNode {
+    pub fn new(kind: CstKind, children: Vec<GreenElement>) -> Self {
+        let width = children.iter().map(GreenElement::width).sum();
+        Self {
+            kind,
+            width,
+            children,
+        }
+    }
+
+    pub fn kind(&self) -> CstKind {
+        self.kind
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+
+    /// A copy with child `index` swapped for `child`.
+    pub fn replace_child(&self, index: usize, child: GreenElement) -> GreenNode {
+        let mut children = self.children.clone();
+        children[index] = child;
+        GreenNode::new(self.kind, children)
+    }
+}
+
+// Children are dropped from a stack, so deep trees can't overflow it.
+impl Drop for GreenNode {
+    fn drop(&mut self) {
+        let mut stack = std::mem::take(&mut self.children);
+        while let Some(element) = stack.pop() {
+            if let GreenElement::Node(node) = element {
+                if let Ok(mut node) = Rc::try_unwrap(node) {
+                    stack.append(&mut node.children);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for GreenNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GreenNode")
+            .field("kind", &self.kind)
+            .field("width", &self.width)
+            .field("children", &self.children.len())
+            .finish()
+    }
+}
+
+/// The node's source text, exactly as it was parsed.
+impl fmt::Display for GreenNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut stack = vec![self.children.iter()];
+        while let Some(children) = stack.last_mut() {
+            match children.next() {
+                Some(GreenElement::Node(node)) => stack.push(node.children.iter()),
+                Some(GreenElement::Token(token)) => f.write_str(&token.text)?,
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A `GreenNode` seen from the root: where it starts in the text and which
+/// node holds it.
+#[derive(Clone)]
+pub struct SyntaxNode {
+    green: Rc<GreenNode>,
+    offset: usize,
+    parent: Option<Rc<SyntaxNode>>,
+    // Which of the parent's children this is.
+    index: usize,
+}
+
+impl SyntaxNode {
+    pub fn new_root(green: Rc<GreenNode>) -> Self {
+        Self {
+            green,
+            offset: 0,
+            parent: None,
+            index: 0,
+        }
+    }
+
+    pub fn kind(&self) -> CstKind {
+        self.green.kind
+    }
+
+    pub fn green(&self) -> &Rc<GreenNode> {
+        &self.green
+    }
+
+    pub fn parent(&self) -> Option<&SyntaxNode> {
+        self.parent.as_deref()
+    }
+
+    /// Where the node's text is, in bytes from the start of the root's.
+    pub fn text_range(&self) -> Range<usize> {
+        self.offset..self.offset + self.green.width
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = SyntaxElement> {
+        let parent = Rc::new(self.clone());
+        let mut offset = self.offset;
+        (0..self.green.children.len()).map(move |index| {
+            let start = offset;
+            let child = &parent.green.children[index];
+            offset += child.width();
+            match child {
+                GreenElement::Node(green) => SyntaxElement::Node(SyntaxNode {
+                    green: green.clone(),
+                    offset: start,
+                    parent: Some(parent.clone()),
+                    index,
+                }),
+                GreenElement::Token(green) => SyntaxElement::Token(SyntaxToken {
+                    green: green.clone(),
+                    offset: start,
+                    parent: parent.clone(),
+                    index,
+                }),
+            }
+        })
+    }
+
+    /// The children that aren't whitespace or comments.
+    pub fn significant_children(&self) -> impl Iterator<Item = SyntaxElement> {
+        self.children().filter(|child| !child.kind().is_trivia())
+    }
+
+    /// The root of a tree that is this one with `green` in this node's
+    /// place. Only the nodes above it are rebuilt.
+    pub fn replace_with(&self, green: GreenNode) -> GreenNode {
+        replace(
+            self.parent.as_deref(),
+            self.index,
+            GreenElement::Node(Rc::new(green)),
+        )
+    }
+}
+
+// Red nodes hold their parents, so drop those from a loop too.
+impl Drop for SyntaxNode {
+    fn drop(&mut self) {
+        let mut parent = self.parent.take();
+        while let Some(node) = parent {
+            parent = match Rc::try_unwrap(node) {
+                Ok(mut node) => node.parent.take(),
+                Err(_) => None,
+            };
+        }
+    }
+}
+
+impl fmt::Debug for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}@{:?}", self.kind(), self.text_range())
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.green.fmt(f)
+    }
+}
+
+#[derive(Clone)]
+pub struct SyntaxToken {
+    green: Rc<GreenToken>,
+    offset: usize,
+    parent: Rc<SyntaxNode>,
+    index: usize,
+}
+
+impl SyntaxToken {
+    pub fn kind(&self) -> CstKind {
+        self.green.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.green.text
+    }
+
+    pub fn parent(&self) -> &SyntaxNode {
+        &self.parent
+    }
+
+    pub fn text_range(&self) -> Range<usize> {
+        self.offset..self.offset + self.green.text.len()
+    }
+
+    /// The root of a tree that is this one with `green` in this token's
+    /// place.
+    pub fn replace_with(&self, green: GreenToken) -> GreenNode {
+        replace(
+            Some(&self.parent),
+            self.index,
+            GreenElement::Token(Rc::new(green)),
+        )
+    }
+}
+
+impl fmt::Debug for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?}@{:?} {:?}",
+            self.kind(),
+            self.text_range(),
+            self.text()
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxElement {
+    pub fn kind(&self) -> CstKind {
+        match self {
+            SyntaxElement::Node(node) => node.kind(),
+            SyntaxElement::Token(token) => token.kind(),
+        }
+    }
+
+    pub fn text_range(&self) -> Range<usize> {
+        match self {
+            SyntaxElement::Node(node) => node.text_range(),
+            SyntaxElement::Token(token) => token.text_range(),
+        }
+    }
+
+    pub fn as_node(&self) -> Option<&SyntaxNode> {
+        match self {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        }
+    }
+
+    pub fn as_token(&self) -> Option<&SyntaxToken> {
+        match self {
+            SyntaxElement::Node(_) => None,
+            SyntaxElement::Token(token) => Some(token),
+        }
+    }
+
+    /// The root of a tree that is this one with `green` in this element's
+    /// place, whether that's a node or a token.
+    pub fn replace_with(&self, green: GreenElement) -> GreenNode {
+        match self {
+            SyntaxElement::Node(node) => replace(node.parent.as_deref(), node.index, green),
+            SyntaxElement::Token(token) => replace(Some(&token.parent), token.index, green),
+        }
+    }
+}
+
+// Puts `element` at `index` in `parent` and rebuilds every node above it. A
+// root node being replaced has no parent and is its own new root.
+fn replace(parent: Option<&SyntaxNode>, index: usize, element: GreenElement) -> GreenNode {
+    let Some(mut parent) = parent else {
+        match element {
+            GreenElement::Node(node) => return Rc::unwrap_or_clone(node),
+            GreenElement::Token(_) => unreachable!("tokens always have a parent"),
+        }
+    };
+    let mut green = parent.green.replace_child(index, element);
+    while let Some(above) = parent.parent.as_deref() {
+        green = above
+            .green
+            .replace_child(parent.index, GreenElement::Node(Rc::new(green)));
+        parent = above;
+    }
+    green
+}
+
+/// A lossless syntax tree of a document, every byte of whitespace and every
+/// comment included, next to the `Document` its values parse to.
+pub struct Cst {
+    root: SyntaxNode,
+    doc: Document,
+    opts: ParseOptions,
+}
+
+impl Cst {
+    pub fn root(&self) -> &SyntaxNode {
+        &self.root
+    }
+
+    pub fn document(&self) -> &Document {
+        &self.doc
+    }
+
+    pub fn into_parts(self) -> (SyntaxNode, Document) {
+        (self.root, self.doc)
+    }
+
+    /// The value an RFC 6901 JSON Pointer leads to, as a node for a
+    /// container and a token for anything else.
+    pub fn pointer(&self, pointer: &str) -> Option<SyntaxElement> {
+        let mut at = self.root.significant_children().next()?;
+        for token in pointer::tokens(pointer)? {
+            let node = at.as_node()?;
+            at = match node.kind() {
+                CstKind::Object => value_of(&member(node, &token, self.opts)?)?,
+                CstKind::Array => elements(node).nth(pointer::index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(at)
+    }
+
+    /// Puts `value` where `pointer` leads, as `Document::set_pointer` does,
+    /// by editing the text: every comment and all the formatting outside of
+    /// the value stay as they are. The value is written compactly. A new
+    /// member or element goes after the last one, indented the same way.
+    /// Returns `false`, changing nothing, where `Document::set_pointer`
+    /// would.
+    pub fn set_pointer(&mut self, pointer: &str, value: impl ToJson) -> bool {
+        let Some(root) = self.edit(pointer, &value) else {
+            return false;
+        };
+        // The text changed in one place, so the document is parsed again
+        // rather than patched.
+        let cst = parse(&root.to_string(), self.opts).expect("edits keep the text valid");
+        *self = cst;
+        true
+    }
+
+    fn edit(&self, pointer: &str, value: &impl ToJson) -> Option<GreenNode> {
+        let text = Document::encode(value).to_string();
+        let value = parse(&text, ParseOptions::default()).ok()?;
+        let value = value.root.green.children[0].clone();
+        let Some((parent, last)) = pointer::split_last(pointer) else {
+            return match pointer {
+                "" => Some(self.pointer("")?.replace_with(value)),
+                _ => None,
+            };
+        };
+        let parent = self.pointer(parent)?;
+        let parent = parent.as_node()?;
+        match parent.kind() {
+            CstKind::Object => match member(parent, &last, self.opts) {
+                Some(member) => Some(value_of(&member)?.replace_with(value)),
+                None => Some(append(parent, new_member(parent, &last, value))),
+            },
+            CstKind::Array => {
+                let len = elements(parent).count();
+                match pointer::index(&last) {
+                    Some(i) if i < len => Some(elements(parent).nth(i)?.replace_with(value)),
+                    Some(i) if i == len => Some(append(parent, value)),
+                    None if last == "-" => Some(append(parent, value)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The source text, byte for byte.
+impl fmt::Display for Cst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.root.fmt(f)
+    }
+}
+
+impl fmt::Debug for Cst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cst")
+            .field("root", &self.root)
+            .field("doc", &self.doc)
+            .finish()
+    }
+}
+
+// The parser checks the input and builds the document; the tree is then
+// built from the lexer's tokens, with the text between them as trivia.
+pub(crate) fn parse(src: &str, opts: ParseOptions) -> Result<Cst, ParseError> {
+    let doc = Par::parse_value(src, 1 << 4, opts)?;
+    let mut lex = Lex::with_options(src, opts);
+    let mut stack = vec![(CstKind::Root, Vec::new())];
+    let mut end = 0;
+    loop {
+        let tok = lex.skim_token()?;
+        let children = &mut top(&mut stack).1;
+        trivia(&src[end..tok.span.start], children);
+        let kind = match tok.token {
+            Token::Eof => break,
+            Token::Str(_) => CstKind::String,
+            Token::Num(_) => CstKind::Number,
+            Token::True => CstKind::True,
+            Token::False => CstKind::False,
+            Token::Null => CstKind::Null,
+            Token::LBrace => CstKind::LBrace,
+            Token::RBrace => CstKind::RBrace,
+            Token::LBracket => CstKind::LBracket,
+            Token::RBracket => CstKind::RBracket,
+            Token::Comma => CstKind::Comma,
+            Token::Colon => CstKind::Colon,
+            Token::Ident(_) => CstKind::Ident,
+        };
+        end = tok.span.end;
+        let token = GreenElement::Token(Rc::new(GreenToken::new(kind, &src[tok.span.start..end])));
+        match kind {
+            CstKind::LBrace => stack.push((CstKind::Object, vec![token])),
+            CstKind::LBracket => stack.push((CstKind::Array, vec![token])),
+            CstKind::RBrace | CstKind::RBracket => {
+                let (kind, mut children) = stack.pop().expect("an open container");
+                children.push(token);
+                value(
+                    &mut stack,
+                    GreenElement::Node(Rc::new(GreenNode::new(kind, children))),
+                );
+            }
+            CstKind::String | CstKind::Ident if top(&mut stack).0 == CstKind::Object => {
+                stack.push((CstKind::Member, vec![token]));
+            }
+            CstKind::Comma | CstKind::Colon => top(&mut stack).1.push(token),
+            _ => value(&mut stack, token),
+        }
+    }
+    let (kind, children) = stack.pop().expect("the root");
+    let root = SyntaxNode::new_root(Rc::new(GreenNode::new(kind, children)));
+    Ok(Cst { root, doc, opts })
+}
+
+// The last member with `key`, which is the one the document keeps unless
+// duplicates are an error.
+fn member(object: &SyntaxNode, key: &str, opts: ParseOptions) -> Option<SyntaxNode> {
+    object
+        .children()
+        .filter_map(|child| match child {
+            SyntaxElement::Node(node) if node.kind() == CstKind::Member => Some(node),
+            _ => None,
+        })
+        .filter(|member| {
+            let Some(SyntaxElement::Token(name)) = member.children().next() else {
+                return false;
+            };
+            match name.kind() {
+                CstKind::Ident => name.text() == key,
+                _ => match Lex::with_options(name.text(), opts).next_token() {
+                    Ok(tok) => tok.token == Token::Str(key.to_owned()),
+                    Err(_) => false,
+                },
+            }
+        })
+        .last()
+}
+
+fn value_of(member: &SyntaxNode) -> Option<SyntaxElement> {
+    member.significant_children().last()
+}
+
+fn elements(array: &SyntaxNode) -> impl Iterator<Item = SyntaxElement> {
+    array.significant_children().filter(|child| {
+        !matches!(
+            child.kind(),
+            CstKind::LBracket | CstKind::RBracket | CstKind::Comma
+        )
+    })
+}
+
+fn token(kind: CstKind, text: &str) -> GreenElement {
+    GreenElement::Token(Rc::new(GreenToken::new(kind, text)))
+}
+
+// A member for `object`, spaced after its colon like its last member is.
+fn new_member(object: &SyntaxNode, key: &str, value: GreenElement) -> GreenElement {
+    let mut name = String::new();
+    canonical::string(key, false, &mut name).expect("writing to a string can't fail");
+    let space = object
+        .green
+        .children
+        .iter()
+        .rev()
+        .find_map(|child| match child {
+            GreenElement::Node(member) if member.kind == CstKind::Member => {
+                let colon = member
+                    .children
+                    .iter()
+                    .position(|c| c.kind() == CstKind::Colon)?;
+                Some(member.children.get(colon + 1).cloned())
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| Some(token(CstKind::Whitespace, " ")))
+        .filter(|after| after.kind() == CstKind::Whitespace);
+    let mut children = vec![token(CstKind::String, &name), token(CstKind::Colon, ":")];
+    children.extend(space);
+    children.push(value);
+    GreenElement::Node(Rc::new(GreenNode::new(CstKind::Member, children)))
+}
+
+// Adds `element` after the last member or element of `container`, behind a
+// comma and the same whitespace that comes before that last one. A trailing
+// comma stays trailing.
+fn append(container: &SyntaxNode, element: GreenElement) -> GreenNode {
+    let children = &container.green.children;
+    let significant = |end: usize| children[..end].iter().rposition(|c| !c.kind().is_trivia());
+    let close = children.len() - 1;
+    let last = significant(close).expect("a container starts with a bracket");
+    let mut added = Vec::new();
+    let at = match children[last].kind() {
+        CstKind::LBrace | CstKind::LBracket => {
+            added.push(element);
+            last + 1
+        }
+        kind => {
+            let trailing = kind == CstKind::Comma;
+            let previous = match trailing {
+                true => significant(last).expect("a comma follows a value"),
+                false => last,
+            };
+            if !trailing {
+                added.push(token(CstKind::Comma, ","));
+            }
+            added.extend(
+                previous
+                    .checked_sub(1)
+                    .map(|i| &children[i])
+                    .filter(|c| c.kind() == CstKind::Whitespace)
+                    .cloned(),
+            );
+            added.push(element);
+            if trailing {
+                added.push(token(CstKind::Comma, ","));
+            }
+            last + 1
+        }
+    };
+    let mut children = children.clone();
+    children.splice(at..at, added);
+    container.replace_with(GreenNode::new(container.kind(), children))
+}
+
+fn top(stack: &mut [(CstKind, Vec<GreenElement>)]) -> &mut (CstKind, Vec<GreenElement>) {
+    stack.last_mut().expect("the root is never popped early")
+}
+
+// Adds a whole value, which completes a member it's the value of.
+fn value(stack: &mut Vec<(CstKind, Vec<GreenElement>)>, element: GreenElement) {
+    top(stack).1.push(element);
+    if top(stack).0 == CstKind::Member {
+        let (kind, children) = stack.pop().expect("the member");
+        let member = GreenNode::new(kind, children);
+        top(stack).1.push(GreenElement::Node(Rc::new(member)));
+    }
+}
+
+// Splits what the lexer skipped into whitespace and comments. A byte order
+// mark counts as whitespace.
+fn trivia(mut text: &str, out: &mut Vec<GreenElement>) {
+    while !text.is_empty() {
+        let (kind, len) = if text.starts_with("//") {
+            (CstKind::Comment, text.find('\n').unwrap_or(text.len()))
+        } else if let Some(body) = text.strip_prefix("/*") {
+            (
+                CstKind::Comment,
+                body.find("*/").map_or(text.len(), |i| i + 4),
+            )
+        } else {
+            (CstKind::Whitespace, text.find('/').unwrap_or(text.len()))
+        };
+        let (token, rest) = text.split_at(len);
+        out.push(GreenElement::Token(Rc::new(GreenToken::new(kind, token))));
+        text = rest;
+    }
+}
@@ -0,0 +1,264 @@
+use std::{collections::HashSet, io};
+
+use crate::{doc::Document, error::CsvError, key::Key, map::Map, value::JsonValue};
+
+/// How `Document::to_csv` writes records, and `Document::from_csv` reads
+/// them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    /// The text of a `null` cell. A missing member is always an empty cell.
+    pub null: String,
+    /// The columns to write, in order. By default they are every key of
+    /// every record, in the order first seen, which is only the document's
+    /// order with `preserve-order`. Reading always takes the header row.
+    pub columns: Option<Vec<String>>,
+    /// Read unquoted numbers and booleans as such, rather than as strings.
+    pub typed: bool,
+}
+
+/// Commas, empty nulls and typed cells.
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            null: String::new(),
+            columns: None,
+            typed: true,
+        }
+    }
+}
+
+impl Document {
+    /// Writes a list of objects as CSV, one row for each, under a header of
+    /// their keys. Nested objects and lists go in a cell as JSON. Strings
+    /// that would read back as something else, like `"1"` or the text of
+    /// `null`, are quoted, and read back as strings.
+    pub fn to_csv(&self, mut writer: impl io::Write, opts: &CsvOptions) -> Result<(), CsvError> {
+        let records = self.value();
+        let list = records
+            .as_array()
+            .ok_or(CsvError::NotRecords { index: None })?;
+        let mut rows = Vec::with_capacity(list.len());
+        for (index, &id) in list.iter().enumerate() {
+            let record = records.child(id).as_object();
+            rows.push(record.ok_or(CsvError::NotRecords { index: Some(index) })?);
+        }
+        let columns = match &opts.columns {
+            Some(columns) => columns.clone(),
+            None => {
+                let mut seen = HashSet::new();
+                let mut columns = Vec::new();
+                for row in &rows {
+                    for key in row.keys() {
+                        if seen.insert(key) {
+                            columns.push(key.to_string());
+                        }
+                    }
+                }
+                columns
+            }
+        };
+        let mut line = String::new();
+        for (i, column) in columns.iter().enumerate() {
+            if i > 0 {
+                line.push(opts.delimiter);
+            }
+            push_cell(&mut line, column, opts, false);
+        }
+        line.push('\n');
+        writer.write_all(line.as_bytes())?;
+        for row in rows {
+            line.clear();
+            for (i, column) in columns.iter().enumerate() {
+                if i > 0 {
+                    line.push(opts.delimiter);
+                }
+                let Some(&id) = row.get(column.as_str()) else {
+                    continue;
+                };
+                let value = records.child(id);
+                match value.value() {
+                    JsonValue::Null => push_cell(&mut line, &opts.null, opts, false),
+                    JsonValue::String(s) => {
+                        let quote = *s == opts.null || scalar(s).is_some();
+                        push_cell(&mut line, s, opts, quote);
+                    }
+                    _ => push_cell(&mut line, &value.to_string(), opts, false),
+                }
+            }
+            line.push('\n');
+            writer.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Reads CSV with a header row into a list of objects, one for each
+    /// row, with the columns as keys. Cells left empty by a short row are
+    /// missing from its object.
+    pub fn from_csv(src: &str, opts: &CsvOptions) -> Result<Document, CsvError> {
+        let mut doc = Document::default();
+        let mut reader = Reader {
+            src,
+            pos: 0,
+            line: 1,
+            row_line: 1,
+            delimiter: opts.delimiter,
+        };
+        let Some(header) = reader.row()? else {
+            *doc.root_mut() = JsonValue::List(Vec::new());
+            return Ok(doc);
+        };
+        let header: Vec<Key> = header.into_iter().map(|(cell, _)| cell.into()).collect();
+        let mut records = Vec::new();
+        while let Some(row) = reader.row()? {
+            if row.len() > header.len() {
+                return Err(CsvError::Syntax {
+                    line: reader.row_line,
+                    message: "more cells than the header has",
+                });
+            }
+            let mut record = Map::new();
+            for ((cell, quoted), key) in row.into_iter().zip(&header) {
+                let value = match quoted {
+                    false if cell == opts.null => JsonValue::Null,
+                    false if opts.typed => scalar(&cell).unwrap_or(JsonValue::String(cell)),
+                    _ => JsonValue::String(cell),
+                };
+                record.insert(key.clone(), doc.alloc(value));
+            }
+            records.push(doc.alloc(JsonValue::Object(record)));
+        }
+        *doc.root_mut() = JsonValue::List(records);
+        Ok(doc)
+    }
+}
+
+// The number or boolean `cell` reads as.
+fn scalar(cell: &str) -> Option<JsonValue> {
+    match cell {
+        "true" => Some(JsonValue::Bool(true)),
+        "false" => Some(JsonValue::Bool(false)),
+        _ if cell.starts_with(|c: char| c == '-' || c.is_ascii_digit()) => {
+            match crate::parse_value(cell).ok()?.root() {
+                // `Number` is only `Copy` without `arbitrary-precision`.
+                #[allow(clippy::clone_on_copy)]
+                JsonValue::Number(n) => Some(JsonValue::Number(n.clone())),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn push_cell(line: &mut String, cell: &str, opts: &CsvOptions, quote: bool) {
+    let quote = quote || cell.contains([opts.delimiter, '"', '\n', '\r']);
+    if !quote {
+        return line.push_str(cell);
+    }
+    line.push('"');
+    line.push_str(&cell.replace('"', "\"\""));
+    line.push('"');
+}
+
+struct Reader<'s> {
+    src: &'s str,
+    pos: usize,
+    line: usize,
+    // Where the last row started.
+    row_line: usize,
+    delimiter: char,
+}
+
+impl Reader<'_> {
+    // The next row's cells, each with whether it was quoted, skipping blank
+    // lines.
+    fn row(&mut self) -> Result<Option<Vec<(String, bool)>>, CsvError> {
+        loop {
+            let rest = &self.src[self.pos..];
+            if rest.is_empty() {
+                return Ok(None);
+            }
+            let blank = rest
+                .strip_prefix("\r\n")
+                .or_else(|| rest.strip_prefix('\n'));
+            match blank {
+                Some(after) => {
+                    self.pos = self.src.len() - after.len();
+                    self.line += 1;
+                }
+                None => break,
+            }
+        }
+        self.row_line = self.line;
+        let mut cells = Vec::new();
+        loop {
+            let (cell, quoted) = self.cell()?;
+            cells.push((cell, quoted));
+            let mut chars = self.src[self.pos..].chars();
+            match chars.next() {
+                Some(c) if c == self.delimiter => self.pos += c.len_utf8(),
+                Some('\r') if chars.next() == Some('\n') => {
+                    self.pos += 2;
+                    self.line += 1;
+                    return Ok(Some(cells));
+                }
+                Some('\n') => {
+                    self.pos += 1;
+                    self.line += 1;
+                    return Ok(Some(cells));
+                }
+                None => return Ok(Some(cells)),
+                Some(_) => {
+                    return Err(CsvError::Syntax {
+                        line: self.line,
+                        message: "text after a quoted cell",
+                    })
+                }
+            }
+        }
+    }
+
+    fn cell(&mut self) -> Result<(String, bool), CsvError> {
+        let rest = &self.src[self.pos..];
+        let Some(quoted) = rest.strip_prefix('"') else {
+            let end = rest
+                .find([self.delimiter, '\n', '\r'])
+                .unwrap_or(rest.len());
+            let cell = &rest[..end];
+            if cell.contains('"') {
+                return Err(CsvError::Syntax {
+                    line: self.line,
+                    message: "a quote inside an unquoted cell",
+                });
+            }
+            self.pos += end;
+            return Ok((cell.to_owned(), false));
+        };
+        let start = self.line;
+        let mut cell = String::new();
+        let mut chars = quoted.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' if quoted[i + 1..].starts_with('"') => {
+                    cell.push('"');
+                    chars.next();
+                }
+                '"' => {
+                    self.pos += 1 + i + 1;
+                    return Ok((cell, true));
+                }
+                c => {
+                    if c == '\n' {
+                        self.line += 1;
+                    }
+                    cell.push(c);
+                }
+            }
+        }
+        Err(CsvError::Syntax {
+            line: start,
+            message: "an unterminated quoted cell",
+        })
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    alloc::Id,
+    codec::ToJson,
+    doc::{Document, ValueRef},
+    eq::EqOptions,
+    key::Key,
+    map::Map,
+    pointer,
+    value::JsonValue,
+};
+
+// Past this many pairs of elements, the middle of two lists isn't searched
+// for a longest common subsequence; only equal ends and moves are found.
+const MAX_LCS: usize = 1 << 20;
+
+enum Op<'doc> {
+    Add(String, ValueRef<'doc>),
+    Remove(String),
+    Replace(String, ValueRef<'doc>),
+    Move { from: String, path: String },
+}
+
+// Pairs of values still to compare, at their pointer in the patched tree.
+type Work<'a, 'b> = Vec<(String, ValueRef<'a>, ValueRef<'b>)>;
+
+pub(crate) fn diff(a: ValueRef<'_>, b: ValueRef<'_>) -> Document {
+    let mut ops = Vec::new();
+    let mut stack = vec![(String::new(), a, b)];
+    while let Some((path, a, b)) = stack.pop() {
+        let start = stack.len();
+        match (a.value(), b.value()) {
+            (JsonValue::Object(x), JsonValue::Object(y)) => {
+                objects(&path, a, b, x, y, &mut ops, &mut stack);
+            }
+            (JsonValue::List(x), JsonValue::List(y)) => {
+                lists(&path, a, b, x, y, &mut ops, &mut stack);
+            }
+            _ if same(a, b) => {}
+            _ => ops.push(Op::Replace(path, b)),
+        }
+        // Children in order, for a patch that reads top to bottom.
+        stack[start..].reverse();
+    }
+    build(ops)
+}
+
+/// How one value differs from another at a path, in `semantic_diff`.
+#[derive(Debug, Clone, Copy)]
+pub enum Change<'doc> {
+    Added(ValueRef<'doc>),
+    Removed(ValueRef<'doc>),
+    Modified {
+        old: ValueRef<'doc>,
+        new: ValueRef<'doc>,
+    },
+}
+
+/// How `Document::semantic_diff_with` compares values.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DiffOptions {
+    /// How far apart two numbers may be and still count as the same, for
+    /// floats that went through a rounding. Numbers are always compared by
+    /// value, so `1` and `1.0` are the same.
+    pub epsilon: f64,
+}
+
+impl Document {
+    /// What changed from this document to `other`, for people to read: every
+    /// member or element added or removed, and every value that differs,
+    /// each at its JSON Pointer. Key order doesn't matter, lists are compared
+    /// position by position, and objects and lists are only `Modified` when
+    /// they aren't both objects or both lists.
+    pub fn semantic_diff<'doc>(&'doc self, other: &'doc Document) -> Vec<(String, Change<'doc>)> {
+        self.semantic_diff_with(other, DiffOptions::default())
+    }
+
+    pub fn semantic_diff_with<'doc>(
+        &'doc self,
+        other: &'doc Document,
+        opts: DiffOptions,
+    ) -> Vec<(String, Change<'doc>)> {
+        let by_value = EqOptions {
+            numbers_by_value: true,
+        };
+        let mut changes = Vec::new();
+        let mut stack = vec![(String::new(), self.value(), other.value())];
+        while let Some((path, old, new)) = stack.pop() {
+            let start = stack.len();
+            match (old.value(), new.value()) {
+                (JsonValue::Object(x), JsonValue::Object(y)) => {
+                    let mut keys: Vec<_> = x
+                        .keys()
+                        .chain(y.keys().filter(|&k| !x.contains_key(k)))
+                        .collect();
+                    keys.sort();
+                    for key in keys {
+                        let path = child(&path, key);
+                        match (old.get(key), new.get(key)) {
+                            (Some(old), Some(new)) => stack.push((path, old, new)),
+                            (Some(old), None) => changes.push((path, Change::Removed(old))),
+                            (None, Some(new)) => changes.push((path, Change::Added(new))),
+                            (None, None) => unreachable!(),
+                        }
+                    }
+                }
+                (JsonValue::List(x), JsonValue::List(y)) => {
+                    for i in 0..x.len().max(y.len()) {
+                        let path = child(&path, i);
+                        match (old.get_index(i), new.get_index(i)) {
+                            (Some(old), Some(new)) => stack.push((path, old, new)),
+                            (Some(old), None) => changes.push((path, Change::Removed(old))),
+                            (None, Some(new)) => changes.push((path, Change::Added(new))),
+                            (None, None) => unreachable!(),
+                        }
+                    }
+                }
+                (JsonValue::Number(x), JsonValue::Number(y))
+                    if opts.epsilon > 0.0 && (x.as_f64() - y.as_f64()).abs() <= opts.epsilon => {}
+                _ if old.deep_eq_with(new, by_value) => {}
+                _ => changes.push((path, Change::Modified { old, new })),
+            }
+            stack[start..].reverse();
+        }
+        changes
+    }
+}
+
+fn same(a: ValueRef<'_>, b: ValueRef<'_>) -> bool {
+    a.deep_eq_with(b, EqOptions::default())
+}
+
+// Objects change into objects and lists into lists; anything else is
+// replaced.
+fn kind(value: ValueRef<'_>) -> u8 {
+    match value.value() {
+        JsonValue::Object(_) => 0,
+        JsonValue::List(_) => 1,
+        _ => 2,
+    }
+}
+
+fn child(path: &str, token: impl std::fmt::Display) -> String {
+    format!("{path}/{}", pointer::escape(&token.to_string()))
+}
+
+fn objects<'a, 'b>(
+    path: &str,
+    a: ValueRef<'a>,
+    b: ValueRef<'b>,
+    x: &'a Map<Key, Id<JsonValue>>,
+    y: &'b Map<Key, Id<JsonValue>>,
+    ops: &mut Vec<Op<'b>>,
+    stack: &mut Work<'a, 'b>,
+) {
+    let mut removed: Vec<_> = x.keys().filter(|&k| !y.contains_key(k)).collect();
+    let mut added: Vec<_> = y.keys().filter(|&k| !x.contains_key(k)).collect();
+    let mut common: Vec<_> = y.keys().filter(|&k| x.contains_key(k)).collect();
+    removed.sort();
+    added.sort();
+    common.sort();
+    // A member that went away and came back under another key moves.
+    let mut gone: HashMap<u64, Vec<&Key>> = HashMap::new();
+    for &key in &removed {
+        let hash = a.child(x[key]).canonical_hash();
+        gone.entry(hash).or_default().push(key);
+    }
+    let mut moved = HashSet::new();
+    added.retain(|&key| {
+        let value = b.child(y[key]);
+        let Some(candidates) = gone.get_mut(&value.canonical_hash()) else {
+            return true;
+        };
+        let Some(i) = candidates.iter().position(|&k| same(a.child(x[k]), value)) else {
+            return true;
+        };
+        let from = candidates.remove(i);
+        moved.insert(from);
+        ops.push(Op::Move {
+            from: child(path, from),
+            path: child(path, key),
+        });
+        false
+    });
+    for key in removed {
+        if !moved.contains(key) {
+            ops.push(Op::Remove(child(path, key)));
+        }
+    }
+    for key in added {
+        ops.push(Op::Add(child(path, key), b.child(y[key])));
+    }
+    for key in common {
+        stack.push((child(path, key), a.child(x[key]), b.child(y[key])));
+    }
+}
+
+fn lists<'a, 'b>(
+    path: &str,
+    a: ValueRef<'a>,
+    b: ValueRef<'b>,
+    x: &[Id<JsonValue>],
+    y: &[Id<JsonValue>],
+    ops: &mut Vec<Op<'b>>,
+    stack: &mut Work<'a, 'b>,
+) {
+    let (n, m) = (x.len(), y.len());
+    let hx: Vec<_> = x.iter().map(|&id| a.child(id).canonical_hash()).collect();
+    let hy: Vec<_> = y.iter().map(|&id| b.child(id).canonical_hash()).collect();
+    let eq = |i: usize, j: usize| hx[i] == hy[j] && same(a.child(x[i]), b.child(y[j]));
+
+    // Where each element of `x` ends up in `y`, if it stays.
+    let mut target = vec![None; n];
+    let mut kept = vec![false; m];
+    let anchors = common_subsequence(n, m, &eq);
+    for &(i, j) in &anchors {
+        target[i] = Some(j);
+        kept[j] = true;
+    }
+    // Elements that are only somewhere else now move there.
+    let mut gone: HashMap<u64, Vec<usize>> = HashMap::new();
+    for i in (0..n).filter(|&i| target[i].is_none()) {
+        gone.entry(hx[i]).or_default().push(i);
+    }
+    let mut moved = vec![false; n];
+    let new: Vec<_> = (0..m).filter(|&j| !kept[j]).collect();
+    for j in new {
+        let Some(candidates) = gone.get_mut(&hy[j]) else {
+            continue;
+        };
+        if let Some(k) = candidates.iter().position(|&i| eq(i, j)) {
+            let i = candidates.remove(k);
+            target[i] = Some(j);
+            moved[i] = true;
+            kept[j] = true;
+        }
+    }
+    // Between two anchors, what's left of each side is changed in place where
+    // the kinds of value match up, in order, and the rest removed or added.
+    let mut changed = Vec::new();
+    let mut ends = anchors.clone();
+    ends.push((n, m));
+    let (mut i0, mut j0) = (0, 0);
+    for (i1, j1) in ends {
+        let to: Vec<_> = (j0..j1).filter(|&j| !kept[j]).collect();
+        let mut rest = &to[..];
+        let from: Vec<_> = (i0..i1).filter(|&i| target[i].is_none()).collect();
+        for i in from {
+            let want = kind(a.child(x[i]));
+            let Some(k) = rest.iter().position(|&j| kind(b.child(y[j])) == want) else {
+                continue;
+            };
+            let j = rest[k];
+            rest = &rest[k + 1..];
+            target[i] = Some(j);
+            kept[j] = true;
+            changed.push((i, j));
+        }
+        (i0, j0) = (i1 + 1, j1 + 1);
+    }
+
+    // Play the operations out on the order of `x`'s elements, so each one's
+    // indices are right for the list as the ones before left it.
+    let mut order: Vec<usize> = (0..n).collect();
+    for i in (0..n).rev() {
+        if target[i].is_none() {
+            ops.push(Op::Remove(child(path, i)));
+            order.remove(i);
+        }
+    }
+    let mut placed: Vec<bool> = moved.iter().map(|&moved| !moved).collect();
+    let mut moves: Vec<_> = (0..n).filter(|&i| moved[i]).collect();
+    moves.sort_by_key(|&i| target[i]);
+    for i in moves {
+        let j = target[i];
+        let from = order.iter().position(|&k| k == i).unwrap_or_default();
+        order.remove(from);
+        // Right after the last element already in place that comes before
+        // it; those are in order, and the others will move.
+        let to = order
+            .iter()
+            .rposition(|&k| placed[k] && target[k] < j)
+            .map_or(0, |p| p + 1);
+        order.insert(to, i);
+        placed[i] = true;
+        if from != to {
+            ops.push(Op::Move {
+                from: child(path, from),
+                path: child(path, to),
+            });
+        }
+    }
+    for j in (0..m).filter(|&j| !kept[j]) {
+        ops.push(Op::Add(child(path, j), b.child(y[j])));
+    }
+    for (i, j) in changed {
+        stack.push((child(path, j), a.child(x[i]), b.child(y[j])));
+    }
+}
+
+// The pairs of equal elements, in order on both sides, that make up a longest
+// common subsequence, or just the equal ends when the middle is too big to
+// search.
+fn common_subsequence(
+    n: usize,
+    m: usize,
+    eq: &impl Fn(usize, usize) -> bool,
+) -> Vec<(usize, usize)> {
+    let mut start = 0;
+    while start < n.min(m) && eq(start, start) {
+        start += 1;
+    }
+    let mut end = 0;
+    while end < n.min(m) - start && eq(n - 1 - end, m - 1 - end) {
+        end += 1;
+    }
+    let mut pairs: Vec<_> = (0..start).map(|i| (i, i)).collect();
+    let (rows, cols) = (n - start - end, m - start - end);
+    if rows > 0 && cols > 0 && rows.saturating_mul(cols) <= MAX_LCS {
+        // `len[r][c]`: the longest common subsequence of what's after
+        // `r` and `c` in the middle.
+        let mut len = vec![vec![0u32; cols + 1]; rows + 1];
+        for r in (0..rows).rev() {
+            for c in (0..cols).rev() {
+                len[r][c] = if eq(start + r, start + c) {
+                    len[r + 1][c + 1] + 1
+                } else {
+                    len[r + 1][c].max(len[r][c + 1])
+                };
+            }
+        }
+        let (mut r, mut c) = (0, 0);
+        while r < rows && c < cols {
+            if eq(start + r, start + c) {
+                pairs.push((start + r, start + c));
+                r += 1;
+                c += 1;
+            } else if len[r + 1][c] >= len[r][c + 1] {
+                r += 1;
+            } else {
+                c += 1;
+            }
+        }
+    }
+    pairs.extend((0..end).rev().map(|k| (n - 1 - k, m - 1 - k)));
+    pairs
+}
+
+fn build(ops: Vec<Op<'_>>) -> Document {
+    let mut doc = Document::default();
+    let mut list = Vec::with_capacity(ops.len());
+    for op in ops {
+        let (name, from, path, value) = match op {
+            Op::Add(path, value) => ("add", None, path, Some(value)),
+            Op::Remove(path) => ("remove", None, path, None),
+            Op::Replace(path, value) => ("replace", None, path, Some(value)),
+            Op::Move { from, path } => ("move", Some(from), path, None),
+        };
+        let mut obj = Map::new();
+        obj.insert("op".into(), doc.alloc(JsonValue::String(name.to_owned())));
+        if let Some(from) = from {
+            obj.insert("from".into(), doc.alloc(JsonValue::String(from)));
+        }
+        obj.insert("path".into(), doc.alloc(JsonValue::String(path)));
+        if let Some(value) = value {
+            let value = value.to_json(doc.arena_mut());
+            obj.insert("value".into(), doc.alloc(value));
+        }
+        list.push(doc.alloc(JsonValue::Object(obj)));
+    }
+    *doc.root_mut() = JsonValue::List(list);
+    doc
+}
@@ -0,0 +1,623 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Index,
+    str::FromStr,
+};
+
+use crate::{
+    alloc::{Allocator, Id},
+    canonical,
+    error::ParseError,
+    key::Key,
+    map::Map,
+    pointer,
+    value::{JsonValue, Number},
+};
+
+#[derive(Clone)]
+pub struct Document {
+    root: JsonValue,
+    mem: Allocator<JsonValue>,
+}
+
+impl Document {
+    pub(crate) fn new(root: JsonValue, mem: Allocator<JsonValue>) -> Self {
+        Self { root, mem }
+    }
+
+    pub fn root(&self) -> &JsonValue {
+        &self.root
+    }
+
+    pub fn get(&self, id: Id<JsonValue>) -> &JsonValue {
+        self.mem.fetch(id)
+    }
+
+    /// Looks up an RFC 6901 JSON Pointer such as `/data/items/3/id`, where
+    /// `~1` stands for '/' and `~0` for '~' in a key. The empty pointer is
+    /// the root.
+    pub fn pointer(&self, pointer: &str) -> Option<&JsonValue> {
+        match self.pointer_id(pointer)? {
+            Some(id) => Some(self.get(id)),
+            None => Some(&self.root),
+        }
+    }
+
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut JsonValue> {
+        match self.pointer_id(pointer)? {
+            Some(id) => Some(self.mem.fetch_mut(id)),
+            None => Some(&mut self.root),
+        }
+    }
+
+    /// Puts `value` where `pointer` leads, replacing what is there or adding
+    /// a member to an existing object. In a list the last token may also be
+    /// the length or `-`, to append. Returns `false`, changing nothing, if
+    /// the pointer's parent doesn't exist or can't hold the value.
+    pub fn set_pointer(&mut self, pointer: &str, value: JsonValue) -> bool {
+        if pointer.is_empty() {
+            self.root = value;
+            return true;
+        }
+        let Some((parent, last)) = pointer::split_last(pointer) else {
+            return false;
+        };
+        let Some(at) = self.pointer_id(parent) else {
+            return false;
+        };
+        self.set_child(at, &last, value)
+    }
+
+    /// `set_pointer`, but with `create` the containers missing along the
+    /// way are made too: a list where the token after is `0` or `-`, an
+    /// object otherwise. They are only added to objects, or appended to
+    /// lists.
+    pub fn pointer_set(&mut self, pointer: &str, value: JsonValue, create: bool) -> bool {
+        if !create {
+            return self.set_pointer(pointer, value);
+        }
+        let Some(tokens) = pointer::tokens(pointer) else {
+            return false;
+        };
+        let tokens: Vec<_> = tokens.collect();
+        let Some((last, path)) = tokens.split_last() else {
+            return self.set_pointer(pointer, value);
+        };
+        // Follow the pointer as far as it already leads.
+        let mut at = None;
+        let mut found = 0;
+        for token in path {
+            match self.child_id(at, token) {
+                Some(id) => at = Some(id),
+                None => break,
+            }
+            found += 1;
+        }
+        if found == path.len() {
+            return self.set_child(at, last, value);
+        }
+        let addable = match self.value_mut(at) {
+            JsonValue::Object(_) => true,
+            JsonValue::List(list) => {
+                tokens[found] == "-" || pointer::index(&tokens[found]) == Some(list.len())
+            }
+            _ => false,
+        };
+        if !addable {
+            return false;
+        }
+        // Build the missing part from the inside out.
+        let mut value = value;
+        for token in tokens[found + 1..].iter().rev() {
+            let id = self.alloc(value);
+            value = match &**token {
+                "0" | "-" => JsonValue::List(vec![id]),
+                key => JsonValue::Object(Map::from_iter([(key.into(), id)])),
+            };
+        }
+        self.set_child(at, &tokens[found], value)
+    }
+
+    /// Takes what `pointer` leads to out of its object or list, shifting
+    /// later elements down, and returns it. The root can't be removed.
+    pub fn pointer_remove(&mut self, pointer: &str) -> Option<Id<JsonValue>> {
+        let (parent, last) = pointer::split_last(pointer)?;
+        let at = self.pointer_id(parent)?;
+        match self.value_mut(at) {
+            JsonValue::Object(_) => ObjectMut { doc: self, at }.remove(&last),
+            JsonValue::List(list) => {
+                let index = pointer::index(&last).filter(|&index| index < list.len())?;
+                Some(ListMut { doc: self, at }.remove(index))
+            }
+            _ => None,
+        }
+    }
+
+    /// The object at `pointer`, to add and remove members.
+    pub fn object_mut(&mut self, pointer: &str) -> Option<ObjectMut<'_>> {
+        let at = self.pointer_id(pointer)?;
+        match self.value_mut(at) {
+            JsonValue::Object(_) => Some(ObjectMut { doc: self, at }),
+            _ => None,
+        }
+    }
+
+    /// The list at `pointer`, to add and remove elements.
+    pub fn list_mut(&mut self, pointer: &str) -> Option<ListMut<'_>> {
+        let at = self.pointer_id(pointer)?;
+        match self.value_mut(at) {
+            JsonValue::List(_) => Some(ListMut { doc: self, at }),
+            _ => None,
+        }
+    }
+
+    /// Moves `value` into the arena, for use as a child.
+    pub fn alloc(&mut self, value: JsonValue) -> Id<JsonValue> {
+        self.mem.alloc(value)
+    }
+
+    pub fn get_mut(&mut self, id: Id<JsonValue>) -> &mut JsonValue {
+        self.mem.fetch_mut(id)
+    }
+
+    pub fn root_mut(&mut self) -> &mut JsonValue {
+        &mut self.root
+    }
+
+    // Puts `value` under `last` in the container at `at`, as `set_pointer`
+    // describes.
+    fn set_child(&mut self, at: Option<Id<JsonValue>>, last: &str, value: JsonValue) -> bool {
+        match self.value_mut(at) {
+            JsonValue::Object(_) => {
+                ObjectMut { doc: self, at }.insert(last, value);
+                true
+            }
+            JsonValue::List(list) => {
+                let len = list.len();
+                let index = match last {
+                    "-" => len,
+                    _ => match pointer::index(last) {
+                        Some(index) if index <= len => index,
+                        _ => return false,
+                    },
+                };
+                let mut list = ListMut { doc: self, at };
+                if index < len {
+                    list.remove(index);
+                }
+                list.insert(index, value);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn value_mut(&mut self, at: Option<Id<JsonValue>>) -> &mut JsonValue {
+        match at {
+            Some(id) => self.mem.fetch_mut(id),
+            None => &mut self.root,
+        }
+    }
+
+    // Where `pointer` leads: `Some(None)` for the root, which isn't in the
+    // arena.
+    fn pointer_id(&self, pointer: &str) -> Option<Option<Id<JsonValue>>> {
+        let mut id = None;
+        for token in pointer::tokens(pointer)? {
+            id = Some(self.child_id(id, &token)?);
+        }
+        Some(id)
+    }
+
+    fn child_id(&self, at: Option<Id<JsonValue>>, token: &str) -> Option<Id<JsonValue>> {
+        match at.map_or(&self.root, |id| self.get(id)) {
+            JsonValue::Object(obj) => obj.get(token).copied(),
+            JsonValue::List(list) => list.get(pointer::index(token)?).copied(),
+            _ => None,
+        }
+    }
+
+    /// The root, as a handle that can look up its children.
+    pub fn value(&self) -> ValueRef<'_> {
+        ValueRef {
+            doc: self,
+            value: &self.root,
+        }
+    }
+
+    /// `value`, which must belong to this document, as a handle.
+    pub fn value_ref<'doc>(&'doc self, value: &'doc JsonValue) -> ValueRef<'doc> {
+        ValueRef { doc: self, value }
+    }
+
+    pub fn arena(&self) -> &Allocator<JsonValue> {
+        &self.mem
+    }
+
+    pub(crate) fn arena_mut(&mut self) -> &mut Allocator<JsonValue> {
+        &mut self.mem
+    }
+
+    pub fn into_parts(self) -> (JsonValue, Allocator<JsonValue>) {
+        (self.root, self.mem)
+    }
+
+    /// Direct children of `value`: list elements in order, object values in map order.
+    pub fn children<'doc>(&'doc self, value: &'doc JsonValue) -> Children<'doc> {
+        let ids: Box<dyn Iterator<Item = &'doc Id<JsonValue>> + 'doc> = match value {
+            JsonValue::List(list) => Box::new(list.iter()),
+            JsonValue::Object(obj) => Box::new(obj.values()),
+            _ => Box::new(std::iter::empty()),
+        };
+        Children { doc: self, ids }
+    }
+
+    /// Pre-order walk over the root and everything below it.
+    pub fn descendants(&self) -> Descendants<'_> {
+        Descendants {
+            doc: self,
+            stack: vec![&self.root],
+        }
+    }
+}
+
+/// `null`, with an empty arena.
+impl Default for Document {
+    fn default() -> Self {
+        Document::new(JsonValue::Null, Allocator::make(1 << 4))
+    }
+}
+
+impl std::fmt::Debug for Document {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Document")
+            .field("root", &self.root)
+            .field("nodes", &self.mem.len())
+            .finish()
+    }
+}
+
+/// An object in a `Document`, borrowed for editing. New values go in the
+/// document's arena; removed ones stay there, unreachable, until the
+/// document is dropped.
+pub struct ObjectMut<'doc> {
+    doc: &'doc mut Document,
+    at: Option<Id<JsonValue>>,
+}
+
+impl ObjectMut<'_> {
+    /// Returns the `Id` of the value replaced, if any.
+    pub fn insert(&mut self, key: impl Into<Key>, value: JsonValue) -> Option<Id<JsonValue>> {
+        let id = self.doc.alloc(value);
+        self.members().insert(key.into(), id)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Id<JsonValue>> {
+        self.members().remove(key)
+    }
+
+    pub fn len(&mut self) -> usize {
+        self.members().len()
+    }
+
+    pub fn is_empty(&mut self) -> bool {
+        self.members().is_empty()
+    }
+
+    fn members(&mut self) -> &mut Map<Key, Id<JsonValue>> {
+        match self.doc.value_mut(self.at) {
+            JsonValue::Object(obj) => obj,
+            _ => unreachable!("ObjectMut of a non-object"),
+        }
+    }
+}
+
+/// A list in a `Document`, borrowed for editing, like `ObjectMut`.
+pub struct ListMut<'doc> {
+    doc: &'doc mut Document,
+    at: Option<Id<JsonValue>>,
+}
+
+impl<'doc> ListMut<'doc> {
+    pub fn push(&mut self, value: JsonValue) -> Id<JsonValue> {
+        let id = self.doc.alloc(value);
+        self.elements().push(id);
+        id
+    }
+
+    /// Panics if `index` is past the end, like `Vec::insert`.
+    pub fn insert(&mut self, index: usize, value: JsonValue) -> Id<JsonValue> {
+        let id = self.doc.alloc(value);
+        self.elements().insert(index, id);
+        id
+    }
+
+    /// Panics if `index` is out of bounds, like `Vec::remove`.
+    pub fn remove(&mut self, index: usize) -> Id<JsonValue> {
+        self.elements().remove(index)
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.elements().truncate(len);
+    }
+
+    pub fn len(&mut self) -> usize {
+        self.elements().len()
+    }
+
+    pub fn is_empty(&mut self) -> bool {
+        self.elements().is_empty()
+    }
+
+    /// Sorts the elements by the value at `pointer` in each, in
+    /// `Document::cmp_values` order, keeping equal ones as they were.
+    /// Elements without one sort as null.
+    pub fn sort_by_pointer(&mut self, pointer: &str) {
+        let doc = &*self.doc;
+        let mut keyed: Vec<_> = self
+            .list()
+            .iter()
+            .map(|&id| (doc.value_ref(doc.get(id)).at_pointer(pointer), id))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| a.cmp_with(*b));
+        let list = keyed.into_iter().map(|(_, id)| id).collect();
+        *self.elements() = list;
+    }
+
+    /// Drops every element whose value at `pointer` equals, by value, that
+    /// of an element before it. Elements without one count as null.
+    pub fn dedupe_by_pointer(&mut self, pointer: &str) {
+        let doc = &*self.doc;
+        let mut seen = HashSet::new();
+        let list = self
+            .list()
+            .iter()
+            .copied()
+            .filter(|&id| {
+                let key = doc.value_ref(doc.get(id)).at_pointer(pointer);
+                seen.insert(canonical::text(key))
+            })
+            .collect();
+        *self.elements() = list;
+    }
+
+    /// Turns the list into an object of lists, grouping the elements, in
+    /// order, by the value at `pointer` in each. A string is its group's
+    /// key, and any other value its JSON text; elements without one go
+    /// under `null`.
+    pub fn group_by_pointer(mut self, pointer: &str) -> ObjectMut<'doc> {
+        let list = std::mem::take(self.elements());
+        let doc = &*self.doc;
+        let mut index = HashMap::new();
+        let mut groups: Vec<(String, Vec<Id<JsonValue>>)> = Vec::new();
+        for id in list {
+            let key = doc.value_ref(doc.get(id)).at_pointer(pointer);
+            let key = match key.as_str() {
+                Some(s) => s.to_owned(),
+                None => key.to_string(),
+            };
+            let group = *index.entry(key.clone()).or_insert_with(|| {
+                groups.push((key, Vec::new()));
+                groups.len() - 1
+            });
+            groups[group].1.push(id);
+        }
+        let mut obj = Map::new();
+        for (key, group) in groups {
+            obj.insert(key.into(), self.doc.alloc(JsonValue::List(group)));
+        }
+        *self.doc.value_mut(self.at) = JsonValue::Object(obj);
+        ObjectMut {
+            doc: self.doc,
+            at: self.at,
+        }
+    }
+
+    fn list(&self) -> &[Id<JsonValue>] {
+        match self.at {
+            Some(id) => self.doc.get(id),
+            None => &self.doc.root,
+        }
+        .as_array()
+        .expect("ListMut of a non-list")
+    }
+
+    fn elements(&mut self) -> &mut Vec<Id<JsonValue>> {
+        match self.doc.value_mut(self.at) {
+            JsonValue::List(list) => list,
+            _ => unreachable!("ListMut of a non-list"),
+        }
+    }
+}
+
+/// A value together with the document holding its children. Derefs to the
+/// `JsonValue`, so its accessors are available too.
+#[derive(Clone, Copy)]
+pub struct ValueRef<'doc> {
+    doc: &'doc Document,
+    value: &'doc JsonValue,
+}
+
+impl<'doc> ValueRef<'doc> {
+    pub fn value(&self) -> &'doc JsonValue {
+        self.value
+    }
+
+    pub fn document(&self) -> &'doc Document {
+        self.doc
+    }
+
+    /// The member `key` of an object.
+    pub fn get(&self, key: &str) -> Option<ValueRef<'doc>> {
+        let id = *self.value.as_object()?.get(key)?;
+        Some(self.child(id))
+    }
+
+    /// Element `index` of a list.
+    pub fn get_index(&self, index: usize) -> Option<ValueRef<'doc>> {
+        let id = *self.value.as_array()?.get(index)?;
+        Some(self.child(id))
+    }
+
+    // The borrowing accessors again, for references that outlive the handle.
+    pub fn as_str(&self) -> Option<&'doc str> {
+        self.value.as_str()
+    }
+
+    pub fn as_number(&self) -> Option<&'doc Number> {
+        self.value.as_number()
+    }
+
+    pub fn as_object(&self) -> Option<&'doc Map<Key, Id<JsonValue>>> {
+        self.value.as_object()
+    }
+
+    pub fn as_array(&self) -> Option<&'doc [Id<JsonValue>]> {
+        self.value.as_array()
+    }
+
+    /// Like `get` and `get_index`, but a missing member or element, or
+    /// indexing anything other than an object or a list, gives `Null`, so
+    /// lookups chain: `doc.value().at("users").at(0).at("name")`.
+    pub fn at(&self, index: impl ValueIndex) -> ValueRef<'doc> {
+        ValueRef {
+            doc: self.doc,
+            value: index.index_into(*self).map_or(&NULL, |v| v.value),
+        }
+    }
+
+    /// The value `pointer` leads to from this one, as `Document::pointer`
+    /// does from the root.
+    pub fn pointer(&self, pointer: &str) -> Option<ValueRef<'doc>> {
+        let mut value = *self;
+        for token in pointer::tokens(pointer)? {
+            value = match value.value {
+                JsonValue::Object(_) => value.get(&token)?,
+                JsonValue::List(_) => value.get_index(pointer::index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    // `pointer`, or `Null` where it leads nowhere.
+    fn at_pointer(&self, pointer: &str) -> ValueRef<'doc> {
+        self.pointer(pointer).unwrap_or(ValueRef {
+            doc: self.doc,
+            value: &NULL,
+        })
+    }
+
+    pub(crate) fn child(&self, id: Id<JsonValue>) -> ValueRef<'doc> {
+        ValueRef {
+            doc: self.doc,
+            value: self.doc.get(id),
+        }
+    }
+}
+
+static NULL: JsonValue = JsonValue::Null;
+
+/// What a `ValueRef` can be indexed by: `&str` for object members, `usize`
+/// for list elements.
+pub trait ValueIndex {
+    fn index_into<'doc>(&self, value: ValueRef<'doc>) -> Option<ValueRef<'doc>>;
+}
+
+impl ValueIndex for &str {
+    fn index_into<'doc>(&self, value: ValueRef<'doc>) -> Option<ValueRef<'doc>> {
+        value.get(self)
+    }
+}
+
+impl ValueIndex for usize {
+    fn index_into<'doc>(&self, value: ValueRef<'doc>) -> Option<ValueRef<'doc>> {
+        value.get_index(*self)
+    }
+}
+
+// `Index` has to return a reference, not a new handle, so these only go one
+// level down; chained lookups use `ValueRef::at`.
+impl<I: ValueIndex> Index<I> for ValueRef<'_> {
+    type Output = JsonValue;
+
+    fn index(&self, index: I) -> &JsonValue {
+        self.at(index).value
+    }
+}
+
+impl<I: ValueIndex> Index<I> for Document {
+    type Output = JsonValue;
+
+    fn index(&self, index: I) -> &JsonValue {
+        self.value().at(index).value
+    }
+}
+
+impl std::ops::Deref for ValueRef<'_> {
+    type Target = JsonValue;
+
+    fn deref(&self) -> &JsonValue {
+        self.value
+    }
+}
+
+impl std::fmt::Debug for ValueRef<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+pub struct Children<'doc> {
+    doc: &'doc Document,
+    ids: Box<dyn Iterator<Item = &'doc Id<JsonValue>> + 'doc>,
+}
+
+impl<'doc> Iterator for Children<'doc> {
+    type Item = &'doc JsonValue;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.ids.next().map(|id| self.doc.get(*id))
+    }
+}
+
+pub struct Descendants<'doc> {
+    doc: &'doc Document,
+    stack: Vec<&'doc JsonValue>,
+}
+
+impl<'doc> Iterator for Descendants<'doc> {
+    type Item = &'doc JsonValue;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.stack.pop()?;
+        let start = self.stack.len();
+        self.stack.extend(self.doc.children(value));
+        self.stack[start..].reverse();
+        Some(value)
+    }
+}
+
+impl FromStr for Document {
+    type Err = ParseError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        crate::parse_value(src)
+    }
+}
+
+impl TryFrom<&str> for Document {
+    type Error = ParseError;
+
+    fn try_from(src: &str) -> Result<Self, Self::Error> {
+        crate::parse_value(src)
+    }
+}
+
+impl TryFrom<&[u8]> for Document {
+    type Error = ParseError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        crate::parse_bytes(bytes)
+    }
+}
@@ -0,0 +1,123 @@
+use std::borrow::Cow;
+
+use crate::{error::ParseError, lex::Span};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Utf8,
+    Utf16Be,
+    Utf16Le,
+    Utf32Be,
+    Utf32Le,
+}
+
+impl Encoding {
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Be => "UTF-16BE",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf32Be => "UTF-32BE",
+            Encoding::Utf32Le => "UTF-32LE",
+        }
+    }
+
+    fn unit(self) -> usize {
+        match self {
+            Encoding::Utf8 => 1,
+            Encoding::Utf16Be | Encoding::Utf16Le => 2,
+            Encoding::Utf32Be | Encoding::Utf32Le => 4,
+        }
+    }
+}
+
+// A byte order mark wins; without one, RFC 4627 section 3 tells the
+// encodings apart by where the zero bytes fall, since JSON text starts
+// with two ASCII characters.
+fn detect(bytes: &[u8]) -> (Encoding, usize) {
+    match bytes {
+        [0x00, 0x00, 0xFE, 0xFF, ..] => (Encoding::Utf32Be, 4),
+        [0xFF, 0xFE, 0x00, 0x00, ..] => (Encoding::Utf32Le, 4),
+        [0xFE, 0xFF, ..] => (Encoding::Utf16Be, 2),
+        [0xFF, 0xFE, ..] => (Encoding::Utf16Le, 2),
+        [0x00, 0x00, 0x00, _, ..] => (Encoding::Utf32Be, 0),
+        [_, 0x00, 0x00, 0x00, ..] => (Encoding::Utf32Le, 0),
+        [0x00, _, ..] => (Encoding::Utf16Be, 0),
+        [_, 0x00, ..] => (Encoding::Utf16Le, 0),
+        _ => (Encoding::Utf8, 0),
+    }
+}
+
+/// Returns `bytes` untouched when they look like UTF-8, and transcoded to
+/// UTF-8 when they are UTF-16 or UTF-32. Spans of later errors then refer to
+/// the transcoded text.
+pub(crate) fn decode(bytes: &[u8]) -> Result<Cow<'_, [u8]>, ParseError> {
+    let (encoding, bom) = detect(bytes);
+    if encoding == Encoding::Utf8 {
+        return Ok(Cow::Borrowed(bytes));
+    }
+
+    let unit = encoding.unit();
+    let mut out = String::with_capacity(bytes.len() / unit);
+    let mut units = bytes[bom..].chunks(unit).enumerate().peekable();
+    let mut span = Span {
+        start: 0,
+        end: 0,
+        line: 1,
+        column: 1,
+    };
+    while let Some((i, chunk)) = units.next() {
+        let start = bom + i * unit;
+        let invalid = |span: Span| ParseError::InvalidEncoding {
+            encoding: encoding.name(),
+            span: Span {
+                start,
+                end: start + chunk.len(),
+                ..span
+            },
+        };
+        if chunk.len() < unit {
+            return Err(invalid(span));
+        }
+        let code = match encoding {
+            Encoding::Utf16Be | Encoding::Utf16Le => {
+                let read = |c: &[u8]| match encoding {
+                    Encoding::Utf16Be => u16::from_be_bytes([c[0], c[1]]),
+                    _ => u16::from_le_bytes([c[0], c[1]]),
+                };
+                let hi = read(chunk);
+                match hi {
+                    0xD800..=0xDBFF => {
+                        let lo = match units.next() {
+                            Some((_, lo)) if lo.len() == 2 => read(lo),
+                            _ => return Err(invalid(span)),
+                        };
+                        if !(0xDC00..=0xDFFF).contains(&lo) {
+                            return Err(invalid(span));
+                        }
+                        0x10000 + ((u32::from(hi) - 0xD800) << 10) + (u32::from(lo) - 0xDC00)
+                    }
+                    _ => u32::from(hi),
+                }
+            }
+            _ => {
+                let c = [chunk[0], chunk[1], chunk[2], chunk[3]];
+                match encoding {
+                    Encoding::Utf32Be => u32::from_be_bytes(c),
+                    _ => u32::from_le_bytes(c),
+                }
+            }
+        };
+        let Some(chr) = char::from_u32(code) else {
+            return Err(invalid(span));
+        };
+        out.push(chr);
+        if chr == '\n' {
+            span.line += 1;
+            span.column = 1;
+        } else {
+            span.column += 1;
+        }
+    }
+    Ok(Cow::Owned(out.into_bytes()))
+}
@@ -0,0 +1,104 @@
+use crate::{
+    doc::{Document, ValueRef},
+    value::{JsonValue, Number},
+};
+
+/// How `Document::deep_eq_with` compares values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EqOptions {
+    /// Compare numbers by value, so `1`, `1.0` and `1e0` are equal. Otherwise
+    /// an integer never equals a float.
+    pub numbers_by_value: bool,
+}
+
+impl Document {
+    /// Whether both documents hold the same tree, whatever their arenas look
+    /// like. Object members may be in any order, and `Raw` values are equal
+    /// when their text is.
+    pub fn deep_eq(&self, other: &Document) -> bool {
+        self.deep_eq_with(other, EqOptions::default())
+    }
+
+    pub fn deep_eq_with(&self, other: &Document, opts: EqOptions) -> bool {
+        self.value().deep_eq_with(other.value(), opts)
+    }
+}
+
+impl<'doc> ValueRef<'doc> {
+    /// `Document::deep_eq` for values that may come from different documents.
+    pub fn deep_eq_with(self, other: ValueRef<'_>, opts: EqOptions) -> bool {
+        let mut stack = vec![(self, other)];
+        while let Some((a, b)) = stack.pop() {
+            let same = match (a.value(), b.value()) {
+                (JsonValue::Object(x), JsonValue::Object(y)) => {
+                    x.len() == y.len()
+                        && x.iter().all(|(key, &id)| match y.get(key) {
+                            Some(&other) => {
+                                stack.push((a.child(id), b.child(other)));
+                                true
+                            }
+                            None => false,
+                        })
+                }
+                (JsonValue::List(x), JsonValue::List(y)) => {
+                    let same = x.len() == y.len();
+                    if same {
+                        stack.extend(x.iter().zip(y).map(|(&i, &j)| (a.child(i), b.child(j))));
+                    }
+                    same
+                }
+                (JsonValue::Number(x), JsonValue::Number(y)) => {
+                    if opts.numbers_by_value {
+                        numbers_eq(x, y)
+                    } else {
+                        x == y
+                    }
+                }
+                (JsonValue::String(x), JsonValue::String(y)) => x == y,
+                (JsonValue::Bool(x), JsonValue::Bool(y)) => x == y,
+                (JsonValue::Raw(x), JsonValue::Raw(y)) => x == y,
+                (JsonValue::Null, JsonValue::Null) => true,
+                _ => false,
+            };
+            if !same {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+// Integers are compared exactly, even past 2^53, and only floats with no
+// fractional part can equal one.
+pub(crate) fn numbers_eq(x: &Number, y: &Number) -> bool {
+    match (integer(x), integer(y)) {
+        (Some(i), Some(j)) => i == j,
+        (Some(i), None) => float_is(y.as_f64(), i),
+        (None, Some(j)) => float_is(x.as_f64(), j),
+        (None, None) => x.as_f64() == y.as_f64(),
+    }
+}
+
+fn integer(n: &Number) -> Option<i128> {
+    match *n {
+        Number::I64(n) => Some(n.into()),
+        Number::U64(n) => Some(n.into()),
+        _ => None,
+    }
+}
+
+fn float_is(f: f64, i: i128) -> bool {
+    f.fract() == 0.0 && f as i128 == i
+}
+
+impl PartialEq for Document {
+    fn eq(&self, other: &Document) -> bool {
+        self.deep_eq(other)
+    }
+}
+
+impl PartialEq<ValueRef<'_>> for ValueRef<'_> {
+    fn eq(&self, other: &ValueRef<'_>) -> bool {
+        self.deep_eq_with(*other, EqOptions::default())
+    }
+}
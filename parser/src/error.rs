@@ -0,0 +1,849 @@
+use std::fmt;
+
+use crate::lex::{LexError, Span};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    UnexpectedToken {
+        found: String,
+        expected: Option<&'static str>,
+        /// A likely fix, for near-misses like `ture` or `None`.
+        hint: Option<&'static str>,
+        span: Span,
+    },
+    UnexpectedEof {
+        span: Span,
+    },
+    UnterminatedString {
+        span: Span,
+    },
+    UnterminatedComment {
+        span: Span,
+    },
+    InvalidNumber {
+        lexeme: String,
+        span: Span,
+    },
+    InvalidEscape {
+        sequence: String,
+        span: Span,
+    },
+    ControlCharacter {
+        chr: char,
+        span: Span,
+    },
+    TrailingComma {
+        span: Span,
+    },
+    TrailingData {
+        span: Span,
+    },
+    DuplicateKey {
+        key: String,
+        span: Span,
+    },
+    InvalidUtf8 {
+        span: Span,
+    },
+    UnsupportedEncoding {
+        encoding: &'static str,
+        span: Span,
+    },
+    InvalidEncoding {
+        encoding: &'static str,
+        span: Span,
+    },
+    Io {
+        kind: std::io::ErrorKind,
+        message: String,
+        span: Span,
+    },
+    DepthExceeded {
+        max: usize,
+        span: Span,
+    },
+    TooManyNodes {
+        max: usize,
+        span: Span,
+    },
+    StringTooLong {
+        max: usize,
+        span: Span,
+    },
+    DocumentTooLarge {
+        max: usize,
+        span: Span,
+    },
+}
+
+/// A problem found by `parse_with_diagnostics`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub error: ParseError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// The input is broken here; the document only approximates it.
+    Error,
+    /// The input parsed, but is likely not what its author meant, such as a
+    /// duplicate key.
+    Warning,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Error => write!(f, "error: {}", self.error),
+            Severity::Warning => write!(f, "warning: {}", self.error),
+        }
+    }
+}
+
+/// Coarse failure classes, for callers that don't care about the exact variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The input is not valid JSON.
+    Syntax,
+    /// The input ended in the middle of a value.
+    Eof,
+    /// The input is valid but exceeds a configured limit.
+    Limit,
+    /// The input bytes are not valid UTF-8 or use another encoding.
+    Encoding,
+    /// Reading the input failed.
+    Io,
+}
+
+impl ParseError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ParseError::UnexpectedToken { .. }
+            | ParseError::InvalidNumber { .. }
+            | ParseError::InvalidEscape { .. }
+            | ParseError::ControlCharacter { .. }
+            | ParseError::TrailingComma { .. }
+            | ParseError::TrailingData { .. }
+            | ParseError::DuplicateKey { .. } => ErrorKind::Syntax,
+            ParseError::UnexpectedEof { .. }
+            | ParseError::UnterminatedString { .. }
+            | ParseError::UnterminatedComment { .. } => ErrorKind::Eof,
+            ParseError::DepthExceeded { .. }
+            | ParseError::TooManyNodes { .. }
+            | ParseError::StringTooLong { .. }
+            | ParseError::DocumentTooLarge { .. } => ErrorKind::Limit,
+            ParseError::InvalidUtf8 { .. }
+            | ParseError::UnsupportedEncoding { .. }
+            | ParseError::InvalidEncoding { .. } => ErrorKind::Encoding,
+            ParseError::Io { .. } => ErrorKind::Io,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnexpectedToken { span, .. }
+            | ParseError::UnexpectedEof { span }
+            | ParseError::UnterminatedString { span }
+            | ParseError::UnterminatedComment { span }
+            | ParseError::InvalidNumber { span, .. }
+            | ParseError::InvalidEscape { span, .. }
+            | ParseError::ControlCharacter { span, .. }
+            | ParseError::TrailingComma { span }
+            | ParseError::TrailingData { span }
+            | ParseError::DuplicateKey { span, .. }
+            | ParseError::InvalidUtf8 { span }
+            | ParseError::UnsupportedEncoding { span, .. }
+            | ParseError::InvalidEncoding { span, .. }
+            | ParseError::Io { span, .. }
+            | ParseError::DepthExceeded { span, .. }
+            | ParseError::TooManyNodes { span, .. }
+            | ParseError::StringTooLong { span, .. }
+            | ParseError::DocumentTooLarge { span, .. } => *span,
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            ParseError::UnexpectedToken { span, .. }
+            | ParseError::UnexpectedEof { span }
+            | ParseError::UnterminatedString { span }
+            | ParseError::UnterminatedComment { span }
+            | ParseError::InvalidNumber { span, .. }
+            | ParseError::InvalidEscape { span, .. }
+            | ParseError::ControlCharacter { span, .. }
+            | ParseError::TrailingComma { span }
+            | ParseError::TrailingData { span }
+            | ParseError::DuplicateKey { span, .. }
+            | ParseError::InvalidUtf8 { span }
+            | ParseError::UnsupportedEncoding { span, .. }
+            | ParseError::InvalidEncoding { span, .. }
+            | ParseError::Io { span, .. }
+            | ParseError::DepthExceeded { span, .. }
+            | ParseError::TooManyNodes { span, .. }
+            | ParseError::StringTooLong { span, .. }
+            | ParseError::DocumentTooLarge { span, .. } => span,
+        }
+    }
+
+    // Moves the error from a slice of some larger input to where the slice
+    // starts in it.
+    pub(crate) fn shift(mut self, offset: usize, lines: usize) -> Self {
+        let span = self.span_mut();
+        span.start += offset;
+        span.end += offset;
+        span.line += lines;
+        self
+    }
+}
+
+impl ParseError {
+    /// The error with the offending line of `src` and a caret underline, in
+    /// the style of rustc:
+    ///
+    /// ```text
+    /// error: Expected ',' or ']', found '3'
+    ///  --> line 1, column 7
+    ///   |
+    /// 1 | [1, 2 3]
+    ///   |       ^
+    /// ```
+    ///
+    /// `src` must be the text the error came from.
+    pub fn render(&self, src: &str) -> String {
+        let span = self.span();
+        let mut out = format!("error: {}\n --> {span}\n", Message(self));
+        let Some(line) = src.lines().nth(span.line.saturating_sub(1)) else {
+            return out;
+        };
+        // Keep tabs in the padding so the caret lines up under them.
+        let pad: String = line
+            .chars()
+            .take(span.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let len = src
+            .get(span.start..span.end)
+            .and_then(|text| text.lines().next())
+            .map_or(0, |text| text.chars().count());
+        let underline = format!("^{}", "~".repeat(len.saturating_sub(1)));
+        let gutter = " ".repeat(span.line.to_string().len());
+        out += &format!(
+            "{gutter} |\n{} | {line}\n{gutter} | {pad}{underline}\n",
+            span.line
+        );
+        out
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}.", Message(self), self.span())
+    }
+}
+
+// The description alone, without the location.
+struct Message<'a>(&'a ParseError);
+
+impl fmt::Display for Message<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            ParseError::UnexpectedToken {
+                found,
+                expected,
+                hint,
+                ..
+            } => {
+                match expected {
+                    Some(expected) => write!(f, "Expected {expected}, found {found}")?,
+                    None => write!(f, "Unexpected {found}")?,
+                }
+                if let Some(hint) = hint {
+                    write!(f, "; {hint}")?
+                }
+            }
+            ParseError::UnexpectedEof { .. } => write!(f, "Unexpected end of input")?,
+            ParseError::UnterminatedString { .. } => write!(f, "Unterminated string")?,
+            ParseError::UnterminatedComment { .. } => write!(f, "Unterminated comment")?,
+            ParseError::InvalidNumber { lexeme, .. } => write!(f, "Invalid number '{lexeme}'")?,
+            ParseError::InvalidEscape { sequence, .. } => {
+                write!(f, "Invalid escape sequence '{sequence}'")?
+            }
+            ParseError::ControlCharacter { chr, .. } => {
+                write!(f, "Unescaped control character {chr:?} in string")?
+            }
+            ParseError::TrailingComma { .. } => write!(f, "Trailing comma")?,
+            ParseError::TrailingData { .. } => {
+                write!(f, "Unexpected data after the end of the value")?
+            }
+            ParseError::DuplicateKey { key, .. } => write!(f, "Duplicate key {key:?}")?,
+            ParseError::InvalidUtf8 { .. } => write!(f, "Invalid UTF-8")?,
+            ParseError::UnsupportedEncoding { encoding, .. } => {
+                write!(f, "Input is {encoding}, only UTF-8 is supported")?
+            }
+            ParseError::InvalidEncoding { encoding, .. } => write!(f, "Invalid {encoding}")?,
+            ParseError::Io { message, .. } => write!(f, "Read failed: {message}")?,
+            ParseError::DepthExceeded { max, .. } => {
+                write!(f, "Nesting exceeds the maximum depth of {max}")?
+            }
+            ParseError::TooManyNodes { max, .. } => {
+                write!(f, "Document has more than {max} values")?
+            }
+            ParseError::StringTooLong { max, .. } => {
+                write!(f, "String is longer than {max} bytes")?
+            }
+            ParseError::DocumentTooLarge { max, .. } => {
+                write!(f, "Document is larger than {max} bytes")?
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A value converted to a Rust type it doesn't hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeError {
+    pub expected: &'static str,
+    pub found: &'static str,
+}
+
+impl TypeError {
+    pub(crate) fn new(expected: &'static str, found: &'static str) -> Self {
+        Self { expected, found }
+    }
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Expected {}, found {}.", self.expected, self.found)
+    }
+}
+
+impl std::error::Error for TypeError {}
+
+impl From<LexError> for ParseError {
+    fn from(e: LexError) -> Self {
+        match e {
+            LexError::InvalidLiteral { text, span } => ParseError::UnexpectedToken {
+                hint: hint(&text),
+                found: format!("'{text}'"),
+                expected: None,
+                span,
+            },
+            LexError::InvalidNumber { lexeme, span } => ParseError::InvalidNumber { lexeme, span },
+            LexError::InvalidEscape { sequence, span } => {
+                ParseError::InvalidEscape { sequence, span }
+            }
+            LexError::ControlCharacter { chr, span } => ParseError::ControlCharacter { chr, span },
+            LexError::UnterminatedString { span } => ParseError::UnterminatedString { span },
+            LexError::UnterminatedComment { span } => ParseError::UnterminatedComment { span },
+            LexError::InvalidUtf8 { span } => ParseError::InvalidUtf8 { span },
+            LexError::UnsupportedEncoding { encoding, span } => {
+                ParseError::UnsupportedEncoding { encoding, span }
+            }
+            LexError::StringTooLong { max, span } => ParseError::StringTooLong { max, span },
+            LexError::DocumentTooLarge { max, span } => ParseError::DocumentTooLarge { max, span },
+            LexError::Io {
+                kind,
+                message,
+                span,
+            } => ParseError::Io {
+                kind,
+                message,
+                span,
+            },
+        }
+    }
+}
+
+// Suggests a fix for a bare word or stray character that isn't JSON.
+pub(crate) fn hint(text: &str) -> Option<&'static str> {
+    // Nothing longer than `undefined` is close to a suggestion, and the
+    // word is untrusted input, so it isn't copied or compared at all.
+    if text.len() > "undefined".len() {
+        return None;
+    }
+    let lower = text.to_ascii_lowercase();
+    match lower.as_str() {
+        "'" => return Some("strings need double quotes"),
+        "none" | "nil" | "undefined" => return Some("did you mean 'null'?"),
+        _ => {}
+    }
+    [
+        ("true", "did you mean 'true'?"),
+        ("false", "did you mean 'false'?"),
+        ("null", "did you mean 'null'?"),
+    ]
+    .into_iter()
+    // A length more than one apart is more than one edit away.
+    .find(|(word, _)| {
+        lower.chars().count().abs_diff(word.len()) <= 1 && distance(&lower, word) <= 1
+    })
+    .map(|(_, hint)| hint)
+}
+
+// Edit distance counting a swap of neighbours as one edit, so `ture` is as
+// close to `true` as `tru` is.
+fn distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Why `FromJson` couldn't decode a value, and where: `pointer` is the JSON
+/// Pointer of the offending value, from the one decoding started at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    Type {
+        expected: &'static str,
+        found: &'static str,
+        pointer: String,
+    },
+    MissingField {
+        field: &'static str,
+        pointer: String,
+    },
+    Custom {
+        message: String,
+        pointer: String,
+    },
+}
+
+impl DecodeError {
+    pub fn custom(message: impl Into<String>) -> Self {
+        DecodeError::Custom {
+            message: message.into(),
+            pointer: String::new(),
+        }
+    }
+
+    pub fn pointer(&self) -> &str {
+        match self {
+            DecodeError::Type { pointer, .. }
+            | DecodeError::MissingField { pointer, .. }
+            | DecodeError::Custom { pointer, .. } => pointer,
+        }
+    }
+
+    /// The same error one level further down, under the key or index
+    /// `token`, for impls passing on their children's errors.
+    pub fn within(mut self, token: impl fmt::Display) -> Self {
+        let (DecodeError::Type { pointer, .. }
+        | DecodeError::MissingField { pointer, .. }
+        | DecodeError::Custom { pointer, .. }) = &mut self;
+        let token = token.to_string().replace('~', "~0").replace('/', "~1");
+        pointer.insert_str(0, &format!("/{token}"));
+        self
+    }
+}
+
+impl From<TypeError> for DecodeError {
+    fn from(e: TypeError) -> Self {
+        DecodeError::Type {
+            expected: e.expected,
+            found: e.found,
+            pointer: String::new(),
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Type {
+                expected, found, ..
+            } => write!(f, "Expected {expected}, found {found}")?,
+            DecodeError::MissingField { field, .. } => write!(f, "Missing field {field:?}")?,
+            DecodeError::Custom { message, .. } => f.write_str(message)?,
+        }
+        match self.pointer() {
+            "" => write!(f, " at the root."),
+            pointer => write!(f, " at {pointer}."),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Why `from_str` failed: the text didn't parse, or didn't fit the type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FromStrError {
+    Parse(ParseError),
+    Decode(DecodeError),
+}
+
+impl From<ParseError> for FromStrError {
+    fn from(e: ParseError) -> Self {
+        FromStrError::Parse(e)
+    }
+}
+
+impl From<DecodeError> for FromStrError {
+    fn from(e: DecodeError) -> Self {
+        FromStrError::Decode(e)
+    }
+}
+
+impl fmt::Display for FromStrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FromStrError::Parse(e) => e.fmt(f),
+            FromStrError::Decode(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for FromStrError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FromStrError::Parse(e) => Some(e),
+            FromStrError::Decode(e) => Some(e),
+        }
+    }
+}
+
+/// Why a `JsonWriter` call failed.
+#[derive(Debug)]
+pub enum WriteError {
+    Io(std::io::Error),
+    /// A call that would have made the output malformed, such as a value
+    /// where a key belongs.
+    Unexpected {
+        call: &'static str,
+        expected: &'static str,
+    },
+}
+
+impl From<std::io::Error> for WriteError {
+    fn from(e: std::io::Error) -> Self {
+        WriteError::Io(e)
+    }
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteError::Io(e) => e.fmt(f),
+            WriteError::Unexpected { call, expected } => {
+                write!(f, "Unexpected {call}, expected {expected}.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WriteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WriteError::Io(e) => Some(e),
+            WriteError::Unexpected { .. } => None,
+        }
+    }
+}
+
+/// Why `Document::to_canonical_string` failed: the JSON Pointer to a value
+/// RFC 8785 has no spelling for, and what it was.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanonicalError {
+    pub pointer: String,
+    pub message: &'static str,
+}
+
+impl fmt::Display for CanonicalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "No canonical form for {} at {:?}.",
+            self.message, self.pointer
+        )
+    }
+}
+
+impl std::error::Error for CanonicalError {}
+
+/// Why `Document::try_to_string` or `to_string_pretty` failed: the JSON
+/// Pointer to a NaN or infinity, which JSON has no spelling for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatError {
+    pub pointer: String,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "No JSON spelling for the non-finite number at {:?}.",
+            self.pointer
+        )
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// Why a JSONPath expression didn't compile: what was wrong, and the byte
+/// offset in the expression where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathError {
+    pub message: &'static str,
+    pub offset: usize,
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}.", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for PathError {}
+
+/// Why `Document::apply_patch` failed. `index` is the position of the
+/// operation in the patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchError {
+    /// The patch isn't a list of operations, or an operation is malformed.
+    Invalid {
+        index: Option<usize>,
+        message: &'static str,
+    },
+    /// A `path` or `from` that doesn't lead anywhere the operation can work.
+    Path { index: usize, pointer: String },
+    /// A `test` that found a different value.
+    Test { index: usize, pointer: String },
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::Invalid {
+                index: None,
+                message,
+            } => write!(f, "Invalid patch: {message}."),
+            PatchError::Invalid {
+                index: Some(index),
+                message,
+            } => write!(f, "Invalid patch operation {index}: {message}."),
+            PatchError::Path { index, pointer } => {
+                write!(f, "Patch operation {index} can't apply at {pointer:?}.")
+            }
+            PatchError::Test { index, pointer } => {
+                write!(f, "Patch operation {index} failed its test at {pointer:?}.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// Why `Schema::compile` rejected a schema: what was wrong, and the JSON
+/// Pointer in the schema document where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    pub pointer: String,
+    pub message: &'static str,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid schema at {:?}: {}.", self.pointer, self.message)
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// One way an instance fails its schema: the JSON Pointer to the value in
+/// the instance, the keyword it failed, and what was wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub instance_path: String,
+    pub keyword: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {}.", self.instance_path, self.message)
+    }
+}
+
+/// Why `Document::from_flat_map` couldn't rebuild a document: the flat key,
+/// and what was wrong with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlattenError {
+    pub key: String,
+    pub message: &'static str,
+}
+
+impl fmt::Display for FlattenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Can't unflatten {:?}: {}.", self.key, self.message)
+    }
+}
+
+impl std::error::Error for FlattenError {}
+
+/// Why `Document::to_csv` or `Document::from_csv` failed.
+#[derive(Debug)]
+pub enum CsvError {
+    Io(std::io::Error),
+    /// The document isn't a list, or the element at `index` isn't an
+    /// object.
+    NotRecords {
+        index: Option<usize>,
+    },
+    /// Malformed CSV, on the 1-based `line`.
+    Syntax {
+        line: usize,
+        message: &'static str,
+    },
+}
+
+impl From<std::io::Error> for CsvError {
+    fn from(e: std::io::Error) -> Self {
+        CsvError::Io(e)
+    }
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(e) => e.fmt(f),
+            CsvError::NotRecords { index: None } => write!(f, "CSV needs a list of objects."),
+            CsvError::NotRecords { index: Some(index) } => {
+                write!(
+                    f,
+                    "CSV needs a list of objects, but element {index} isn't one."
+                )
+            }
+            CsvError::Syntax { line, message } => {
+                write!(f, "Invalid CSV on line {line}: {message}.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CsvError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Why a binary format couldn't be read: what was wrong, and the byte
+/// offset where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryError {
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for BinaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid input at byte {}: {}.",
+            self.offset, self.message
+        )
+    }
+}
+
+impl std::error::Error for BinaryError {}
+
+/// Why `parse_yaml` couldn't read its input: what was wrong, and the
+/// 1-based line and column where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YamlError {
+    pub line: usize,
+    pub column: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid YAML at line {}, column {}: {}.",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for YamlError {}
+
+/// Why a document couldn't become TOML, or TOML couldn't be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TomlError {
+    /// `to_toml_string` met a value TOML can't hold, at `pointer`.
+    Unrepresentable {
+        pointer: String,
+        message: &'static str,
+    },
+    /// `from_toml` found invalid TOML at a 1-based line and column.
+    Syntax {
+        line: usize,
+        column: usize,
+        message: &'static str,
+    },
+}
+
+impl fmt::Display for TomlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TomlError::Unrepresentable { pointer, message } => {
+                write!(f, "Can't write {pointer:?} as TOML: {message}.")
+            }
+            TomlError::Syntax {
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "Invalid TOML at line {line}, column {column}: {message}."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TomlError {}
+
+/// Why form data couldn't be read or written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlencodedError {
+    /// `to_urlencoded` was given a document that isn't an object.
+    NotObject,
+    /// `from_urlencoded` found the field `key` where an earlier one already
+    /// put a value, or a value where it put a list or an object.
+    Conflict { key: String },
+}
+
+impl fmt::Display for UrlencodedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UrlencodedError::NotObject => write!(f, "Form data needs an object."),
+            UrlencodedError::Conflict { key } => {
+                write!(f, "The form field {key:?} conflicts with an earlier one.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for UrlencodedError {}
@@ -0,0 +1,225 @@
+use crate::{doc::Document, error::ParseError, lex::Span, options::ParseOptions, par::Par};
+
+/// What `Feeder::poll` found in the bytes fed so far.
+#[derive(Debug)]
+pub enum Poll {
+    /// No document is complete yet.
+    NeedMoreData,
+    Document(Document),
+    /// `finish` was called and every document has been returned.
+    Finished,
+}
+
+// Where the scan for the end of the next document is.
+#[derive(Clone, Copy)]
+enum Scan {
+    // Between values, or inside a container outside of strings.
+    Structure,
+    // After the closing quote has been seen if `escape` is false.
+    Str { quote: u8, escape: bool },
+    Scalar,
+    LineComment,
+    BlockComment { star: bool },
+}
+
+/// A push parser for input that arrives in pieces, such as network reads.
+/// Bytes are buffered until a whole value has come in, which is then parsed
+/// in one go; values may follow each other like in a `Stream`. After an
+/// error the bad document is dropped and polling can go on with the next.
+pub struct Feeder {
+    opts: ParseOptions,
+    buf: Vec<u8>,
+    // How far `buf` has been scanned, and the scanner's state there.
+    scanned: usize,
+    scan: Scan,
+    depth: usize,
+    started: bool,
+    finished: bool,
+    // Where `buf` starts in the whole input, and the line it starts on.
+    offset: usize,
+    line: usize,
+}
+
+impl Feeder {
+    pub fn new(opts: ParseOptions) -> Self {
+        Self {
+            opts,
+            buf: Vec::new(),
+            scanned: 0,
+            scan: Scan::Structure,
+            depth: 0,
+            started: false,
+            finished: false,
+            offset: 0,
+            line: 1,
+        }
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Marks the end of the input, so a number or literal at the end of the
+    /// buffer is complete and an unfinished document is an error.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// The next complete document, if any. Each byte is only scanned once no
+    /// matter how the input is split up.
+    pub fn poll(&mut self) -> Result<Poll, ParseError> {
+        let end = match self.scan() {
+            Some(end) => end,
+            None if !self.finished => {
+                if !self.started && matches!(self.scan, Scan::Structure) {
+                    // Nothing but whitespace so far.
+                    self.reset(self.scanned);
+                }
+                if let Some(max) = self.opts.max_document_size {
+                    if self.buf.len() > max {
+                        let span = self.span(self.buf.len());
+                        self.reset(self.buf.len());
+                        return Err(ParseError::DocumentTooLarge { max, span });
+                    }
+                }
+                return Ok(Poll::NeedMoreData);
+            }
+            None if self.started || matches!(self.scan, Scan::BlockComment { .. }) => {
+                self.buf.len()
+            }
+            None => {
+                self.reset(self.buf.len());
+                return Ok(Poll::Finished);
+            }
+        };
+        let (offset, line) = (self.offset, self.line);
+        let doc = Par::parse_utf8(&self.buf[..end], 1 << 4, self.opts);
+        self.reset(end);
+        match doc {
+            Ok(doc) => Ok(Poll::Document(doc)),
+            Err(e) => Err(e.shift(offset, line - 1)),
+        }
+    }
+
+    // Scans on from where the last call stopped, returning the end of the
+    // document once there is a whole one.
+    fn scan(&mut self) -> Option<usize> {
+        let comments = self.opts.comments();
+        let single_quotes = self.opts.json5();
+        while let Some(&byte) = self.buf.get(self.scanned) {
+            let i = self.scanned;
+            self.scanned += 1;
+            self.scan = match self.scan {
+                Scan::Str { quote, escape } => match byte {
+                    b'\\' if !escape => Scan::Str {
+                        quote,
+                        escape: true,
+                    },
+                    _ if byte == quote && !escape => {
+                        if self.depth == 0 {
+                            return Some(self.scanned);
+                        }
+                        Scan::Structure
+                    }
+                    _ => Scan::Str {
+                        quote,
+                        escape: false,
+                    },
+                },
+                Scan::LineComment if byte == b'\n' => Scan::Structure,
+                Scan::LineComment => Scan::LineComment,
+                Scan::BlockComment { star: true } if byte == b'/' => Scan::Structure,
+                Scan::BlockComment { .. } => Scan::BlockComment { star: byte == b'*' },
+                Scan::Scalar => match byte {
+                    b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'+' | b'-' | b'.' | b'_' | b'$' => {
+                        Scan::Scalar
+                    }
+                    _ => {
+                        self.scanned = i;
+                        return Some(i);
+                    }
+                },
+                Scan::Structure => match byte {
+                    b'/' if comments => match self.buf.get(i + 1) {
+                        Some(b'/') => {
+                            self.scanned += 1;
+                            Scan::LineComment
+                        }
+                        Some(b'*') => {
+                            self.scanned += 1;
+                            Scan::BlockComment { star: false }
+                        }
+                        Some(_) => Scan::Structure,
+                        None => {
+                            // Wait for the byte that says what this is.
+                            self.scanned = i;
+                            return None;
+                        }
+                    },
+                    b'"' => self.open_str(b'"'),
+                    b'\'' if single_quotes => self.open_str(b'\''),
+                    b'{' | b'[' => {
+                        self.started = true;
+                        self.depth += 1;
+                        Scan::Structure
+                    }
+                    b'}' | b']' if self.depth > 1 => {
+                        self.depth -= 1;
+                        Scan::Structure
+                    }
+                    // The end of the root, or a stray token for the parser
+                    // to report.
+                    b'}' | b']' => {
+                        self.started = true;
+                        return Some(self.scanned);
+                    }
+                    b',' | b':' if self.depth == 0 => {
+                        self.started = true;
+                        return Some(self.scanned);
+                    }
+                    _ if byte.is_ascii_whitespace() => Scan::Structure,
+                    _ if self.depth == 0 => {
+                        self.started = true;
+                        Scan::Scalar
+                    }
+                    _ => Scan::Structure,
+                },
+            };
+        }
+        None
+    }
+
+    fn open_str(&mut self, quote: u8) -> Scan {
+        self.started = true;
+        Scan::Str {
+            quote,
+            escape: false,
+        }
+    }
+
+    // Drops what's been parsed, ready for the next document.
+    fn reset(&mut self, end: usize) {
+        self.offset += end;
+        self.line += self.buf[..end].iter().filter(|&&b| b == b'\n').count();
+        self.buf.drain(..end);
+        self.scanned = 0;
+        self.scan = Scan::Structure;
+        self.depth = 0;
+        self.started = false;
+    }
+
+    fn span(&self, end: usize) -> Span {
+        Span {
+            start: self.offset,
+            end: self.offset + end,
+            line: self.line,
+            column: 1,
+        }
+    }
+}
+
+impl Default for Feeder {
+    fn default() -> Self {
+        Self::new(ParseOptions::default())
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{
+    alloc::Id,
+    codec::ToJson,
+    doc::{Document, ValueRef},
+    error::FlattenError,
+    map::Map,
+    value::JsonValue,
+};
+
+/// How `Document::flatten_with` writes paths, and how
+/// `Document::from_flat_map_with` reads them back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlattenOptions {
+    /// Goes between a key and the key of its member.
+    pub separator: String,
+    pub indices: IndexStyle,
+}
+
+/// `a.b[0].c`.
+impl Default for FlattenOptions {
+    fn default() -> Self {
+        Self {
+            separator: ".".into(),
+            indices: IndexStyle::Brackets,
+        }
+    }
+}
+
+/// How list indices are written in flat keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexStyle {
+    /// `tags[0]`.
+    Brackets,
+    /// `tags.0`, with the separator. Reading back, a key of digits is then
+    /// always an index.
+    Separated,
+}
+
+// A value being rebuilt from flat keys.
+enum Node<'f> {
+    // Made for a step, and not yet known to be an object, a list or a leaf.
+    Pending,
+    Leaf(ValueRef<'f>),
+    Object(Map<String, usize>),
+    // Gaps are left `null`.
+    List(Vec<Option<usize>>),
+}
+
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+impl Document {
+    /// An object of every scalar, and every empty object or list, keyed by
+    /// its path, like `{"a.b[0].c": 1}`, for tools that only take flat
+    /// records. A scalar root has the key `""`.
+    pub fn flatten(&self) -> Document {
+        self.flatten_with(FlattenOptions::default())
+    }
+
+    pub fn flatten_with(&self, opts: FlattenOptions) -> Document {
+        let mut flat = Document::default();
+        let mut members = Map::new();
+        let mut stack = vec![(self.value(), String::new())];
+        while let Some((value, path)) = stack.pop() {
+            let start = stack.len();
+            match value.value() {
+                JsonValue::Object(obj) if !obj.is_empty() => {
+                    for (key, &id) in obj {
+                        let path = match path.is_empty() {
+                            true => key.to_string(),
+                            false => format!("{path}{}{key}", opts.separator),
+                        };
+                        stack.push((value.child(id), path));
+                    }
+                }
+                JsonValue::List(list) if !list.is_empty() => {
+                    for (i, &id) in list.iter().enumerate() {
+                        let path = match opts.indices {
+                            IndexStyle::Brackets => format!("{path}[{i}]"),
+                            IndexStyle::Separated if path.is_empty() => i.to_string(),
+                            IndexStyle::Separated => format!("{path}{}{i}", opts.separator),
+                        };
+                        stack.push((value.child(id), path));
+                    }
+                }
+                _ => {
+                    let copy = value.to_json(flat.arena_mut());
+                    let id = flat.alloc(copy);
+                    members.insert(path.into(), id);
+                }
+            }
+            // In document order.
+            stack[start..].reverse();
+        }
+        *flat.root_mut() = JsonValue::Object(members);
+        flat
+    }
+
+    /// Rebuilds a document from what `flatten` made of it. Keys that contain
+    /// the separator, or look like indices, can't be told apart from paths,
+    /// so those don't come back as they were.
+    pub fn from_flat_map(flat: &Document) -> Result<Document, FlattenError> {
+        Self::from_flat_map_with(flat, FlattenOptions::default())
+    }
+
+    pub fn from_flat_map_with(
+        flat: &Document,
+        opts: FlattenOptions,
+    ) -> Result<Document, FlattenError> {
+        let fail = |key: &str, message| FlattenError {
+            key: key.to_owned(),
+            message,
+        };
+        let members = flat
+            .value()
+            .as_object()
+            .ok_or_else(|| fail("", "the flat map must be an object"))?;
+        // The root is the first, and a node's children come after it.
+        let mut nodes = vec![Node::Pending];
+        for (key, &id) in members {
+            let steps =
+                steps(key, &opts, members.len()).ok_or_else(|| fail(key, "invalid index"))?;
+            let mut at = 0;
+            for step in steps {
+                if let Node::Pending = nodes[at] {
+                    nodes[at] = match step {
+                        Step::Key(_) => Node::Object(Map::new()),
+                        Step::Index(_) => Node::List(Vec::new()),
+                    };
+                }
+                let fresh = nodes.len();
+                let next = match (&mut nodes[at], step) {
+                    (Node::Object(obj), Step::Key(k)) => match obj.get(&k) {
+                        Some(&next) => next,
+                        None => {
+                            obj.insert(k, fresh);
+                            fresh
+                        }
+                    },
+                    (Node::List(list), Step::Index(i)) => {
+                        if list.len() <= i {
+                            list.resize(i + 1, None);
+                        }
+                        *list[i].get_or_insert(fresh)
+                    }
+                    _ => return Err(fail(key, "conflicts with another key")),
+                };
+                if next == fresh {
+                    nodes.push(Node::Pending);
+                }
+                at = next;
+            }
+            match nodes[at] {
+                Node::Pending => nodes[at] = Node::Leaf(flat.value().child(id)),
+                _ => return Err(fail(key, "conflicts with another key")),
+            }
+        }
+        if members.is_empty() {
+            nodes[0] = Node::Object(Map::new());
+        }
+        Ok(build(nodes))
+    }
+}
+
+// The steps from the root that `key` takes. Indices can't reach past the
+// number of keys, so a stray one can't pad a list with any more nulls than
+// there are values.
+fn steps(key: &str, opts: &FlattenOptions, keys: usize) -> Option<Vec<Step>> {
+    let mut steps = Vec::new();
+    if key.is_empty() {
+        return Some(steps);
+    }
+    let segments: Vec<&str> = match opts.separator.is_empty() {
+        true => vec![key],
+        false => key.split(opts.separator.as_str()).collect(),
+    };
+    let index = |digits: &str| digits.parse().ok().filter(|&i: &usize| i < keys);
+    for mut segment in segments {
+        match opts.indices {
+            IndexStyle::Separated
+                if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                steps.push(Step::Index(index(segment)?));
+            }
+            IndexStyle::Separated => steps.push(Step::Key(segment.to_owned())),
+            IndexStyle::Brackets => {
+                // Trailing `[n]`s, last first.
+                let mut indices = Vec::new();
+                while let Some(rest) = segment.strip_suffix(']') {
+                    let Some((name, digits)) = rest.rsplit_once('[') else {
+                        break;
+                    };
+                    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                        break;
+                    }
+                    indices.push(index(digits)?);
+                    segment = name;
+                }
+                if !segment.is_empty() || indices.is_empty() {
+                    steps.push(Step::Key(segment.to_owned()));
+                }
+                steps.extend(indices.into_iter().rev().map(Step::Index));
+            }
+        }
+    }
+    Some(steps)
+}
+
+fn build(nodes: Vec<Node<'_>>) -> Document {
+    let mut doc = Document::default();
+    let mut ids: Vec<Option<Id<JsonValue>>> = vec![None; nodes.len()];
+    for (index, node) in nodes.into_iter().enumerate().rev() {
+        let value = match node {
+            Node::Pending => JsonValue::Null,
+            Node::Leaf(value) => value.to_json(doc.arena_mut()),
+            Node::Object(obj) => JsonValue::Object(
+                obj.into_iter()
+                    .map(|(key, child)| (key.into(), ids[child].take().unwrap()))
+                    .collect(),
+            ),
+            Node::List(list) => {
+                let ids: Vec<_> = list
+                    .into_iter()
+                    .map(|child| match child {
+                        Some(child) => ids[child].take().unwrap(),
+                        None => doc.alloc(JsonValue::Null),
+                    })
+                    .collect();
+                JsonValue::List(ids)
+            }
+        };
+        match index {
+            0 => *doc.root_mut() = value,
+            _ => ids[index] = Some(doc.alloc(value)),
+        }
+    }
+    doc
+}
@@ -0,0 +1,103 @@
+use std::ops::ControlFlow;
+
+use crate::{
+    error::ParseError,
+    options::ParseOptions,
+    pull::{Event, JsonPullParser},
+    source::Source,
+    value::Number,
+};
+
+/// Whether to report what's inside a container, or the value of a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visit {
+    Enter,
+    Skip,
+}
+
+/// Callbacks for `parse_events`. Every method defaults to carrying on, so
+/// a handler only implements what it cares about; returning `Break` stops
+/// the parse.
+pub trait JsonHandler {
+    /// A `Skip` leaves out everything up to and including the matching
+    /// `end_object`.
+    fn start_object(&mut self) -> ControlFlow<(), Visit> {
+        ControlFlow::Continue(Visit::Enter)
+    }
+
+    fn end_object(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// A `Skip` leaves out everything up to and including the matching
+    /// `end_array`.
+    fn start_array(&mut self) -> ControlFlow<(), Visit> {
+        ControlFlow::Continue(Visit::Enter)
+    }
+
+    fn end_array(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// A `Skip` leaves out the member's value.
+    fn key(&mut self, _key: &str) -> ControlFlow<(), Visit> {
+        ControlFlow::Continue(Visit::Enter)
+    }
+
+    fn string(&mut self, _s: &str) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    fn number(&mut self, _n: Number) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    fn bool(&mut self, _b: bool) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    fn null(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// Feeds every event of `parser` to `handler`. `Break` means the handler
+/// stopped early, in which case the rest of the input is left unchecked.
+pub fn drive<S: Source>(
+    parser: &mut JsonPullParser<S>,
+    handler: &mut impl JsonHandler,
+) -> Result<ControlFlow<()>, ParseError> {
+    while let Some(event) = parser.next_event()? {
+        let key = matches!(event, Event::Key(_));
+        let flow = match event {
+            Event::StartObject => handler.start_object(),
+            Event::StartArray => handler.start_array(),
+            Event::Key(key) => handler.key(key),
+            Event::EndObject => handler.end_object().map_continue(|()| Visit::Enter),
+            Event::EndArray => handler.end_array().map_continue(|()| Visit::Enter),
+            Event::String(s) => handler.string(s).map_continue(|()| Visit::Enter),
+            Event::Number(n) => handler.number(n).map_continue(|()| Visit::Enter),
+            Event::Bool(b) => handler.bool(b).map_continue(|()| Visit::Enter),
+            Event::Null => handler.null().map_continue(|()| Visit::Enter),
+        };
+        match flow {
+            ControlFlow::Break(()) => return Ok(ControlFlow::Break(())),
+            ControlFlow::Continue(Visit::Enter) => {}
+            ControlFlow::Continue(Visit::Skip) if key => {
+                parser.skip_value()?;
+            }
+            ControlFlow::Continue(Visit::Skip) => {
+                parser.skip_rest()?;
+            }
+        }
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+pub(crate) fn parse_events(
+    src: &str,
+    opts: ParseOptions,
+    handler: &mut impl JsonHandler,
+) -> Result<ControlFlow<()>, ParseError> {
+    drive(&mut JsonPullParser::with_options(src, opts), handler)
+}
@@ -0,0 +1,891 @@
+use std::{fmt, io::Read, ops::Range};
+
+use crate::{
+    options::ParseOptions,
+    source::{ReadSource, SliceSource, Source},
+    value::Number,
+};
+
+/// Tokenizer over a JSON source. Iterating yields tokens until the end of
+/// input or the first error.
+pub struct Lex<S> {
+    code: S,
+    offset: usize,
+    line: usize,
+    column: usize,
+    mark: Span,
+    opts: ParseOptions,
+    done: bool,
+    skim: bool,
+    // Whether the last string had an escape in it.
+    escaped: bool,
+    // Every byte consumed since `keep`, and the offset it started at.
+    kept: Option<(usize, Vec<u8>)>,
+}
+
+impl<'json> Lex<SliceSource<'json>> {
+    pub fn new(code: &'json str) -> Self {
+        Self::with_options(code, ParseOptions::default())
+    }
+
+    pub fn with_options(code: &'json str, opts: ParseOptions) -> Self {
+        Self::from_bytes(code.as_bytes(), opts)
+    }
+
+    /// Lexes raw bytes. Only string literals are checked for valid UTF-8;
+    /// everything else has to be ASCII to form a token anyway.
+    pub fn from_bytes(code: &'json [u8], opts: ParseOptions) -> Self {
+        Self::from_source(SliceSource::new(code), opts)
+    }
+}
+
+impl<R: Read> Lex<ReadSource<R>> {
+    pub fn from_reader(reader: R, opts: ParseOptions) -> Self {
+        Self::from_source(ReadSource::new(reader), opts)
+    }
+}
+
+impl<S: Source> Lex<S> {
+    pub fn from_source(code: S, opts: ParseOptions) -> Self {
+        Self {
+            code,
+            offset: 0,
+            line: 1,
+            column: 1,
+            mark: Span::default(),
+            opts,
+            done: false,
+            skim: false,
+            escaped: false,
+            kept: None,
+        }
+    }
+}
+
+/// Location of a token in the source. `start..end` are byte offsets, `line`
+/// and `column` (both 1-based, column counted in chars) point at `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Token {
+    /// A string literal with its escapes already decoded.
+    Str(String),
+    Num(Number),
+    False,
+    True,
+    Null,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Comma,
+    Colon,
+    /// An unquoted JSON5 object key. Only produced with `ParseOptions::json5`.
+    Ident(String),
+    /// End of input. Returned by `Lex::next_token`, never by the iterator.
+    Eof,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Str(s) => write!(f, "'\"{s}\"'"),
+            Token::Num(n) => write!(f, "'{n}'"),
+            Token::False => write!(f, "'false'"),
+            Token::True => write!(f, "'true'"),
+            Token::Null => write!(f, "'null'"),
+            Token::LBrace => write!(f, "'{{'"),
+            Token::RBrace => write!(f, "'}}'"),
+            Token::LBracket => write!(f, "'['"),
+            Token::RBracket => write!(f, "']'"),
+            Token::Comma => write!(f, "','"),
+            Token::Colon => write!(f, "':'"),
+            Token::Ident(s) => write!(f, "'{s}'"),
+            Token::Eof => write!(f, "end of input"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexError {
+    /// A bare word that isn't `true`, `false` or `null`, or a stray character.
+    InvalidLiteral {
+        text: String,
+        span: Span,
+    },
+    InvalidNumber {
+        lexeme: String,
+        span: Span,
+    },
+    InvalidEscape {
+        sequence: String,
+        span: Span,
+    },
+    ControlCharacter {
+        chr: char,
+        span: Span,
+    },
+    /// A string literal without its closing quote; the span is the opening one.
+    UnterminatedString {
+        span: Span,
+    },
+    /// A `/*` comment that never ends; the span is the opening `/*`.
+    UnterminatedComment {
+        span: Span,
+    },
+    /// A string literal that isn't valid UTF-8.
+    InvalidUtf8 {
+        span: Span,
+    },
+    /// The input starts with a byte order mark for an encoding other than UTF-8.
+    UnsupportedEncoding {
+        encoding: &'static str,
+        span: Span,
+    },
+    /// A string longer than `ParseOptions::max_string_length`; the span is
+    /// its opening quote.
+    StringTooLong {
+        max: usize,
+        span: Span,
+    },
+    /// More input than `ParseOptions::max_document_size`.
+    DocumentTooLarge {
+        max: usize,
+        span: Span,
+    },
+    /// The underlying reader failed.
+    Io {
+        kind: std::io::ErrorKind,
+        message: String,
+        span: Span,
+    },
+}
+
+impl LexError {
+    pub fn span(&self) -> Span {
+        match self {
+            LexError::InvalidLiteral { span, .. }
+            | LexError::InvalidNumber { span, .. }
+            | LexError::InvalidEscape { span, .. }
+            | LexError::ControlCharacter { span, .. }
+            | LexError::UnterminatedString { span }
+            | LexError::UnterminatedComment { span }
+            | LexError::InvalidUtf8 { span }
+            | LexError::UnsupportedEncoding { span, .. }
+            | LexError::StringTooLong { span, .. }
+            | LexError::DocumentTooLarge { span, .. }
+            | LexError::Io { span, .. } => *span,
+        }
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        crate::error::ParseError::from(self.clone()).fmt(f)
+    }
+}
+
+impl std::error::Error for LexError {}
+
+impl<S: Source> Iterator for Lex<S> {
+    type Item = Result<SpannedToken, LexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.next_token() {
+            Ok(SpannedToken {
+                token: Token::Eof, ..
+            }) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+            tok => Some(tok),
+        }
+    }
+}
+
+impl<S: Source> Lex<S> {
+    pub fn next_token(&mut self) -> Result<SpannedToken, LexError> {
+        if self.offset == 0 {
+            self.bom()?;
+        }
+        let token = self.skip_trivia().and_then(|()| {
+            self.mark();
+            self.token()
+        });
+        if let Some(e) = self.code.take_error() {
+            return Err(LexError::Io {
+                kind: e.kind(),
+                message: e.to_string(),
+                span: self.span(),
+            });
+        }
+        let token = token?;
+        self.check_size()?;
+        let span = self.span();
+        Ok(SpannedToken { token, span })
+    }
+
+    /// Like `next_token`, but strings come back empty: their escapes and
+    /// closing quote are checked and their contents thrown away.
+    pub(crate) fn skim_token(&mut self) -> Result<SpannedToken, LexError> {
+        self.skim = true;
+        let tok = self.next_token();
+        self.skim = false;
+        tok
+    }
+
+    /// Whether the last string token had escapes in it. Without any, its
+    /// text is the source between its quotes.
+    pub(crate) fn escaped(&self) -> bool {
+        self.escaped
+    }
+
+    // Starts keeping a copy of the input from here on, so `JsonValue::Raw`
+    // can hold its text whatever the source.
+    pub(crate) fn keep(&mut self) {
+        self.kept = Some((self.offset, Vec::new()));
+    }
+
+    // Stops keeping the input, returning the part of it at `span`, if it was
+    // all kept.
+    pub(crate) fn take_kept(&mut self, span: Range<usize>) -> Option<String> {
+        let (from, kept) = self.kept.take()?;
+        let text = kept.get(span.start.checked_sub(from)?..span.end - from)?;
+        Some(String::from_utf8_lossy(text).into_owned())
+    }
+
+    // Also called while skipping whitespace and inside strings, so endless
+    // input can't keep the lexer busy past the limit.
+    fn check_size(&self) -> Result<(), LexError> {
+        match self.opts.max_document_size {
+            Some(max) if self.offset > max => Err(LexError::DocumentTooLarge {
+                max,
+                span: self.span(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn skip_trivia(&mut self) -> Result<(), LexError> {
+        loop {
+            self.check_size()?;
+            if self.run(|b| matches!(b, b' ' | b'\n' | b'\t' | b'\r'), None) > 0 {
+                continue;
+            }
+            match self.code.peek() {
+                Some(b' ' | b'\n' | b'\t' | b'\r') => {
+                    self.bump();
+                }
+                Some(0x0B | 0x0C) if self.opts.json5() => {
+                    self.bump();
+                }
+                Some(b'/') if self.opts.comments() => self.comment()?,
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn comment(&mut self) -> Result<(), LexError> {
+        self.mark();
+        self.bump();
+        match self.code.peek() {
+            Some(b'/') => {
+                while self.code.peek().is_some_and(|b| b != b'\n') {
+                    self.bump();
+                }
+                Ok(())
+            }
+            Some(b'*') => {
+                self.bump();
+                let open = self.span();
+                let mut star = false;
+                loop {
+                    match self.bump() {
+                        None => return Err(LexError::UnterminatedComment { span: open }),
+                        Some(b'/') if star => return Ok(()),
+                        Some(b) => star = b == b'*',
+                    }
+                }
+            }
+            _ => Err(LexError::InvalidLiteral {
+                text: "/".to_string(),
+                span: self.span(),
+            }),
+        }
+    }
+
+    // A UTF-8 byte order mark may precede the first token and is skipped. A
+    // UTF-16 one means the input isn't UTF-8 at all.
+    fn bom(&mut self) -> Result<(), LexError> {
+        self.mark();
+        let (trail, encoding): (&[u8], _) = match self.code.peek() {
+            Some(0xEF) => (&[0xBB, 0xBF], None),
+            Some(0xFE) => (&[0xFF], Some("UTF-16BE")),
+            Some(0xFF) => (&[0xFE], Some("UTF-16LE")),
+            _ => return Ok(()),
+        };
+        self.bump();
+        for &expected in trail {
+            if self.code.peek() != Some(expected) {
+                return Err(LexError::InvalidUtf8 { span: self.span() });
+            }
+            self.bump();
+        }
+        match encoding {
+            Some(encoding) => Err(LexError::UnsupportedEncoding {
+                encoding,
+                span: self.span(),
+            }),
+            None => {
+                self.column = 1;
+                Ok(())
+            }
+        }
+    }
+
+    fn span(&self) -> Span {
+        Span {
+            end: self.offset,
+            ..self.mark
+        }
+    }
+
+    // Start the span of the token being lexed at the current position. Lexing
+    // errors re-mark so the span points at the offending part of the token.
+    fn mark(&mut self) {
+        self.mark = Span {
+            start: self.offset,
+            end: self.offset,
+            line: self.line,
+            column: self.column,
+        };
+    }
+
+    fn bump(&mut self) -> Option<u8> {
+        let byte = self.code.bump()?;
+        if let Some((_, kept)) = &mut self.kept {
+            kept.push(byte);
+        }
+        self.offset += 1;
+        if byte == b'\n' {
+            self.line += 1;
+            self.column = 1;
+        } else if !is_continuation(byte) {
+            self.column += 1;
+        }
+        Some(byte)
+    }
+
+    // Moves past the bytes at hand that `plain` accepts in a row, in one
+    // pass instead of a `bump` each, adding them to `out` if there is one.
+    // Stops where `max_document_size` would, so a run trips the limit just
+    // where single bytes do. Returns how many bytes there were.
+    fn run(&mut self, plain: impl Fn(u8) -> bool, out: Option<&mut Vec<u8>>) -> usize {
+        let left = self.left();
+        let bytes = self.code.buffered();
+        let bytes = &bytes[..bytes.len().min(left)];
+        let (mut line, mut column) = (self.line, self.column);
+        let mut n = 0;
+        for &b in bytes {
+            if !plain(b) {
+                break;
+            }
+            if b == b'\n' {
+                line += 1;
+                column = 1;
+            } else if !is_continuation(b) {
+                column += 1;
+            }
+            n += 1;
+        }
+        if let Some(out) = out {
+            out.extend_from_slice(&bytes[..n]);
+        }
+        if let Some((_, kept)) = &mut self.kept {
+            kept.extend_from_slice(&bytes[..n]);
+        }
+        self.code.skip(n);
+        self.offset += n;
+        self.line = line;
+        self.column = column;
+        n
+    }
+
+    // A string's bytes up to its next quote, escape or control character,
+    // found a block at a time with the `simd` feature. Control characters
+    // let a string hold newlines, which blocks don't count, so those go
+    // through `run`.
+    fn string_run(&mut self, close: u8, control: bool, out: Option<&mut Vec<u8>>) {
+        #[cfg(feature = "simd")]
+        if !control {
+            let left = self.left();
+            let bytes = self.code.buffered();
+            let bytes = &bytes[..bytes.len().min(left)];
+            let (n, chars) = crate::simd::string_run(bytes, close);
+            if let Some(out) = out {
+                out.extend_from_slice(&bytes[..n]);
+            }
+            if let Some((_, kept)) = &mut self.kept {
+                kept.extend_from_slice(&bytes[..n]);
+            }
+            self.code.skip(n);
+            self.offset += n;
+            self.column += chars;
+            return;
+        }
+        self.run(|b| b != close && b != b'\\' && (b >= b' ' || control), out);
+    }
+
+    fn left(&self) -> usize {
+        match self.opts.max_document_size {
+            Some(max) => max.saturating_sub(self.offset),
+            None => usize::MAX,
+        }
+    }
+
+    fn token(&mut self) -> Result<Token, LexError> {
+        if let Some(byte) = self.code.peek() {
+            match byte {
+                b'"' => self.str(b'"'),
+                b'\'' if self.opts.json5() => self.str(b'\''),
+                b':' => self.just(Token::Colon),
+                b',' => self.just(Token::Comma),
+                b'[' => self.just(Token::LBracket),
+                b']' => self.just(Token::RBracket),
+                b'{' => self.just(Token::LBrace),
+                b'}' => self.just(Token::RBrace),
+                b'0'..=b'9' | b'-' => self.num(),
+                b'+' | b'.' if self.opts.json5() => self.num(),
+                _ => self.ident(),
+            }
+        } else {
+            Ok(Token::Eof)
+        }
+    }
+
+    // Collects raw bytes and checks UTF-8 once per string, which is the only
+    // place non-ASCII input is allowed anyway.
+    fn str(&mut self, close: u8) -> Result<Token, LexError> {
+        let quote = Span {
+            end: self.offset + 1,
+            ..self.mark
+        };
+        self.bump();
+        self.escaped = false;
+        let mut s = Vec::new();
+        let control = self.opts.control_characters();
+        loop {
+            let out = (!self.skim).then_some(&mut s);
+            self.string_run(close, control, out);
+            match self.opts.max_string_length {
+                Some(max) if s.len() > max => {
+                    return Err(LexError::StringTooLong { max, span: quote })
+                }
+                _ => self.check_size()?,
+            }
+            let Some(byte) = self.code.peek() else {
+                return Err(LexError::UnterminatedString { span: quote });
+            };
+            match byte {
+                b if b == close => {
+                    self.bump();
+                    break;
+                }
+                b'\\' => {
+                    self.escaped = true;
+                    match self.escape() {
+                        Ok(Some(chr)) if !self.skim => {
+                            s.extend_from_slice(chr.encode_utf8(&mut [0; 4]).as_bytes())
+                        }
+                        Ok(_) => {}
+                        Err(sequence) => {
+                            return Err(LexError::InvalidEscape {
+                                sequence,
+                                span: self.span(),
+                            })
+                        }
+                    }
+                }
+                b if b < b' ' && !self.opts.control_characters() => {
+                    self.mark();
+                    self.bump();
+                    return Err(LexError::ControlCharacter {
+                        chr: b as char,
+                        span: self.span(),
+                    });
+                }
+                _ => {
+                    self.bump();
+                    if !self.skim {
+                        s.push(byte);
+                    }
+                }
+            }
+        }
+        // Escapes re-mark for their errors, but the token is the whole string.
+        self.mark = Span {
+            end: quote.start,
+            ..quote
+        };
+        if self.skim {
+            return Ok(Token::Str(String::new()));
+        }
+        match String::from_utf8(s) {
+            Ok(s) => Ok(Token::Str(s)),
+            Err(_) => Err(LexError::InvalidUtf8 { span: self.span() }),
+        }
+    }
+
+    // `None` is a JSON5 line continuation, which contributes nothing.
+    fn escape(&mut self) -> Result<Option<char>, String> {
+        self.mark();
+        let mut seq = String::new();
+        seq.extend(self.bump().map(char::from));
+        let chr = match self.bump() {
+            Some(b) if b.is_ascii() => {
+                seq.push(b as char);
+                b
+            }
+            _ => return Err(seq),
+        };
+        let chr = match chr {
+            b'"' => '"',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\u{8}',
+            b'f' => '\u{c}',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => return self.unicode_escape(seq).map(Some),
+            _ if self.opts.json5() => return self.json5_escape(chr, seq),
+            _ => return Err(seq),
+        };
+        Ok(Some(chr))
+    }
+
+    fn json5_escape(&mut self, chr: u8, mut seq: String) -> Result<Option<char>, String> {
+        match chr {
+            b'\'' => Ok(Some('\'')),
+            b'v' => Ok(Some('\u{b}')),
+            b'0' if !self.code.peek().is_some_and(|b| b.is_ascii_digit()) => Ok(Some('\0')),
+            b'x' => {
+                let code = self.hex(2, &mut seq)?;
+                Ok(char::from_u32(code))
+            }
+            b'\n' => Ok(None),
+            b'\r' => {
+                if self.code.peek() == Some(b'\n') {
+                    self.bump();
+                }
+                Ok(None)
+            }
+            _ => Err(seq),
+        }
+    }
+
+    fn unicode_escape(&mut self, mut seq: String) -> Result<char, String> {
+        let hi = self.hex(4, &mut seq)?;
+        match hi {
+            0xD800..=0xDBFF => {
+                for expected in [b'\\', b'u'] {
+                    match self.code.peek() {
+                        Some(b) if b == expected => {
+                            self.bump();
+                            seq.push(b as char);
+                        }
+                        _ => return Err(seq),
+                    }
+                }
+                let lo = self.hex(4, &mut seq)?;
+                if !(0xDC00..=0xDFFF).contains(&lo) {
+                    return Err(seq);
+                }
+                let code = 0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00);
+                char::from_u32(code).ok_or(seq)
+            }
+            0xDC00..=0xDFFF => Err(seq),
+            _ => char::from_u32(hi).ok_or(seq),
+        }
+    }
+
+    fn hex(&mut self, digits: usize, seq: &mut String) -> Result<u32, String> {
+        let mut code = 0;
+        for _ in 0..digits {
+            match self.code.peek().and_then(|b| (b as char).to_digit(16)) {
+                Some(digit) => {
+                    seq.extend(self.bump().map(char::from));
+                    code = code * 16 + digit;
+                }
+                None => return Err(std::mem::take(seq)),
+            }
+        }
+        Ok(code)
+    }
+
+    fn num(&mut self) -> Result<Token, LexError> {
+        if let Some(n) = self.small_int() {
+            return Ok(Token::Num(Number::I64(n)));
+        }
+        let mut s = String::new();
+        let mut is_float = false;
+        let mut is_exp = false;
+        match self.code.peek() {
+            Some(b'-') => s.push(self.bump_char()),
+            Some(b'+') if self.opts.json5() => s.push(self.bump_char()),
+            _ => {}
+        }
+        if self.opts.non_finite_numbers() && matches!(self.code.peek(), Some(b'I' | b'N')) {
+            return self.non_finite(s);
+        }
+        while let Some(byte) = self.code.peek() {
+            match byte {
+                b'0'..=b'9' => s.push(self.bump_char()),
+                b'x' | b'X' if self.opts.json5() && s.trim_start_matches(['+', '-']) == "0" => {
+                    return self.hex_number(s);
+                }
+                b'.' if !is_float => {
+                    s.push(self.bump_char());
+                    is_float = true;
+                }
+                b'e' | b'E' if !is_exp => {
+                    s.push(self.bump_char());
+                    if let Some(b'+' | b'-') = self.code.peek() {
+                        s.push(self.bump_char());
+                    }
+                    is_float = true;
+                    is_exp = true;
+                }
+                _ => break,
+            }
+        }
+
+        if self.opts.strict && !is_rfc_number(&s) {
+            return Err(self.invalid_number(s));
+        }
+
+        // Integers too large for 64 bits still parse, as floats.
+        let n = match (is_float, s.parse::<i64>(), s.parse::<u64>()) {
+            (false, Ok(0), _) if s.starts_with('-') => Number::F64(-0.0),
+            (false, Ok(n), _) => Number::I64(n),
+            (false, _, Ok(n)) => Number::U64(n),
+            #[cfg(feature = "arbitrary-precision")]
+            _ if s.parse::<f64>().is_ok() => Number::Decimal(rfc_spelling(s)),
+            // Literals too large even for a float would come out infinite,
+            // which only `allow_non_finite_numbers` lets in.
+            _ => match s.parse::<f64>() {
+                Ok(n) if n.is_finite() || self.opts.non_finite_numbers() => Number::F64(n),
+                _ => return Err(self.invalid_number(s)),
+            },
+        };
+        Ok(Token::Num(n))
+    }
+
+    // An integer of up to 18 digits, which always fits an `i64`, read
+    // straight from the bytes at hand when they hold all of it. Anything
+    // `num` would treat differently (`-0`, leading zeros, fractions) is left
+    // to it.
+    fn small_int(&mut self) -> Option<i64> {
+        let bytes = self.code.buffered();
+        let negative = bytes.first() == Some(&b'-');
+        let digits = &bytes[usize::from(negative)..];
+        let len = digits.iter().position(|b| !b.is_ascii_digit())?;
+        if len == 0
+            || len > 18
+            || len > 1 && digits[0] == b'0'
+            || matches!(digits[len], b'.' | b'e' | b'E' | b'x' | b'X')
+        {
+            return None;
+        }
+        let n = digits[..len]
+            .iter()
+            .fold(0, |n, &d| n * 10 + i64::from(d - b'0'));
+        if negative && n == 0 {
+            return None;
+        }
+        let len = len + usize::from(negative);
+        if let Some((_, kept)) = &mut self.kept {
+            kept.extend_from_slice(&bytes[..len]);
+        }
+        self.code.skip(len);
+        self.offset += len;
+        self.column += len;
+        Some(if negative { -n } else { n })
+    }
+
+    fn hex_number(&mut self, mut s: String) -> Result<Token, LexError> {
+        s.push(self.bump_char());
+        let start = s.len();
+        while let Some(b'0'..=b'9' | b'a'..=b'f' | b'A'..=b'F') = self.code.peek() {
+            s.push(self.bump_char());
+        }
+        match u64::from_str_radix(&s[start..], 16) {
+            Ok(n) if s.starts_with('-') => Ok(Token::Num(
+                0i64.checked_sub_unsigned(n)
+                    .map_or(Number::F64(-(n as f64)), Number::I64),
+            )),
+            Ok(n) => Ok(Token::Num(Number::from(n))),
+            Err(_) => Err(self.invalid_number(s)),
+        }
+    }
+
+    // `Infinity` or `NaN` after an optional sign, which is already in `s`.
+    fn non_finite(&mut self, mut s: String) -> Result<Token, LexError> {
+        while let Some(byte) = self.code.peek().filter(u8::is_ascii_alphanumeric) {
+            self.bump();
+            s.push(char::from(byte));
+        }
+        let n = match s.trim_start_matches(['+', '-']) {
+            "Infinity" => f64::INFINITY,
+            // Python never writes a signed NaN; JSON5 allows one.
+            "NaN" if s.len() == 3 || self.opts.json5() => f64::NAN,
+            _ => return Err(self.invalid_number(s)),
+        };
+        Ok(Token::Num(Number::F64(if s.starts_with('-') {
+            -n
+        } else {
+            n
+        })))
+    }
+
+    // Only for bytes already known to be ASCII.
+    fn bump_char(&mut self) -> char {
+        self.bump().map_or('\0', char::from)
+    }
+
+    fn invalid_number(&self, lexeme: String) -> LexError {
+        LexError::InvalidNumber {
+            lexeme,
+            span: self.span(),
+        }
+    }
+
+    fn just(&mut self, t: Token) -> Result<Token, LexError> {
+        self.bump();
+        Ok(t)
+    }
+
+    fn ident(&mut self) -> Result<Token, LexError> {
+        let json5 = self.opts.json5();
+        let non_finite = self.opts.non_finite_numbers();
+        let word = |b: u8| b.is_ascii_alphanumeric() || json5 && matches!(b, b'_' | b'$');
+        // The literals, without collecting them, when the bytes at hand
+        // hold all of one and what follows it.
+        let bytes = self.code.buffered();
+        let literal = match bytes.iter().position(|&b| !word(b)).map(|n| &bytes[..n]) {
+            Some(b"false") => Some(Token::False),
+            Some(b"true") => Some(Token::True),
+            Some(b"null") => Some(Token::Null),
+            _ => None,
+        };
+        if let Some(token) = literal {
+            self.run(word, None);
+            return Ok(token);
+        }
+        let mut s = Vec::new();
+        while let Some(byte) = self.code.peek() {
+            if word(byte) {
+                s.extend(self.bump());
+            } else {
+                break;
+            }
+        }
+        if s.is_empty() {
+            // A stray character; take all of it so the error can show it.
+            s.extend(self.bump());
+            while let Some(byte) = self.code.peek().filter(|b| is_continuation(*b)) {
+                self.bump();
+                s.push(byte);
+            }
+        }
+        match &s[..] {
+            b"false" => Ok(Token::False),
+            b"true" => Ok(Token::True),
+            b"null" => Ok(Token::Null),
+            b"Infinity" if non_finite => Ok(Token::Num(Number::F64(f64::INFINITY))),
+            b"NaN" if non_finite => Ok(Token::Num(Number::F64(f64::NAN))),
+            [b'A'..=b'Z' | b'a'..=b'z' | b'_' | b'$', ..] if json5 => {
+                Ok(Token::Ident(String::from_utf8_lossy(&s).into_owned()))
+            }
+            _ => Err(LexError::InvalidLiteral {
+                text: String::from_utf8_lossy(&s).into_owned(),
+                span: self.span(),
+            }),
+        }
+    }
+}
+
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+// How RFC 8259 spells a number the lenient or JSON5 lexer accepted, since a
+// `Decimal` is written back out as it is kept: without a `+`, leading
+// zeros or a point missing digits on either side.
+#[cfg(feature = "arbitrary-precision")]
+fn rfc_spelling(s: String) -> String {
+    if is_rfc_number(&s) {
+        return s;
+    }
+    let (sign, rest) = match s.as_bytes().first() {
+        Some(b'-') => ("-", &s[1..]),
+        Some(b'+') => ("", &s[1..]),
+        _ => ("", &s[..]),
+    };
+    let (int, rest) = rest.split_at(rest.find(['.', 'e', 'E']).unwrap_or(rest.len()));
+    let (frac, exp) = match rest.strip_prefix('.') {
+        Some(rest) => rest.split_at(rest.find(['e', 'E']).unwrap_or(rest.len())),
+        None => ("", rest),
+    };
+    let int = int.trim_start_matches('0');
+    let mut out = String::from(sign);
+    out.push_str(if int.is_empty() { "0" } else { int });
+    if !frac.is_empty() {
+        out.push('.');
+        out.push_str(frac);
+    }
+    out.push_str(exp);
+    out
+}
+
+// The parts of the RFC 8259 number grammar that `Lex::num` lets slide:
+// no leading zeros and at least one digit after the decimal point.
+fn is_rfc_number(s: &str) -> bool {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    let int_len = digits
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(digits.len());
+    if int_len == 0 || int_len > 1 && digits.starts_with('0') {
+        return false;
+    }
+    match digits[int_len..].strip_prefix('.') {
+        Some(frac) => frac.starts_with(|c: char| c.is_ascii_digit()),
+        None => true,
+    }
+}
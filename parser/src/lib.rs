@@ -0,0 +1,337 @@
+#[macro_use]
+mod trace;
+
+mod macros;
+
+#[cfg(feature = "async")]
+mod aio;
+mod alloc;
+#[cfg(any(feature = "msgpack", feature = "cbor", feature = "base64"))]
+mod base64;
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+mod binary;
+mod borrowed;
+mod build;
+mod canonical;
+#[cfg(feature = "cbor")]
+mod cbor;
+mod cmp;
+mod codec;
+mod codegen;
+mod convert;
+mod cst;
+mod csv;
+mod diff;
+mod doc;
+mod encoding;
+mod eq;
+mod error;
+mod feed;
+mod flatten;
+mod handler;
+mod key;
+mod lex;
+mod lines;
+mod map;
+mod merge;
+mod minify;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+mod options;
+mod owned;
+mod par;
+mod parser;
+mod patch;
+mod path;
+mod pointer;
+mod project;
+mod pull;
+mod redact;
+mod regex;
+mod schema;
+mod ser;
+#[cfg(feature = "simd")]
+mod simd;
+mod source;
+mod stats;
+mod stream;
+mod tape;
+mod toml;
+mod urlencoded;
+mod value;
+mod walk;
+mod writer;
+mod yaml;
+
+#[cfg(feature = "async")]
+pub use aio::{AsyncLines, AsyncRead};
+pub use alloc::{Allocator, Id, Storage};
+pub use borrowed::BorrowedValue;
+pub use build::{ArrayBuilder, ObjectBuilder};
+pub use codec::{FromJson, ToJson};
+pub use codegen::CodegenOptions;
+pub use cst::{
+    Cst, CstKind, GreenElement, GreenNode, GreenToken, SyntaxElement, SyntaxNode, SyntaxToken,
+};
+pub use csv::CsvOptions;
+pub use diff::{Change, DiffOptions};
+pub use doc::{Children, Descendants, Document, ListMut, ObjectMut, ValueIndex, ValueRef};
+pub use eq::EqOptions;
+pub use error::{
+    BinaryError, CanonicalError, CsvError, DecodeError, Diagnostic, ErrorKind, FlattenError,
+    FormatError, FromStrError, ParseError, PatchError, PathError, SchemaError, Severity, TomlError,
+    TypeError, UrlencodedError, Violation, WriteError, YamlError,
+};
+pub use feed::{Feeder, Poll};
+pub use flatten::{FlattenOptions, IndexStyle};
+pub use handler::{drive, JsonHandler, Visit};
+#[cfg(feature = "derive")]
+pub use jsonparser_derive::{FromJson, ToJson};
+pub use key::Key;
+pub use lex::{Lex, LexError, Span, SpannedToken, Token};
+pub use lines::Lines;
+pub use map::{IndexMap, Map};
+pub use merge::{ArrayMerge, MergeStrategy, NullMerge};
+pub use options::{DuplicateKeys, ParseOptions};
+pub use owned::OwnedValue;
+pub use par::Par;
+pub use parser::Parser;
+pub use path::JsonPath;
+pub use pull::{Event, JsonPullParser};
+pub use redact::{Mask, Redactor};
+pub use schema::Schema;
+pub use ser::FormatOptions;
+pub use source::{ReadSource, SliceSource, Source};
+pub use stats::{PathProfile, Stats};
+pub use stream::Stream;
+pub use tape::{Cursor, Elements, Members, Tape};
+pub use value::{JsonValue, Number};
+pub use walk::WalkOrder;
+pub use writer::JsonWriter;
+
+pub fn parse(src: &str, mem: usize) -> Result<Vec<Document>, ParseError> {
+    Par::parse(src, mem, ParseOptions::default())
+}
+
+pub fn parse_value(src: &str) -> Result<Document, ParseError> {
+    parse_value_with(src, ParseOptions::default())
+}
+
+pub fn parse_value_with(src: &str, opts: ParseOptions) -> Result<Document, ParseError> {
+    Par::parse_value(src, 1 << 4, opts)
+}
+
+/// Parses a single value into a tree whose strings and keys point into
+/// `src` instead of being copied, except those with escapes to decode.
+pub fn parse_borrowed(src: &str) -> Result<BorrowedValue<'_>, ParseError> {
+    parse_borrowed_with(src, ParseOptions::default())
+}
+
+/// `max_nodes` and `raw_keys` don't apply, since nothing goes in an arena.
+pub fn parse_borrowed_with(src: &str, opts: ParseOptions) -> Result<BorrowedValue<'_>, ParseError> {
+    borrowed::parse(src, opts)
+}
+
+/// Parses a single value onto a `Tape`, for reading without the per-node
+/// allocations of a `Document`.
+pub fn parse_tape(src: &str) -> Result<Tape, ParseError> {
+    parse_tape_with(src, ParseOptions::default())
+}
+
+/// `max_nodes` and `raw_keys` don't apply, since nothing goes in an arena.
+pub fn parse_tape_with(src: &str, opts: ParseOptions) -> Result<Tape, ParseError> {
+    tape::parse(src, opts)
+}
+
+/// Parses a single value and decodes it as a `T`.
+///
+/// This is the crate's answer to serde's `from_str`, not serde itself: the
+/// crate builds without any dependency, so there is no `serde` feature and
+/// no `Serialize`, `Deserialize` or `Deserializer` impls. Types decode
+/// through `FromJson` instead, which the `derive` feature can implement.
+pub fn from_str<T: FromJson>(src: &str) -> Result<T, FromStrError> {
+    from_str_with(src, ParseOptions::default())
+}
+
+pub fn from_str_with<T: FromJson>(src: &str, opts: ParseOptions) -> Result<T, FromStrError> {
+    Ok(parse_value_with(src, opts)?.decode()?)
+}
+
+pub fn parse_bytes(src: &[u8]) -> Result<Document, ParseError> {
+    parse_bytes_with(src, ParseOptions::default())
+}
+
+pub fn parse_bytes_with(src: &[u8], opts: ParseOptions) -> Result<Document, ParseError> {
+    Par::parse_bytes(src, 1 << 4, opts)
+}
+
+pub fn parse_reader(reader: impl std::io::Read) -> Result<Document, ParseError> {
+    Par::parse_reader(reader, ParseOptions::default())
+}
+
+/// Parses newline-delimited JSON: one document per line, skipping blank
+/// lines. Errors carry their line in the whole input, and a bad line doesn't
+/// stop the ones after it; a read error ends the iteration.
+pub fn parse_lines<R: std::io::BufRead>(reader: R) -> Lines<R> {
+    parse_lines_with(reader, ParseOptions::default())
+}
+
+pub fn parse_lines_with<R: std::io::BufRead>(reader: R, opts: ParseOptions) -> Lines<R> {
+    Lines::new(reader, opts)
+}
+
+/// Parses one value from the start of `src` and returns the rest, for JSON
+/// embedded in a larger format.
+pub fn parse_prefix(src: &str) -> Result<(Document, &str), ParseError> {
+    parse_prefix_with(src, ParseOptions::default())
+}
+
+pub fn parse_prefix_with(src: &str, opts: ParseOptions) -> Result<(Document, &str), ParseError> {
+    Par::parse_prefix(src, 1 << 4, opts)
+}
+
+/// Reads all of `reader` without blocking, then parses it like `parse_bytes`.
+#[cfg(feature = "async")]
+pub async fn parse_async(reader: impl AsyncRead + Unpin) -> Result<Document, ParseError> {
+    parse_async_with(reader, ParseOptions::default()).await
+}
+
+#[cfg(feature = "async")]
+pub async fn parse_async_with(
+    reader: impl AsyncRead + Unpin,
+    opts: ParseOptions,
+) -> Result<Document, ParseError> {
+    aio::parse(reader, opts).await
+}
+
+/// `parse_lines` for a reader that is waited on.
+#[cfg(feature = "async")]
+pub fn parse_lines_async<R: AsyncRead + Unpin>(reader: R) -> AsyncLines<R> {
+    AsyncLines::new(reader, ParseOptions::default())
+}
+
+/// Parses a sequence of top-level values. Outside strict mode they may be
+/// separated by commas.
+pub fn parse_many(src: &str) -> Result<Vec<Document>, ParseError> {
+    parse_many_with(src, ParseOptions::default())
+}
+
+pub fn parse_many_with(src: &str, opts: ParseOptions) -> Result<Vec<Document>, ParseError> {
+    Par::parse(src, 1 << 4, opts)
+}
+
+/// Parses values written one after another, as `jq -c` or a log would, with
+/// or without whitespace between them; see `Stream`.
+pub fn parse_stream(src: &str) -> Stream<SliceSource<'_>> {
+    parse_stream_with(src, ParseOptions::default())
+}
+
+pub fn parse_stream_with(src: &str, opts: ParseOptions) -> Stream<SliceSource<'_>> {
+    Stream::new(src, opts)
+}
+
+/// Parses a single value into a lossless syntax tree, which keeps every bit
+/// of whitespace and every comment, along with its `Document`.
+pub fn parse_cst(src: &str) -> Result<Cst, ParseError> {
+    parse_cst_with(src, ParseOptions::default())
+}
+
+pub fn parse_cst_with(src: &str, opts: ParseOptions) -> Result<Cst, ParseError> {
+    cst::parse(src, opts)
+}
+
+/// Strips the whitespace, and comments if `allow_comments` is set, from
+/// between the tokens of `src`. Only the tokens are checked, not their
+/// order, and their text is kept as written.
+pub fn minify(src: &str) -> Result<String, ParseError> {
+    minify_with(src, ParseOptions::default())
+}
+
+pub fn minify_with(src: &str, opts: ParseOptions) -> Result<String, ParseError> {
+    minify::minify(src, opts)
+}
+
+/// An RFC 6902 JSON Patch that turns `a` into `b`, for `apply_patch`.
+/// Lists are compared by longest common subsequence, so an element added in
+/// the middle is one `add`, and an element that only changed place is one
+/// `move`, as is a member only renamed. Objects and lists that changed are
+/// patched inside rather than replaced.
+pub fn diff(a: &Document, b: &Document) -> Document {
+    diff::diff(a.value(), b.value())
+}
+
+/// A JSON Schema that all of `samples` satisfy: the types seen at each
+/// place, with `null` in the union where a value was sometimes null, the
+/// members of objects, `required` for those every sample had, and the
+/// `items` of lists. Integers and fractions together make a `number`.
+pub fn infer_schema(samples: &[Document]) -> Document {
+    schema::infer(samples)
+}
+
+/// Rust definitions for types that `samples` decode into: a struct for
+/// each kind of object, named after its key, with `Option`s for members
+/// that were sometimes missing or null and `Vec`s for lists. Objects in the
+/// same list share a struct.
+pub fn codegen(samples: &[Document], opts: &CodegenOptions) -> String {
+    codegen::generate(samples, opts)
+}
+
+/// Reads a MessagePack value. Binary values become strings of their bytes
+/// in URL-safe base64 without padding, and integer map keys their decimal
+/// text; extension types have no JSON equivalent and are an error.
+#[cfg(feature = "msgpack")]
+pub fn parse_msgpack(src: &[u8]) -> Result<Document, BinaryError> {
+    msgpack::parse(src)
+}
+
+/// Reads a CBOR data item, converting what JSON has no value for as RFC
+/// 8949 suggests: byte strings become URL-safe base64 without padding, or
+/// padded standard base64 or lowercase hex when tagged 22 or 23 expects it;
+/// infinities, NaN, `undefined` and other simple values become `null`;
+/// integer map keys become their decimal text; other tags are dropped.
+#[cfg(feature = "cbor")]
+pub fn parse_cbor(src: &[u8]) -> Result<Document, BinaryError> {
+    cbor::parse(src)
+}
+
+/// Reads a YAML document, limited to what JSON can hold: block and flow
+/// collections with string keys, and scalars read by the YAML 1.2 core
+/// schema. Anchors, aliases, tags, explicit `?` keys, plain scalars that go
+/// on over more than one line, and more than one document are errors, as
+/// are duplicate keys.
+#[cfg(feature = "yaml")]
+pub fn parse_yaml(src: &str) -> Result<Document, YamlError> {
+    yaml::parse(src)
+}
+
+/// Checks `src` the way `parse_value` would, without building the document.
+pub fn is_valid(src: &str) -> Result<(), ParseError> {
+    is_valid_with(src, ParseOptions::default())
+}
+
+pub fn is_valid_with(src: &str, opts: ParseOptions) -> Result<(), ParseError> {
+    Par::validate(src, opts)
+}
+
+/// Reports each part of `src` to `handler` as it is parsed, without building
+/// a document.
+pub fn parse_events(
+    src: &str,
+    handler: &mut impl JsonHandler,
+) -> Result<std::ops::ControlFlow<()>, ParseError> {
+    parse_events_with(src, ParseOptions::default(), handler)
+}
+
+pub fn parse_events_with(
+    src: &str,
+    opts: ParseOptions,
+    handler: &mut impl JsonHandler,
+) -> Result<std::ops::ControlFlow<()>, ParseError> {
+    handler::parse_events(src, opts, handler)
+}
+
+/// Parses a single value, collecting every problem instead of failing on the
+/// first; see `Par::parse_with_diagnostics`.
+pub fn parse_with_diagnostics(src: &str, opts: ParseOptions) -> (Document, Vec<Diagnostic>) {
+    Par::parse_with_diagnostics(src, 1 << 4, opts)
+}
@@ -0,0 +1,87 @@
+use std::io::BufRead;
+
+use crate::{doc::Document, error::ParseError, lex::Span, options::ParseOptions, par::Par};
+
+/// The documents of newline-delimited JSON, one per line; see `parse_lines`.
+pub struct Lines<R> {
+    reader: R,
+    opts: ParseOptions,
+    buf: Vec<u8>,
+    // Where the next line starts, and its number counting from 1.
+    offset: usize,
+    line: usize,
+    done: bool,
+}
+
+impl<R: BufRead> Lines<R> {
+    pub fn new(reader: R, opts: ParseOptions) -> Self {
+        Self {
+            reader,
+            opts,
+            buf: Vec::new(),
+            offset: 0,
+            line: 1,
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = Result<Document, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buf.clear();
+            let (offset, line) = (self.offset, self.line);
+            match self.reader.read_until(b'\n', &mut self.buf) {
+                Ok(0) => self.done = true,
+                Ok(n) => {
+                    self.offset += n;
+                    self.line += 1;
+                    if let Some(doc) = parse_line(&self.buf, offset, line, self.opts) {
+                        return Some(doc);
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(io_error(e, offset, line)));
+                }
+            }
+        }
+        None
+    }
+}
+
+// Parses the line starting at byte `offset` and numbered `line`, or returns
+// `None` if it's blank.
+pub(crate) fn parse_line(
+    buf: &[u8],
+    offset: usize,
+    line: usize,
+    opts: ParseOptions,
+) -> Option<Result<Document, ParseError>> {
+    if buf.iter().all(u8::is_ascii_whitespace) {
+        return None;
+    }
+    // Without the newline, an error at the end stays on this line.
+    let buf = match buf.strip_suffix(b"\n") {
+        Some(rest) => rest.strip_suffix(b"\r").unwrap_or(rest),
+        None => buf,
+    };
+    let doc = Par::parse_utf8(buf, 1 << 4, opts);
+    Some(doc.map_err(|e| e.shift(offset, line - 1)))
+}
+
+pub(crate) fn io_error(e: std::io::Error, offset: usize, line: usize) -> ParseError {
+    ParseError::Io {
+        kind: e.kind(),
+        message: e.to_string(),
+        span: Span {
+            start: offset,
+            end: offset,
+            line,
+            column: 1,
+        },
+    }
+}
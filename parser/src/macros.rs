@@ -0,0 +1,68 @@
+/// Builds an `OwnedValue` from JSON-like syntax, where anything that isn't
+/// `null`, a list or an object is a Rust expression converted with `From`:
+///
+/// ```text
+/// json!({ "name": name, "tags": [1, 2, 3], (key): null })
+/// ```
+///
+/// Keys are string literals, or expressions in parentheses. Wrap the result
+/// in `Document::from` for the arena form.
+#[macro_export]
+macro_rules! json {
+    (null) => {
+        $crate::OwnedValue::Null
+    };
+    ([ $($elements:tt)* ]) => {
+        $crate::OwnedValue::List($crate::json!(@list [] $($elements)*))
+    };
+    ({ $($members:tt)* }) => {{
+        let mut object = $crate::Map::new();
+        $crate::json!(@object object $($members)*);
+        $crate::OwnedValue::Object(object)
+    }};
+    ($value:expr) => {
+        $crate::OwnedValue::from($value)
+    };
+
+    // Lists: elements are collected as expressions until none are left.
+    (@list [$($done:expr,)*]) => {
+        ::std::vec![$($done,)*]
+    };
+    (@list [$($done:expr,)*] null $(, $($rest:tt)*)?) => {
+        $crate::json!(@list [$($done,)* $crate::json!(null),] $($($rest)*)?)
+    };
+    (@list [$($done:expr,)*] [$($list:tt)*] $(, $($rest:tt)*)?) => {
+        $crate::json!(@list [$($done,)* $crate::json!([$($list)*]),] $($($rest)*)?)
+    };
+    (@list [$($done:expr,)*] {$($object:tt)*} $(, $($rest:tt)*)?) => {
+        $crate::json!(@list [$($done,)* $crate::json!({$($object)*}),] $($($rest)*)?)
+    };
+    (@list [$($done:expr,)*] $next:expr $(, $($rest:tt)*)?) => {
+        $crate::json!(@list [$($done,)* $crate::json!($next),] $($($rest)*)?)
+    };
+
+    // Objects: one member at a time, inserted into `$object`.
+    (@object $object:ident) => {};
+    (@object $object:ident ($key:expr) : $($rest:tt)*) => {
+        $crate::json!(@member $object (::std::string::String::from($key)) $($rest)*)
+    };
+    (@object $object:ident $key:literal : $($rest:tt)*) => {
+        $crate::json!(@member $object (::std::string::String::from($key)) $($rest)*)
+    };
+    (@member $object:ident ($key:expr) null $(, $($rest:tt)*)?) => {
+        $object.insert($key, $crate::json!(null));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+    (@member $object:ident ($key:expr) [$($list:tt)*] $(, $($rest:tt)*)?) => {
+        $object.insert($key, $crate::json!([$($list)*]));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+    (@member $object:ident ($key:expr) {$($inner:tt)*} $(, $($rest:tt)*)?) => {
+        $object.insert($key, $crate::json!({$($inner)*}));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+    (@member $object:ident ($key:expr) $value:expr $(, $($rest:tt)*)?) => {
+        $object.insert($key, $crate::json!($value));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+}
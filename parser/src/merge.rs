@@ -0,0 +1,166 @@
+use crate::{
+    alloc::Id,
+    codec::ToJson,
+    doc::{Document, ValueRef},
+    eq::EqOptions,
+    value::JsonValue,
+};
+
+/// How `Document::deep_merge` combines two values. Objects are always merged
+/// member by member; anything else in the other document replaces what was
+/// there, except as set out here.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeStrategy {
+    pub arrays: ArrayMerge,
+    pub nulls: NullMerge,
+}
+
+/// Replaces lists and sets `null` members, so the other document wins
+/// everywhere it says something.
+impl Default for MergeStrategy {
+    fn default() -> Self {
+        Self {
+            arrays: ArrayMerge::Replace,
+            nulls: NullMerge::Set,
+        }
+    }
+}
+
+/// What becomes of a list that both documents have.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArrayMerge {
+    /// The other document's list takes its place.
+    Replace,
+    /// The other document's elements go on the end.
+    Append,
+    /// Like `Append`, but leaving out elements already in the list.
+    Union,
+    /// Objects with equal values of this member are merged, as records with
+    /// the same id; other elements go on the end.
+    ByKey(String),
+}
+
+/// What a `null` member of the other document does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullMerge {
+    /// Sets the member to `null`.
+    Set,
+    /// Removes the member, as RFC 7396 JSON Merge Patch does.
+    Remove,
+    /// Leaves the member as it was.
+    Ignore,
+}
+
+impl Document {
+    /// Merges `other` into the document, for layering overrides on a base
+    /// configuration. Values from `other` are copied in.
+    pub fn deep_merge(&mut self, other: &Document, strategy: MergeStrategy) {
+        let mut stack = vec![(None, other.value())];
+        while let Some((at, value)) = stack.pop() {
+            self.merge_value(at, value, &strategy, &mut stack);
+        }
+    }
+
+    fn merge_value<'o>(
+        &mut self,
+        at: Option<Id<JsonValue>>,
+        other: ValueRef<'o>,
+        strategy: &MergeStrategy,
+        stack: &mut Vec<(Option<Id<JsonValue>>, ValueRef<'o>)>,
+    ) {
+        match (slot(self, at), other.value()) {
+            (JsonValue::Object(_), JsonValue::Object(members)) => {
+                for (key, &id) in members {
+                    let value = other.child(id);
+                    let existing = match slot(self, at) {
+                        JsonValue::Object(obj) => obj.get(key).copied(),
+                        _ => unreachable!(),
+                    };
+                    if value.is_null() {
+                        match strategy.nulls {
+                            NullMerge::Set => {}
+                            NullMerge::Remove => {
+                                if let JsonValue::Object(obj) = slot(self, at) {
+                                    obj.remove(key);
+                                }
+                                continue;
+                            }
+                            NullMerge::Ignore => continue,
+                        }
+                    }
+                    match existing {
+                        Some(id) if mergeable(self.get(id), &value) => {
+                            stack.push((Some(id), value))
+                        }
+                        _ => {
+                            let copy = value.to_json(self.arena_mut());
+                            let id = self.alloc(copy);
+                            if let JsonValue::Object(obj) = slot(self, at) {
+                                obj.insert(key.clone(), id);
+                            }
+                        }
+                    }
+                }
+            }
+            (JsonValue::List(_), JsonValue::List(elements))
+                if strategy.arrays != ArrayMerge::Replace =>
+            {
+                let mut list = match slot(self, at) {
+                    JsonValue::List(list) => list.clone(),
+                    _ => unreachable!(),
+                };
+                for &id in elements {
+                    let value = other.child(id);
+                    let same = |doc: &Document, id| {
+                        doc.value_ref(doc.get(id))
+                            .deep_eq_with(value, EqOptions::default())
+                    };
+                    match &strategy.arrays {
+                        ArrayMerge::Union if list.iter().any(|&id| same(self, id)) => continue,
+                        ArrayMerge::ByKey(key) => {
+                            let record = value.get(key).and_then(|k| {
+                                list.iter().copied().find(|&id| {
+                                    let element = self.value_ref(self.get(id));
+                                    element
+                                        .get(key)
+                                        .is_some_and(|j| j.deep_eq_with(k, EqOptions::default()))
+                                })
+                            });
+                            if let Some(id) = record {
+                                stack.push((Some(id), value));
+                                continue;
+                            }
+                        }
+                        _ => {}
+                    }
+                    let copy = value.to_json(self.arena_mut());
+                    let id = self.alloc(copy);
+                    list.push(id);
+                    if let JsonValue::List(elements) = slot(self, at) {
+                        elements.push(id);
+                    }
+                }
+            }
+            _ => {
+                let copy = other.to_json(self.arena_mut());
+                *slot(self, at) = copy;
+            }
+        }
+    }
+}
+
+fn slot(doc: &mut Document, at: Option<Id<JsonValue>>) -> &mut JsonValue {
+    match at {
+        Some(id) => doc.get_mut(id),
+        None => doc.root_mut(),
+    }
+}
+
+// Whether merging `other` into `value` goes inside it, rather than
+// replacing it.
+fn mergeable(value: &JsonValue, other: &ValueRef<'_>) -> bool {
+    matches!(
+        (value, other.value()),
+        (JsonValue::Object(_), JsonValue::Object(_)) | (JsonValue::List(_), JsonValue::List(_))
+    )
+}
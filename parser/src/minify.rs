@@ -0,0 +1,27 @@
+use crate::{
+    error::ParseError,
+    lex::{Lex, Token},
+    options::ParseOptions,
+};
+
+// Copies each token's text, so strings and numbers keep their spelling, and
+// puts a space back only between two tokens that would otherwise run
+// together, such as `1 2`.
+pub(crate) fn minify(src: &str, opts: ParseOptions) -> Result<String, ParseError> {
+    let mut lex = Lex::with_options(src, opts);
+    let mut out = String::with_capacity(src.len());
+    let mut word = false;
+    loop {
+        let tok = lex.skim_token()?;
+        let next = match tok.token {
+            Token::Eof => return Ok(out),
+            Token::Num(_) | Token::True | Token::False | Token::Null | Token::Ident(_) => true,
+            _ => false,
+        };
+        if word && next {
+            out.push(' ');
+        }
+        word = next;
+        out.push_str(&src[tok.span.start..tok.span.end]);
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{
+    base64,
+    binary::{Builder, Reader},
+    doc::{Document, ValueRef},
+    error::BinaryError,
+    value::{JsonValue, Number},
+};
+
+// Something still to write: a member's key goes before its value.
+enum Item<'doc> {
+    Key(&'doc str),
+    Value(ValueRef<'doc>),
+}
+
+impl Document {
+    /// The document as MessagePack, with each integer in its smallest
+    /// encoding and floats as float 64. A `Raw` value, which MessagePack has
+    /// no room for, is a string of its JSON text.
+    pub fn to_msgpack(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut stack = vec![Item::Value(self.value())];
+        while let Some(item) = stack.pop() {
+            let value = match item {
+                Item::Key(key) => {
+                    string(key, &mut out);
+                    continue;
+                }
+                Item::Value(value) => value,
+            };
+            let start = stack.len();
+            match value.value() {
+                JsonValue::Null => out.push(0xc0),
+                JsonValue::Bool(b) => out.push(0xc2 | *b as u8),
+                JsonValue::Number(n) => number(n, &mut out),
+                JsonValue::String(s) | JsonValue::Raw(s) => string(s, &mut out),
+                JsonValue::List(list) => {
+                    header(list.len(), 0x90, 0xdc, &mut out);
+                    stack.extend(list.iter().map(|&id| Item::Value(value.child(id))));
+                }
+                JsonValue::Object(obj) => {
+                    header(obj.len(), 0x80, 0xde, &mut out);
+                    for (key, &id) in obj {
+                        stack.push(Item::Key(key));
+                        stack.push(Item::Value(value.child(id)));
+                    }
+                }
+            }
+            stack[start..].reverse();
+        }
+        out
+    }
+}
+
+fn number(n: &Number, out: &mut Vec<u8>) {
+    match (n.as_i64(), n.as_u64()) {
+        (_, Some(n)) if n < 0x80 => out.push(n as u8),
+        (_, Some(n)) if n <= u8::MAX as u64 => out.extend([0xcc, n as u8]),
+        (_, Some(n)) if n <= u16::MAX as u64 => {
+            out.push(0xcd);
+            out.extend((n as u16).to_be_bytes());
+        }
+        (_, Some(n)) if n <= u32::MAX as u64 => {
+            out.push(0xce);
+            out.extend((n as u32).to_be_bytes());
+        }
+        (_, Some(n)) => {
+            out.push(0xcf);
+            out.extend(n.to_be_bytes());
+        }
+        (Some(n), _) if n >= -32 => out.push(n as u8),
+        (Some(n), _) if n >= i8::MIN as i64 => out.extend([0xd0, n as u8]),
+        (Some(n), _) if n >= i16::MIN as i64 => {
+            out.push(0xd1);
+            out.extend((n as i16).to_be_bytes());
+        }
+        (Some(n), _) if n >= i32::MIN as i64 => {
+            out.push(0xd2);
+            out.extend((n as i32).to_be_bytes());
+        }
+        (Some(n), _) => {
+            out.push(0xd3);
+            out.extend(n.to_be_bytes());
+        }
+        (None, None) => {
+            out.push(0xcb);
+            out.extend(n.as_f64().to_be_bytes());
+        }
+    }
+}
+
+fn string(s: &str, out: &mut Vec<u8>) {
+    let len = s.len();
+    match len {
+        0..=31 => out.push(0xa0 | len as u8),
+        32..=0xff => out.extend([0xd9, len as u8]),
+        _ => header_sized(len, 0xda, out),
+    }
+    out.extend(s.as_bytes());
+}
+
+// An array or map header, `fix` for up to 15 entries, or the 16-bit `wide`
+// form and the 32-bit one after it.
+fn header(len: usize, fix: u8, wide: u8, out: &mut Vec<u8>) {
+    match len {
+        0..=15 => out.push(fix | len as u8),
+        _ => header_sized(len, wide, out),
+    }
+}
+
+// The 16-bit form `marker`, or the 32-bit one after it.
+fn header_sized(len: usize, marker: u8, out: &mut Vec<u8>) {
+    match u16::try_from(len) {
+        Ok(len) => {
+            out.push(marker);
+            out.extend(len.to_be_bytes());
+        }
+        Err(_) => {
+            out.push(marker + 1);
+            out.extend((len as u32).to_be_bytes());
+        }
+    }
+}
+
+pub(crate) fn parse(src: &[u8]) -> Result<Document, BinaryError> {
+    let mut reader = Reader { src, pos: 0 };
+    let mut builder = Builder::new();
+    while !builder.done() {
+        let offset = reader.pos;
+        let marker = reader.byte()?;
+        let fail = |message| BinaryError { offset, message };
+        let value = match marker {
+            0x00..=0x7f => JsonValue::Number(Number::I64(marker as i64)),
+            0xe0..=0xff => JsonValue::Number(Number::I64(marker as i8 as i64)),
+            0xc0 => JsonValue::Null,
+            0xc2 => JsonValue::Bool(false),
+            0xc3 => JsonValue::Bool(true),
+            0xcc..=0xcf => {
+                let n = reader.uint(1 << (marker - 0xcc))?;
+                JsonValue::Number(n.into())
+            }
+            0xd0..=0xd3 => {
+                let width = 1 << (marker - 0xd0);
+                let n = reader.uint(width)?;
+                // Sign-extended from the top bit of its width.
+                let shift = 64 - 8 * width as u32;
+                JsonValue::Number(Number::I64(((n << shift) as i64) >> shift))
+            }
+            0xca => JsonValue::Number(Number::F64(f32::from_bits(reader.uint(4)? as u32) as f64)),
+            0xcb => JsonValue::Number(Number::F64(f64::from_bits(reader.uint(8)?))),
+            0xa0..=0xbf | 0xd9..=0xdb => {
+                let len = match marker {
+                    0xa0..=0xbf => (marker & 0x1f) as usize,
+                    _ => reader.uint(1 << (marker - 0xd9))? as usize,
+                };
+                let bytes = reader.take(len)?;
+                let s =
+                    std::str::from_utf8(bytes).map_err(|_| fail("a string that isn't UTF-8"))?;
+                JsonValue::String(s.to_owned())
+            }
+            0xc4..=0xc6 => {
+                let len = reader.uint(1 << (marker - 0xc4))? as usize;
+                JsonValue::String(base64::encode_url(reader.take(len)?))
+            }
+            0x90..=0x9f | 0xdc | 0xdd => {
+                let len = match marker {
+                    0xdc => reader.uint(2)?,
+                    0xdd => reader.uint(4)?,
+                    _ => (marker & 0x0f) as u64,
+                };
+                builder.list(Some(len as usize), &reader, offset)?;
+                continue;
+            }
+            0x80..=0x8f | 0xde | 0xdf => {
+                let len = match marker {
+                    0xde => reader.uint(2)?,
+                    0xdf => reader.uint(4)?,
+                    _ => (marker & 0x0f) as u64,
+                };
+                builder.object(Some(len as usize), offset)?;
+                continue;
+            }
+            0xc7..=0xc9 | 0xd4..=0xd8 => {
+                return Err(fail("an extension type, which JSON has no value for"))
+            }
+            _ => return Err(fail("a reserved byte")),
+        };
+        builder.value(value, offset)?;
+    }
+    builder.finish(&reader)
+}
@@ -0,0 +1,82 @@
+/// What to do when an object has the same key more than once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// Fail with `ParseError::DuplicateKey`, for input where parsers that
+    /// disagree about duplicates could be played against each other.
+    Error,
+    /// Keep the first value and drop later ones.
+    FirstWins,
+    /// Keep the last value, as most JSON parsers do.
+    #[default]
+    LastWins,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions {
+    /// Reject anything RFC 8259 doesn't allow: leading zeros, trailing
+    /// commas and more than one top-level value. Overrides the `allow_*`
+    /// options below.
+    pub strict: bool,
+    /// Accept raw control characters (tabs, newlines, ...) inside strings
+    /// instead of requiring them to be escaped.
+    pub allow_control_characters: bool,
+    /// Skip `//` line comments and `/* */` block comments, as in JSONC
+    /// config files.
+    pub allow_comments: bool,
+    /// Accept a comma right before a closing `]` or `}`.
+    pub allow_trailing_commas: bool,
+    /// Accept the `NaN`, `Infinity` and `-Infinity` literals that Python's
+    /// `json.dumps` writes for non-finite floats. `-NaN`, which it never
+    /// writes, is an invalid number unless `json5` is on.
+    pub allow_non_finite_numbers: bool,
+    /// Accept JSON5: unquoted keys, single-quoted strings, trailing commas,
+    /// hex numbers, `+`/leading-dot numbers, `Infinity`/`NaN` and escaped
+    /// line breaks in strings. Implies `allow_comments`,
+    /// `allow_trailing_commas` and `allow_non_finite_numbers`. Unquoted keys
+    /// are limited to ASCII identifiers.
+    #[cfg(feature = "json5")]
+    pub json5: bool,
+    pub duplicate_keys: DuplicateKeys,
+    /// Object members with one of these keys are not parsed but kept as
+    /// `JsonValue::Raw`. Their tokens are still lexed and their brackets
+    /// matched, so the kept source text always holds a whole value.
+    pub raw_keys: &'static [&'static str],
+    /// How deeply arrays and objects may nest. Unlimited by default; the
+    /// parser keeps its own stack, so only memory bounds the depth then.
+    pub max_depth: Option<usize>,
+    /// How many values a single document may allocate in its arena.
+    pub max_nodes: Option<usize>,
+    /// How many bytes a string may hold once its escapes are decoded.
+    pub max_string_length: Option<usize>,
+    /// How many bytes of input the parser reads before giving up. Counted
+    /// after any UTF-16/32 transcoding.
+    pub max_document_size: Option<usize>,
+}
+
+impl ParseOptions {
+    pub(crate) fn control_characters(&self) -> bool {
+        self.allow_control_characters && !self.strict
+    }
+
+    pub(crate) fn comments(&self) -> bool {
+        (self.allow_comments || self.json5()) && !self.strict
+    }
+
+    pub(crate) fn trailing_commas(&self) -> bool {
+        (self.allow_trailing_commas || self.json5()) && !self.strict
+    }
+
+    pub(crate) fn non_finite_numbers(&self) -> bool {
+        (self.allow_non_finite_numbers || self.json5()) && !self.strict
+    }
+
+    #[cfg(feature = "json5")]
+    pub(crate) fn json5(&self) -> bool {
+        self.json5 && !self.strict
+    }
+
+    #[cfg(not(feature = "json5"))]
+    pub(crate) fn json5(&self) -> bool {
+        false
+    }
+}
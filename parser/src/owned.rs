@@ -0,0 +1,215 @@
+use crate::{
+    alloc::{Allocator, Id},
+    codec::{FromJson, ToJson},
+    doc::{Document, ValueRef},
+    error::DecodeError,
+    key::{Interner, Key},
+    map::Map,
+    value::{JsonValue, Number},
+};
+
+/// A value that owns its children, for passing trees around without their
+/// arena. Compares structurally, like `Document::deep_eq`.
+///
+/// There are no conversions to or from `serde_json::Value`, since the
+/// crate takes no dependencies; going through JSON text, with
+/// `serde_json::to_string` and `parse_value`, converts either way.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum OwnedValue {
+    String(String),
+    Number(Number),
+    Bool(bool),
+    Object(Map<String, OwnedValue>),
+    List(Vec<OwnedValue>),
+    Raw(String),
+    #[default]
+    Null,
+}
+
+// A container being copied out of the arena, with the children still to go.
+enum ToOwned<'doc> {
+    List(Vec<OwnedValue>, std::slice::Iter<'doc, Id<JsonValue>>),
+    Object(
+        Map<String, OwnedValue>,
+        std::vec::IntoIter<(&'doc Key, Id<JsonValue>)>,
+        String,
+    ),
+}
+
+// A container being moved into the arena.
+enum FromOwned {
+    List(Vec<Id<JsonValue>>, std::vec::IntoIter<OwnedValue>),
+    Object(
+        Map<Key, Id<JsonValue>>,
+        <Map<String, OwnedValue> as IntoIterator>::IntoIter,
+        String,
+    ),
+}
+
+impl Document {
+    pub fn to_owned_tree(&self) -> OwnedValue {
+        self.value().to_owned_tree()
+    }
+
+    /// Moves `value` into a document, children first, so the root's
+    /// descendants are laid out in the arena in post-order.
+    pub fn from_owned(value: OwnedValue) -> Document {
+        let mut mem = Allocator::make(1 << 4);
+        let root = move_in(value, &mut mem);
+        Document::new(root, mem)
+    }
+}
+
+pub(crate) fn move_in(value: OwnedValue, mem: &mut Allocator<JsonValue>) -> JsonValue {
+    let mut keys = Interner::default();
+    let mut stack = Vec::new();
+    let mut next = value;
+    loop {
+        let mut value = match next {
+            OwnedValue::List(list) => {
+                stack.push(FromOwned::List(
+                    Vec::with_capacity(list.len()),
+                    list.into_iter(),
+                ));
+                None
+            }
+            OwnedValue::Object(obj) => {
+                stack.push(FromOwned::Object(
+                    Map::new(),
+                    obj.into_iter(),
+                    String::new(),
+                ));
+                None
+            }
+            OwnedValue::String(s) => Some(JsonValue::String(s)),
+            OwnedValue::Number(n) => Some(JsonValue::Number(n)),
+            OwnedValue::Bool(b) => Some(JsonValue::Bool(b)),
+            OwnedValue::Raw(text) => Some(JsonValue::Raw(text)),
+            OwnedValue::Null => Some(JsonValue::Null),
+        };
+        // Hand finished values to their parents until one has another
+        // child to move.
+        next = loop {
+            let Some(frame) = stack.last_mut() else {
+                return value.unwrap_or_default();
+            };
+            let child = match frame {
+                FromOwned::List(ids, rest) => {
+                    if let Some(value) = value.take() {
+                        ids.push(mem.alloc(value));
+                    }
+                    rest.next()
+                }
+                FromOwned::Object(ids, rest, key) => {
+                    if let Some(value) = value.take() {
+                        ids.insert(keys.intern(key), mem.alloc(value));
+                    }
+                    rest.next().map(|(k, child)| {
+                        *key = k;
+                        child
+                    })
+                }
+            };
+            match child {
+                Some(child) => break child,
+                None => {
+                    value = Some(match stack.pop() {
+                        Some(FromOwned::List(ids, _)) => JsonValue::List(ids),
+                        Some(FromOwned::Object(ids, ..)) => JsonValue::Object(ids),
+                        None => unreachable!(),
+                    });
+                }
+            }
+        };
+    }
+}
+
+impl ValueRef<'_> {
+    /// Copies the value and everything below it out of the arena.
+    pub fn to_owned_tree(&self) -> OwnedValue {
+        copy_out(self.value(), self.document().arena())
+    }
+}
+
+fn copy_out(value: &JsonValue, arena: &Allocator<JsonValue>) -> OwnedValue {
+    let mut stack = Vec::new();
+    let mut next = value;
+    loop {
+        let mut value = match next {
+            JsonValue::List(list) => {
+                stack.push(ToOwned::List(Vec::with_capacity(list.len()), list.iter()));
+                None
+            }
+            JsonValue::Object(obj) => {
+                let members: Vec<_> = obj.iter().map(|(k, &id)| (k, id)).collect();
+                stack.push(ToOwned::Object(
+                    Map::new(),
+                    members.into_iter(),
+                    String::new(),
+                ));
+                None
+            }
+            JsonValue::String(s) => Some(OwnedValue::String(s.clone())),
+            // `Number` is only `Copy` without `arbitrary-precision`.
+            #[allow(clippy::clone_on_copy)]
+            JsonValue::Number(n) => Some(OwnedValue::Number(n.clone())),
+            JsonValue::Bool(b) => Some(OwnedValue::Bool(*b)),
+            JsonValue::Raw(text) => Some(OwnedValue::Raw(text.clone())),
+            JsonValue::Null => Some(OwnedValue::Null),
+        };
+        next = loop {
+            let Some(frame) = stack.last_mut() else {
+                return value.unwrap_or_default();
+            };
+            let child = match frame {
+                ToOwned::List(values, rest) => {
+                    values.extend(value.take());
+                    rest.next().copied()
+                }
+                ToOwned::Object(values, rest, key) => {
+                    if let Some(value) = value.take() {
+                        values.insert(std::mem::take(key), value);
+                    }
+                    rest.next().map(|(k, id)| {
+                        k.as_str().clone_into(key);
+                        id
+                    })
+                }
+            };
+            match child {
+                Some(id) => break arena.fetch(id),
+                None => {
+                    value = Some(match stack.pop() {
+                        Some(ToOwned::List(values, _)) => OwnedValue::List(values),
+                        Some(ToOwned::Object(values, ..)) => OwnedValue::Object(values),
+                        None => unreachable!(),
+                    });
+                }
+            }
+        };
+    }
+}
+
+impl FromJson for OwnedValue {
+    fn from_json(value: &JsonValue, arena: &Allocator<JsonValue>) -> Result<Self, DecodeError> {
+        Ok(copy_out(value, arena))
+    }
+}
+
+impl ToJson for OwnedValue {
+    fn to_json(&self, arena: &mut Allocator<JsonValue>) -> JsonValue {
+        move_in(self.clone(), arena)
+    }
+}
+
+impl From<&Document> for OwnedValue {
+    fn from(doc: &Document) -> Self {
+        doc.to_owned_tree()
+    }
+}
+
+impl From<OwnedValue> for Document {
+    fn from(value: OwnedValue) -> Self {
+        Document::from_owned(value)
+    }
+}
@@ -0,0 +1,718 @@
+use std::{collections::HashSet, io::Read, ops::Range};
+
+use crate::{
+    alloc::{self, Allocator, Id, Storage},
+    doc::Document,
+    encoding,
+    error::{self, Diagnostic, ErrorKind, ParseError, Severity},
+    key::{Interner, Key},
+    lex::{Lex, Span, SpannedToken, Token},
+    map::Map,
+    options::{DuplicateKeys, ParseOptions},
+    source::{ReadSource, SliceSource, Source},
+    value::JsonValue,
+};
+
+enum Frame {
+    List(Vec<Id<JsonValue>>),
+    Object(Map<Key, Id<JsonValue>>, String),
+}
+
+// An object open in `check`: the current key, and when the duplicate key
+// policy needs them, the keys before it and whether that one repeats them.
+struct Members {
+    key: String,
+    seen: HashSet<String>,
+    repeat: bool,
+}
+
+pub struct Par<S, M = Vec<JsonValue>> {
+    cur: SpannedToken,
+    lex: Lex<S>,
+    mem: Allocator<JsonValue, M>,
+    // The keys of the document being parsed, so repeats share their text.
+    keys: Interner,
+    // `go_parse`'s open containers, kept between calls for the capacity.
+    stack: Vec<Frame>,
+    opts: ParseOptions,
+    diagnostics: Option<Vec<Diagnostic>>,
+    // In prefix mode nothing past the root value is lexed until the next
+    // document is asked for, and `end` is where the last one ended.
+    prefix: bool,
+    end: usize,
+}
+
+impl<'json> Par<SliceSource<'json>> {
+    pub fn parse(
+        src: &'json str,
+        mem: usize,
+        opts: ParseOptions,
+    ) -> Result<Vec<Document>, ParseError> {
+        Self::init(Lex::with_options(src, opts), mem, opts)?.documents(mem)
+    }
+
+    pub fn parse_value(
+        src: &'json str,
+        mem: usize,
+        opts: ParseOptions,
+    ) -> Result<Document, ParseError> {
+        Self::init(Lex::with_options(src, opts), mem, opts)?.document()
+    }
+
+    /// Parses the value at the start of `src`, returning whatever follows it
+    /// untouched, leading whitespace included.
+    pub fn parse_prefix(
+        src: &'json str,
+        mem: usize,
+        opts: ParseOptions,
+    ) -> Result<(Document, &'json str), ParseError> {
+        let mut par = Self::prefix(Lex::with_options(src, opts), mem, opts);
+        par.advance()?;
+        let root = par.go_parse()?;
+        Ok((Document::new(root, par.mem), &src[par.end..]))
+    }
+
+    /// Also accepts UTF-16 and UTF-32 input, which is transcoded first.
+    pub fn parse_bytes(
+        src: &'json [u8],
+        mem: usize,
+        opts: ParseOptions,
+    ) -> Result<Document, ParseError> {
+        let src = encoding::decode(src)?;
+        Par::init(Lex::from_bytes(&src, opts), mem, opts)?.document()
+    }
+
+    // For input already known not to be UTF-16 or UTF-32.
+    pub(crate) fn parse_utf8(
+        src: &'json [u8],
+        mem: usize,
+        opts: ParseOptions,
+    ) -> Result<Document, ParseError> {
+        Par::init(Lex::from_bytes(src, opts), mem, opts)?.document()
+    }
+
+    /// Checks that `src` holds a single valid value without building it,
+    /// failing wherever `parse_value` would with the same options.
+    pub fn validate(src: &'json str, opts: ParseOptions) -> Result<(), ParseError> {
+        let mut par = Self::init(Lex::with_options(src, opts), 0, opts)?;
+        par.check()?;
+        par.expect_eof()
+    }
+
+    /// Parses a single value without stopping at the first syntax error:
+    /// the parser skips or papers over what it can't make sense of, so the
+    /// document is a best effort whenever any diagnostic is an error.
+    pub fn parse_with_diagnostics(
+        src: &'json str,
+        mem: usize,
+        opts: ParseOptions,
+    ) -> (Document, Vec<Diagnostic>) {
+        let mut par = Self::new(Lex::with_options(src, opts), mem, opts);
+        par.diagnostics = Some(Vec::new());
+        let root = par
+            .advance()
+            .and_then(|_| par.go_parse())
+            .and_then(|root| par.expect_eof().map(|()| root));
+        let mut diagnostics = par.diagnostics.take().unwrap_or_default();
+        let root = root.unwrap_or_else(|error| {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                error,
+            });
+            JsonValue::Null
+        });
+        (Document::new(root, par.mem), diagnostics)
+    }
+}
+
+/// What a parse allocates besides the document that the next can use
+/// again: the keys seen and the container stack.
+#[derive(Default)]
+pub(crate) struct Scratch {
+    keys: Interner,
+    stack: Vec<Frame>,
+}
+
+// Past this many keys the interner starts over, so input with ever-new
+// keys can't grow it without bound.
+const KEEP_KEYS: usize = 1 << 12;
+
+impl<'json, M: Storage<JsonValue>> Par<SliceSource<'json>, M> {
+    /// `parse_value` into `mem`, which must be empty, with the scratch an
+    /// earlier parse left.
+    pub(crate) fn parse_reusing(
+        src: &'json str,
+        opts: ParseOptions,
+        mem: Allocator<JsonValue, M>,
+        scratch: &mut Scratch,
+    ) -> Result<JsonValue, ParseError> {
+        let mut par = Self::with_arena(Lex::with_options(src, opts), mem, opts);
+        par.keys = std::mem::take(&mut scratch.keys);
+        par.stack = std::mem::take(&mut scratch.stack);
+        let root = par
+            .advance()
+            .and_then(|_| par.go_parse())
+            .and_then(|root| par.expect_eof().map(|()| root));
+        if par.keys.len() > KEEP_KEYS {
+            par.keys.clear();
+        }
+        scratch.keys = par.keys;
+        scratch.stack = par.stack;
+        root
+    }
+}
+
+impl<R: Read> Par<ReadSource<R>> {
+    /// Parses a single value, reading `reader` in chunks as the parser needs them.
+    pub fn parse_reader(reader: R, opts: ParseOptions) -> Result<Document, ParseError> {
+        Self::init(Lex::from_reader(reader, opts), 1 << 4, opts)?.document()
+    }
+}
+
+impl<S: Source> Par<S> {
+    fn new(lex: Lex<S>, mem: usize, opts: ParseOptions) -> Self {
+        Self::with_arena(lex, Allocator::make(mem), opts)
+    }
+
+    fn init(lex: Lex<S>, mem: usize, opts: ParseOptions) -> Result<Self, ParseError> {
+        let mut par = Self::new(lex, mem, opts);
+        par.advance()?;
+        Ok(par)
+    }
+
+    pub(crate) fn prefix(lex: Lex<S>, mem: usize, opts: ParseOptions) -> Self {
+        let mut par = Self::new(lex, mem, opts);
+        par.prefix = true;
+        par
+    }
+
+    /// The byte offset just past the last value of a prefix parser.
+    pub(crate) fn end(&self) -> usize {
+        self.end
+    }
+
+    /// The next value of a prefix parser, or `None` if only whitespace and
+    /// comments are left.
+    pub(crate) fn next_document(&mut self, mem: usize) -> Result<Option<Document>, ParseError> {
+        self.advance()?;
+        if matches!(self.cur.token, Token::Eof) {
+            return Ok(None);
+        }
+        let root = self.go_parse()?;
+        let arena = std::mem::replace(&mut self.mem, Allocator::make(mem));
+        self.keys = Interner::default();
+        Ok(Some(Document::new(root, arena)))
+    }
+
+    fn documents(mut self, mem: usize) -> Result<Vec<Document>, ParseError> {
+        let mut results = Vec::new();
+        loop {
+            let result = self.go_parse()?;
+            trace!("document {} done, {} nodes", results.len(), self.mem.len());
+            let arena = std::mem::replace(&mut self.mem, Allocator::make(mem));
+            self.keys = Interner::default();
+            results.push(Document::new(result, arena));
+            if matches!(self.cur.token, Token::Eof) {
+                break;
+            }
+            if self.opts.strict {
+                return Err(ParseError::TrailingData {
+                    span: self.cur.span,
+                });
+            }
+            if matches!(self.cur.token, Token::Comma) {
+                self.advance()?;
+            }
+        }
+        Ok(results)
+    }
+
+    fn document(mut self) -> Result<Document, ParseError> {
+        let root = self.go_parse()?;
+        self.expect_eof()?;
+        Ok(Document::new(root, self.mem))
+    }
+}
+
+impl<S: Source, M: Storage<JsonValue>> Par<S, M> {
+    fn with_arena(lex: Lex<S>, mem: Allocator<JsonValue, M>, opts: ParseOptions) -> Self {
+        Self {
+            cur: SpannedToken {
+                token: Token::Eof,
+                span: Span::default(),
+            },
+            lex,
+            mem,
+            keys: Interner::default(),
+            stack: Vec::new(),
+            opts,
+            diagnostics: None,
+            prefix: false,
+            end: 0,
+        }
+    }
+
+    fn advance(&mut self) -> Result<SpannedToken, ParseError> {
+        let next = match self.lex.next_token() {
+            Ok(next) => next,
+            Err(e) => {
+                let span = e.span();
+                self.recover(e.into())?;
+                // Stand in for the token that failed to lex.
+                SpannedToken {
+                    token: Token::Null,
+                    span,
+                }
+            }
+        };
+        trace!("token {:?} at {}", next.token, next.span);
+        Ok(std::mem::replace(&mut self.cur, next))
+    }
+
+    // Moves past the current token, unless it ends the root value in prefix
+    // mode.
+    fn consume(&mut self, root: bool) -> Result<(), ParseError> {
+        if root && self.prefix {
+            self.end = self.cur.span.end;
+            return Ok(());
+        }
+        self.advance().map(drop)
+    }
+
+    fn expect_eof(&mut self) -> Result<(), ParseError> {
+        match self.cur.token {
+            Token::Eof => Ok(()),
+            _ => self.recover(ParseError::TrailingData {
+                span: self.cur.span,
+            }),
+        }
+    }
+
+    pub fn go_parse(&mut self) -> Result<JsonValue, ParseError> {
+        let mut stack = std::mem::take(&mut self.stack);
+        loop {
+            let raw = match stack.last() {
+                Some(Frame::Object(_, key)) => self.opts.raw_keys.contains(&key.as_str()),
+                _ => false,
+            };
+            let missing = !raw
+                && matches!(
+                    self.cur.token,
+                    Token::RBracket | Token::RBrace | Token::Comma | Token::Eof
+                );
+            let mut value = match &mut self.cur.token {
+                _ if raw => self.raw()?,
+                // In diagnostics mode a null stands in for the value, and the
+                // token is left for the container to deal with.
+                _ if missing => {
+                    self.recover(self.unexpected(Some("a value")))?;
+                    JsonValue::Null
+                }
+                Token::False => JsonValue::Bool(false),
+                Token::True => JsonValue::Bool(true),
+                Token::Null => JsonValue::Null,
+                Token::Str(s) => JsonValue::String(std::mem::take(s)),
+                Token::Num(n) => JsonValue::Number(std::mem::take(n)),
+
+                Token::LBracket => {
+                    self.check_depth(stack.len())?;
+                    self.advance()?;
+                    if matches!(self.cur.token, Token::RBracket) {
+                        JsonValue::List(Vec::new())
+                    } else {
+                        trace!("open list at depth {}", stack.len() + 1);
+                        stack.push(Frame::List(Vec::new()));
+                        continue;
+                    }
+                }
+
+                Token::LBrace => {
+                    self.check_depth(stack.len())?;
+                    self.advance()?;
+                    if matches!(self.cur.token, Token::RBrace) {
+                        JsonValue::Object(Map::new())
+                    } else if let Some(key) = self.next_key(&Map::new())? {
+                        trace!("open object at depth {}", stack.len() + 1);
+                        stack.push(Frame::Object(Map::new(), key));
+                        continue;
+                    } else {
+                        JsonValue::Object(Map::new())
+                    }
+                }
+
+                Token::RBracket
+                | Token::RBrace
+                | Token::Comma
+                | Token::Colon
+                | Token::Ident(_)
+                | Token::Eof => {
+                    self.recover(self.unexpected(Some("a value")))?;
+                    self.advance()?;
+                    continue;
+                }
+            };
+            if !missing {
+                self.consume(stack.is_empty())?;
+            }
+
+            // Hand the finished value to its parent, closing every container
+            // that ends right after it. In diagnostics mode a container cut
+            // short by the wrong bracket is closed without consuming it.
+            loop {
+                let closing = match stack.last_mut() {
+                    None => {
+                        self.stack = stack;
+                        return Ok(value);
+                    }
+                    Some(Frame::List(list)) => {
+                        list.push(self.alloc(value)?);
+                        let closing = match self.cur.token {
+                            Token::Comma => {
+                                if !self.comma(Token::RBracket)? {
+                                    break;
+                                }
+                                true
+                            }
+                            Token::RBracket => true,
+                            _ => {
+                                self.recover(self.unexpected(Some("',' or ']'")))?;
+                                if !matches!(self.cur.token, Token::RBrace | Token::Eof) {
+                                    break;
+                                }
+                                false
+                            }
+                        };
+                        value = JsonValue::List(std::mem::take(list));
+                        closing
+                    }
+                    Some(Frame::Object(obj, key)) => {
+                        let name = std::mem::take(key);
+                        let first_wins = self.opts.duplicate_keys == DuplicateKeys::FirstWins;
+                        if !(first_wins && obj.contains_key(name.as_str())) {
+                            obj.insert(self.keys.intern(&name), self.alloc(value)?);
+                        }
+                        let next = match self.cur.token {
+                            Token::Comma => {
+                                if self.comma(Token::RBrace)? {
+                                    None
+                                } else {
+                                    self.next_key(obj)?
+                                }
+                            }
+                            Token::RBrace => None,
+                            _ => {
+                                self.recover(self.unexpected(Some("',' or '}'")))?;
+                                match self.cur.token {
+                                    Token::RBracket | Token::Eof => None,
+                                    _ => self.next_key(obj)?,
+                                }
+                            }
+                        };
+                        if let Some(next) = next {
+                            *key = next;
+                            break;
+                        }
+                        value = JsonValue::Object(std::mem::take(obj));
+                        matches!(self.cur.token, Token::RBrace)
+                    }
+                };
+                trace!("close container at depth {}", stack.len());
+                stack.pop();
+                if closing {
+                    self.consume(stack.is_empty())?;
+                }
+            }
+        }
+    }
+
+    // `go_parse` without the values: the same grammar, with the same limits,
+    // keeping only each open object's keys. Nodes are counted where
+    // `go_parse` would allocate them.
+    fn check(&mut self) -> Result<(), ParseError> {
+        let track = match self.opts.duplicate_keys {
+            DuplicateKeys::Error => true,
+            DuplicateKeys::FirstWins => self.opts.max_nodes.is_some(),
+            DuplicateKeys::LastWins => false,
+        };
+        let mut open: Vec<Option<Members>> = Vec::new();
+        let mut nodes = 0;
+        loop {
+            let raw = match open.last() {
+                Some(Some(members)) => self.opts.raw_keys.contains(&members.key.as_str()),
+                _ => false,
+            };
+            match self.cur.token {
+                _ if raw => drop(self.raw()?),
+                Token::False | Token::True | Token::Null | Token::Str(_) | Token::Num(_) => {}
+                Token::LBracket => {
+                    self.check_depth(open.len())?;
+                    self.advance()?;
+                    if !matches!(self.cur.token, Token::RBracket) {
+                        open.push(None);
+                        continue;
+                    }
+                }
+                Token::LBrace => {
+                    self.check_depth(open.len())?;
+                    self.advance()?;
+                    if !matches!(self.cur.token, Token::RBrace) {
+                        let key = self.expect_key()?;
+                        open.push(Some(Members {
+                            key,
+                            seen: HashSet::new(),
+                            repeat: false,
+                        }));
+                        continue;
+                    }
+                }
+                _ => return Err(self.unexpected(Some("a value"))),
+            }
+            self.advance()?;
+
+            loop {
+                let (close, expected) = match open.last() {
+                    None => return Ok(()),
+                    Some(None) => (Token::RBracket, "',' or ']'"),
+                    Some(Some(_)) => (Token::RBrace, "',' or '}'"),
+                };
+                // A repeated key's value is dropped under `FirstWins`.
+                if !matches!(open.last(), Some(Some(Members { repeat: true, .. }))) {
+                    self.count_node(nodes)?;
+                    nodes += 1;
+                }
+                if matches!(self.cur.token, Token::Comma) {
+                    if !self.comma(close)? {
+                        if let Some(Some(members)) = open.last_mut() {
+                            let span = self.cur.span;
+                            let key = self.expect_key()?;
+                            let last = std::mem::replace(&mut members.key, key);
+                            if track {
+                                members.seen.insert(last);
+                                members.repeat = members.seen.contains(&members.key);
+                            }
+                            if members.repeat && self.opts.duplicate_keys == DuplicateKeys::Error {
+                                return Err(ParseError::DuplicateKey {
+                                    key: std::mem::take(&mut members.key),
+                                    span,
+                                });
+                            }
+                        }
+                        break;
+                    }
+                } else if self.cur.token != close {
+                    return Err(self.unexpected(Some(expected)));
+                }
+                open.pop();
+                self.advance()?;
+            }
+        }
+    }
+
+    // The text of the value starting at `cur`, which `expect_key` had the
+    // lexer keep, leaving `cur` on its last token. Without a colon before it,
+    // in diagnostics mode, nothing was kept and the text is empty.
+    fn raw(&mut self) -> Result<JsonValue, ParseError> {
+        let span = self.skip_raw();
+        // Stops keeping the input, whether or not the value was whole.
+        let text = self.lex.take_kept(span.clone().unwrap_or(0..0));
+        span?;
+        Ok(JsonValue::Raw(text.unwrap_or_default()))
+    }
+
+    // Skips over the value starting at `cur`, leaving `cur` on its last token.
+    fn skip_raw(&mut self) -> Result<Range<usize>, ParseError> {
+        let start = self.cur.span.start;
+        let mut open = Vec::new();
+        loop {
+            match self.cur.token {
+                Token::LBracket => open.push(Token::RBracket),
+                Token::LBrace => open.push(Token::RBrace),
+                Token::RBracket | Token::RBrace if open.last() == Some(&self.cur.token) => {
+                    open.pop();
+                }
+                Token::Str(_) | Token::Num(_) | Token::False | Token::True | Token::Null => {}
+                Token::Comma | Token::Colon | Token::Ident(_) if !open.is_empty() => {}
+                _ if open.is_empty() => return Err(self.unexpected(Some("a value"))),
+                _ => {
+                    let close = match open.last() {
+                        Some(Token::RBracket) => "']'",
+                        _ => "'}'",
+                    };
+                    return Err(self.unexpected(Some(close)));
+                }
+            }
+            if open.is_empty() {
+                return Ok(start..self.cur.span.end);
+            }
+            self.advance()?;
+        }
+    }
+
+    fn alloc(&mut self, value: JsonValue) -> Result<Id<JsonValue>, ParseError> {
+        self.count_node(self.mem.len())?;
+        Ok(self.mem.alloc(value))
+    }
+
+    // Whether there's room for another node past `nodes`. Running out of ids
+    // is `TooManyNodes` too, rather than a panic.
+    fn count_node(&self, nodes: usize) -> Result<(), ParseError> {
+        match self
+            .opts
+            .max_nodes
+            .map_or(alloc::MAX_IDS, |max| max.min(alloc::MAX_IDS))
+        {
+            max if nodes >= max => Err(ParseError::TooManyNodes {
+                max,
+                span: self.cur.span,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_depth(&self, depth: usize) -> Result<(), ParseError> {
+        match self.opts.max_depth {
+            Some(max) if depth >= max => Err(ParseError::DepthExceeded {
+                max,
+                span: self.cur.span,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    // The key after `{` or `,`, with its colon. `None` only in diagnostics
+    // mode, when no key could be found before the `}` or end of input.
+    fn next_key(&mut self, obj: &Map<Key, Id<JsonValue>>) -> Result<Option<String>, ParseError> {
+        let span = self.cur.span;
+        let key = match self.expect_key() {
+            Ok(key) => key,
+            Err(e) => {
+                self.recover(e)?;
+                match self.skip_to_key()? {
+                    Some(key) => key,
+                    None => return Ok(None),
+                }
+            }
+        };
+        if obj.contains_key(key.as_str()) {
+            let e = ParseError::DuplicateKey {
+                key: key.clone(),
+                span,
+            };
+            match self.opts.duplicate_keys {
+                DuplicateKeys::Error => self.recover(e)?,
+                _ => self.warn(e),
+            }
+        }
+        Ok(Some(key))
+    }
+
+    fn skip_to_key(&mut self) -> Result<Option<String>, ParseError> {
+        loop {
+            match self.cur.token {
+                Token::RBrace | Token::Eof => return Ok(None),
+                Token::Str(_) | Token::Ident(_) => return self.expect_key().map(Some),
+                Token::Colon => {
+                    self.advance()?;
+                    return Ok(Some(String::new()));
+                }
+                _ => {
+                    self.advance()?;
+                }
+            }
+        }
+    }
+
+    fn expect_key(&mut self) -> Result<String, ParseError> {
+        let key = self.expect_str()?;
+        if matches!(self.cur.token, Token::Colon) {
+            // The value's first token is lexed next, so a raw one is kept
+            // from here.
+            if self.opts.raw_keys.contains(&key.as_str()) {
+                self.lex.keep();
+            }
+            self.advance()?;
+        } else {
+            self.recover(self.unexpected(Some("':'")))?;
+        }
+        Ok(key)
+    }
+
+    fn expect_str(&mut self) -> Result<String, ParseError> {
+        let json5 = self.opts.json5();
+        let s = match &mut self.cur.token {
+            Token::Str(s) => std::mem::take(s),
+            Token::Ident(s) if json5 => std::mem::take(s),
+            _ => return Err(self.unexpected(Some("a string key"))),
+        };
+        self.advance()?;
+        Ok(s)
+    }
+
+    // Steps over the comma in `cur`. Returns whether it was a trailing comma
+    // that is allowed, in which case `cur` is the closing `close`; strict mode
+    // refuses trailing commas outright.
+    fn comma(&mut self, close: Token) -> Result<bool, ParseError> {
+        let comma = self.advance()?;
+        if self.cur.token != close {
+            return Ok(false);
+        }
+        if self.opts.strict {
+            self.recover(ParseError::TrailingComma { span: comma.span })?;
+            return Ok(true);
+        }
+        Ok(self.opts.trailing_commas())
+    }
+
+    fn unexpected(&self, expected: Option<&'static str>) -> ParseError {
+        unexpected(&self.cur, expected)
+    }
+
+    // Outside diagnostics mode every error ends the parse. In it, syntax
+    // errors are recorded and parsing carries on; limits, encoding and I/O
+    // failures still end it.
+    fn recover(&mut self, error: ParseError) -> Result<(), ParseError> {
+        match &mut self.diagnostics {
+            Some(diagnostics) if matches!(error.kind(), ErrorKind::Syntax | ErrorKind::Eof) => {
+                // Several recovery steps can trip over the same token, and once
+                // the input has run out every container reports it.
+                let repeat = diagnostics.last().is_some_and(|last| {
+                    last.error.span() == error.span()
+                        || last.error.kind() == ErrorKind::Eof && error.kind() == ErrorKind::Eof
+                });
+                if !repeat {
+                    diagnostics.push(Diagnostic {
+                        severity: Severity::Error,
+                        error,
+                    });
+                }
+                Ok(())
+            }
+            _ => Err(error),
+        }
+    }
+
+    fn warn(&mut self, error: ParseError) {
+        if let Some(diagnostics) = &mut self.diagnostics {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                error,
+            });
+        }
+    }
+}
+
+pub(crate) fn unexpected(tok: &SpannedToken, expected: Option<&'static str>) -> ParseError {
+    let span = tok.span;
+    match &tok.token {
+        Token::Eof => ParseError::UnexpectedEof { span },
+        token => ParseError::UnexpectedToken {
+            found: token.to_string(),
+            expected,
+            hint: match token {
+                Token::Ident(s) => error::hint(s),
+                _ => None,
+            },
+            span,
+        },
+    }
+}
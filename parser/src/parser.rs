@@ -0,0 +1,59 @@
+use crate::{
+    alloc::{Allocator, Storage},
+    doc::Document,
+    error::ParseError,
+    options::ParseOptions,
+    par::{Par, Scratch},
+    value::JsonValue,
+};
+
+/// Parses one value after another, reusing what each parse allocates: the
+/// arena of the document parsed into, the keys already seen and the stack
+/// of open containers. Once warmed up, a server parsing many small requests
+/// allocates only the strings, lists and objects of each value.
+#[derive(Default)]
+pub struct Parser {
+    opts: ParseOptions,
+    scratch: Scratch,
+}
+
+impl Parser {
+    pub fn new(opts: ParseOptions) -> Self {
+        Self {
+            opts,
+            scratch: Scratch::default(),
+        }
+    }
+
+    /// Parses `src` as `parse_value_with` would, into `doc`. Whatever `doc`
+    /// held is dropped, but its arena keeps its capacity. On an error `doc`
+    /// is left `null`.
+    pub fn parse_into(&mut self, src: &str, doc: &mut Document) -> Result<(), ParseError> {
+        *doc.root_mut() = JsonValue::Null;
+        *doc.root_mut() = self.parse_in(src, doc.arena_mut())?;
+        Ok(())
+    }
+
+    /// Parses `src` into an arena of the caller's, with whatever storage it
+    /// has, and returns the root, whose children are in `mem`. `mem` is
+    /// reset first, and left empty on an error.
+    pub fn parse_in<M: Storage<JsonValue>>(
+        &mut self,
+        src: &str,
+        mem: &mut Allocator<JsonValue, M>,
+    ) -> Result<JsonValue, ParseError> {
+        mem.reset();
+        let root = Par::parse_reusing(src, self.opts, mem.by_ref(), &mut self.scratch);
+        if root.is_err() {
+            mem.reset();
+        }
+        root
+    }
+
+    /// `parse_into` a new document, for when the last one is still in use.
+    pub fn parse(&mut self, src: &str) -> Result<Document, ParseError> {
+        let mut doc = Document::default();
+        self.parse_into(src, &mut doc)?;
+        Ok(doc)
+    }
+}
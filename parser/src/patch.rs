@@ -0,0 +1,174 @@
+use crate::{
+    alloc::Id,
+    codec::ToJson,
+    doc::{Document, ValueRef},
+    eq::EqOptions,
+    error::PatchError,
+    pointer,
+    value::JsonValue,
+};
+
+impl Document {
+    /// Applies an RFC 6902 JSON Patch: a list of `add`, `remove`, `replace`,
+    /// `move`, `copy` and `test` operations, in order. They are applied to a
+    /// copy, so if one fails the document is left as it was.
+    pub fn apply_patch(&mut self, patch: &Document) -> Result<(), PatchError> {
+        let Some(ops) = patch.root().as_array() else {
+            return Err(PatchError::Invalid {
+                index: None,
+                message: "a patch must be a list of operations",
+            });
+        };
+        let mut doc = self.clone();
+        for (index, &id) in ops.iter().enumerate() {
+            doc.apply_op(index, patch.value().child(id))?;
+        }
+        *self = doc;
+        Ok(())
+    }
+
+    fn apply_op(&mut self, index: usize, op: ValueRef<'_>) -> Result<(), PatchError> {
+        let name = string(index, op, "op")?;
+        let path = string(index, op, "path")?;
+        let missing = || PatchError::Path {
+            index,
+            pointer: path.to_owned(),
+        };
+        match name {
+            "add" => {
+                let value = value(index, op)?.to_json(self.arena_mut());
+                self.add(path, value).then_some(()).ok_or_else(missing)
+            }
+            "remove" => self.pointer_remove(path).map(drop).ok_or_else(missing),
+            "replace" => {
+                self.pointer(path).ok_or_else(missing)?;
+                let value = value(index, op)?.to_json(self.arena_mut());
+                self.set_pointer(path, value);
+                Ok(())
+            }
+            "move" => {
+                let from = string(index, op, "from")?;
+                if from == path {
+                    return self.pointer(from).map(drop).ok_or_else(missing);
+                }
+                // Nothing can be moved into itself.
+                if path.starts_with(from) && path[from.len()..].starts_with('/') {
+                    return Err(missing());
+                }
+                let id = self.pointer_remove(from).ok_or_else(|| PatchError::Path {
+                    index,
+                    pointer: from.to_owned(),
+                })?;
+                let value = std::mem::take(self.get_mut(id));
+                self.add(path, value).then_some(()).ok_or_else(missing)
+            }
+            "copy" => {
+                let from = string(index, op, "from")?;
+                let value = self.duplicate(from).ok_or_else(|| PatchError::Path {
+                    index,
+                    pointer: from.to_owned(),
+                })?;
+                self.add(path, value).then_some(()).ok_or_else(missing)
+            }
+            "test" => {
+                let expected = value(index, op)?;
+                let found = self.pointer(path).ok_or_else(missing)?;
+                let opts = EqOptions {
+                    numbers_by_value: true,
+                };
+                match self.value_ref(found).deep_eq_with(expected, opts) {
+                    true => Ok(()),
+                    false => Err(PatchError::Test {
+                        index,
+                        pointer: path.to_owned(),
+                    }),
+                }
+            }
+            _ => Err(PatchError::Invalid {
+                index: Some(index),
+                message: "unknown \"op\"",
+            }),
+        }
+    }
+
+    // RFC 6902's `add`: unlike `set_pointer`, a list index inserts rather
+    // than replaces.
+    fn add(&mut self, path: &str, value: JsonValue) -> bool {
+        if path.is_empty() {
+            *self.root_mut() = value;
+            return true;
+        }
+        let Some((parent, last)) = pointer::split_last(path) else {
+            return false;
+        };
+        if let Some(mut obj) = self.object_mut(parent) {
+            obj.insert(&*last, value);
+            return true;
+        }
+        let Some(mut list) = self.list_mut(parent) else {
+            return false;
+        };
+        let len = list.len();
+        match &*last {
+            "-" => list.insert(len, value),
+            last => match pointer::index(last) {
+                Some(index) if index <= len => list.insert(index, value),
+                _ => return false,
+            },
+        };
+        true
+    }
+
+    // A deep copy of the value at `pointer`, with children of its own in the
+    // arena.
+    fn duplicate(&mut self, pointer: &str) -> Option<JsonValue> {
+        let mut value = self.pointer(pointer)?.clone();
+        let mut stack = Vec::new();
+        self.reallocate_children(&mut value, &mut stack);
+        while let Some(id) = stack.pop() {
+            let mut child = std::mem::take(self.get_mut(id));
+            self.reallocate_children(&mut child, &mut stack);
+            *self.get_mut(id) = child;
+        }
+        Some(value)
+    }
+
+    // Points `value` at fresh copies of its children, which still point at
+    // the old grandchildren until they come off `stack`.
+    fn reallocate_children(&mut self, value: &mut JsonValue, stack: &mut Vec<Id<JsonValue>>) {
+        let ids: Box<dyn Iterator<Item = &mut Id<JsonValue>>> = match value {
+            JsonValue::List(list) => Box::new(list.iter_mut()),
+            JsonValue::Object(obj) => Box::new(obj.values_mut()),
+            _ => return,
+        };
+        for id in ids {
+            *id = self.alloc(self.get(*id).clone());
+            stack.push(*id);
+        }
+    }
+}
+
+fn string<'doc>(
+    index: usize,
+    op: ValueRef<'doc>,
+    key: &'static str,
+) -> Result<&'doc str, PatchError> {
+    let message = match key {
+        "op" => "\"op\" must be a string",
+        "path" => "\"path\" must be a string",
+        _ => "\"from\" must be a string",
+    };
+    op.get(key)
+        .and_then(|v| v.as_str())
+        .ok_or(PatchError::Invalid {
+            index: Some(index),
+            message,
+        })
+}
+
+fn value(index: usize, op: ValueRef<'_>) -> Result<ValueRef<'_>, PatchError> {
+    op.get("value").ok_or(PatchError::Invalid {
+        index: Some(index),
+        message: "missing \"value\"",
+    })
+}
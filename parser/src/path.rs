@@ -0,0 +1,727 @@
+use std::{cmp::Ordering, fmt::Write, str::FromStr};
+
+use crate::{
+    doc::{Document, ValueRef},
+    eq::EqOptions,
+    error::PathError,
+    value::JsonValue,
+};
+
+// How deeply parentheses, `!` and filters may nest, so a hostile expression
+// can't exhaust the stack.
+const MAX_NESTING: usize = 64;
+
+/// A compiled JSONPath expression, in the subset of RFC 9535 without
+/// function extensions: `$.store.book[*].author`, `$..price`,
+/// `$['a'][0, -1]`, slices like `[1:5:2]` and filters like
+/// `[?@.price < 10 && !@.sold]`, where `@` is the value being filtered
+/// and `$` the root.
+#[derive(Debug, Clone)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+struct Segment {
+    // `..`: the selectors apply to the value and everything below it.
+    descendant: bool,
+    selectors: Vec<Selector>,
+}
+
+#[derive(Debug, Clone)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+        step: i64,
+    },
+    Filter(Expr),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Or(Vec<Expr>),
+    And(Vec<Expr>),
+    Not(Box<Expr>),
+    Exists(Query),
+    Compare(Operand, Op, Operand),
+}
+
+#[derive(Debug, Clone)]
+struct Query {
+    // From `$` rather than `@`.
+    absolute: bool,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Query(Query),
+    Literal(JsonValue),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl JsonPath {
+    pub fn parse(expr: &str) -> Result<JsonPath, PathError> {
+        let mut parser = Parser {
+            src: expr,
+            pos: 0,
+            nesting: 0,
+        };
+        if !parser.eat("$") {
+            return Err(parser.error("Expected '$'"));
+        }
+        let segments = parser.segments()?;
+        if parser.pos < expr.len() {
+            return Err(parser.error("Expected '.' or '['"));
+        }
+        Ok(JsonPath { segments })
+    }
+
+    /// Every value the path selects in `doc`, with its normalized path, such
+    /// as `$['book'][0]`. They come in the order RFC 9535 gives: lists in
+    /// element order, objects in map order, and a value before those below
+    /// it.
+    pub fn query<'doc>(&self, doc: &'doc Document) -> Vec<(String, ValueRef<'doc>)> {
+        let root = doc.value();
+        select(&self.segments, root, root, true)
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = PathError;
+
+    fn from_str(expr: &str) -> Result<Self, Self::Err> {
+        JsonPath::parse(expr)
+    }
+}
+
+impl Document {
+    /// Runs the JSONPath `expr`; see `JsonPath`. Each match comes with its
+    /// normalized path.
+    pub fn query(&self, expr: &str) -> Result<Vec<(String, ValueRef<'_>)>, PathError> {
+        Ok(JsonPath::parse(expr)?.query(self))
+    }
+}
+
+// The values `segments` lead to from `start`, with their normalized paths
+// unless `paths` is off, as it is inside filters.
+fn select<'doc>(
+    segments: &[Segment],
+    root: ValueRef<'doc>,
+    start: ValueRef<'doc>,
+    paths: bool,
+) -> Vec<(String, ValueRef<'doc>)> {
+    let path = if paths { "$".to_owned() } else { String::new() };
+    let mut nodes = vec![(path, start)];
+    for segment in segments {
+        let mut next = Vec::new();
+        for (path, value) in nodes {
+            if segment.descendant {
+                for (path, value) in descendants(path, value, paths) {
+                    segment.apply(root, &path, value, paths, &mut next);
+                }
+            } else {
+                segment.apply(root, &path, value, paths, &mut next);
+            }
+        }
+        nodes = next;
+    }
+    nodes
+}
+
+// `value` and everything below it, each before its children.
+fn descendants<'doc>(
+    path: String,
+    value: ValueRef<'doc>,
+    paths: bool,
+) -> Vec<(String, ValueRef<'doc>)> {
+    let mut out = Vec::new();
+    let mut stack = vec![(path, value)];
+    while let Some((path, value)) = stack.pop() {
+        let start = stack.len();
+        children(&path, value, paths, &mut stack);
+        stack[start..].reverse();
+        out.push((path, value));
+    }
+    out
+}
+
+fn children<'doc>(
+    path: &str,
+    value: ValueRef<'doc>,
+    paths: bool,
+    out: &mut Vec<(String, ValueRef<'doc>)>,
+) {
+    match value.value() {
+        JsonValue::Object(obj) => {
+            for (key, &id) in obj {
+                out.push((child_path(path, key, paths), value.child(id)));
+            }
+        }
+        JsonValue::List(list) => {
+            for (i, &id) in list.iter().enumerate() {
+                out.push((index_path(path, i, paths), value.child(id)));
+            }
+        }
+        _ => {}
+    }
+}
+
+pub(crate) fn child_path(path: &str, key: &str, paths: bool) -> String {
+    if !paths {
+        return String::new();
+    }
+    let mut path = format!("{path}['");
+    for c in key.chars() {
+        match c {
+            '\'' => path.push_str("\\'"),
+            '\\' => path.push_str("\\\\"),
+            '\u{8}' => path.push_str("\\b"),
+            '\u{c}' => path.push_str("\\f"),
+            '\n' => path.push_str("\\n"),
+            '\r' => path.push_str("\\r"),
+            '\t' => path.push_str("\\t"),
+            c if c < ' ' => {
+                let _ = write!(path, "\\u{:04x}", c as u32);
+            }
+            c => path.push(c),
+        }
+    }
+    path.push_str("']");
+    path
+}
+
+fn index_path(path: &str, index: usize, paths: bool) -> String {
+    if paths {
+        format!("{path}[{index}]")
+    } else {
+        String::new()
+    }
+}
+
+impl Segment {
+    fn apply<'doc>(
+        &self,
+        root: ValueRef<'doc>,
+        path: &str,
+        value: ValueRef<'doc>,
+        paths: bool,
+        out: &mut Vec<(String, ValueRef<'doc>)>,
+    ) {
+        for selector in &self.selectors {
+            match selector {
+                Selector::Name(name) => {
+                    if let Some(child) = value.get(name) {
+                        out.push((child_path(path, name, paths), child));
+                    }
+                }
+                Selector::Wildcard => children(path, value, paths, out),
+                Selector::Index(index) => {
+                    let Some(list) = value.as_array() else {
+                        continue;
+                    };
+                    let len = list.len() as i64;
+                    let index = if *index < 0 { index + len } else { *index };
+                    if (0..len).contains(&index) {
+                        let i = index as usize;
+                        out.push((index_path(path, i, paths), value.child(list[i])));
+                    }
+                }
+                &Selector::Slice { start, end, step } => {
+                    let Some(list) = value.as_array() else {
+                        continue;
+                    };
+                    for i in slice(list.len() as i64, start, end, step) {
+                        let i = i as usize;
+                        out.push((index_path(path, i, paths), value.child(list[i])));
+                    }
+                }
+                Selector::Filter(expr) => {
+                    let mut candidates = Vec::new();
+                    children(path, value, paths, &mut candidates);
+                    out.extend(
+                        candidates
+                            .into_iter()
+                            .filter(|&(_, child)| expr.test(root, child)),
+                    );
+                }
+            }
+        }
+    }
+}
+
+// The indices a slice picks from a list of `len`, as RFC 9535 defines them.
+fn slice(len: i64, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<i64> {
+    let normalize = |i: i64| if i < 0 { len + i } else { i };
+    let mut out = Vec::new();
+    match step.cmp(&0) {
+        Ordering::Equal => {}
+        Ordering::Greater => {
+            let lower = normalize(start.unwrap_or(0)).clamp(0, len);
+            let upper = normalize(end.unwrap_or(len)).clamp(0, len);
+            let mut i = lower;
+            while i < upper {
+                out.push(i);
+                i += step;
+            }
+        }
+        Ordering::Less => {
+            let upper = start.map_or(len - 1, normalize).clamp(-1, len - 1);
+            let lower = end.map_or(-1, normalize).clamp(-1, len - 1);
+            let mut i = upper;
+            while lower < i {
+                out.push(i);
+                i += step;
+            }
+        }
+    }
+    out
+}
+
+impl Expr {
+    fn test(&self, root: ValueRef<'_>, current: ValueRef<'_>) -> bool {
+        match self {
+            Expr::Or(exprs) => exprs.iter().any(|e| e.test(root, current)),
+            Expr::And(exprs) => exprs.iter().all(|e| e.test(root, current)),
+            Expr::Not(expr) => !expr.test(root, current),
+            Expr::Exists(query) => !query.select(root, current).is_empty(),
+            Expr::Compare(a, op, b) => {
+                let a = a.value(root, current);
+                let b = b.value(root, current);
+                match op {
+                    Op::Eq => equal(a, b),
+                    Op::Ne => !equal(a, b),
+                    Op::Lt => less(a, b),
+                    Op::Le => less(a, b) || equal(a, b),
+                    Op::Gt => less(b, a),
+                    Op::Ge => less(b, a) || equal(a, b),
+                }
+            }
+        }
+    }
+}
+
+impl Query {
+    fn select<'doc>(
+        &self,
+        root: ValueRef<'doc>,
+        current: ValueRef<'doc>,
+    ) -> Vec<(String, ValueRef<'doc>)> {
+        let start = if self.absolute { root } else { current };
+        select(&self.segments, root, start, false)
+    }
+
+    // Whether it can select at most one value, as a comparison needs.
+    fn is_singular(&self) -> bool {
+        self.segments.iter().all(|segment| {
+            !segment.descendant
+                && matches!(
+                    segment.selectors[..],
+                    [Selector::Name(_)] | [Selector::Index(_)]
+                )
+        })
+    }
+}
+
+impl Operand {
+    // `None` when a query selects nothing.
+    fn value<'a>(&'a self, root: ValueRef<'a>, current: ValueRef<'a>) -> Option<ValueRef<'a>> {
+        match self {
+            Operand::Query(query) => query.select(root, current).pop().map(|(_, v)| v),
+            // Literals are scalars, so any document will do.
+            Operand::Literal(value) => Some(root.document().value_ref(value)),
+        }
+    }
+}
+
+fn equal(a: Option<ValueRef<'_>>, b: Option<ValueRef<'_>>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => a.deep_eq_with(
+            b,
+            EqOptions {
+                numbers_by_value: true,
+            },
+        ),
+        _ => false,
+    }
+}
+
+// Only numbers and strings are ordered, each among themselves.
+fn less(a: Option<ValueRef<'_>>, b: Option<ValueRef<'_>>) -> bool {
+    let (Some(a), Some(b)) = (a, b) else {
+        return false;
+    };
+    match (a.value(), b.value()) {
+        (JsonValue::Number(_), JsonValue::Number(_))
+        | (JsonValue::String(_), JsonValue::String(_)) => {
+            a.cmp_with(b) == Ordering::Less && !equal(Some(a), Some(b))
+        }
+        _ => false,
+    }
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    nesting: usize,
+}
+
+impl Parser<'_> {
+    fn segments(&mut self) -> Result<Vec<Segment>, PathError> {
+        let mut segments = Vec::new();
+        loop {
+            // Whitespace may separate segments, but in a filter it may also
+            // come before an operator.
+            let before = self.pos;
+            self.skip_whitespace();
+            let descendant = self.eat("..");
+            let selectors = if descendant {
+                match self.peek() {
+                    Some('[') => self.brackets()?,
+                    Some('*') => {
+                        self.pos += 1;
+                        vec![Selector::Wildcard]
+                    }
+                    _ => vec![Selector::Name(self.name()?)],
+                }
+            } else if self.eat(".") {
+                if self.eat("*") {
+                    vec![Selector::Wildcard]
+                } else {
+                    vec![Selector::Name(self.name()?)]
+                }
+            } else if self.peek() == Some('[') {
+                self.brackets()?
+            } else {
+                self.pos = before;
+                return Ok(segments);
+            };
+            segments.push(Segment {
+                descendant,
+                selectors,
+            });
+        }
+    }
+
+    // A name after a dot: a letter, `_` or anything past ASCII, then digits
+    // too.
+    fn name(&mut self) -> Result<String, PathError> {
+        let rest = &self.src[self.pos..];
+        let len = rest
+            .char_indices()
+            .find(|&(i, c)| {
+                !(c.is_ascii_alphabetic()
+                    || c == '_'
+                    || !c.is_ascii()
+                    || i > 0 && c.is_ascii_digit())
+            })
+            .map_or(rest.len(), |(i, _)| i);
+        if len == 0 {
+            return Err(self.error("Expected a name"));
+        }
+        self.pos += len;
+        Ok(rest[..len].to_owned())
+    }
+
+    fn brackets(&mut self) -> Result<Vec<Selector>, PathError> {
+        self.pos += 1;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_whitespace();
+            selectors.push(self.selector()?);
+            self.skip_whitespace();
+            if self.eat("]") {
+                return Ok(selectors);
+            }
+            if !self.eat(",") {
+                return Err(self.error("Expected ',' or ']'"));
+            }
+        }
+    }
+
+    fn selector(&mut self) -> Result<Selector, PathError> {
+        match self.peek() {
+            Some('\'' | '"') => Ok(Selector::Name(self.string()?)),
+            Some('*') => {
+                self.pos += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.pos += 1;
+                self.nest()?;
+                let expr = self.or()?;
+                self.nesting -= 1;
+                Ok(Selector::Filter(expr))
+            }
+            Some('-' | '0'..='9' | ':') => {
+                let start = self.optional_int()?;
+                self.skip_whitespace();
+                if !self.eat(":") {
+                    return start
+                        .map(Selector::Index)
+                        .ok_or_else(|| self.error("Expected an index"));
+                }
+                self.skip_whitespace();
+                let end = self.optional_int()?;
+                self.skip_whitespace();
+                let mut step = 1;
+                if self.eat(":") {
+                    self.skip_whitespace();
+                    step = self.optional_int()?.unwrap_or(1);
+                }
+                Ok(Selector::Slice { start, end, step })
+            }
+            _ => Err(self.error("Expected a selector")),
+        }
+    }
+
+    fn optional_int(&mut self) -> Result<Option<i64>, PathError> {
+        if !matches!(self.peek(), Some('-' | '0'..='9')) {
+            return Ok(None);
+        }
+        let start = self.pos;
+        self.eat("-");
+        let digits = self.src[self.pos..]
+            .bytes()
+            .take_while(u8::is_ascii_digit)
+            .count();
+        self.pos += digits;
+        let text = &self.src[start..self.pos];
+        let leading_zero =
+            text.trim_start_matches('-').len() > 1 && text.trim_start_matches('-').starts_with('0');
+        if digits == 0 || leading_zero || text == "-0" {
+            self.pos = start;
+            return Err(self.error("Invalid integer"));
+        }
+        match text.parse() {
+            Ok(n) => Ok(Some(n)),
+            Err(_) => {
+                self.pos = start;
+                Err(self.error("Integer out of range"))
+            }
+        }
+    }
+
+    // A quoted string, in single or double quotes, with JSON's escapes and
+    // `\'`.
+    fn string(&mut self) -> Result<String, PathError> {
+        let start = self.pos;
+        let quote = self.peek();
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            let Some(c) = self.peek() else {
+                self.pos = start;
+                return Err(self.error("Unterminated string"));
+            };
+            self.pos += c.len_utf8();
+            match c {
+                c if Some(c) == quote => return Ok(out),
+                '\\' => out.push(self.escape()?),
+                c if c < ' ' => {
+                    self.pos -= 1;
+                    return Err(self.error("Control character in string"));
+                }
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, PathError> {
+        let start = self.pos - 1;
+        let c = match self.peek() {
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some(c @ ('/' | '\\' | '\'' | '"')) => c,
+            Some('u') => {
+                self.pos += 1;
+                let high = self.hex4(start)?;
+                let c = if (0xd800..0xdc00).contains(&high) && self.eat("\\u") {
+                    let low = self.hex4(start)?;
+                    let c = 0x10000 + ((high - 0xd800) << 10) + low.wrapping_sub(0xdc00);
+                    char::from_u32(c).filter(|_| (0xdc00..0xe000).contains(&low))
+                } else {
+                    char::from_u32(high)
+                };
+                return c.ok_or_else(|| self.error_at(start, "Invalid escape"));
+            }
+            _ => return Err(self.error_at(start, "Invalid escape")),
+        };
+        self.pos += 1;
+        Ok(c)
+    }
+
+    fn hex4(&mut self, start: usize) -> Result<u32, PathError> {
+        let hex = self.src.get(self.pos..self.pos + 4);
+        match hex.and_then(|hex| u32::from_str_radix(hex, 16).ok()) {
+            Some(n) if hex.is_some_and(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit())) => {
+                self.pos += 4;
+                Ok(n)
+            }
+            _ => Err(self.error_at(start, "Invalid escape")),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, PathError> {
+        let mut exprs = vec![self.and()?];
+        while self.skip_whitespace() && self.eat("||") {
+            exprs.push(self.and()?);
+        }
+        Ok(match exprs.len() {
+            1 => exprs.remove(0),
+            _ => Expr::Or(exprs),
+        })
+    }
+
+    fn and(&mut self) -> Result<Expr, PathError> {
+        let mut exprs = vec![self.not()?];
+        while self.skip_whitespace() && self.eat("&&") {
+            exprs.push(self.not()?);
+        }
+        Ok(match exprs.len() {
+            1 => exprs.remove(0),
+            _ => Expr::And(exprs),
+        })
+    }
+
+    fn not(&mut self) -> Result<Expr, PathError> {
+        self.skip_whitespace();
+        if self.eat("!") {
+            self.nest()?;
+            let expr = self.not()?;
+            self.nesting -= 1;
+            return Ok(Expr::Not(Box::new(expr)));
+        }
+        if self.eat("(") {
+            self.nest()?;
+            let expr = self.or()?;
+            self.nesting -= 1;
+            self.skip_whitespace();
+            if !self.eat(")") {
+                return Err(self.error("Expected ')'"));
+            }
+            return Ok(expr);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, PathError> {
+        let start = self.pos;
+        let left = self.operand()?;
+        self.skip_whitespace();
+        let ops = [
+            ("==", Op::Eq),
+            ("!=", Op::Ne),
+            ("<=", Op::Le),
+            ("<", Op::Lt),
+            (">=", Op::Ge),
+            (">", Op::Gt),
+        ];
+        let Some(op) = ops.into_iter().find(|(s, _)| self.eat(s)).map(|(_, op)| op) else {
+            return match left {
+                Operand::Query(query) => Ok(Expr::Exists(query)),
+                Operand::Literal(_) => Err(self.error("Expected a comparison")),
+            };
+        };
+        self.skip_whitespace();
+        let right_start = self.pos;
+        let right = self.operand()?;
+        for (operand, at) in [(&left, start), (&right, right_start)] {
+            if matches!(operand, Operand::Query(query) if !query.is_singular()) {
+                return Err(self.error_at(at, "Expected a query for a single value"));
+            }
+        }
+        Ok(Expr::Compare(left, op, right))
+    }
+
+    fn operand(&mut self) -> Result<Operand, PathError> {
+        let absolute = match self.peek() {
+            Some('$') => true,
+            Some('@') => false,
+            Some('\'' | '"') => return Ok(Operand::Literal(JsonValue::String(self.string()?))),
+            Some('-' | '0'..='9') => return self.number(),
+            _ => {
+                for (word, value) in [
+                    ("true", JsonValue::Bool(true)),
+                    ("false", JsonValue::Bool(false)),
+                    ("null", JsonValue::Null),
+                ] {
+                    if self.eat(word) {
+                        return Ok(Operand::Literal(value));
+                    }
+                }
+                return Err(self.error("Expected a value or a query"));
+            }
+        };
+        self.pos += 1;
+        let segments = self.segments()?;
+        Ok(Operand::Query(Query { absolute, segments }))
+    }
+
+    // A number literal, read as JSON reads it.
+    fn number(&mut self) -> Result<Operand, PathError> {
+        let start = self.pos;
+        let len = self.src[start..]
+            .find(|c: char| !matches!(c, '-' | '+' | '.' | 'e' | 'E' | '0'..='9'))
+            .unwrap_or(self.src.len() - start);
+        match crate::parse_value(&self.src[start..start + len]).map(Document::into_parts) {
+            Ok((value @ JsonValue::Number(_), _)) => {
+                self.pos += len;
+                Ok(Operand::Literal(value))
+            }
+            _ => Err(self.error("Invalid number")),
+        }
+    }
+
+    fn nest(&mut self) -> Result<(), PathError> {
+        if self.nesting == MAX_NESTING {
+            return Err(self.error("Nested too deeply"));
+        }
+        self.nesting += 1;
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        let found = self.src[self.pos..].starts_with(s);
+        if found {
+            self.pos += s.len();
+        }
+        found
+    }
+
+    // Always `true`, to chain in conditions.
+    fn skip_whitespace(&mut self) -> bool {
+        let rest = &self.src[self.pos..];
+        self.pos += rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+        true
+    }
+
+    fn error(&self, message: &'static str) -> PathError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, offset: usize, message: &'static str) -> PathError {
+        PathError { message, offset }
+    }
+}
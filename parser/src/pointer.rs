@@ -0,0 +1,51 @@
+use std::borrow::Cow;
+
+// The reference tokens of an RFC 6901 JSON Pointer, unescaped, or `None`
+// if `pointer` is neither empty nor starts with a '/'.
+pub(crate) fn tokens(pointer: &str) -> Option<impl Iterator<Item = Cow<'_, str>>> {
+    let rest = match pointer {
+        "" => None,
+        _ => Some(pointer.strip_prefix('/')?),
+    };
+    Some(
+        rest.into_iter()
+            .flat_map(|rest| rest.split('/'))
+            .map(unescape),
+    )
+}
+
+// Splits a non-empty pointer into its parent's pointer and its last token.
+pub(crate) fn split_last(pointer: &str) -> Option<(&str, Cow<'_, str>)> {
+    if !pointer.starts_with('/') {
+        return None;
+    }
+    let (parent, last) = pointer.rsplit_once('/')?;
+    Some((parent, unescape(last)))
+}
+
+// A key or index as a reference token, the inverse of `unescape`.
+pub(crate) fn escape(token: &str) -> Cow<'_, str> {
+    if token.contains(['~', '/']) {
+        Cow::Owned(token.replace('~', "~0").replace('/', "~1"))
+    } else {
+        Cow::Borrowed(token)
+    }
+}
+
+fn unescape(token: &str) -> Cow<'_, str> {
+    if token.contains('~') {
+        Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+    } else {
+        Cow::Borrowed(token)
+    }
+}
+
+// A token as a list index: digits without leading zeros. `-`, the element
+// past the end, never exists to be read.
+pub(crate) fn index(token: &str) -> Option<usize> {
+    let digits = token.bytes().all(|b| b.is_ascii_digit());
+    if token.is_empty() || !digits || (token.len() > 1 && token.starts_with('0')) {
+        return None;
+    }
+    token.parse().ok()
+}
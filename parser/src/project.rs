@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use crate::{alloc::Id, doc::Document, map::Map, owned, value::JsonValue};
+
+// The paths given to `project` or `strip`, as a tree of keys. `whole` marks
+// where a path ends, selecting everything below.
+#[derive(Default)]
+struct Selection<'p> {
+    keys: HashMap<&'p str, Selection<'p>>,
+    whole: bool,
+}
+
+impl Document {
+    /// A new document with only what `paths` select. A path is dotted keys,
+    /// like `address.city`, and goes through lists into every element, so
+    /// `items.price` is each item's price. Objects and lists on the way to a
+    /// selected value are kept with just what was selected in them, but a
+    /// value a path would have to go into and can't is left out.
+    pub fn project(&self, paths: &[&str]) -> Document {
+        self.select(paths, true)
+    }
+
+    /// A new document without what `paths` select, read as `project` reads
+    /// them: `strip(&["password", "users.token"])`.
+    pub fn strip(&self, paths: &[&str]) -> Document {
+        self.select(paths, false)
+    }
+
+    // What `project` gives if `keep`, and `strip` otherwise.
+    fn select(&self, paths: &[&str], keep: bool) -> Document {
+        let mut selection = Selection::default();
+        for path in paths {
+            let mut at = &mut selection;
+            for key in path.split('.') {
+                at = at.keys.entry(key).or_default();
+            }
+            at.whole = true;
+        }
+        let nothing = Selection::default();
+        let mut doc = Document::default();
+        let mut stack = vec![(&selection, self.value(), None)];
+        while let Some((selection, value, at)) = stack.pop() {
+            // Copied as it is: everything in a selected value for `project`,
+            // and anything no path goes into for `strip`.
+            let whole = match keep {
+                true => selection.whole,
+                false => selection.keys.is_empty(),
+            };
+            match value.value() {
+                JsonValue::Object(obj) if !whole => {
+                    let mut members = Map::new();
+                    for (key, &id) in obj {
+                        let value = value.child(id);
+                        if let Some(selection) =
+                            child(selection.keys.get(key.as_str()), &value, keep, &nothing)
+                        {
+                            let id = doc.alloc(JsonValue::Null);
+                            members.insert(key.clone(), id);
+                            stack.push((selection, value, Some(id)));
+                        }
+                    }
+                    *slot(&mut doc, at) = JsonValue::Object(members);
+                }
+                JsonValue::List(list) if !whole => {
+                    let mut elements = Vec::new();
+                    for &id in list {
+                        let value = value.child(id);
+                        if let Some(selection) = child(Some(selection), &value, keep, &nothing) {
+                            let id = doc.alloc(JsonValue::Null);
+                            elements.push(id);
+                            stack.push((selection, value, Some(id)));
+                        }
+                    }
+                    *slot(&mut doc, at) = JsonValue::List(elements);
+                }
+                // A scalar a path goes on into, which `project` only gets to
+                // at the root, and leaves null.
+                _ if keep && !whole => {}
+                _ => {
+                    let copy = owned::move_in(value.to_owned_tree(), doc.arena_mut());
+                    *slot(&mut doc, at) = copy;
+                }
+            }
+        }
+        doc
+    }
+}
+
+// Which selection a child of a kept container goes on with, or `None` to
+// leave it out.
+fn child<'s, 'p>(
+    selection: Option<&'s Selection<'p>>,
+    value: &JsonValue,
+    keep: bool,
+    nothing: &'s Selection<'p>,
+) -> Option<&'s Selection<'p>> {
+    match selection {
+        Some(selection) if selection.whole => keep.then_some(selection),
+        Some(selection) if keep => {
+            matches!(value, JsonValue::Object(_) | JsonValue::List(_)).then_some(selection)
+        }
+        Some(selection) => Some(selection),
+        None if keep => None,
+        None => Some(nothing),
+    }
+}
+
+fn slot(doc: &mut Document, at: Option<Id<JsonValue>>) -> &mut JsonValue {
+    match at {
+        Some(id) => doc.get_mut(id),
+        None => doc.root_mut(),
+    }
+}
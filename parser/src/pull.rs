@@ -0,0 +1,457 @@
+use std::{borrow::Cow, io::Read};
+
+use crate::{
+    error::ParseError,
+    lex::{Lex, Span, SpannedToken, Token},
+    options::ParseOptions,
+    par,
+    source::{ReadSource, SliceSource, Source},
+    value::Number,
+};
+
+/// One step through a document, in source order. Strings and keys borrow
+/// from the parser and only live until the next call.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<'a> {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    Key(&'a str),
+    String(&'a str),
+    Number(Number),
+    Bool(bool),
+    Null,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Container {
+    Array,
+    Object,
+}
+
+impl Container {
+    fn close(self) -> Token {
+        match self {
+            Container::Array => Token::RBracket,
+            Container::Object => Token::RBrace,
+        }
+    }
+}
+
+// Where a value or key is being read: whether a closing bracket may take its
+// place, which is the case right after the opening one and, with trailing
+// commas, after a comma.
+#[derive(Clone, Copy)]
+enum Slot {
+    Root,
+    First,
+    Member,
+    AfterComma(Span),
+}
+
+#[derive(Clone, Copy)]
+enum State {
+    Value(Slot),
+    Key(Slot),
+    AfterValue,
+    End,
+    Done,
+}
+
+/// Parses a single value one event at a time, without building it. Only the
+/// stack of open containers is kept, so memory use doesn't grow with the
+/// input. `max_nodes`, `raw_keys` and a `duplicate_keys` policy of `Error`
+/// don't apply.
+pub struct JsonPullParser<S> {
+    lex: Lex<S>,
+    opts: ParseOptions,
+    stack: Vec<Container>,
+    state: State,
+    buf: String,
+    // A token `skip_value` looked at but left for the next event.
+    pending: Option<SpannedToken>,
+    // For `parse_borrowed`, strings are skimmed and read from the source
+    // afterwards: whether to, and whether and where the last string or key
+    // was.
+    borrow: bool,
+    skimmed: bool,
+    span: Span,
+}
+
+impl<'json> JsonPullParser<SliceSource<'json>> {
+    pub fn new(src: &'json str) -> Self {
+        Self::with_options(src, ParseOptions::default())
+    }
+
+    pub fn with_options(src: &'json str, opts: ParseOptions) -> Self {
+        Self::from_lex(Lex::with_options(src, opts), opts)
+    }
+
+    // A parser for `step` and `text`, which leaves strings in `src` where it
+    // can.
+    pub(crate) fn borrowing(src: &'json str, opts: ParseOptions) -> Self {
+        let mut par = Self::with_options(src, opts);
+        par.borrow = true;
+        par
+    }
+
+    // The text of the string or key `step` just gave, read from `src` unless
+    // it had escapes to decode.
+    pub(crate) fn text(&mut self, src: &'json str) -> Result<Cow<'json, str>, ParseError> {
+        if !self.skimmed {
+            return Ok(Cow::Owned(std::mem::take(&mut self.buf)));
+        }
+        let span = self.span;
+        let quote = Span {
+            end: span.start + 1,
+            ..span
+        };
+        let literal = &src[span.start..span.end];
+        if self.lex.escaped() {
+            // It lexed once already, so only its length can fail it now.
+            return match Lex::with_options(literal, self.opts).next_token() {
+                Ok(SpannedToken {
+                    token: Token::Str(s),
+                    ..
+                }) => Ok(Cow::Owned(s)),
+                _ => Err(ParseError::StringTooLong {
+                    max: self.opts.max_string_length.unwrap_or_default(),
+                    span: quote,
+                }),
+            };
+        }
+        let text = &literal[1..literal.len() - 1];
+        match self.opts.max_string_length {
+            Some(max) if text.len() > max => Err(ParseError::StringTooLong { max, span: quote }),
+            _ => Ok(Cow::Borrowed(text)),
+        }
+    }
+
+    // `step` for a parser from `borrowing`. A string it skimmed and then
+    // found out of place is named by its text in `src`, as the other parsers
+    // name it.
+    pub(crate) fn step_in(&mut self, src: &'json str) -> Result<Option<Step>, ParseError> {
+        self.step().map_err(|error| {
+            let ParseError::UnexpectedToken { expected, span, .. } = error else {
+                return error;
+            };
+            match Lex::with_options(&src[span.start..span.end], self.opts).next_token() {
+                Ok(SpannedToken {
+                    token: token @ Token::Str(_),
+                    ..
+                }) => par::unexpected(&SpannedToken { token, span }, expected),
+                _ => error,
+            }
+        })
+    }
+
+    // Where the last string or key was.
+    pub(crate) fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl<R: Read> JsonPullParser<ReadSource<R>> {
+    pub fn from_reader(reader: R, opts: ParseOptions) -> Self {
+        Self::from_lex(Lex::from_reader(reader, opts), opts)
+    }
+}
+
+impl<S: Source> JsonPullParser<S> {
+    pub fn from_lex(lex: Lex<S>, opts: ParseOptions) -> Self {
+        Self {
+            lex,
+            opts,
+            stack: Vec::new(),
+            state: State::Value(Slot::Root),
+            buf: String::new(),
+            pending: None,
+            borrow: false,
+            skimmed: false,
+            span: Span::default(),
+        }
+    }
+
+    /// How many containers are open.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// The next event, or `None` once the value is complete. After an error
+    /// the parser stays finished.
+    pub fn next_event(&mut self) -> Result<Option<Event<'_>>, ParseError> {
+        match self.step() {
+            Ok(Some(event)) => Ok(Some(event.resolve(&self.buf))),
+            Ok(None) => {
+                self.state = State::Done;
+                Ok(None)
+            }
+            Err(e) => {
+                self.state = State::Done;
+                Err(e)
+            }
+        }
+    }
+
+    /// Skips the value the next event would start: a member's value right
+    /// after its `Key`, the next array element, or the whole input before
+    /// the first event. Only brackets are matched inside it, and strings are
+    /// checked for escapes and their closing quote but not kept. Returns
+    /// `false`, consuming nothing, when no value comes next, such as at the
+    /// end of a container.
+    pub fn skip_value(&mut self) -> Result<bool, ParseError> {
+        let skipped = self.skip();
+        if skipped.is_err() {
+            self.state = State::Done;
+        }
+        skipped
+    }
+
+    /// Skips the rest of the innermost open container, through its closing
+    /// bracket, checking it as loosely as `skip_value` does. There is no end
+    /// event for it. Returns `false` outside of any container.
+    pub fn skip_rest(&mut self) -> Result<bool, ParseError> {
+        let Some(&container) = self.stack.last() else {
+            return Ok(false);
+        };
+        if let Err(e) = self.skip_to_close(vec![container.close()]) {
+            self.state = State::Done;
+            return Err(e);
+        }
+        self.stack.pop();
+        self.state = self.after_value();
+        Ok(true)
+    }
+
+    fn skip(&mut self) -> Result<bool, ParseError> {
+        // An array element may still be behind its comma.
+        if matches!(self.state, State::AfterValue) && self.stack.last() == Some(&Container::Array) {
+            let tok = self.token(false)?;
+            if tok.token != Token::Comma {
+                self.pending = Some(tok);
+                return Ok(false);
+            }
+            self.state = State::Value(Slot::AfterComma(tok.span));
+        }
+        if !matches!(self.state, State::Value(_)) {
+            return Ok(false);
+        }
+        let tok = self.token(true)?;
+        let close = match tok.token {
+            Token::LBracket => Container::Array.close(),
+            Token::LBrace => Container::Object.close(),
+            Token::RBracket
+            | Token::RBrace
+            | Token::Comma
+            | Token::Colon
+            | Token::Ident(_)
+            | Token::Eof => {
+                self.pending = Some(tok);
+                return Ok(false);
+            }
+            _ => {
+                self.state = self.after_value();
+                return Ok(true);
+            }
+        };
+        self.skip_to_close(vec![close])?;
+        self.state = self.after_value();
+        Ok(true)
+    }
+
+    fn skip_to_close(&mut self, mut closers: Vec<Token>) -> Result<(), ParseError> {
+        while let Some(close) = closers.last() {
+            let tok = self.token(true)?;
+            match tok.token {
+                Token::LBracket | Token::LBrace => {
+                    if let Some(max) = self.opts.max_depth {
+                        if self.stack.len() + closers.len() >= max {
+                            return Err(ParseError::DepthExceeded {
+                                max,
+                                span: tok.span,
+                            });
+                        }
+                    }
+                    closers.push(match tok.token {
+                        Token::LBracket => Token::RBracket,
+                        _ => Token::RBrace,
+                    });
+                }
+                ref token if token == close => {
+                    closers.pop();
+                }
+                Token::RBracket | Token::RBrace | Token::Eof => {
+                    let expected = match close {
+                        Token::RBracket => "']'",
+                        _ => "'}'",
+                    };
+                    return Err(par::unexpected(&tok, Some(expected)));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn token(&mut self, skim: bool) -> Result<SpannedToken, ParseError> {
+        if let Some(tok) = self.pending.take() {
+            return Ok(tok);
+        }
+        let tok = if skim {
+            self.lex.skim_token()?
+        } else {
+            self.lex.next_token()?
+        };
+        Ok(tok)
+    }
+
+    pub(crate) fn step(&mut self) -> Result<Option<Step>, ParseError> {
+        loop {
+            if matches!(self.state, State::Done) {
+                return Ok(None);
+            }
+            let tok = self.token(self.borrow)?;
+            match self.state {
+                State::Value(slot) => return self.value(tok, slot).map(Some),
+                State::Key(slot) => return self.key(tok, slot).map(Some),
+                State::AfterValue => match (tok.token, self.stack.last()) {
+                    (Token::Comma, Some(Container::Array)) => {
+                        self.state = State::Value(Slot::AfterComma(tok.span));
+                    }
+                    (Token::Comma, Some(Container::Object)) => {
+                        self.state = State::Key(Slot::AfterComma(tok.span));
+                    }
+                    (Token::RBracket, Some(Container::Array)) => return Ok(Some(self.close())),
+                    (Token::RBrace, Some(Container::Object)) => return Ok(Some(self.close())),
+                    (token, Some(Container::Array)) => {
+                        return Err(unexpected(token, tok.span, "',' or ']'"))
+                    }
+                    (token, _) => return Err(unexpected(token, tok.span, "',' or '}'")),
+                },
+                State::End => match tok.token {
+                    Token::Eof => return Ok(None),
+                    _ => return Err(ParseError::TrailingData { span: tok.span }),
+                },
+                State::Done => return Ok(None),
+            }
+        }
+    }
+
+    fn value(&mut self, tok: SpannedToken, slot: Slot) -> Result<Step, ParseError> {
+        let step = match tok.token {
+            Token::False => Step::Bool(false),
+            Token::True => Step::Bool(true),
+            Token::Null => Step::Null,
+            Token::Num(n) => Step::Number(n),
+            Token::Str(s) => {
+                self.buf = s;
+                self.skimmed = self.borrow;
+                self.span = tok.span;
+                Step::String
+            }
+            Token::LBracket => return self.open(Container::Array, tok.span),
+            Token::LBrace => return self.open(Container::Object, tok.span),
+            Token::RBracket if self.closes(slot)? => return Ok(self.close()),
+            token => return Err(unexpected(token, tok.span, "a value")),
+        };
+        self.state = self.after_value();
+        Ok(step)
+    }
+
+    fn key(&mut self, tok: SpannedToken, slot: Slot) -> Result<Step, ParseError> {
+        self.skimmed = self.borrow && matches!(tok.token, Token::Str(_));
+        self.span = tok.span;
+        match tok.token {
+            Token::Str(s) => self.buf = s,
+            Token::Ident(s) if self.opts.json5() => self.buf = s,
+            Token::RBrace if self.closes(slot)? => return Ok(self.close()),
+            token => return Err(unexpected(token, tok.span, "a string key")),
+        }
+        let colon = self.token(false)?;
+        if colon.token != Token::Colon {
+            return Err(unexpected(colon.token, colon.span, "':'"));
+        }
+        self.state = State::Value(Slot::Member);
+        Ok(Step::Key)
+    }
+
+    // Whether a closing bracket is allowed in `slot`.
+    fn closes(&self, slot: Slot) -> Result<bool, ParseError> {
+        match slot {
+            Slot::First => Ok(true),
+            Slot::AfterComma(span) if self.opts.strict => Err(ParseError::TrailingComma { span }),
+            Slot::AfterComma(_) => Ok(self.opts.trailing_commas()),
+            Slot::Root | Slot::Member => Ok(false),
+        }
+    }
+
+    fn open(&mut self, container: Container, span: Span) -> Result<Step, ParseError> {
+        if let Some(max) = self.opts.max_depth {
+            if self.stack.len() >= max {
+                return Err(ParseError::DepthExceeded { max, span });
+            }
+        }
+        self.stack.push(container);
+        Ok(match container {
+            Container::Array => {
+                self.state = State::Value(Slot::First);
+                Step::StartArray
+            }
+            Container::Object => {
+                self.state = State::Key(Slot::First);
+                Step::StartObject
+            }
+        })
+    }
+
+    fn close(&mut self) -> Step {
+        let container = self.stack.pop();
+        self.state = self.after_value();
+        match container {
+            Some(Container::Object) => Step::EndObject,
+            _ => Step::EndArray,
+        }
+    }
+
+    fn after_value(&self) -> State {
+        if self.stack.is_empty() {
+            State::End
+        } else {
+            State::AfterValue
+        }
+    }
+}
+
+// An `Event` before it borrows the string buffer.
+pub(crate) enum Step {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    Key,
+    String,
+    Number(Number),
+    Bool(bool),
+    Null,
+}
+
+impl Step {
+    fn resolve(self, buf: &str) -> Event<'_> {
+        match self {
+            Step::StartObject => Event::StartObject,
+            Step::EndObject => Event::EndObject,
+            Step::StartArray => Event::StartArray,
+            Step::EndArray => Event::EndArray,
+            Step::Key => Event::Key(buf),
+            Step::String => Event::String(buf),
+            Step::Number(n) => Event::Number(n),
+            Step::Bool(b) => Event::Bool(b),
+            Step::Null => Event::Null,
+        }
+    }
+}
+
+fn unexpected(token: Token, span: Span, expected: &'static str) -> ParseError {
+    par::unexpected(&SpannedToken { token, span }, Some(expected))
+}
@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+use crate::{
+    alloc::Id,
+    canonical,
+    doc::{Document, ValueRef},
+    map::Map,
+    owned,
+    value::JsonValue,
+};
+
+/// What `Redactor` puts in place of a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mask {
+    /// This string, whatever the value was.
+    Text(String),
+    /// The 64-bit FNV-1a digest of `salt` and then the value's canonical
+    /// form, as 16 hex digits, so equal values still match up. FNV isn't a
+    /// cryptographic hash: without a secret salt, a value that can be
+    /// guessed, like a phone number, can be found from its digest.
+    Hash { salt: String },
+}
+
+impl Default for Mask {
+    /// `"***"`.
+    fn default() -> Self {
+        Mask::Text("***".to_owned())
+    }
+}
+
+/// Masks values at the paths and under the keys registered with it, for
+/// logging documents that hold personal data:
+/// `Redactor::new().path("*.ssn").key("password").redact(&doc)`.
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    paths: Vec<Vec<String>>,
+    keys: HashSet<String>,
+    mask: Mask,
+}
+
+impl Redactor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Masks what a path from the root selects. A path is dotted keys, with
+    /// `*` for any key, and goes through lists into every element, as
+    /// `Document::project` reads it, so `*.ssn` is the `ssn` of every
+    /// top-level member, or of every element of one that's a list.
+    pub fn path(mut self, path: &str) -> Self {
+        self.paths
+            .push(path.split('.').map(str::to_owned).collect());
+        self
+    }
+
+    /// Masks every member named `key`, at any depth.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.keys.insert(key.into());
+        self
+    }
+
+    pub fn mask(mut self, mask: Mask) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    /// A copy of `doc` with everything registered masked. The masked values
+    /// aren't copied, so nothing of them is left in the new arena.
+    pub fn redact(&self, doc: &Document) -> Document {
+        let mut out = Document::default();
+        // Each value with how far along each path its parents got it, as
+        // (path, segment) pairs.
+        let roots: Vec<_> = (0..self.paths.len()).map(|path| (path, 0)).collect();
+        let mut stack = vec![(doc.value(), roots, None)];
+        while let Some((value, states, at)) = stack.pop() {
+            match value.value() {
+                JsonValue::Object(obj) => {
+                    let mut members = Map::new();
+                    for (key, &id) in obj {
+                        let child = value.child(id);
+                        let mut masked = self.keys.contains(key.as_str());
+                        let mut next = Vec::new();
+                        for &(path, segment) in &states {
+                            let segments = &self.paths[path];
+                            if segments[segment] != "*" && *key != segments[segment] {
+                                continue;
+                            }
+                            match segment + 1 == segments.len() {
+                                true => masked = true,
+                                false => next.push((path, segment + 1)),
+                            }
+                        }
+                        let id = match masked {
+                            true => out.alloc(self.masked(child)),
+                            false => {
+                                let id = out.alloc(JsonValue::Null);
+                                stack.push((child, next, Some(id)));
+                                id
+                            }
+                        };
+                        members.insert(key.clone(), id);
+                    }
+                    *slot(&mut out, at) = JsonValue::Object(members);
+                }
+                JsonValue::List(list) => {
+                    let mut elements = Vec::new();
+                    for &id in list {
+                        let copy = out.alloc(JsonValue::Null);
+                        elements.push(copy);
+                        stack.push((value.child(id), states.clone(), Some(copy)));
+                    }
+                    *slot(&mut out, at) = JsonValue::List(elements);
+                }
+                _ => {
+                    let copy = owned::move_in(value.to_owned_tree(), out.arena_mut());
+                    *slot(&mut out, at) = copy;
+                }
+            }
+        }
+        out
+    }
+
+    fn masked(&self, value: ValueRef<'_>) -> JsonValue {
+        match &self.mask {
+            Mask::Text(text) => JsonValue::String(text.clone()),
+            Mask::Hash { salt } => {
+                JsonValue::String(format!("{:016x}", canonical::salted_hash(value, salt)))
+            }
+        }
+    }
+}
+
+fn slot(doc: &mut Document, at: Option<Id<JsonValue>>) -> &mut JsonValue {
+    match at {
+        Some(id) => doc.get_mut(id),
+        None => doc.root_mut(),
+    }
+}
@@ -0,0 +1,535 @@
+// The regular expressions of JSON Schema's `pattern`: the common ground of
+// ECMA-262 syntax, run as a Pike VM so that matching takes time linear in
+// the input whatever the pattern.
+
+// How deeply groups may nest, and how many instructions a pattern may
+// compile to once counted repetitions are spelled out.
+const MAX_NESTING: usize = 64;
+const MAX_PROGRAM: usize = 1 << 16;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Regex {
+    program: Vec<Inst>,
+}
+
+#[derive(Debug, Clone)]
+enum Inst {
+    Char(char),
+    // `.`, which doesn't match line terminators.
+    Any,
+    Class(Class),
+    // Try both.
+    Split(usize, usize),
+    Jmp(usize),
+    Assert(Look),
+    Match,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Look {
+    Start,
+    End,
+    WordBoundary,
+    NotWordBoundary,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Class {
+    ranges: Vec<(char, char)>,
+    negated: bool,
+}
+
+impl Class {
+    fn contains(&self, c: char) -> bool {
+        self.ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != self.negated
+    }
+}
+
+enum Node {
+    Char(char),
+    Any,
+    Class(Class),
+    Look(Look),
+    Concat(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: u32,
+        max: Option<u32>,
+    },
+}
+
+impl Regex {
+    /// Compiles `pattern`, or says what's wrong with it.
+    pub(crate) fn new(pattern: &str) -> Result<Regex, &'static str> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+            nesting: 0,
+        };
+        let node = parser.alternation()?;
+        if parser.pos < parser.chars.len() {
+            return Err("unmatched ')'");
+        }
+        let mut program = Vec::new();
+        compile(&node, &mut program)?;
+        program.push(Inst::Match);
+        Ok(Regex { program })
+    }
+
+    /// Whether the pattern matches anywhere in `text`; JSON Schema patterns
+    /// aren't anchored.
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        let chars: Vec<char> = text.chars().collect();
+        let mut current = Threads::new(self.program.len());
+        let mut next = Threads::new(self.program.len());
+        for i in 0..=chars.len() {
+            self.add(&mut current, 0, &chars, i);
+            for k in 0..current.list.len() {
+                let pc = current.list[k];
+                let step = match (&self.program[pc], chars.get(i)) {
+                    (Inst::Match, _) => return true,
+                    (Inst::Char(c), Some(d)) => c == d,
+                    (Inst::Any, Some(&d)) => !matches!(d, '\n' | '\r' | '\u{2028}' | '\u{2029}'),
+                    (Inst::Class(class), Some(&d)) => class.contains(d),
+                    _ => false,
+                };
+                if step {
+                    self.add(&mut next, pc + 1, &chars, i + 1);
+                }
+            }
+            std::mem::swap(&mut current, &mut next);
+            next.clear();
+        }
+        false
+    }
+
+    // Adds the thread at `pc`, following jumps and checking assertions at
+    // position `i`.
+    fn add(&self, threads: &mut Threads, pc: usize, chars: &[char], i: usize) {
+        let mut stack = vec![pc];
+        while let Some(pc) = stack.pop() {
+            if !threads.insert(pc) {
+                continue;
+            }
+            match self.program[pc] {
+                Inst::Jmp(to) => stack.push(to),
+                Inst::Split(a, b) => {
+                    stack.push(b);
+                    stack.push(a);
+                }
+                Inst::Assert(look) => {
+                    let word = |c: Option<&char>| {
+                        c.is_some_and(|&c| c.is_ascii_alphanumeric() || c == '_')
+                    };
+                    let before = word(i.checked_sub(1).and_then(|i| chars.get(i)));
+                    let after = word(chars.get(i));
+                    let holds = match look {
+                        Look::Start => i == 0,
+                        Look::End => i == chars.len(),
+                        Look::WordBoundary => before != after,
+                        Look::NotWordBoundary => before == after,
+                    };
+                    if holds {
+                        stack.push(pc + 1);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// The program counters of live threads, in order, without repeats.
+struct Threads {
+    list: Vec<usize>,
+    seen: Vec<bool>,
+}
+
+impl Threads {
+    fn new(len: usize) -> Self {
+        Self {
+            list: Vec::new(),
+            seen: vec![false; len],
+        }
+    }
+
+    fn insert(&mut self, pc: usize) -> bool {
+        if self.seen[pc] {
+            return false;
+        }
+        self.seen[pc] = true;
+        self.list.push(pc);
+        true
+    }
+
+    fn clear(&mut self) {
+        for &pc in &self.list {
+            self.seen[pc] = false;
+        }
+        self.list.clear();
+    }
+}
+
+fn compile(node: &Node, program: &mut Vec<Inst>) -> Result<(), &'static str> {
+    if program.len() > MAX_PROGRAM {
+        return Err("pattern too large");
+    }
+    match node {
+        Node::Char(c) => program.push(Inst::Char(*c)),
+        Node::Any => program.push(Inst::Any),
+        Node::Class(class) => program.push(Inst::Class(class.clone())),
+        Node::Look(look) => program.push(Inst::Assert(*look)),
+        Node::Concat(nodes) => {
+            for node in nodes {
+                compile(node, program)?;
+            }
+        }
+        Node::Alt(nodes) => {
+            // Each alternative but the last is split off, and jumps to the
+            // end when done.
+            let mut jumps = Vec::new();
+            for (i, node) in nodes.iter().enumerate() {
+                if i + 1 < nodes.len() {
+                    let split = program.len();
+                    program.push(Inst::Split(split + 1, 0));
+                    compile(node, program)?;
+                    jumps.push(program.len());
+                    program.push(Inst::Jmp(0));
+                    let next = program.len();
+                    program[split] = Inst::Split(split + 1, next);
+                } else {
+                    compile(node, program)?;
+                }
+            }
+            let end = program.len();
+            for jump in jumps {
+                program[jump] = Inst::Jmp(end);
+            }
+        }
+        Node::Repeat { node, min, max } => {
+            for _ in 0..*min {
+                compile(node, program)?;
+            }
+            match max {
+                None => {
+                    let split = program.len();
+                    program.push(Inst::Split(split + 1, 0));
+                    compile(node, program)?;
+                    program.push(Inst::Jmp(split));
+                    let end = program.len();
+                    program[split] = Inst::Split(split + 1, end);
+                }
+                Some(max) => {
+                    let mut splits = Vec::new();
+                    for _ in *min..*max {
+                        splits.push(program.len());
+                        program.push(Inst::Split(0, 0));
+                        compile(node, program)?;
+                    }
+                    let end = program.len();
+                    for split in splits {
+                        program[split] = Inst::Split(split + 1, end);
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    nesting: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn alternation(&mut self) -> Result<Node, &'static str> {
+        let mut alternatives = vec![self.concatenation()?];
+        while self.eat('|') {
+            alternatives.push(self.concatenation()?);
+        }
+        Ok(match alternatives.len() {
+            1 => alternatives.remove(0),
+            _ => Node::Alt(alternatives),
+        })
+    }
+
+    fn concatenation(&mut self) -> Result<Node, &'static str> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantified(atom)?);
+        }
+        Ok(Node::Concat(nodes))
+    }
+
+    fn quantified(&mut self, atom: Node) -> Result<Node, &'static str> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => match self.braces() {
+                Some(bounds) => bounds,
+                // Not a quantifier after all, so a literal brace.
+                None => return Ok(atom),
+            },
+            _ => return Ok(atom),
+        };
+        // The quantifier's last character.
+        self.pos += 1;
+        if matches!(atom, Node::Look(_)) {
+            return Err("nothing to repeat");
+        }
+        if max.is_some_and(|max| max < min) {
+            return Err("numbers out of order in {} quantifier");
+        }
+        // Lazy and greedy match the same texts.
+        self.eat('?');
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+        })
+    }
+
+    // `{n}`, `{n,}` or `{n,m}`, if well-formed, consumed up to the closing
+    // brace.
+    fn braces(&mut self) -> Option<(u32, Option<u32>)> {
+        let start = self.pos;
+        self.pos += 1;
+        let min = self.number();
+        let bounds = match (min, self.eat(',')) {
+            (Some(min), false) => Some((min, Some(min))),
+            (Some(min), true) => match self.peek() {
+                Some('}') => Some((min, None)),
+                _ => self.number().map(|max| (min, Some(max))),
+            },
+            (None, _) => None,
+        };
+        match bounds {
+            Some(bounds) if self.peek() == Some('}') => Some(bounds),
+            _ => {
+                self.pos = start;
+                None
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits.parse().ok()
+    }
+
+    fn atom(&mut self) -> Result<Node, &'static str> {
+        let c = self.peek().ok_or("unexpected end")?;
+        self.pos += 1;
+        Ok(match c {
+            '.' => Node::Any,
+            '^' => Node::Look(Look::Start),
+            '$' => Node::Look(Look::End),
+            '(' => {
+                if self.nesting == MAX_NESTING {
+                    return Err("groups nested too deeply");
+                }
+                if self.eat('?') && !self.eat(':') {
+                    return Err("unsupported group");
+                }
+                self.nesting += 1;
+                let node = self.alternation()?;
+                self.nesting -= 1;
+                if !self.eat(')') {
+                    return Err("unterminated group");
+                }
+                node
+            }
+            '[' => Node::Class(self.class()?),
+            '\\' => match self.peek() {
+                Some('b') => {
+                    self.pos += 1;
+                    Node::Look(Look::WordBoundary)
+                }
+                Some('B') => {
+                    self.pos += 1;
+                    Node::Look(Look::NotWordBoundary)
+                }
+                _ => match self.escape()? {
+                    Escape::Char(c) => Node::Char(c),
+                    Escape::Class(class) => Node::Class(class),
+                },
+            },
+            '*' | '+' | '?' => return Err("nothing to repeat"),
+            c => Node::Char(c),
+        })
+    }
+
+    fn class(&mut self) -> Result<Class, &'static str> {
+        let mut class = Class {
+            negated: self.eat('^'),
+            ..Class::default()
+        };
+        loop {
+            let lo = match self.peek() {
+                None => return Err("unterminated character class"),
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(class);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.escape()? {
+                        Escape::Char(c) => c,
+                        Escape::Class(other) => {
+                            class.ranges.extend(other.ranges_matched());
+                            continue;
+                        }
+                    }
+                }
+                Some(c) => {
+                    self.pos += 1;
+                    c
+                }
+            };
+            // A dash makes a range, unless it ends the class.
+            let range = self.peek() == Some('-')
+                && !matches!(self.chars.get(self.pos + 1), Some(']') | None);
+            if !range {
+                class.ranges.push((lo, lo));
+                continue;
+            }
+            self.pos += 1;
+            let hi = match self.peek() {
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.escape()? {
+                        Escape::Char(c) => c,
+                        Escape::Class(_) => return Err("invalid character class range"),
+                    }
+                }
+                Some(c) => {
+                    self.pos += 1;
+                    c
+                }
+                None => return Err("unterminated character class"),
+            };
+            if hi < lo {
+                return Err("range out of order in character class");
+            }
+            class.ranges.push((lo, hi));
+        }
+    }
+
+    // What follows a backslash.
+    fn escape(&mut self) -> Result<Escape, &'static str> {
+        let c = self.peek().ok_or("\\ at end of pattern")?;
+        self.pos += 1;
+        let class = |ranges: &[(char, char)], negated| {
+            Escape::Class(Class {
+                ranges: ranges.to_vec(),
+                negated,
+            })
+        };
+        const DIGIT: &[(char, char)] = &[('0', '9')];
+        const WORD: &[(char, char)] = &[('0', '9'), ('A', 'Z'), ('_', '_'), ('a', 'z')];
+        const SPACE: &[(char, char)] = &[
+            ('\t', '\r'),
+            (' ', ' '),
+            ('\u{a0}', '\u{a0}'),
+            ('\u{1680}', '\u{1680}'),
+            ('\u{2000}', '\u{200a}'),
+            ('\u{2028}', '\u{2029}'),
+            ('\u{202f}', '\u{202f}'),
+            ('\u{205f}', '\u{205f}'),
+            ('\u{3000}', '\u{3000}'),
+            ('\u{feff}', '\u{feff}'),
+        ];
+        Ok(match c {
+            'd' => class(DIGIT, false),
+            'D' => class(DIGIT, true),
+            'w' => class(WORD, false),
+            'W' => class(WORD, true),
+            's' => class(SPACE, false),
+            'S' => class(SPACE, true),
+            'n' => Escape::Char('\n'),
+            'r' => Escape::Char('\r'),
+            't' => Escape::Char('\t'),
+            'f' => Escape::Char('\u{c}'),
+            'v' => Escape::Char('\u{b}'),
+            '0' => Escape::Char('\0'),
+            'x' => Escape::Char(self.hex(2)?),
+            'u' => Escape::Char(self.hex(4)?),
+            c if c.is_ascii_alphanumeric() => return Err("unsupported escape"),
+            c => Escape::Char(c),
+        })
+    }
+
+    fn hex(&mut self, len: usize) -> Result<char, &'static str> {
+        let digits: String = self.chars.iter().skip(self.pos).take(len).collect();
+        if digits.len() != len || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err("invalid escape");
+        }
+        self.pos += len;
+        let n = u32::from_str_radix(&digits, 16).map_err(|_| "invalid escape")?;
+        char::from_u32(n).ok_or("invalid escape")
+    }
+}
+
+enum Escape {
+    Char(char),
+    Class(Class),
+}
+
+impl Class {
+    // The ranges of characters the class matches, with negation worked out,
+    // for putting inside another class.
+    fn ranges_matched(&self) -> Vec<(char, char)> {
+        if !self.negated {
+            return self.ranges.clone();
+        }
+        let mut ranges = self.ranges.clone();
+        ranges.sort();
+        let mut out = Vec::new();
+        let mut next = 0u32;
+        for (lo, hi) in ranges {
+            if (lo as u32) > next {
+                out.extend(char_range(next, lo as u32 - 1));
+            }
+            next = next.max(hi as u32 + 1);
+        }
+        out.extend(char_range(next, char::MAX as u32));
+        out
+    }
+}
+
+// `lo..=hi` as characters, skipping the surrogates, which aren't any.
+fn char_range(lo: u32, hi: u32) -> Vec<(char, char)> {
+    let mut out = Vec::new();
+    for (lo, hi) in [(lo, hi.min(0xd7ff)), (lo.max(0xe000), hi)] {
+        if let (Some(lo), Some(hi)) = (char::from_u32(lo), char::from_u32(hi)) {
+            if lo <= hi {
+                out.push((lo, hi));
+            }
+        }
+    }
+    out
+}
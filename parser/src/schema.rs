@@ -0,0 +1,563 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+};
+
+use crate::{
+    cmp,
+    doc::{Document, ValueRef},
+    eq::{self, EqOptions},
+    error::{SchemaError, Violation},
+    key::Key,
+    map::Map,
+    pointer,
+    regex::Regex,
+    value::{JsonValue, Number},
+};
+
+/// A JSON Schema compiled for checking instances against, in a subset of
+/// draft 2020-12: `type`, `enum`, `const`, `properties`, `required`,
+/// `additionalProperties`, `items`, `pattern`, `minimum`, `maximum`,
+/// `exclusiveMinimum`, `exclusiveMaximum`, `minLength`, `maxLength`,
+/// `minItems`, `maxItems`, boolean schemas, and `$ref` to a JSON Pointer
+/// within the same document, like `#/$defs/item`. Other keywords are
+/// ignored.
+#[derive(Debug, Clone)]
+pub struct Schema {
+    // The root is the first.
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    // A `true` or `false` schema.
+    always: Option<bool>,
+    types: Option<Vec<&'static str>>,
+    allowed: Option<Vec<Document>>,
+    constant: Option<Document>,
+    // Sorted by key.
+    properties: Vec<(String, usize)>,
+    additional: Option<usize>,
+    required: Vec<String>,
+    items: Option<usize>,
+    pattern: Option<(String, Regex)>,
+    minimum: Option<Bound>,
+    maximum: Option<Bound>,
+    exclusive_minimum: Option<Bound>,
+    exclusive_maximum: Option<Bound>,
+    min_length: Option<u64>,
+    max_length: Option<u64>,
+    min_items: Option<u64>,
+    max_items: Option<u64>,
+    reference: Option<usize>,
+}
+
+// A number from the schema, with its text for messages.
+#[derive(Debug, Clone)]
+struct Bound {
+    number: Number,
+    text: String,
+}
+
+const TYPES: [&str; 7] = [
+    "null", "boolean", "object", "array", "number", "string", "integer",
+];
+
+impl Schema {
+    /// Compiles `schema`, checking that its keywords are well-formed, its
+    /// patterns compile and its references resolve.
+    pub fn compile(schema: &Document) -> Result<Schema, SchemaError> {
+        let mut compiler = Compiler {
+            schema,
+            nodes: Vec::new(),
+            pointers: HashMap::new(),
+            queue: Vec::new(),
+        };
+        compiler.node(String::new());
+        while let Some((index, at)) = compiler.queue.pop() {
+            let node = compiler.compile(&at)?;
+            compiler.nodes[index] = node;
+        }
+        Ok(Schema {
+            nodes: compiler.nodes,
+        })
+    }
+
+    /// Checks `instance`, collecting every violation rather than stopping at
+    /// the first, in document order.
+    pub fn validate(&self, instance: &Document) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        // A `$ref` can lead back to a schema already applied to the same
+        // value, which would add nothing but repeats, or loop forever.
+        let mut seen = HashSet::new();
+        let mut stack = vec![(0, instance.value(), String::new())];
+        while let Some((index, value, path)) = stack.pop() {
+            if !seen.insert((index, value.value() as *const JsonValue)) {
+                continue;
+            }
+            let start = stack.len();
+            self.nodes[index].check(value, &path, &mut violations, &mut stack);
+            stack[start..].reverse();
+        }
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(violations),
+        }
+    }
+
+    pub fn is_valid(&self, instance: &Document) -> bool {
+        self.validate(instance).is_ok()
+    }
+}
+
+struct Compiler<'s> {
+    schema: &'s Document,
+    nodes: Vec<Node>,
+    // Where each schema is in the schema document, so references to it
+    // share it.
+    pointers: HashMap<String, usize>,
+    queue: Vec<(usize, String)>,
+}
+
+impl Compiler<'_> {
+    // The node for the schema at `at`, compiled later if it's new.
+    fn node(&mut self, at: String) -> usize {
+        if let Some(&index) = self.pointers.get(&at) {
+            return index;
+        }
+        let index = self.nodes.len();
+        self.nodes.push(Node::default());
+        self.pointers.insert(at.clone(), index);
+        self.queue.push((index, at));
+        index
+    }
+
+    fn compile(&mut self, at: &str) -> Result<Node, SchemaError> {
+        let doc = self.schema;
+        let value = doc.value_ref(doc.pointer(at).ok_or_else(|| error(at, "no schema here"))?);
+        let mut node = Node::default();
+        let members = match value.value() {
+            JsonValue::Bool(b) => {
+                node.always = Some(*b);
+                return Ok(node);
+            }
+            JsonValue::Object(members) => members,
+            _ => return Err(error(at, "a schema must be an object or a boolean")),
+        };
+        let mut keys: Vec<&Key> = members.keys().collect();
+        keys.sort();
+        for key in keys {
+            let keyword = value.at(key.as_str());
+            let at_key = format!("{at}/{}", pointer::escape(key));
+            let fail = |message| error(&at_key, message);
+            match key.as_str() {
+                "type" => {
+                    let names: Vec<ValueRef> = match keyword.as_array() {
+                        Some(list) => list.iter().map(|&id| keyword.child(id)).collect(),
+                        None => vec![keyword],
+                    };
+                    let mut types = Vec::new();
+                    for name in names {
+                        let name = name.as_str().ok_or_else(|| fail("types must be strings"))?;
+                        let name = TYPES
+                            .iter()
+                            .find(|&&t| t == name)
+                            .ok_or_else(|| fail("unknown type"))?;
+                        types.push(*name);
+                    }
+                    node.types = Some(types);
+                }
+                "enum" => {
+                    let list = keyword.as_array().ok_or_else(|| fail("must be a list"))?;
+                    node.allowed = Some(
+                        list.iter()
+                            .map(|&id| Document::encode(&keyword.child(id)))
+                            .collect(),
+                    );
+                }
+                "const" => node.constant = Some(Document::encode(&keyword)),
+                "properties" => {
+                    let properties = keyword
+                        .as_object()
+                        .ok_or_else(|| fail("must be an object"))?;
+                    let mut names: Vec<&Key> = properties.keys().collect();
+                    names.sort();
+                    for name in names {
+                        let child = self.node(format!("{at_key}/{}", pointer::escape(name)));
+                        node.properties.push((name.to_string(), child));
+                    }
+                }
+                "additionalProperties" => node.additional = Some(self.node(at_key)),
+                "items" => node.items = Some(self.node(at_key)),
+                "required" => {
+                    let list = keyword.as_array().ok_or_else(|| fail("must be a list"))?;
+                    for &id in list {
+                        let name = keyword
+                            .child(id)
+                            .as_str()
+                            .ok_or_else(|| fail("must be a list of strings"))?;
+                        node.required.push(name.to_string());
+                    }
+                }
+                "pattern" => {
+                    let source = keyword.as_str().ok_or_else(|| fail("must be a string"))?;
+                    let regex = Regex::new(source).map_err(fail)?;
+                    node.pattern = Some((source.to_string(), regex));
+                }
+                "minimum" => {
+                    node.minimum = Some(bound(keyword).ok_or_else(|| fail("must be a number"))?)
+                }
+                "maximum" => {
+                    node.maximum = Some(bound(keyword).ok_or_else(|| fail("must be a number"))?)
+                }
+                "exclusiveMinimum" => {
+                    node.exclusive_minimum =
+                        Some(bound(keyword).ok_or_else(|| fail("must be a number"))?)
+                }
+                "exclusiveMaximum" => {
+                    node.exclusive_maximum =
+                        Some(bound(keyword).ok_or_else(|| fail("must be a number"))?)
+                }
+                "minLength" | "maxLength" | "minItems" | "maxItems" => {
+                    let n = keyword
+                        .as_number()
+                        .and_then(Number::as_u64)
+                        .ok_or_else(|| fail("must be a non-negative integer"))?;
+                    match key.as_str() {
+                        "minLength" => node.min_length = Some(n),
+                        "maxLength" => node.max_length = Some(n),
+                        "minItems" => node.min_items = Some(n),
+                        _ => node.max_items = Some(n),
+                    }
+                }
+                "$ref" => {
+                    let target = keyword
+                        .as_str()
+                        .and_then(|r| r.strip_prefix('#'))
+                        .filter(|r| r.is_empty() || r.starts_with('/'))
+                        .ok_or_else(|| {
+                            fail("only references to a pointer in this document are supported")
+                        })?;
+                    if doc.pointer(target).is_none() {
+                        return Err(fail("reference to nothing"));
+                    }
+                    node.reference = Some(self.node(target.to_string()));
+                }
+                _ => {}
+            }
+        }
+        Ok(node)
+    }
+}
+
+fn error(at: &str, message: &'static str) -> SchemaError {
+    SchemaError {
+        pointer: at.to_string(),
+        message,
+    }
+}
+
+fn bound(value: ValueRef<'_>) -> Option<Bound> {
+    // `Number` is only `Copy` without `arbitrary-precision`.
+    #[allow(clippy::clone_on_copy)]
+    Some(Bound {
+        number: value.as_number()?.clone(),
+        text: value.to_string(),
+    })
+}
+
+type Work<'i> = (usize, ValueRef<'i>, String);
+
+impl Node {
+    // Checks `value` against the keywords that look at it directly, adding
+    // the subschemas for it and its children to `stack`.
+    fn check<'i>(
+        &self,
+        value: ValueRef<'i>,
+        path: &str,
+        violations: &mut Vec<Violation>,
+        stack: &mut Vec<Work<'i>>,
+    ) {
+        let mut fail = |keyword, message: String| {
+            violations.push(Violation {
+                instance_path: path.to_string(),
+                keyword,
+                message,
+            })
+        };
+        if self.always == Some(false) {
+            fail("false", "isn't allowed".into());
+        }
+        if let Some(index) = self.reference {
+            stack.push((index, value, path.to_string()));
+        }
+        if let Some(types) = &self.types {
+            if !types.iter().any(|&t| has_type(value.value(), t)) {
+                let found = type_name(value.value());
+                fail(
+                    "type",
+                    format!("must be {}, not {found}", types.join(" or ")),
+                );
+            }
+        }
+        let by_value = EqOptions {
+            numbers_by_value: true,
+        };
+        if let Some(allowed) = &self.allowed {
+            if !allowed
+                .iter()
+                .any(|a| value.deep_eq_with(a.value(), by_value))
+            {
+                fail("enum", "isn't one of the allowed values".into());
+            }
+        }
+        if let Some(constant) = &self.constant {
+            if !value.deep_eq_with(constant.value(), by_value) {
+                fail("const", format!("must be {constant}"));
+            }
+        }
+        match value.value() {
+            JsonValue::String(s) => {
+                let len = s.chars().count() as u64;
+                if let Some((source, regex)) = &self.pattern {
+                    if !regex.is_match(s) {
+                        fail("pattern", format!("doesn't match the pattern {source:?}"));
+                    }
+                }
+                if let Some(min) = self.min_length.filter(|&min| len < min) {
+                    fail("minLength", format!("is shorter than {min} characters"));
+                }
+                if let Some(max) = self.max_length.filter(|&max| len > max) {
+                    fail("maxLength", format!("is longer than {max} characters"));
+                }
+            }
+            JsonValue::Number(n) => {
+                let checks = [
+                    ("minimum", &self.minimum, &[Ordering::Less][..], "less than"),
+                    (
+                        "maximum",
+                        &self.maximum,
+                        &[Ordering::Greater],
+                        "greater than",
+                    ),
+                    (
+                        "exclusiveMinimum",
+                        &self.exclusive_minimum,
+                        &[Ordering::Less, Ordering::Equal],
+                        "not greater than",
+                    ),
+                    (
+                        "exclusiveMaximum",
+                        &self.exclusive_maximum,
+                        &[Ordering::Greater, Ordering::Equal],
+                        "not less than",
+                    ),
+                ];
+                for (keyword, bound, failing, words) in checks {
+                    if let Some(bound) = bound {
+                        if failing.contains(&compare(n, &bound.number)) {
+                            fail(keyword, format!("is {words} {}", bound.text));
+                        }
+                    }
+                }
+            }
+            JsonValue::List(elements) => {
+                let len = elements.len() as u64;
+                if let Some(min) = self.min_items.filter(|&min| len < min) {
+                    fail("minItems", format!("has fewer than {min} items"));
+                }
+                if let Some(max) = self.max_items.filter(|&max| len > max) {
+                    fail("maxItems", format!("has more than {max} items"));
+                }
+                if let Some(index) = self.items {
+                    for (i, &id) in elements.iter().enumerate() {
+                        stack.push((index, value.child(id), format!("{path}/{i}")));
+                    }
+                }
+            }
+            JsonValue::Object(members) => {
+                for name in &self.required {
+                    if !members.contains_key(name.as_str()) {
+                        fail(
+                            "required",
+                            format!("is missing the required member {name:?}"),
+                        );
+                    }
+                }
+                let mut keys: Vec<&Key> = members.keys().collect();
+                keys.sort();
+                for key in keys {
+                    let child = value.child(members[key]);
+                    let child_path = format!("{path}/{}", pointer::escape(key));
+                    match self
+                        .properties
+                        .binary_search_by(|(name, _)| name.as_str().cmp(key))
+                    {
+                        Ok(i) => stack.push((self.properties[i].1, child, child_path)),
+                        Err(_) => {
+                            if let Some(index) = self.additional {
+                                stack.push((index, child, child_path));
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn has_type(value: &JsonValue, name: &str) -> bool {
+    match (value, name) {
+        (JsonValue::Number(n), "integer") => n.is_integer() || n.as_f64().fract() == 0.0,
+        _ => type_name(value) == name,
+    }
+}
+
+pub(crate) fn type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Object(_) => "object",
+        JsonValue::List(_) => "array",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Raw(_) => "raw",
+    }
+}
+
+// By value, so `1` equals `1.0`.
+fn compare(x: &Number, y: &Number) -> Ordering {
+    match eq::numbers_eq(x, y) {
+        true => Ordering::Equal,
+        false => cmp::numbers(x, y),
+    }
+}
+
+// What the samples had at one place: each type seen, and for objects and
+// lists what was inside.
+#[derive(Default)]
+pub(crate) struct Shape {
+    pub(crate) null: bool,
+    pub(crate) boolean: bool,
+    pub(crate) integer: bool,
+    pub(crate) number: bool,
+    pub(crate) string: bool,
+    // How many objects there were, and each member with how many of them
+    // had it.
+    pub(crate) objects: usize,
+    pub(crate) members: BTreeMap<Key, (usize, usize)>,
+    pub(crate) list: bool,
+    pub(crate) items: Option<usize>,
+}
+
+// The shapes of `samples` taken together, the root first; a shape's
+// children come after it.
+pub(crate) fn shapes(samples: &[Document]) -> Vec<Shape> {
+    let mut shapes = vec![Shape::default()];
+    let mut stack: Vec<(usize, ValueRef)> = samples.iter().map(|doc| (0, doc.value())).collect();
+    while let Some((index, value)) = stack.pop() {
+        match value.value() {
+            JsonValue::Null => shapes[index].null = true,
+            JsonValue::Bool(_) => shapes[index].boolean = true,
+            JsonValue::Number(n) if n.is_integer() => shapes[index].integer = true,
+            JsonValue::Number(_) => shapes[index].number = true,
+            JsonValue::String(_) | JsonValue::Raw(_) => shapes[index].string = true,
+            JsonValue::List(elements) => {
+                shapes[index].list = true;
+                if elements.is_empty() {
+                    continue;
+                }
+                let items = match shapes[index].items {
+                    Some(items) => items,
+                    None => {
+                        shapes.push(Shape::default());
+                        shapes[index].items = Some(shapes.len() - 1);
+                        shapes.len() - 1
+                    }
+                };
+                stack.extend(elements.iter().map(|&id| (items, value.child(id))));
+            }
+            JsonValue::Object(members) => {
+                shapes[index].objects += 1;
+                for (key, &id) in members {
+                    let next = shapes.len();
+                    let (child, seen) = shapes[index]
+                        .members
+                        .entry(key.clone())
+                        .or_insert((next, 0));
+                    *seen += 1;
+                    let child = *child;
+                    if child == next {
+                        shapes.push(Shape::default());
+                    }
+                    stack.push((child, value.child(id)));
+                }
+            }
+        }
+    }
+    shapes
+}
+
+pub(crate) fn infer(samples: &[Document]) -> Document {
+    let shapes = shapes(samples);
+    let mut doc = Document::default();
+    let mut schemas = vec![None; shapes.len()];
+    for (index, shape) in shapes.iter().enumerate().rev() {
+        let mut obj = Map::new();
+        if index == 0 {
+            let draft = doc.alloc(JsonValue::String(DRAFT.to_owned()));
+            obj.insert("$schema".into(), draft);
+        }
+        let types: Vec<&str> = [
+            (shape.boolean, "boolean"),
+            (shape.integer && !shape.number, "integer"),
+            (shape.number, "number"),
+            (shape.string, "string"),
+            (shape.list, "array"),
+            (shape.objects > 0, "object"),
+            (shape.null, "null"),
+        ]
+        .into_iter()
+        .filter_map(|(seen, name)| seen.then_some(name))
+        .collect();
+        let string = |doc: &mut Document, s: &str| doc.alloc(JsonValue::String(s.to_owned()));
+        let types = match &types[..] {
+            [] => None,
+            [name] => Some(string(&mut doc, name)),
+            names => {
+                let names = names.iter().map(|name| string(&mut doc, name)).collect();
+                Some(doc.alloc(JsonValue::List(names)))
+            }
+        };
+        if let Some(types) = types {
+            obj.insert("type".into(), types);
+        }
+        if shape.objects > 0 {
+            let mut properties = Map::new();
+            let mut required = Vec::new();
+            for (key, &(child, seen)) in &shape.members {
+                properties.insert(key.clone(), schemas[child].take().unwrap());
+                if seen == shape.objects {
+                    required.push(string(&mut doc, key));
+                }
+            }
+            let properties = doc.alloc(JsonValue::Object(properties));
+            obj.insert("properties".into(), properties);
+            if !required.is_empty() {
+                let required = doc.alloc(JsonValue::List(required));
+                obj.insert("required".into(), required);
+            }
+        }
+        if let Some(items) = shape.items {
+            obj.insert("items".into(), schemas[items].take().unwrap());
+        }
+        match index {
+            0 => *doc.root_mut() = JsonValue::Object(obj),
+            _ => schemas[index] = Some(doc.alloc(JsonValue::Object(obj))),
+        }
+    }
+    doc
+}
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
@@ -0,0 +1,329 @@
+use std::{
+    fmt::{self, Write},
+    io,
+};
+
+use crate::{
+    alloc::Id,
+    canonical,
+    doc::{Document, ValueRef},
+    error::FormatError,
+    key::Key,
+    value::{JsonValue, Number},
+};
+
+/// How `to_string_pretty` lays out a document. Every container that isn't
+/// empty gets a line per member, except for arrays kept on one line by
+/// `array_width`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// How many `indent_char`s each level is indented by.
+    pub indent: usize,
+    pub indent_char: char,
+    pub space_after_colon: bool,
+    pub trailing_newline: bool,
+    /// Arrays holding only scalars stay on one line, as `[1, 2, 3]`, if that
+    /// line is at most this many characters long, brackets included. 0
+    /// never keeps them.
+    pub array_width: usize,
+    /// Write object members in key order, by code point, rather than the
+    /// map's.
+    pub sort_keys: bool,
+    /// Escape every character outside of ASCII, as Python's `json.dumps`
+    /// does by default.
+    pub ensure_ascii: bool,
+    /// Write floats with nothing after the point, such as `3.0`, as `3`.
+    /// They read back as integers then.
+    pub trim_float_fraction: bool,
+    /// Write NaN and the infinities as `NaN`, `Infinity` and `-Infinity`,
+    /// which `ParseOptions::allow_non_finite_numbers` reads back. JSON has
+    /// no spelling for them, so otherwise writing one is a `FormatError`,
+    /// wrapped in an `io::Error` of kind `InvalidData` by `write_pretty_to`.
+    pub allow_non_finite_numbers: bool,
+}
+
+/// Two spaces, a space after colons, no trailing newline, every array
+/// broken up, members, text and floats left as they are, and no non-finite
+/// numbers.
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            indent_char: ' ',
+            space_after_colon: true,
+            trailing_newline: false,
+            array_width: 0,
+            sort_keys: false,
+            ensure_ascii: false,
+            trim_float_fraction: false,
+            allow_non_finite_numbers: false,
+        }
+    }
+}
+
+impl Document {
+    /// Writes the document as compact JSON, the same text `try_to_string`
+    /// gives.
+    pub fn write_to(&self, out: impl io::Write) -> io::Result<()> {
+        self.value().write_to(out)
+    }
+
+    /// Compact JSON, failing on NaN and the infinities where `to_string`
+    /// spells them out.
+    pub fn try_to_string(&self) -> Result<String, FormatError> {
+        self.value().try_to_string()
+    }
+
+    pub fn to_string_pretty(&self, opts: &FormatOptions) -> Result<String, FormatError> {
+        self.value().to_string_pretty(opts)
+    }
+
+    pub fn write_pretty_to(&self, out: impl io::Write, opts: &FormatOptions) -> io::Result<()> {
+        self.value().write_pretty_to(out, opts)
+    }
+}
+
+impl ValueRef<'_> {
+    pub fn write_to(&self, out: impl io::Write) -> io::Result<()> {
+        write_io(*self, None, out)
+    }
+
+    pub fn try_to_string(&self) -> Result<String, FormatError> {
+        to_string(*self, None)
+    }
+
+    pub fn to_string_pretty(&self, opts: &FormatOptions) -> Result<String, FormatError> {
+        to_string(*self, Some(opts))
+    }
+
+    pub fn write_pretty_to(&self, out: impl io::Write, opts: &FormatOptions) -> io::Result<()> {
+        write_io(*self, Some(opts), out)
+    }
+}
+
+/// Compact JSON, with no whitespace but what a `Raw` value's source text
+/// holds, which is written as it was. NaN and the infinities have no JSON
+/// spelling, so they come out as `NaN`, `Infinity` and `-Infinity`, which
+/// only `ParseOptions::allow_non_finite_numbers` reads; `try_to_string`
+/// fails on them instead.
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value().fmt(f)
+    }
+}
+
+impl fmt::Display for ValueRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        emit(*self, None, true, f)
+    }
+}
+
+// Can only fail on a non-finite number, which `write` doesn't report.
+fn to_string(value: ValueRef<'_>, pretty: Option<&FormatOptions>) -> Result<String, FormatError> {
+    let mut out = String::new();
+    match write(value, pretty, &mut out) {
+        Ok(()) => Ok(out),
+        Err(fmt::Error) => Err(non_finite(value)),
+    }
+}
+
+fn non_finite(value: ValueRef<'_>) -> FormatError {
+    FormatError {
+        pointer: canonical::find(value, |value| match value {
+            JsonValue::Number(n) => !n.as_f64().is_finite(),
+            _ => false,
+        })
+        .expect("only non-finite numbers fail")
+        .0,
+    }
+}
+
+pub(crate) fn write_io(
+    value: ValueRef<'_>,
+    pretty: Option<&FormatOptions>,
+    out: impl io::Write,
+) -> io::Result<()> {
+    let mut adapter = IoWrite { out, error: None };
+    match write(value, pretty, &mut adapter) {
+        Ok(()) => Ok(()),
+        Err(fmt::Error) => Err(adapter
+            .error
+            .unwrap_or_else(|| io::Error::new(io::ErrorKind::InvalidData, non_finite(value)))),
+    }
+}
+
+// Keeps the `io::Error` that `fmt::Write` has no room for.
+struct IoWrite<W> {
+    out: W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> Write for IoWrite<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.out.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+enum Frame<'doc> {
+    List(std::slice::Iter<'doc, Id<JsonValue>>),
+    Object(std::vec::IntoIter<(&'doc Key, Id<JsonValue>)>),
+}
+
+// Writes `value` compactly, or laid out by `pretty`.
+pub(crate) fn write(
+    value: ValueRef<'_>,
+    pretty: Option<&FormatOptions>,
+    out: &mut impl Write,
+) -> fmt::Result {
+    let non_finite = pretty.is_some_and(|opts| opts.allow_non_finite_numbers);
+    emit(value, pretty, non_finite, out)
+}
+
+fn emit(
+    value: ValueRef<'_>,
+    pretty: Option<&FormatOptions>,
+    non_finite: bool,
+    out: &mut impl Write,
+) -> fmt::Result {
+    let doc = value.document();
+    let ascii = pretty.is_some_and(|opts| opts.ensure_ascii);
+    let mut stack = Vec::new();
+    let mut next = value.value();
+    loop {
+        match next {
+            JsonValue::Object(obj) if obj.is_empty() => out.write_str("{}")?,
+            JsonValue::List(list) if list.is_empty() => out.write_str("[]")?,
+            JsonValue::Object(obj) => {
+                let mut members: Vec<_> = obj.iter().map(|(k, &id)| (k, id)).collect();
+                if pretty.is_some_and(|opts| opts.sort_keys) {
+                    members.sort_by_key(|&(k, _)| k);
+                }
+                out.write_char('{')?;
+                stack.push((Frame::Object(members.into_iter()), true));
+            }
+            JsonValue::List(list) => {
+                match pretty.and_then(|opts| inline(doc, list, opts, non_finite)) {
+                    Some(line) => out.write_str(&line)?,
+                    None => {
+                        out.write_char('[')?;
+                        stack.push((Frame::List(list.iter()), true));
+                    }
+                }
+            }
+            scalar => self::scalar(scalar, pretty, non_finite, out)?,
+        }
+        // Close every container that's done, up to the next value.
+        loop {
+            let depth = stack.len();
+            let Some((frame, first)) = stack.last_mut() else {
+                if pretty.is_some_and(|opts| opts.trailing_newline) {
+                    out.write_char('\n')?;
+                }
+                return Ok(());
+            };
+            let id = match frame {
+                Frame::List(iter) => iter.next().map(|&id| (None, id)),
+                Frame::Object(iter) => iter.next().map(|(key, id)| (Some(key), id)),
+            };
+            let Some((key, id)) = id else {
+                if let Some(opts) = pretty {
+                    newline(depth - 1, opts, out)?;
+                }
+                out.write_char(match frame {
+                    Frame::List(_) => ']',
+                    Frame::Object(_) => '}',
+                })?;
+                stack.pop();
+                continue;
+            };
+            if !std::mem::take(first) {
+                out.write_char(',')?;
+            }
+            if let Some(opts) = pretty {
+                newline(depth, opts, out)?;
+            }
+            if let Some(key) = key {
+                canonical::string(key, ascii, out)?;
+                out.write_char(':')?;
+                if pretty.is_some_and(|opts| opts.space_after_colon) {
+                    out.write_char(' ')?;
+                }
+            }
+            next = doc.get(id);
+            break;
+        }
+    }
+}
+
+fn newline(depth: usize, opts: &FormatOptions, out: &mut impl Write) -> fmt::Result {
+    out.write_char('\n')?;
+    (0..depth * opts.indent).try_for_each(|_| out.write_char(opts.indent_char))
+}
+
+// `list` on one line, if it's only scalars and fits in `array_width`.
+fn inline(
+    doc: &Document,
+    list: &[Id<JsonValue>],
+    opts: &FormatOptions,
+    non_finite: bool,
+) -> Option<String> {
+    let mut line = String::from("[");
+    for (i, &id) in list.iter().enumerate() {
+        let value = doc.get(id);
+        if matches!(value, JsonValue::Object(_) | JsonValue::List(_)) {
+            return None;
+        }
+        if i > 0 {
+            line.push_str(", ");
+        }
+        // A number that can't be written fails again when broken up.
+        scalar(value, Some(opts), non_finite, &mut line).ok()?;
+        if line.chars().count() >= opts.array_width {
+            return None;
+        }
+    }
+    line.push(']');
+    Some(line)
+}
+
+fn scalar(
+    value: &JsonValue,
+    pretty: Option<&FormatOptions>,
+    non_finite: bool,
+    out: &mut impl Write,
+) -> fmt::Result {
+    match value {
+        JsonValue::String(s) => {
+            canonical::string(s, pretty.is_some_and(|opts| opts.ensure_ascii), out)
+        }
+        JsonValue::Number(n) => {
+            let trim = pretty.is_some_and(|opts| opts.trim_float_fraction);
+            number(n, trim, non_finite, out)
+        }
+        JsonValue::Bool(b) => write!(out, "{b}"),
+        JsonValue::Raw(text) => out.write_str(text),
+        _ => out.write_str("null"),
+    }
+}
+
+// Floats get the fewest digits that read back as the same float, which is
+// what `{:?}` gives, and keep a fraction or exponent unless `trim` is set.
+// NaN and the infinities fail unless `non_finite` is set.
+fn number(n: &Number, trim: bool, non_finite: bool, out: &mut impl Write) -> fmt::Result {
+    match n {
+        Number::F64(n) if !n.is_finite() && !non_finite => Err(fmt::Error),
+        Number::F64(n) if n.is_nan() => out.write_str("NaN"),
+        Number::F64(n) if n.is_infinite() => {
+            out.write_str(if *n > 0.0 { "Infinity" } else { "-Infinity" })
+        }
+        Number::F64(n) if trim && n.fract() == 0.0 => {
+            let text = format!("{n:?}");
+            out.write_str(text.strip_suffix(".0").unwrap_or(&text))
+        }
+        Number::F64(n) => write!(out, "{n:?}"),
+        n => write!(out, "{n}"),
+    }
+}
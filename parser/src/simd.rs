@@ -0,0 +1,223 @@
+//! Block scanning for the lexer, simdjson-style: a whole block of string
+//! bytes is compared against the quote, the backslash and the control
+//! characters at once, and the first hit read off the resulting bit mask.
+//! AVX2 is used when the CPU has it, SSE2 otherwise on x86_64, NEON on
+//! aarch64, and eight bytes to a `u64` anywhere else.
+//!
+//! Only string contents are scanned this way. Finding the structural
+//! characters as well, to hand the parser an index of them, is left for
+//! later: the lexer dispatches on each token's first byte as it reaches it,
+//! so an index would only pay off with a stage that builds a tape from it.
+
+/// How many bytes at the start of `bytes` are neither `close`, a backslash
+/// nor a control character, and how many chars they make up.
+pub(crate) fn string_run(bytes: &[u8], close: u8) -> (usize, usize) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if std::is_x86_feature_detected!("avx2") {
+            // SAFETY: the CPU was just checked for AVX2.
+            return unsafe { x86::avx2(bytes, close) };
+        }
+        // SAFETY: SSE2 is part of x86_64.
+        unsafe { x86::sse2(bytes, close) }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        // SAFETY: NEON is part of aarch64.
+        unsafe { neon::string_run(bytes, close) }
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        swar(bytes, close)
+    }
+}
+
+/// The byte at a time version, for the tails too short for a block.
+fn scalar(bytes: &[u8], close: u8) -> (usize, usize) {
+    let mut chars = 0;
+    for (i, &b) in bytes.iter().enumerate() {
+        if b == close || b == b'\\' || b < b' ' {
+            return (i, chars);
+        }
+        chars += usize::from(!is_continuation(b));
+    }
+    (bytes.len(), chars)
+}
+
+fn is_continuation(b: u8) -> bool {
+    b & 0xC0 == 0x80
+}
+
+// The chars among the first `n` bytes of a block whose continuation bytes
+// are the set bits of `cont`.
+fn chars(n: usize, cont: u64) -> usize {
+    n - (cont & ((1 << n) - 1)).count_ones() as usize
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn avx2(bytes: &[u8], close: u8) -> (usize, usize) {
+        let quote = _mm256_set1_epi8(close as i8);
+        let backslash = _mm256_set1_epi8(b'\\' as i8);
+        let control = _mm256_set1_epi8(0x1F);
+        // Continuation bytes are 0x80 to 0xBF, below -64 as signed bytes.
+        let lead = _mm256_set1_epi8(-64);
+        let (mut i, mut chars) = (0, 0);
+        while i + 32 <= bytes.len() {
+            let v = _mm256_loadu_si256(bytes.as_ptr().add(i).cast());
+            let stop = _mm256_or_si256(
+                _mm256_or_si256(_mm256_cmpeq_epi8(v, quote), _mm256_cmpeq_epi8(v, backslash)),
+                _mm256_cmpeq_epi8(_mm256_min_epu8(v, control), v),
+            );
+            let stop = _mm256_movemask_epi8(stop) as u32;
+            let cont = _mm256_movemask_epi8(_mm256_cmpgt_epi8(lead, v)) as u32;
+            if stop != 0 {
+                let n = stop.trailing_zeros() as usize;
+                return (i + n, chars + super::chars(n, cont.into()));
+            }
+            chars += 32 - cont.count_ones() as usize;
+            i += 32;
+        }
+        let (n, rest) = sse2(&bytes[i..], close);
+        (i + n, chars + rest)
+    }
+
+    pub(super) unsafe fn sse2(bytes: &[u8], close: u8) -> (usize, usize) {
+        let quote = _mm_set1_epi8(close as i8);
+        let backslash = _mm_set1_epi8(b'\\' as i8);
+        let control = _mm_set1_epi8(0x1F);
+        let lead = _mm_set1_epi8(-64);
+        let (mut i, mut chars) = (0, 0);
+        while i + 16 <= bytes.len() {
+            let v = _mm_loadu_si128(bytes.as_ptr().add(i).cast());
+            let stop = _mm_or_si128(
+                _mm_or_si128(_mm_cmpeq_epi8(v, quote), _mm_cmpeq_epi8(v, backslash)),
+                _mm_cmpeq_epi8(_mm_min_epu8(v, control), v),
+            );
+            let stop = _mm_movemask_epi8(stop) as u32;
+            let cont = _mm_movemask_epi8(_mm_cmplt_epi8(v, lead)) as u32;
+            if stop != 0 {
+                let n = stop.trailing_zeros() as usize;
+                return (i + n, chars + super::chars(n, cont.into()));
+            }
+            chars += 16 - cont.count_ones() as usize;
+            i += 16;
+        }
+        let (n, rest) = super::scalar(&bytes[i..], close);
+        (i + n, chars + rest)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod neon {
+    use std::arch::aarch64::*;
+
+    // NEON has no movemask; narrowing each 16-bit lane by 4 bits leaves a
+    // nibble per byte instead, all ones where the comparison held.
+    unsafe fn nibbles(v: uint8x16_t) -> u64 {
+        vget_lane_u64::<0>(vreinterpret_u64_u8(vshrn_n_u16::<4>(vreinterpretq_u16_u8(
+            v,
+        ))))
+    }
+
+    pub(super) unsafe fn string_run(bytes: &[u8], close: u8) -> (usize, usize) {
+        let quote = vdupq_n_u8(close);
+        let backslash = vdupq_n_u8(b'\\');
+        let control = vdupq_n_u8(b' ');
+        let (mut i, mut chars) = (0, 0);
+        while i + 16 <= bytes.len() {
+            let v = vld1q_u8(bytes.as_ptr().add(i));
+            let stop = vorrq_u8(
+                vorrq_u8(vceqq_u8(v, quote), vceqq_u8(v, backslash)),
+                vcltq_u8(v, control),
+            );
+            let stop = nibbles(stop);
+            let cont = nibbles(vceqq_u8(vandq_u8(v, vdupq_n_u8(0xC0)), vdupq_n_u8(0x80)));
+            if stop != 0 {
+                let n = stop.trailing_zeros() as usize / 4;
+                let below = (1u64 << (4 * n)) - 1;
+                return (i + n, chars + n - (cont & below).count_ones() as usize / 4);
+            }
+            chars += 16 - cont.count_ones() as usize / 4;
+            i += 16;
+        }
+        let (n, rest) = super::scalar(&bytes[i..], close);
+        (i + n, chars + rest)
+    }
+}
+
+// Eight bytes at a time in a `u64`, with the usual tricks: a byte of
+// `x - 0x01..` has its high bit set where `x` had a zero byte.
+#[cfg_attr(any(target_arch = "x86_64", target_arch = "aarch64"), allow(dead_code))]
+fn swar(bytes: &[u8], close: u8) -> (usize, usize) {
+    const ONES: u64 = u64::from_ne_bytes([0x01; 8]);
+    const HIGH: u64 = u64::from_ne_bytes([0x80; 8]);
+    let zero = |x: u64| x.wrapping_sub(ONES) & !x & HIGH;
+    let (mut i, mut chars) = (0, 0);
+    while i + 8 <= bytes.len() {
+        let word = u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+        let stop = zero(word ^ (ONES * u64::from(close)))
+            | zero(word ^ (ONES * u64::from(b'\\')))
+            | zero(word & (ONES * 0xE0));
+        if stop != 0 {
+            // Borrows can set high bits above the first hit, but never
+            // below it, so only the lowest one is exact.
+            let n = stop.trailing_zeros() as usize / 8;
+            let (_, rest) = scalar(&bytes[i..i + n], close);
+            return (i + n, chars + rest);
+        }
+        let cont = word & !(word << 1) & HIGH;
+        chars += 8 - cont.count_ones() as usize;
+        i += 8;
+    }
+    let (n, rest) = scalar(&bytes[i..], close);
+    (i + n, chars + rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Run = fn(&[u8], u8) -> (usize, usize);
+
+    // Every block version against `scalar`, at every start offset and
+    // length, with each stop byte at every position, the last included.
+    #[test]
+    fn blocks_agree_with_scalar() {
+        let text = "ab\u{e9}c\u{20ac}d \x7f\u{10348}".repeat(12);
+        let mut versions: Vec<(&str, Run)> = vec![("swar", swar)];
+        #[cfg(target_arch = "x86_64")]
+        {
+            // SAFETY: SSE2 is part of x86_64, and AVX2 is checked for.
+            versions.push(("sse2", |b, c| unsafe { x86::sse2(b, c) }));
+            if std::is_x86_feature_detected!("avx2") {
+                versions.push(("avx2", |b, c| unsafe { x86::avx2(b, c) }));
+            }
+        }
+        // SAFETY: NEON is part of aarch64.
+        #[cfg(target_arch = "aarch64")]
+        versions.push(("neon", |b, c| unsafe { neon::string_run(b, c) }));
+        for close in [b'"', b'\''] {
+            for offset in 0..16 {
+                for len in 0..80 {
+                    for at in 0..=len {
+                        for stop in [close, b'\\', 0x00, 0x1F] {
+                            let mut bytes = text.as_bytes()[..offset + len].to_vec();
+                            if at < len {
+                                bytes[offset + at] = stop;
+                            }
+                            let bytes = &bytes[offset..];
+                            let expected = scalar(bytes, close);
+                            for (name, run) in &versions {
+                                assert_eq!(run(bytes, close), expected, "{name}: {bytes:x?}");
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::io::{self, Read};
+
+/// Byte input for the lexer. Running out of input and failing to read look
+/// the same to the lexer; it asks `take_error` which of the two happened.
+pub trait Source {
+    fn peek(&mut self) -> Option<u8>;
+    fn bump(&mut self) -> Option<u8>;
+
+    /// The bytes already at hand, starting with the one `peek` would return,
+    /// so the lexer can scan runs of them at once. Empty by default, which
+    /// leaves it going byte by byte.
+    fn buffered(&mut self) -> &[u8] {
+        &[]
+    }
+
+    /// Moves past the first `n` bytes `buffered` returned.
+    fn skip(&mut self, n: usize) {
+        for _ in 0..n {
+            self.bump();
+        }
+    }
+
+    fn take_error(&mut self) -> Option<io::Error> {
+        None
+    }
+}
+
+pub struct SliceSource<'json> {
+    bytes: &'json [u8],
+    pos: usize,
+}
+
+impl<'json> SliceSource<'json> {
+    pub fn new(bytes: &'json [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+}
+
+impl Source for SliceSource<'_> {
+    fn peek(&mut self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn buffered(&mut self) -> &[u8] {
+        &self.bytes[self.pos..]
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.pos += n;
+    }
+}
+
+/// Pulls bytes from a reader in fixed-size chunks, so the whole input never
+/// has to be in memory at once.
+pub struct ReadSource<R> {
+    reader: R,
+    buf: Box<[u8]>,
+    pos: usize,
+    len: usize,
+    error: Option<io::Error>,
+    eof: bool,
+}
+
+impl<R: Read> ReadSource<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_capacity(8 * 1024, reader)
+    }
+
+    pub fn with_capacity(capacity: usize, reader: R) -> Self {
+        Self {
+            reader,
+            buf: vec![0; capacity.max(1)].into_boxed_slice(),
+            pos: 0,
+            len: 0,
+            error: None,
+            eof: false,
+        }
+    }
+
+    fn fill(&mut self) {
+        while self.pos == self.len && !self.eof {
+            match self.reader.read(&mut self.buf) {
+                Ok(0) => self.eof = true,
+                Ok(n) => {
+                    self.pos = 0;
+                    self.len = n;
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.error = Some(e);
+                    self.eof = true;
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read> Source for ReadSource<R> {
+    fn peek(&mut self) -> Option<u8> {
+        self.fill();
+        (self.pos < self.len).then(|| self.buf[self.pos])
+    }
+
+    fn bump(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn buffered(&mut self) -> &[u8] {
+        self.fill();
+        &self.buf[self.pos..self.len]
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.pos += n;
+    }
+
+    fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{doc::Document, path, schema, value::JsonValue};
+
+/// What a document holds, from `Document::stats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub nulls: usize,
+    pub bools: usize,
+    pub numbers: usize,
+    pub strings: usize,
+    pub lists: usize,
+    pub objects: usize,
+    /// Values kept unparsed for `ParseOptions::raw_keys`.
+    pub raws: usize,
+    /// How deeply lists and objects nest: 0 for a scalar, 1 for `[]`, as
+    /// `ParseOptions::max_depth` counts.
+    pub max_depth: usize,
+    /// The UTF-8 bytes of every string value, not counting keys.
+    pub string_bytes: usize,
+    /// The UTF-8 bytes of every object key.
+    pub key_bytes: usize,
+    /// The length of the longest list.
+    pub max_list_len: usize,
+    /// Values in the arena, whether the root still reaches them or not, as
+    /// `ParseOptions::max_nodes` counts them.
+    pub nodes: usize,
+}
+
+/// A path through a document's lists and objects, from
+/// `Document::path_profile`, with what was found at it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathProfile {
+    /// The path as JSONPath, with `[*]` for every list index, as in
+    /// `$.items[*].price`. Keys that aren't identifiers are quoted:
+    /// `$['a b']`.
+    pub path: String,
+    /// How many values were at it.
+    pub count: usize,
+    /// How many of them were of each type, by its name in JSON Schema, or
+    /// `raw` for values kept unparsed.
+    pub types: BTreeMap<&'static str, usize>,
+}
+
+impl Document {
+    /// Counts what the document holds, from the root down.
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats {
+            nodes: self.arena().len(),
+            ..Stats::default()
+        };
+        // Each value with how many lists and objects it's inside.
+        let mut stack = vec![(self.root(), 0)];
+        while let Some((value, depth)) = stack.pop() {
+            match value {
+                JsonValue::Null => stats.nulls += 1,
+                JsonValue::Bool(_) => stats.bools += 1,
+                JsonValue::Number(_) => stats.numbers += 1,
+                JsonValue::String(s) => {
+                    stats.strings += 1;
+                    stats.string_bytes += s.len();
+                }
+                JsonValue::Raw(_) => stats.raws += 1,
+                JsonValue::List(list) => {
+                    stats.lists += 1;
+                    stats.max_depth = stats.max_depth.max(depth + 1);
+                    stats.max_list_len = stats.max_list_len.max(list.len());
+                    stack.extend(list.iter().map(|&id| (self.get(id), depth + 1)));
+                }
+                JsonValue::Object(obj) => {
+                    stats.objects += 1;
+                    stats.max_depth = stats.max_depth.max(depth + 1);
+                    stats.key_bytes += obj.keys().map(|key| key.len()).sum::<usize>();
+                    stack.extend(obj.values().map(|&id| (self.get(id), depth + 1)));
+                }
+            }
+        }
+        stats
+    }
+
+    /// Every distinct path in the document, with how often it occurs and
+    /// the types found there, sorted by path. Elements of a list all share
+    /// one path, so this shows the shape of a payload rather than its size.
+    pub fn path_profile(&self) -> Vec<PathProfile> {
+        let mut profiles = vec![PathProfile {
+            path: "$".to_owned(),
+            count: 0,
+            types: BTreeMap::new(),
+        }];
+        // Each child path by its parent's index and its key, or `None` for
+        // list elements, so each is only spelled once.
+        let mut children: HashMap<(usize, Option<&str>), usize> = HashMap::new();
+        let mut stack = vec![(0, self.value())];
+        while let Some((index, value)) = stack.pop() {
+            let profile = &mut profiles[index];
+            profile.count += 1;
+            *profile
+                .types
+                .entry(schema::type_name(value.value()))
+                .or_default() += 1;
+            let members: Vec<_> = match value.value() {
+                JsonValue::Object(obj) => obj
+                    .iter()
+                    .map(|(key, &id)| (Some(key.as_str()), id))
+                    .collect(),
+                JsonValue::List(list) => list.iter().map(|&id| (None, id)).collect(),
+                _ => continue,
+            };
+            for (key, id) in members {
+                let child = *children.entry((index, key)).or_insert_with(|| {
+                    let parent = &profiles[index].path;
+                    let path = match key {
+                        Some(key) => member_path(parent, key),
+                        None => format!("{parent}[*]"),
+                    };
+                    profiles.push(PathProfile {
+                        path,
+                        count: 0,
+                        types: BTreeMap::new(),
+                    });
+                    profiles.len() - 1
+                });
+                stack.push((child, value.child(id)));
+            }
+        }
+        profiles.sort_by(|a, b| a.path.cmp(&b.path));
+        profiles
+    }
+}
+
+// `path` and then `key`, in shorthand if the key is an identifier.
+fn member_path(path: &str, key: &str) -> String {
+    let mut chars = key.chars();
+    let identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    match identifier {
+        true => format!("{path}.{key}"),
+        false => path::child_path(path, key, true),
+    }
+}
@@ -0,0 +1,58 @@
+use std::io::Read;
+
+use crate::{
+    doc::Document,
+    error::ParseError,
+    lex::Lex,
+    options::ParseOptions,
+    par::Par,
+    source::{ReadSource, SliceSource, Source},
+};
+
+/// The documents of a stream of concatenated values, which may be separated
+/// by whitespace or by nothing at all, until the end of the input. The
+/// iteration ends after an error.
+pub struct Stream<S> {
+    par: Par<S>,
+    done: bool,
+}
+
+impl<'json> Stream<SliceSource<'json>> {
+    pub fn new(src: &'json str, opts: ParseOptions) -> Self {
+        Self::from_lex(Lex::with_options(src, opts), opts)
+    }
+}
+
+impl<R: Read> Stream<ReadSource<R>> {
+    pub fn from_reader(reader: R, opts: ParseOptions) -> Self {
+        Self::from_lex(Lex::from_reader(reader, opts), opts)
+    }
+}
+
+impl<S: Source> Stream<S> {
+    pub fn from_lex(lex: Lex<S>, opts: ParseOptions) -> Self {
+        Self {
+            par: Par::prefix(lex, 1 << 4, opts),
+            done: false,
+        }
+    }
+
+    /// The byte offset just past the last document returned, where the next
+    /// one's leading whitespace starts.
+    pub fn byte_offset(&self) -> usize {
+        self.par.end()
+    }
+}
+
+impl<S: Source> Iterator for Stream<S> {
+    type Item = Result<Document, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let next = self.par.next_document(1 << 4).transpose();
+        self.done = !matches!(next, Some(Ok(_)));
+        next
+    }
+}
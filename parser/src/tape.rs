@@ -0,0 +1,442 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::ParseError,
+    map::Map,
+    options::{DuplicateKeys, ParseOptions},
+    owned::OwnedValue,
+    pointer,
+    pull::{JsonPullParser, Step},
+    value::Number,
+};
+
+/// A parsed value laid out flat, simdjson-style: one entry per value and
+/// key, in document order, with every string in one buffer. Containers
+/// record how many entries they take up, so a `Cursor` steps over one
+/// without looking inside. Nothing is allocated per object or list, which
+/// makes a tape quicker to build and to read than a `Document` when
+/// nothing needs to change; looking a member up walks the object, though.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tape {
+    entries: Vec<Entry>,
+    strings: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Entry {
+    Null,
+    Bool(bool),
+    Number(Number),
+    // Where the text is in `strings`.
+    String(usize, usize),
+    Key(usize, usize),
+    // The entries after this one that the contents take up, and how many
+    // members or elements there are.
+    Object { len: usize, count: usize },
+    List { len: usize, count: usize },
+}
+
+impl Tape {
+    pub fn root(&self) -> Cursor<'_> {
+        Cursor { tape: self, at: 0 }
+    }
+
+    /// How many entries the tape holds: one for each value and each key.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn text(&self, start: usize, end: usize) -> &str {
+        &self.strings[start..end]
+    }
+
+    // One past the last entry of the value at `at`.
+    fn end(&self, at: usize) -> usize {
+        match self.entries[at] {
+            Entry::Object { len, .. } | Entry::List { len, .. } => at + 1 + len,
+            _ => at + 1,
+        }
+    }
+
+    // The key at `at`, which must be one.
+    fn key(&self, at: usize) -> &str {
+        match self.entries[at] {
+            Entry::Key(start, end) => self.text(start, end),
+            _ => unreachable!("members start with their key"),
+        }
+    }
+}
+
+/// A place on a `Tape`, standing for the value there. Cheap to copy, and
+/// moving down only adds to an index.
+#[derive(Clone, Copy)]
+pub struct Cursor<'tape> {
+    tape: &'tape Tape,
+    at: usize,
+}
+
+impl<'tape> Cursor<'tape> {
+    pub fn tape(&self) -> &'tape Tape {
+        self.tape
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self.entry(), Entry::Null)
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(self.entry(), Entry::Object { .. })
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self.entry(), Entry::List { .. })
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self.entry() {
+            Entry::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<&'tape Number> {
+        match self.entry() {
+            Entry::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&'tape str> {
+        match *self.entry() {
+            Entry::String(start, end) => Some(self.tape.text(start, end)),
+            _ => None,
+        }
+    }
+
+    /// How many members an object has or elements a list has.
+    pub fn len(&self) -> Option<usize> {
+        match *self.entry() {
+            Entry::Object { count, .. } | Entry::List { count, .. } => Some(count),
+            _ => None,
+        }
+    }
+
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|n| n == 0)
+    }
+
+    /// The member `key` of an object. Steps over each member before it.
+    pub fn get(&self, key: &str) -> Option<Cursor<'tape>> {
+        self.members()?.find(|(k, _)| *k == key).map(|(_, v)| v)
+    }
+
+    /// Element `index` of a list. Steps over each element before it.
+    pub fn get_index(&self, index: usize) -> Option<Cursor<'tape>> {
+        self.elements()?.nth(index)
+    }
+
+    /// The members of an object in document order, or `None` for anything
+    /// else.
+    pub fn members(&self) -> Option<Members<'tape>> {
+        let Entry::Object { count, .. } = *self.entry() else {
+            return None;
+        };
+        Some(Members {
+            tape: self.tape,
+            at: self.at + 1,
+            left: count,
+        })
+    }
+
+    /// The elements of a list, or `None` for anything else.
+    pub fn elements(&self) -> Option<Elements<'tape>> {
+        let Entry::List { count, .. } = *self.entry() else {
+            return None;
+        };
+        Some(Elements {
+            tape: self.tape,
+            at: self.at + 1,
+            left: count,
+        })
+    }
+
+    /// The value `pointer` leads to from this one, as `Document::pointer`
+    /// does from the root.
+    pub fn pointer(&self, pointer: &str) -> Option<Cursor<'tape>> {
+        let mut value = *self;
+        for token in pointer::tokens(pointer)? {
+            value = match value.entry() {
+                Entry::Object { .. } => value.get(&token)?,
+                Entry::List { .. } => value.get_index(pointer::index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    /// Copies the value out into an `OwnedValue`.
+    pub fn to_owned_tree(&self) -> OwnedValue {
+        let tape = self.tape;
+        // Containers being filled, with where they end on the tape and the
+        // key they go under. A container is done once the tape reaches its
+        // end.
+        let mut stack: Vec<(OwnedValue, usize, String)> = Vec::new();
+        let mut key = String::new();
+        let mut at = self.at;
+        loop {
+            let entry = &tape.entries[at];
+            let end = tape.end(at);
+            at += 1;
+            let mut value = match *entry {
+                Entry::Key(start, end) => {
+                    key = tape.text(start, end).to_owned();
+                    continue;
+                }
+                Entry::Object { .. } => {
+                    let obj = OwnedValue::Object(Map::new());
+                    stack.push((obj, end, std::mem::take(&mut key)));
+                    None
+                }
+                Entry::List { count, .. } => {
+                    let list = OwnedValue::List(Vec::with_capacity(count));
+                    stack.push((list, end, std::mem::take(&mut key)));
+                    None
+                }
+                Entry::String(start, end) => {
+                    Some(OwnedValue::String(tape.text(start, end).to_owned()))
+                }
+                #[allow(clippy::clone_on_copy)]
+                Entry::Number(ref n) => Some(OwnedValue::Number(n.clone())),
+                Entry::Bool(b) => Some(OwnedValue::Bool(b)),
+                Entry::Null => Some(OwnedValue::Null),
+            };
+            // Close the containers that just ended, handing each finished
+            // value to its parent.
+            loop {
+                if let Some(done) = value.take() {
+                    match stack.last_mut() {
+                        None => return done,
+                        Some((OwnedValue::List(list), ..)) => list.push(done),
+                        Some((OwnedValue::Object(obj), ..)) => {
+                            obj.insert(std::mem::take(&mut key), done);
+                        }
+                        Some(_) => unreachable!(),
+                    }
+                }
+                match stack.last() {
+                    Some(&(_, end, _)) if end == at => {
+                        let (done, _, under) = stack.pop().unwrap();
+                        key = under;
+                        value = Some(done);
+                    }
+                    _ => break,
+                }
+            }
+        }
+    }
+
+    fn entry(&self) -> &'tape Entry {
+        &self.tape.entries[self.at]
+    }
+}
+
+impl std::fmt::Debug for Cursor<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cursor").field("at", &self.at).finish()
+    }
+}
+
+pub struct Members<'tape> {
+    tape: &'tape Tape,
+    at: usize,
+    left: usize,
+}
+
+impl<'tape> Iterator for Members<'tape> {
+    type Item = (&'tape str, Cursor<'tape>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.left = self.left.checked_sub(1)?;
+        let key = self.tape.key(self.at);
+        let value = Cursor {
+            tape: self.tape,
+            at: self.at + 1,
+        };
+        self.at = self.tape.end(self.at + 1);
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.left, Some(self.left))
+    }
+}
+
+pub struct Elements<'tape> {
+    tape: &'tape Tape,
+    at: usize,
+    left: usize,
+}
+
+impl<'tape> Iterator for Elements<'tape> {
+    type Item = Cursor<'tape>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.left = self.left.checked_sub(1)?;
+        let value = Cursor {
+            tape: self.tape,
+            at: self.at,
+        };
+        self.at = self.tape.end(self.at);
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.left, Some(self.left))
+    }
+}
+
+// Objects up to this size are searched for duplicate keys on the tape;
+// bigger ones get a map.
+const SCAN: usize = 16;
+
+// A container being written, with where it starts on the tape.
+struct Open {
+    at: usize,
+    count: usize,
+    // Where the member being parsed started, and the tape's lengths then,
+    // if it repeats a key under `FirstWins` and is to be dropped.
+    drop: Option<(usize, usize)>,
+    keys: Option<HashMap<String, usize>>,
+}
+
+pub(crate) fn parse(src: &str, opts: ParseOptions) -> Result<Tape, ParseError> {
+    let mut par = JsonPullParser::borrowing(src, opts);
+    let mut tape = Tape {
+        entries: Vec::new(),
+        strings: String::new(),
+    };
+    let mut stack: Vec<Open> = Vec::new();
+    while let Some(step) = par.step_in(src)? {
+        match step {
+            Step::StartObject | Step::StartArray => {
+                stack.push(Open {
+                    at: tape.entries.len(),
+                    count: 0,
+                    drop: None,
+                    keys: None,
+                });
+                tape.entries.push(match step {
+                    Step::StartObject => Entry::Object { len: 0, count: 0 },
+                    _ => Entry::List { len: 0, count: 0 },
+                });
+                continue;
+            }
+            Step::Key => {
+                let key = par.text(src)?;
+                let open = stack.last_mut().expect("keys only come inside objects");
+                let before = (tape.entries.len(), tape.strings.len());
+                if let Some(prev) = find(&tape, open, &key) {
+                    match opts.duplicate_keys {
+                        DuplicateKeys::Error => {
+                            return Err(ParseError::DuplicateKey {
+                                key: key.into_owned(),
+                                span: par.span(),
+                            })
+                        }
+                        DuplicateKeys::FirstWins => open.drop = Some(before),
+                        DuplicateKeys::LastWins => remove(&mut tape, open, prev),
+                    }
+                }
+                let at = tape.entries.len();
+                if open.drop.is_none() {
+                    if let Some(keys) = &mut open.keys {
+                        keys.insert(key.to_string(), at);
+                    }
+                }
+                let start = tape.strings.len();
+                tape.strings.push_str(&key);
+                tape.entries.push(Entry::Key(start, tape.strings.len()));
+                continue;
+            }
+            Step::EndObject | Step::EndArray => {
+                let open = stack.pop().unwrap();
+                let len = tape.entries.len() - open.at - 1;
+                tape.entries[open.at] = match tape.entries[open.at] {
+                    Entry::Object { .. } => Entry::Object {
+                        len,
+                        count: open.count,
+                    },
+                    _ => Entry::List {
+                        len,
+                        count: open.count,
+                    },
+                };
+            }
+            Step::String => {
+                let text = par.text(src)?;
+                let start = tape.strings.len();
+                tape.strings.push_str(&text);
+                tape.entries.push(Entry::String(start, tape.strings.len()));
+            }
+            Step::Number(n) => tape.entries.push(Entry::Number(n)),
+            Step::Bool(b) => tape.entries.push(Entry::Bool(b)),
+            Step::Null => tape.entries.push(Entry::Null),
+        }
+        // A value is done; count it, or drop it with its key.
+        if let Some(open) = stack.last_mut() {
+            match open.drop.take() {
+                Some((entries, strings)) => {
+                    tape.entries.truncate(entries);
+                    tape.strings.truncate(strings);
+                }
+                None => open.count += 1,
+            }
+        }
+    }
+    Ok(tape)
+}
+
+// Where the member of `open` with `key` starts, if it has one.
+fn find(tape: &Tape, open: &mut Open, key: &str) -> Option<usize> {
+    if open.keys.is_none() && open.count >= SCAN {
+        let mut keys = HashMap::new();
+        let mut at = open.at + 1;
+        for _ in 0..open.count {
+            keys.insert(tape.key(at).to_owned(), at);
+            at = tape.end(at + 1);
+        }
+        open.keys = Some(keys);
+    }
+    if let Some(keys) = &open.keys {
+        return keys.get(key).copied();
+    }
+    let mut at = open.at + 1;
+    for _ in 0..open.count {
+        if tape.key(at) == key {
+            return Some(at);
+        }
+        at = tape.end(at + 1);
+    }
+    None
+}
+
+// Takes the member starting at `at` out of `open`, the innermost open
+// object, so everything after it on the tape belongs to `open` too. Its
+// text stays behind in `strings`.
+fn remove(tape: &mut Tape, open: &mut Open, at: usize) {
+    let end = tape.end(at + 1);
+    tape.entries.drain(at..end);
+    open.count -= 1;
+    if let Some(keys) = &mut open.keys {
+        keys.retain(|_, k| *k != at);
+        for k in keys.values_mut() {
+            if *k > at {
+                *k -= end - at;
+            }
+        }
+    }
+}
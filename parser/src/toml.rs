@@ -0,0 +1,953 @@
+// TOML 1.0, for configuration kept as JSON. A TOML document is always a
+// table, and its dates and times, which JSON has no values for, are
+// strings of their text.
+
+use std::{borrow::Cow, collections::HashMap, fmt::Write};
+
+use crate::{
+    alloc::Id,
+    doc::{Document, ValueRef},
+    error::TomlError,
+    key::Key,
+    map::Map,
+    pointer,
+    value::{JsonValue, Number},
+};
+
+// A table still to write under its header: the keys to it, and the JSON
+// Pointer to it for errors.
+struct Section<'doc> {
+    path: Vec<&'doc str>,
+    table: ValueRef<'doc>,
+    array: bool,
+    pointer: String,
+}
+
+impl Document {
+    /// The document as TOML. Objects are tables under `[headers]`, and lists
+    /// of nothing but objects are arrays of tables under `[[headers]]`.
+    /// Other lists, along with any objects in them, are written inline, as
+    /// TOML 1.0 lets arrays mix types. TOML has no null and no integers
+    /// past 64 bits, and a document has to be a table, so those are
+    /// errors, which name the value by its JSON Pointer. A `Raw` value is a
+    /// string of its JSON text.
+    pub fn to_toml_string(&self) -> Result<String, TomlError> {
+        let root = self.value();
+        if root.as_object().is_none() {
+            return Err(TomlError::Unrepresentable {
+                pointer: String::new(),
+                message: "a document that isn't an object",
+            });
+        }
+        let mut out = String::new();
+        let mut stack = vec![Section {
+            path: Vec::new(),
+            table: root,
+            array: false,
+            pointer: String::new(),
+        }];
+        while let Some(section) = stack.pop() {
+            if !section.path.is_empty() {
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                out.push_str(if section.array { "[[" } else { "[" });
+                for (i, key) in section.path.iter().enumerate() {
+                    if i > 0 {
+                        out.push('.');
+                    }
+                    push_key(key, &mut out);
+                }
+                out.push_str(if section.array { "]]\n" } else { "]\n" });
+            }
+            let Some(obj) = section.table.as_object() else {
+                continue;
+            };
+            let start = stack.len();
+            for (key, &id) in obj {
+                let value = section.table.child(id);
+                let pointer = format!("{}/{}", section.pointer, pointer::escape(key));
+                let path = || {
+                    let mut path = section.path.clone();
+                    path.push(key.as_str());
+                    path
+                };
+                match value.value() {
+                    JsonValue::Object(_) => stack.push(Section {
+                        path: path(),
+                        table: value,
+                        array: false,
+                        pointer,
+                    }),
+                    JsonValue::List(list) if tables(value) => {
+                        for (i, &id) in list.iter().enumerate() {
+                            stack.push(Section {
+                                path: path(),
+                                table: value.child(id),
+                                array: true,
+                                pointer: format!("{pointer}/{i}"),
+                            });
+                        }
+                    }
+                    _ => {
+                        push_key(key, &mut out);
+                        out.push_str(" = ");
+                        inline(value, pointer, &mut out)?;
+                        out.push('\n');
+                    }
+                }
+            }
+            stack[start..].reverse();
+        }
+        Ok(out)
+    }
+
+    /// Reads a TOML document into an object. Integers and floats become
+    /// numbers, and offset and local dates and times strings of their text.
+    pub fn from_toml(src: &str) -> Result<Document, TomlError> {
+        let src = match src.contains('\r') {
+            true => Cow::Owned(src.replace("\r\n", "\n")),
+            false => Cow::Borrowed(src),
+        };
+        let mut doc = Document::default();
+        let root = doc.alloc(JsonValue::Object(Map::new()));
+        let mut kinds = HashMap::new();
+        kinds.insert(root.index(), Kind::Header);
+        Parser {
+            src: &src,
+            pos: 0,
+            line: 1,
+            line_start: 0,
+            doc,
+            kinds,
+            table: root,
+        }
+        .document(root)
+    }
+}
+
+// Whether `value` is a list that can be an array of tables.
+fn tables(value: ValueRef<'_>) -> bool {
+    let doc = value.document();
+    value.as_array().is_some_and(|list| {
+        !list.is_empty()
+            && list
+                .iter()
+                .all(|&id| matches!(doc.get(id), JsonValue::Object(_)))
+    })
+}
+
+enum Frame<'doc> {
+    List(std::iter::Enumerate<std::slice::Iter<'doc, Id<JsonValue>>>),
+    Table(Box<dyn Iterator<Item = (&'doc Key, &'doc Id<JsonValue>)> + 'doc>),
+}
+
+// Writes `value`, at `pointer`, as an inline TOML value.
+fn inline(value: ValueRef<'_>, pointer: String, out: &mut String) -> Result<(), TomlError> {
+    let doc = value.document();
+    // Each container being written, with the pointer to it and whether an
+    // entry has been written.
+    let mut stack = Vec::new();
+    let mut next = (value.value(), pointer);
+    loop {
+        let (value, pointer) = next;
+        match value {
+            JsonValue::List(list) => {
+                out.push('[');
+                stack.push((Frame::List(list.iter().enumerate()), pointer, false));
+            }
+            JsonValue::Object(obj) => {
+                out.push('{');
+                stack.push((Frame::Table(Box::new(obj.iter())), pointer, false));
+            }
+            JsonValue::String(s) | JsonValue::Raw(s) => push_string(s, out),
+            JsonValue::Number(n) => number(n, pointer, out)?,
+            JsonValue::Bool(b) => write!(out, "{b}").expect("writing to a string can't fail"),
+            JsonValue::Null => {
+                return Err(TomlError::Unrepresentable {
+                    pointer,
+                    message: "null, which TOML has no value for",
+                })
+            }
+        }
+        // Close every container that's done, up to the next value.
+        loop {
+            let Some((frame, pointer, written)) = stack.last_mut() else {
+                return Ok(());
+            };
+            let entry = match frame {
+                Frame::List(iter) => iter.next().map(|(i, &id)| (None, i.to_string(), id)),
+                Frame::Table(iter) => iter
+                    .next()
+                    .map(|(key, &id)| (Some(key), pointer::escape(key).into_owned(), id)),
+            };
+            let Some((key, token, id)) = entry else {
+                out.push_str(match (frame, *written) {
+                    (Frame::List(_), _) => "]",
+                    (Frame::Table(_), true) => " }",
+                    (Frame::Table(_), false) => "}",
+                });
+                stack.pop();
+                continue;
+            };
+            if std::mem::replace(written, true) {
+                out.push_str(", ");
+            } else if key.is_some() {
+                out.push(' ');
+            }
+            if let Some(key) = key {
+                push_key(key, out);
+                out.push_str(" = ");
+            }
+            next = (doc.get(id), format!("{pointer}/{token}"));
+            break;
+        }
+    }
+}
+
+fn number(n: &Number, pointer: String, out: &mut String) -> Result<(), TomlError> {
+    let too_big = TomlError::Unrepresentable {
+        pointer,
+        message: "an integer past TOML's 64 bits",
+    };
+    match n {
+        Number::F64(n) if n.is_nan() => out.push_str("nan"),
+        Number::F64(n) if n.is_infinite() => out.push_str(if *n > 0.0 { "inf" } else { "-inf" }),
+        // `{:?}` keeps a fraction or an exponent on every float.
+        Number::F64(n) => write!(out, "{n:?}").expect("writing to a string can't fail"),
+        Number::U64(n) if i64::try_from(*n).is_err() => return Err(too_big),
+        #[cfg(feature = "arbitrary-precision")]
+        Number::Decimal(s) if !s.contains(['.', 'e', 'E']) && s.parse::<i64>().is_err() => {
+            return Err(too_big)
+        }
+        n => write!(out, "{n}").expect("writing to a string can't fail"),
+    }
+    Ok(())
+}
+
+// `key` bare if it can be, and quoted otherwise.
+fn push_key(key: &str, out: &mut String) {
+    let bare = !key.is_empty()
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-');
+    match bare {
+        true => out.push_str(key),
+        false => push_string(key, out),
+    }
+}
+
+// `s` as a basic string.
+fn push_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{c}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if forbidden(c) => {
+                write!(out, "\\u{:04x}", c as u32).expect("writing to a string can't fail")
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// The control characters that TOML strings can only hold escaped. Tabs,
+// and line breaks in multi-line strings, can go as they are.
+fn forbidden(c: char) -> bool {
+    matches!(c, '\0'..='\u{8}' | '\n'..='\u{1f}' | '\u{7f}')
+}
+
+// A line and a column, both 1-based.
+type Position = (usize, usize);
+
+fn fail((line, column): Position, message: &'static str) -> TomlError {
+    TomlError::Syntax {
+        line,
+        column,
+        message,
+    }
+}
+
+// What made a table, which decides what can add to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    // Only named on the way to another table's header, so a header of its
+    // own can still define it.
+    Implicit,
+    Header,
+    // Made by a dotted key, so more dotted keys can add to it.
+    Dotted,
+    // An array of tables, the list that `[[headers]]` add to.
+    Tables,
+}
+
+// An array or an inline table being read, with whether an entry was just
+// read. An inline table has the dotted key waiting for its value.
+enum Inline {
+    Array(Vec<Id<JsonValue>>),
+    Table(Id<JsonValue>, Option<Vec<(String, Position)>>),
+}
+
+struct Parser<'s> {
+    src: &'s str,
+    pos: usize,
+    line: usize,
+    line_start: usize,
+    doc: Document,
+    // What made each table, by its place in the arena. Other objects are
+    // inline tables, which nothing can add to.
+    kinds: HashMap<usize, Kind>,
+    // The table that key/value pairs go in.
+    table: Id<JsonValue>,
+}
+
+impl<'s> Parser<'s> {
+    fn document(mut self, root: Id<JsonValue>) -> Result<Document, TomlError> {
+        loop {
+            self.skip_spaces();
+            match self.peek() {
+                None => break,
+                Some('\n') => {
+                    self.bump();
+                }
+                Some('#') => self.line_end()?,
+                Some('[') => {
+                    self.pos += 1;
+                    let array = self.eat('[');
+                    let keys = self.keys()?;
+                    if !self.eat(']') || array && !self.eat(']') {
+                        return Err(fail(self.at(), "a header without its closing bracket"));
+                    }
+                    self.header(root, &keys, array)?;
+                    self.line_end()?;
+                }
+                Some(_) => {
+                    let keys = self.keys()?;
+                    self.equals()?;
+                    let value = self.value()?;
+                    self.insert(self.table, &keys, value)?;
+                    self.line_end()?;
+                }
+            }
+        }
+        let root = std::mem::take(self.doc.get_mut(root));
+        *self.doc.root_mut() = root;
+        Ok(self.doc)
+    }
+
+    fn rest(&self) -> &'s str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn at(&self) -> Position {
+        let column = self.src[self.line_start..self.pos].chars().count() + 1;
+        (self.line, column)
+    }
+
+    // Moves past the next character, counting lines.
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.line_start = self.pos;
+        }
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let eaten = self.peek() == Some(c);
+        if eaten {
+            self.pos += c.len_utf8();
+        }
+        eaten
+    }
+
+    fn skip_spaces(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn comment(&mut self) -> Result<(), TomlError> {
+        while let Some(c) = self.peek().filter(|&c| c != '\n') {
+            if forbidden(c) {
+                return Err(fail(self.at(), "a control character in a comment"));
+            }
+            self.pos += c.len_utf8();
+        }
+        Ok(())
+    }
+
+    // Past the end of the line, with only a comment left on it.
+    fn line_end(&mut self) -> Result<(), TomlError> {
+        self.skip_spaces();
+        if self.peek() == Some('#') {
+            self.comment()?;
+        }
+        match self.peek() {
+            None => Ok(()),
+            Some('\n') => {
+                self.bump();
+                Ok(())
+            }
+            Some(_) => Err(fail(self.at(), "more on the line after its end")),
+        }
+    }
+
+    // Past whitespace, line breaks and comments in an array.
+    fn skip_array_space(&mut self) -> Result<(), TomlError> {
+        loop {
+            match self.peek() {
+                Some(' ' | '\t' | '\n') => {
+                    self.bump();
+                }
+                Some('#') => self.comment()?,
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn equals(&mut self) -> Result<(), TomlError> {
+        if !self.eat('=') {
+            return Err(fail(self.at(), "a key without `=` after it"));
+        }
+        self.skip_spaces();
+        Ok(())
+    }
+
+    // A dotted key, each part with where it was.
+    fn keys(&mut self) -> Result<Vec<(String, Position)>, TomlError> {
+        let mut keys = Vec::new();
+        loop {
+            self.skip_spaces();
+            let at = self.at();
+            let key = match self.peek() {
+                Some('"') => self.basic()?,
+                Some('\'') => self.literal()?,
+                _ => {
+                    let rest = self.rest();
+                    let len = rest
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                        .unwrap_or(rest.len());
+                    if len == 0 {
+                        return Err(fail(at, "a missing key"));
+                    }
+                    self.pos += len;
+                    rest[..len].to_owned()
+                }
+            };
+            keys.push((key, at));
+            self.skip_spaces();
+            if !self.eat('.') {
+                return Ok(keys);
+            }
+        }
+    }
+
+    fn member(&self, table: Id<JsonValue>, key: &str) -> Option<Id<JsonValue>> {
+        match self.doc.get(table) {
+            JsonValue::Object(obj) => obj.get(key).copied(),
+            _ => None,
+        }
+    }
+
+    fn kind(&self, id: Id<JsonValue>) -> Option<Kind> {
+        self.kinds.get(&id.index()).copied()
+    }
+
+    // A new, empty table.
+    fn table(&mut self, kind: Kind) -> Id<JsonValue> {
+        let id = self.doc.alloc(JsonValue::Object(Map::new()));
+        self.kinds.insert(id.index(), kind);
+        id
+    }
+
+    fn add(&mut self, table: Id<JsonValue>, key: &str, id: Id<JsonValue>) {
+        if let JsonValue::Object(obj) = self.doc.get_mut(table) {
+            obj.insert(key.into(), id);
+        }
+    }
+
+    // Makes the table under a `[header]` or `[[header]]` the one that
+    // key/value pairs go in.
+    fn header(
+        &mut self,
+        root: Id<JsonValue>,
+        keys: &[(String, Position)],
+        array: bool,
+    ) -> Result<(), TomlError> {
+        let mut table = root;
+        for (i, (key, at)) in keys.iter().enumerate() {
+            let last = i + 1 == keys.len();
+            let child = self.member(table, key);
+            table = match (child, child.and_then(|child| self.kind(child))) {
+                (None, _) if last && array => {
+                    let element = self.table(Kind::Header);
+                    let list = self.doc.alloc(JsonValue::List(vec![element]));
+                    self.kinds.insert(list.index(), Kind::Tables);
+                    self.add(table, key, list);
+                    element
+                }
+                (None, _) => {
+                    let child = self.table(if last { Kind::Header } else { Kind::Implicit });
+                    self.add(table, key, child);
+                    child
+                }
+                // The last table of the array, or a new one for `[[header]]`.
+                (Some(list), Some(Kind::Tables)) if !last || array => {
+                    let element = match last {
+                        true => Some(self.table(Kind::Header)),
+                        false => None,
+                    };
+                    let JsonValue::List(elements) = self.doc.get_mut(list) else {
+                        unreachable!("arrays of tables are lists");
+                    };
+                    elements.extend(element);
+                    *elements.last().expect("arrays of tables have a table")
+                }
+                (Some(child), Some(Kind::Implicit)) if last && !array => {
+                    self.kinds.insert(child.index(), Kind::Header);
+                    child
+                }
+                (Some(child), Some(_)) if !last => child,
+                (Some(_), Some(_)) => return Err(fail(*at, "a table that is already defined")),
+                (Some(_), None) => return Err(fail(*at, "a key that is already defined")),
+            };
+        }
+        self.table = table;
+        Ok(())
+    }
+
+    // Adds `id` to `table` under the dotted `keys`, making tables for all
+    // but the last, or going into the ones dotted keys made before.
+    fn insert(
+        &mut self,
+        mut table: Id<JsonValue>,
+        keys: &[(String, Position)],
+        id: Id<JsonValue>,
+    ) -> Result<(), TomlError> {
+        let Some(((last, last_at), parents)) = keys.split_last() else {
+            return Ok(());
+        };
+        for (key, at) in parents {
+            table = match self.member(table, key) {
+                None => {
+                    let child = self.table(Kind::Dotted);
+                    self.add(table, key, child);
+                    child
+                }
+                Some(child) if self.kind(child) == Some(Kind::Dotted) => child,
+                Some(_) => return Err(fail(*at, "a key that is already defined")),
+            };
+        }
+        if self.member(table, last).is_some() {
+            return Err(fail(*last_at, "a key that is already defined"));
+        }
+        self.add(table, last, id);
+        Ok(())
+    }
+
+    // A value, with arrays and inline tables read through to their ends.
+    fn value(&mut self) -> Result<Id<JsonValue>, TomlError> {
+        let mut stack: Vec<(Inline, bool)> = Vec::new();
+        loop {
+            let closed = match stack.last_mut() {
+                Some((Inline::Array(ids), ready)) => {
+                    self.skip_array_space()?;
+                    let at = self.at();
+                    match self.peek() {
+                        Some(']') => {
+                            self.pos += 1;
+                            let list = JsonValue::List(std::mem::take(ids));
+                            Some(self.doc.alloc(list))
+                        }
+                        Some(',') if *ready => {
+                            self.pos += 1;
+                            *ready = false;
+                            continue;
+                        }
+                        Some(',') => return Err(fail(at, "a comma without a value before it")),
+                        None => return Err(fail(at, "an unterminated array")),
+                        _ if *ready => return Err(fail(at, "a missing comma")),
+                        _ => None,
+                    }
+                }
+                Some((Inline::Table(table, key @ None), ready)) => {
+                    self.skip_spaces();
+                    let at = self.at();
+                    let empty =
+                        matches!(self.doc.get(*table), JsonValue::Object(obj) if obj.is_empty());
+                    match self.peek() {
+                        None | Some('\n') => {
+                            return Err(fail(at, "an inline table that doesn't end on its line"))
+                        }
+                        Some('}') if *ready || empty => {
+                            self.pos += 1;
+                            Some(*table)
+                        }
+                        Some('}') => return Err(fail(at, "a trailing comma in an inline table")),
+                        Some(',') if *ready => {
+                            self.pos += 1;
+                            *ready = false;
+                            continue;
+                        }
+                        Some(',') => return Err(fail(at, "a comma without a value before it")),
+                        _ if *ready => return Err(fail(at, "a missing comma")),
+                        _ => {
+                            *key = Some(self.keys()?);
+                            self.equals()?;
+                            continue;
+                        }
+                    }
+                }
+                Some((Inline::Table(..), _)) | None => None,
+            };
+            let id = match closed {
+                Some(id) => {
+                    stack.pop();
+                    id
+                }
+                None => match self.peek() {
+                    Some('[') => {
+                        self.pos += 1;
+                        stack.push((Inline::Array(Vec::new()), false));
+                        continue;
+                    }
+                    Some('{') => {
+                        self.pos += 1;
+                        let table = self.doc.alloc(JsonValue::Object(Map::new()));
+                        stack.push((Inline::Table(table, None), false));
+                        continue;
+                    }
+                    _ => {
+                        let value = self.scalar()?;
+                        self.doc.alloc(value)
+                    }
+                },
+            };
+            match stack.last_mut() {
+                None => return Ok(id),
+                Some((Inline::Array(ids), ready)) => {
+                    ids.push(id);
+                    *ready = true;
+                }
+                Some((Inline::Table(table, key), ready)) => {
+                    let table = *table;
+                    let keys = key.take().unwrap_or_default();
+                    *ready = true;
+                    self.insert(table, &keys, id)?;
+                }
+            }
+        }
+    }
+
+    // A string, a boolean, a number, or a date or time.
+    fn scalar(&mut self) -> Result<JsonValue, TomlError> {
+        let at = self.at();
+        let rest = self.rest();
+        if rest.starts_with("\"\"\"") {
+            return Ok(JsonValue::String(self.multiline('"')?));
+        }
+        if rest.starts_with("'''") {
+            return Ok(JsonValue::String(self.multiline('\'')?));
+        }
+        match self.peek() {
+            Some('"') => return Ok(JsonValue::String(self.basic()?)),
+            Some('\'') => return Ok(JsonValue::String(self.literal()?)),
+            None | Some('\n' | '#') => return Err(fail(at, "a missing value")),
+            _ => {}
+        }
+        let word_len = |s: &str| {
+            s.find(|c: char| {
+                !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+' | '.' | ':'))
+            })
+            .unwrap_or(s.len())
+        };
+        let mut len = word_len(rest);
+        // A space can go between a date and its time.
+        let time = rest[len..].strip_prefix(' ').unwrap_or_default();
+        if is_date(&rest[..len]) && time.len() > 2 && is_clock_start(time) {
+            len += 1 + word_len(time);
+        }
+        let word = &rest[..len];
+        let value = match word {
+            "" => return Err(fail(at, "an unexpected character")),
+            "true" => JsonValue::Bool(true),
+            "false" => JsonValue::Bool(false),
+            _ if datetime(word) => JsonValue::String(word.to_owned()),
+            _ => JsonValue::Number(number_from(word).map_err(|message| fail(at, message))?),
+        };
+        self.pos += len;
+        Ok(value)
+    }
+
+    fn basic(&mut self) -> Result<String, TomlError> {
+        let start = self.at();
+        self.pos += 1;
+        let mut text = String::new();
+        loop {
+            let at = self.at();
+            match self.peek() {
+                None | Some('\n') => return Err(fail(start, "an unterminated string")),
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(text);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    text.push(self.escape(at)?);
+                }
+                Some(c) if forbidden(c) => return Err(fail(at, "a control character in a string")),
+                Some(c) => {
+                    self.pos += c.len_utf8();
+                    text.push(c);
+                }
+            }
+        }
+    }
+
+    fn literal(&mut self) -> Result<String, TomlError> {
+        let start = self.at();
+        self.pos += 1;
+        let rest = self.rest();
+        let end = rest.find(['\'', '\n']).unwrap_or(rest.len());
+        if !rest[end..].starts_with('\'') {
+            return Err(fail(start, "an unterminated string"));
+        }
+        if let Some(i) = rest[..end].find(forbidden) {
+            self.pos += i;
+            return Err(fail(self.at(), "a control character in a string"));
+        }
+        self.pos += end + 1;
+        Ok(rest[..end].to_owned())
+    }
+
+    // A `"""` or `'''` string, for `quote`, which can span lines.
+    fn multiline(&mut self, quote: char) -> Result<String, TomlError> {
+        let start = self.at();
+        self.pos += 3;
+        // A line break right after the opening quotes isn't in the string.
+        if self.peek() == Some('\n') {
+            self.bump();
+        }
+        let mut text = String::new();
+        loop {
+            let at = self.at();
+            match self.peek() {
+                None => return Err(fail(start, "an unterminated string")),
+                // Up to two quotes can go right before the closing ones.
+                Some(c) if c == quote => {
+                    let run = self.rest().len() - self.rest().trim_start_matches(quote).len();
+                    self.pos += run;
+                    if run < 3 {
+                        text.extend(std::iter::repeat_n(quote, run));
+                        continue;
+                    }
+                    if run > 5 {
+                        return Err(fail(at, "too many quotes at the end of a string"));
+                    }
+                    text.extend(std::iter::repeat_n(quote, run - 3));
+                    return Ok(text);
+                }
+                Some('\\') if quote == '"' => {
+                    self.pos += 1;
+                    // A backslash ending a line trims the whitespace after it,
+                    // line breaks included.
+                    let rest = self.rest().trim_start_matches([' ', '\t']);
+                    if !rest.starts_with('\n') {
+                        text.push(self.escape(at)?);
+                        continue;
+                    }
+                    while matches!(self.peek(), Some(' ' | '\t' | '\n')) {
+                        self.bump();
+                    }
+                }
+                Some('\n') => {
+                    self.bump();
+                    text.push('\n');
+                }
+                Some(c) if forbidden(c) => return Err(fail(at, "a control character in a string")),
+                Some(c) => {
+                    self.pos += c.len_utf8();
+                    text.push(c);
+                }
+            }
+        }
+    }
+
+    // The character of the escape at `at`, after its backslash.
+    fn escape(&mut self, at: Position) -> Result<char, TomlError> {
+        let width = match self.bump() {
+            Some('b') => return Ok('\u{8}'),
+            Some('t') => return Ok('\t'),
+            Some('n') => return Ok('\n'),
+            Some('f') => return Ok('\u{c}'),
+            Some('r') => return Ok('\r'),
+            Some(c @ ('"' | '\\')) => return Ok(c),
+            Some('u') => 4,
+            Some('U') => 8,
+            _ => return Err(fail(at, "an unknown escape")),
+        };
+        let digits = self
+            .rest()
+            .get(..width)
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or(fail(at, "an escape without enough hex digits"))?;
+        self.pos += width;
+        u32::from_str_radix(digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(fail(at, "an escape that isn't a character"))
+    }
+}
+
+// A TOML integer or float: decimal, with `_`s between digits, or a hex,
+// octal or binary integer.
+fn number_from(word: &str) -> Result<Number, &'static str> {
+    const INVALID: &str = "an invalid value";
+    const RANGE: &str = "an integer past 64 bits";
+    let (negative, unsigned) = match word.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, word.strip_prefix('+').unwrap_or(word)),
+    };
+    match unsigned {
+        "inf" if negative => return Ok(Number::F64(f64::NEG_INFINITY)),
+        "inf" => return Ok(Number::F64(f64::INFINITY)),
+        "nan" => return Ok(Number::F64(f64::NAN)),
+        _ => {}
+    }
+    for (prefix, radix) in [("0x", 16), ("0o", 8), ("0b", 2)] {
+        if let Some(digits) = word.strip_prefix(prefix) {
+            let digits = self::digits(digits, radix).ok_or(INVALID)?;
+            let n = u64::from_str_radix(&digits, radix).map_err(|_| RANGE)?;
+            return i64::try_from(n).map(Number::I64).map_err(|_| RANGE);
+        }
+    }
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (unsigned, None),
+    };
+    let (int, fraction) = match mantissa.split_once('.') {
+        Some((int, fraction)) => (int, Some(fraction)),
+        None => (mantissa, None),
+    };
+    let int = digits(int, 10).ok_or(INVALID)?;
+    if int.len() > 1 && int.starts_with('0') {
+        return Err("a number with a leading zero");
+    }
+    let mut text = format!("{}{int}", if negative { "-" } else { "" });
+    if fraction.is_none() && exponent.is_none() {
+        return text.parse().map(Number::I64).map_err(|_| RANGE);
+    }
+    if let Some(fraction) = fraction {
+        text.push('.');
+        text.push_str(&digits(fraction, 10).ok_or(INVALID)?);
+    }
+    if let Some(exponent) = exponent {
+        let (sign, exponent) = match exponent.strip_prefix(['+', '-']) {
+            Some(rest) => (&exponent[..1], rest),
+            None => ("", exponent),
+        };
+        text.push('e');
+        text.push_str(sign);
+        text.push_str(&digits(exponent, 10).ok_or(INVALID)?);
+    }
+    text.parse().map(Number::F64).map_err(|_| INVALID)
+}
+
+// `s` without its `_`s, if each is between two digits of `radix`.
+fn digits(s: &str, radix: u32) -> Option<String> {
+    let mut out = String::with_capacity(s.len());
+    for part in s.split('_') {
+        if part.is_empty() || !part.chars().all(|c| c.is_digit(radix)) {
+            return None;
+        }
+        out.push_str(part);
+    }
+    Some(out)
+}
+
+// Whether `word` is an offset or local date-time, a local date, or a local
+// time.
+fn datetime(word: &str) -> bool {
+    match word.find(['T', 't', ' ']) {
+        Some(i) => is_date(&word[..i]) && is_time(&word[i + 1..], true),
+        None if word.contains(':') => is_time(word, false),
+        None => is_date(word),
+    }
+}
+
+// `YYYY-MM-DD`, a day that exists.
+fn is_date(s: &str) -> bool {
+    let b = s.as_bytes();
+    let number = |range: std::ops::Range<usize>| {
+        b[range.clone()]
+            .iter()
+            .all(u8::is_ascii_digit)
+            .then(|| s[range].parse::<u32>().unwrap_or_default())
+    };
+    if b.len() != 10 || b[4] != b'-' || b[7] != b'-' {
+        return false;
+    }
+    let (Some(year), Some(month), Some(day)) = (number(0..4), number(5..7), number(8..10)) else {
+        return false;
+    };
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => 0,
+    };
+    (1..=days).contains(&day)
+}
+
+// Whether `s` starts like `HH:`, as the time after a date and a space does.
+fn is_clock_start(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() > 2 && b[0].is_ascii_digit() && b[1].is_ascii_digit() && b[2] == b':'
+}
+
+// `HH:MM:SS` with an optional fraction, and after a date an optional `Z` or
+// `+HH:MM` offset.
+fn is_time(s: &str, offset: bool) -> bool {
+    let (time, zone) = match s.find(['Z', 'z', '+', '-']) {
+        Some(i) if offset => s.split_at(i),
+        Some(_) => return false,
+        None => (s, ""),
+    };
+    let (clock, fraction) = match time.split_once('.') {
+        Some((clock, fraction)) => (clock, Some(fraction)),
+        None => (time, None),
+    };
+    fraction.is_none_or(|f| !f.is_empty() && f.bytes().all(|b| b.is_ascii_digit()))
+        && is_clock(clock, true)
+        && (matches!(zone, "" | "Z" | "z")
+            || zone
+                .strip_prefix(['+', '-'])
+                .is_some_and(|zone| is_clock(zone, false)))
+}
+
+// `HH:MM`, or `HH:MM:SS` with `seconds`, each in range.
+fn is_clock(s: &str, seconds: bool) -> bool {
+    let parts: Vec<&str> = s.split(':').collect();
+    let two_digits = |part: &&str| part.len() == 2 && part.bytes().all(|b| b.is_ascii_digit());
+    parts.len() == 2 + seconds as usize
+        && parts.iter().all(two_digits)
+        && parts[0] < "24"
+        && parts[1] < "60"
+        && parts.get(2).is_none_or(|s| *s <= "60")
+}
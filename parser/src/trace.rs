@@ -0,0 +1,9 @@
+// Diagnostics for debugging the parser itself. Compiled out unless the
+// `trace` feature is on, in which case events are written to stderr.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if cfg!(feature = "trace") {
+            eprintln!("[jsonparser] {}", format_args!($($arg)*));
+        }
+    };
+}
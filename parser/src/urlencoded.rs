@@ -0,0 +1,220 @@
+use std::fmt::Write;
+
+use crate::{
+    alloc::Id,
+    doc::{Document, ValueRef},
+    error::UrlencodedError,
+    map::Map,
+    value::JsonValue,
+};
+
+// A part of a field name in bracket notation: `a[b][]` is `a`, then `b`,
+// then a push onto a list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment<'s> {
+    Key(&'s str),
+    Push,
+}
+
+impl Document {
+    /// Reads `application/x-www-form-urlencoded` text, like a query string,
+    /// into an object of strings. Bracketed names nest the way Rack and PHP
+    /// read them: `b[]=2&b[]=3` is a list, `c[d]=4` an object, and
+    /// `e[][f]=5&e[][g]=6&e[][f]=7` a list of objects, where a key the last
+    /// one already has starts the next. A name that isn't in bracket
+    /// notation is taken as it is. Bad percent-encoding is kept as text,
+    /// and bytes that aren't UTF-8 become U+FFFD.
+    pub fn from_urlencoded(src: &str) -> Result<Document, UrlencodedError> {
+        let mut doc = Document::default();
+        let root = doc.alloc(JsonValue::Object(Map::new()));
+        for pair in src.split('&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let name = decode(name);
+            let value = doc.alloc(JsonValue::String(decode(value)));
+            let conflict = || UrlencodedError::Conflict { key: name.clone() };
+            let path = segments(&name);
+            let mut at = root;
+            for (i, segment) in path.iter().enumerate() {
+                // What the next segment needs this one to be, if there's one.
+                let next = path.get(i + 1).map(|next| match next {
+                    Segment::Key(_) => JsonValue::Object(Map::new()),
+                    Segment::Push => JsonValue::List(Vec::new()),
+                });
+                let found = match (doc.get(at), segment) {
+                    (JsonValue::Object(obj), Segment::Key(key)) => obj.get(*key).copied(),
+                    (JsonValue::List(list), Segment::Push) => list
+                        .last()
+                        .copied()
+                        .filter(|&last| next.is_some() && continues(&doc, last, &path[i + 1..])),
+                    _ => return Err(conflict()),
+                };
+                let Some(next) = next else {
+                    if found.is_some() {
+                        return Err(conflict());
+                    }
+                    add(&mut doc, at, segment, value);
+                    break;
+                };
+                at = match found {
+                    Some(id) if same_kind(doc.get(id), &next) => id,
+                    Some(_) => return Err(conflict()),
+                    None => {
+                        let id = doc.alloc(next);
+                        add(&mut doc, at, segment, id);
+                        id
+                    }
+                };
+            }
+        }
+        let root = std::mem::take(doc.get_mut(root));
+        *doc.root_mut() = root;
+        Ok(doc)
+    }
+
+    /// The document, which has to be an object, as
+    /// `application/x-www-form-urlencoded` text in bracket notation, which
+    /// `from_urlencoded` reads back as strings. Nulls have empty values,
+    /// and empty lists and objects, which forms can't say, are left out.
+    pub fn to_urlencoded(&self) -> Result<String, UrlencodedError> {
+        let root = self.value();
+        let obj = root.as_object().ok_or(UrlencodedError::NotObject)?;
+        let mut out = String::new();
+        let mut stack: Vec<(String, ValueRef<'_>)> = obj
+            .iter()
+            .map(|(key, &id)| (encode(key), root.child(id)))
+            .collect();
+        stack.reverse();
+        while let Some((name, value)) = stack.pop() {
+            let start = stack.len();
+            match value.value() {
+                JsonValue::Object(obj) => {
+                    for (key, &id) in obj {
+                        stack.push((format!("{name}[{}]", encode(key)), value.child(id)));
+                    }
+                }
+                JsonValue::List(list) => {
+                    for &id in list {
+                        stack.push((format!("{name}[]"), value.child(id)));
+                    }
+                }
+                scalar => {
+                    if !out.is_empty() {
+                        out.push('&');
+                    }
+                    out.push_str(&name);
+                    out.push('=');
+                    match scalar {
+                        JsonValue::String(s) => out.push_str(&encode(s)),
+                        JsonValue::Number(n) => out.push_str(&encode(&n.to_string())),
+                        JsonValue::Bool(b) => {
+                            write!(out, "{b}").expect("writing to a string can't fail")
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            stack[start..].reverse();
+        }
+        Ok(out)
+    }
+}
+
+// Puts `id` in the container at `at`, under `segment`.
+fn add(doc: &mut Document, at: Id<JsonValue>, segment: &Segment<'_>, id: Id<JsonValue>) {
+    match (doc.get_mut(at), segment) {
+        (JsonValue::Object(obj), Segment::Key(key)) => {
+            obj.insert((*key).into(), id);
+        }
+        (JsonValue::List(list), Segment::Push) => list.push(id),
+        _ => {}
+    }
+}
+
+fn same_kind(a: &JsonValue, b: &JsonValue) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+}
+
+// Whether a `[]` before `rest` goes on into the list's `last` element,
+// rather than starting another: it does when `last` is an object that
+// doesn't have the field yet. Fields with another push in them always go
+// on, as Rack has them.
+fn continues(doc: &Document, last: Id<JsonValue>, rest: &[Segment<'_>]) -> bool {
+    let mut at = last;
+    for (i, segment) in rest.iter().enumerate() {
+        match (doc.get(at), segment) {
+            (JsonValue::Object(obj), Segment::Key(key)) => match obj.get(*key) {
+                Some(_) if i + 1 == rest.len() => return false,
+                Some(&id) => at = id,
+                None => return true,
+            },
+            (JsonValue::List(_), Segment::Push) if i > 0 => return true,
+            _ => return false,
+        }
+    }
+    true
+}
+
+// The segments of `name`, or just `name` if it isn't in bracket notation.
+fn segments(name: &str) -> Vec<Segment<'_>> {
+    let whole = vec![Segment::Key(name)];
+    let Some(open) = name.find('[').filter(|&open| open > 0) else {
+        return whole;
+    };
+    let mut path = vec![Segment::Key(&name[..open])];
+    let mut rest = &name[open..];
+    while !rest.is_empty() {
+        let Some((inner, after)) = rest
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+            .filter(|(inner, _)| !inner.contains('['))
+        else {
+            return whole;
+        };
+        path.push(match inner {
+            "" => Segment::Push,
+            key => Segment::Key(key),
+        });
+        rest = after;
+    }
+    path
+}
+
+// Percent-decodes `s`, with `+` for a space.
+fn decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+            (b'+', _) => out.push(b' '),
+            (byte, _) => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// Percent-encodes `s` as HTML forms do, with `+` for a space.
+fn encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => {
+                out.push(byte as char)
+            }
+            b' ' => out.push('+'),
+            _ => write!(out, "%{byte:02X}").expect("writing to a string can't fail"),
+        }
+    }
+    out
+}
@@ -0,0 +1,203 @@
+use std::fmt;
+
+use crate::{alloc::Id, key::Key, map::Map};
+
+#[derive(Default, Debug, Clone)]
+pub enum JsonValue {
+    String(String),
+    Number(Number),
+    Bool(bool),
+    Object(Map<Key, Id<JsonValue>>),
+    List(Vec<Id<JsonValue>>),
+    /// A value left unparsed because its key is in `ParseOptions::raw_keys`:
+    /// its text as it was in the source, ready to be parsed later. JSON
+    /// output writes it back verbatim.
+    Raw(String),
+    #[default]
+    Null,
+}
+
+// Children are `Id`s, so these only look at the value itself; `ValueRef`
+// resolves them.
+impl JsonValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<&Number> {
+        match self {
+            JsonValue::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(Number::as_f64)
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number().and_then(Number::as_i64)
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number().and_then(Number::as_u64)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            JsonValue::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&Map<Key, Id<JsonValue>>> {
+        match self {
+            JsonValue::Object(obj) => Some(obj),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Id<JsonValue>]> {
+        match self {
+            JsonValue::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsonValue::Null)
+    }
+}
+
+// Binary data kept in JSON strings.
+#[cfg(feature = "base64")]
+impl JsonValue {
+    /// The bytes of a base64 string, in the standard or the URL-safe
+    /// alphabet, with or without padding. `None` for anything else,
+    /// strings that aren't base64 included.
+    pub fn as_base64_bytes(&self) -> Option<Vec<u8>> {
+        self.as_str().and_then(crate::base64::decode)
+    }
+
+    /// The bytes of a hex string, in either case.
+    pub fn as_hex_bytes(&self) -> Option<Vec<u8>> {
+        let s = self.as_str()?;
+        if !s.len().is_multiple_of(2) || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+            .collect()
+    }
+
+    /// A string of `bytes` in padded, standard base64, which is what most
+    /// JSON that carries binary data expects.
+    pub fn from_bytes_base64(bytes: &[u8]) -> JsonValue {
+        JsonValue::String(crate::base64::encode_standard(bytes))
+    }
+
+    /// A string of `bytes` in lowercase hex.
+    pub fn from_bytes_hex(bytes: &[u8]) -> JsonValue {
+        JsonValue::String(bytes.iter().map(|b| format!("{b:02x}")).collect())
+    }
+}
+
+/// A JSON number. Integers are kept exactly: `I64` when they fit, `U64` only
+/// above `i64::MAX`, and `F64` for everything else, including `-0` and
+/// integers too large for either.
+///
+/// With the `arbitrary-precision` feature, fractions, exponents and
+/// oversized integers keep their source text as `Decimal` instead, so no
+/// digits are lost until a conversion asks for them; `Number` is not `Copy`
+/// then.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(not(feature = "arbitrary-precision"), derive(Copy))]
+pub enum Number {
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    #[cfg(feature = "arbitrary-precision")]
+    Decimal(String),
+}
+
+impl Default for Number {
+    fn default() -> Self {
+        Number::I64(0)
+    }
+}
+
+impl Number {
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Number::I64(n) => Some(n),
+            Number::U64(n) => i64::try_from(n).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Number::I64(n) => u64::try_from(n).ok(),
+            Number::U64(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Lossy for integers beyond 2^53.
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            Number::I64(n) => *n as f64,
+            Number::U64(n) => *n as f64,
+            Number::F64(n) => *n,
+            #[cfg(feature = "arbitrary-precision")]
+            Number::Decimal(s) => s.parse().unwrap_or(f64::NAN),
+        }
+    }
+
+    /// The number as it was written, for `Decimal`s.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            #[cfg(feature = "arbitrary-precision")]
+            Number::Decimal(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Number::I64(_) | Number::U64(_))
+    }
+}
+
+impl From<i64> for Number {
+    fn from(n: i64) -> Self {
+        Number::I64(n)
+    }
+}
+
+impl From<u64> for Number {
+    fn from(n: u64) -> Self {
+        i64::try_from(n).map_or(Number::U64(n), Number::I64)
+    }
+}
+
+impl From<f64> for Number {
+    fn from(n: f64) -> Self {
+        Number::F64(n)
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::I64(n) => write!(f, "{n}"),
+            Number::U64(n) => write!(f, "{n}"),
+            Number::F64(n) => write!(f, "{n}"),
+            #[cfg(feature = "arbitrary-precision")]
+            Number::Decimal(s) => f.write_str(s),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::{collections::VecDeque, ops::ControlFlow};
+
+use crate::{
+    doc::{Document, ValueRef},
+    pointer,
+    value::JsonValue,
+};
+
+/// The order `Document::walk_with` visits values in. Either way a value
+/// comes before everything below it, and siblings in document order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WalkOrder {
+    /// Everything below a value before its next sibling.
+    #[default]
+    DepthFirst,
+    /// Everything at one depth before anything deeper.
+    BreadthFirst,
+}
+
+impl Document {
+    /// Calls `f` with the JSON Pointer and value of the root and of
+    /// everything below it, depth-first, until it returns `Break`.
+    pub fn walk<'doc, B>(
+        &'doc self,
+        f: impl FnMut(&str, ValueRef<'doc>) -> ControlFlow<B>,
+    ) -> ControlFlow<B> {
+        self.walk_with(WalkOrder::DepthFirst, f)
+    }
+
+    pub fn walk_with<'doc, B>(
+        &'doc self,
+        order: WalkOrder,
+        mut f: impl FnMut(&str, ValueRef<'doc>) -> ControlFlow<B>,
+    ) -> ControlFlow<B> {
+        let mut queue = VecDeque::from([(String::new(), self.value())]);
+        loop {
+            let next = match order {
+                WalkOrder::DepthFirst => queue.pop_back(),
+                WalkOrder::BreadthFirst => queue.pop_front(),
+            };
+            let Some((path, value)) = next else {
+                return ControlFlow::Continue(());
+            };
+            f(&path, value)?;
+            let start = queue.len();
+            match value.value() {
+                JsonValue::Object(obj) => {
+                    for (key, &id) in obj {
+                        queue.push_back((
+                            format!("{path}/{}", pointer::escape(key)),
+                            value.child(id),
+                        ));
+                    }
+                }
+                JsonValue::List(list) => {
+                    for (i, &id) in list.iter().enumerate() {
+                        queue.push_back((format!("{path}/{i}"), value.child(id)));
+                    }
+                }
+                _ => continue,
+            }
+            if order == WalkOrder::DepthFirst {
+                queue.make_contiguous()[start..].reverse();
+            }
+        }
+    }
+
+    /// Every member named `key`, at any depth, with its JSON Pointer. An
+    /// object's own member comes before any found below it, and objects
+    /// come in `walk`'s order.
+    pub fn find_key(&self, key: &str) -> Vec<(String, ValueRef<'_>)> {
+        let mut found = Vec::new();
+        let _: ControlFlow<()> = self.walk(|path, value| {
+            if let Some(&id) = value.as_object().and_then(|obj| obj.get(key)) {
+                let path = format!("{path}/{}", pointer::escape(key));
+                found.push((path, value.child(id)));
+            }
+            ControlFlow::Continue(())
+        });
+        found
+    }
+}
@@ -0,0 +1,172 @@
+use std::io;
+
+use crate::{canonical, codec::ToJson, doc::Document, doc::ValueRef, error::WriteError, ser};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Scope {
+    Array,
+    Object,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Expect {
+    Value,
+    Key,
+    // Right after a key: its value, and nothing else.
+    Member,
+    Done,
+}
+
+/// Writes compact JSON as it's described, call by call, without building a
+/// document. Calls that would make the output malformed fail with
+/// `WriteError::Unexpected` and write nothing; the writer can carry on
+/// after them.
+pub struct JsonWriter<W> {
+    out: W,
+    stack: Vec<(Scope, bool)>,
+    expect: Expect,
+    // Where `value` builds what it writes, reused from call to call.
+    scratch: Document,
+}
+
+impl<W: io::Write> JsonWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            stack: Vec::new(),
+            expect: Expect::Value,
+            scratch: Document::default(),
+        }
+    }
+
+    /// How many containers are open.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    pub fn begin_object(&mut self) -> Result<(), WriteError> {
+        self.before_value("begin_object")?;
+        self.out.write_all(b"{")?;
+        self.stack.push((Scope::Object, true));
+        self.expect = Expect::Key;
+        Ok(())
+    }
+
+    pub fn end_object(&mut self) -> Result<(), WriteError> {
+        self.end(Scope::Object, "end_object")
+    }
+
+    pub fn begin_array(&mut self) -> Result<(), WriteError> {
+        self.before_value("begin_array")?;
+        self.out.write_all(b"[")?;
+        self.stack.push((Scope::Array, true));
+        self.expect = Expect::Value;
+        Ok(())
+    }
+
+    pub fn end_array(&mut self) -> Result<(), WriteError> {
+        self.end(Scope::Array, "end_array")
+    }
+
+    pub fn key(&mut self, key: &str) -> Result<(), WriteError> {
+        if self.expect != Expect::Key {
+            return Err(self.unexpected("key"));
+        }
+        self.separate()?;
+        let mut text = String::with_capacity(key.len() + 3);
+        canonical::string(key, false, &mut text).expect("writing to a string can't fail");
+        text.push(':');
+        self.out.write_all(text.as_bytes())?;
+        self.expect = Expect::Member;
+        Ok(())
+    }
+
+    /// Writes a whole value, whether a scalar or, say, a `Vec` or a derived
+    /// struct.
+    pub fn value<T: ToJson + ?Sized>(&mut self, value: &T) -> Result<(), WriteError> {
+        self.before_value("value")?;
+        let root = value.to_json(self.scratch.arena_mut());
+        *self.scratch.root_mut() = root;
+        let written = ser::write_io(self.scratch.value(), None, &mut self.out);
+        *self.scratch.root_mut() = Default::default();
+        self.scratch.arena_mut().reset();
+        written?;
+        self.after_value();
+        Ok(())
+    }
+
+    /// Writes a value out of a parsed document.
+    pub fn value_ref(&mut self, value: ValueRef<'_>) -> Result<(), WriteError> {
+        self.before_value("value_ref")?;
+        ser::write_io(value, None, &mut self.out)?;
+        self.after_value();
+        Ok(())
+    }
+
+    /// Hands back the output once the root value is complete.
+    pub fn finish(mut self) -> Result<W, WriteError> {
+        if self.expect != Expect::Done {
+            return Err(self.unexpected("finish"));
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn before_value(&mut self, call: &'static str) -> Result<(), WriteError> {
+        match self.expect {
+            Expect::Value => self.separate(),
+            Expect::Member => Ok(()),
+            Expect::Key | Expect::Done => Err(self.unexpected(call)),
+        }
+    }
+
+    // The comma before every member but the first.
+    fn separate(&mut self) -> Result<(), WriteError> {
+        if let Some((_, first)) = self.stack.last_mut() {
+            if !std::mem::take(first) {
+                self.out.write_all(b",")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, scope: Scope, call: &'static str) -> Result<(), WriteError> {
+        let open = match self.stack.last() {
+            Some(&(open, _)) => open,
+            None => return Err(self.unexpected(call)),
+        };
+        let ready = match open {
+            Scope::Array => self.expect == Expect::Value,
+            Scope::Object => self.expect == Expect::Key,
+        };
+        if open != scope || !ready {
+            return Err(self.unexpected(call));
+        }
+        self.out.write_all(match scope {
+            Scope::Array => b"]",
+            Scope::Object => b"}",
+        })?;
+        self.stack.pop();
+        self.after_value();
+        Ok(())
+    }
+
+    fn after_value(&mut self) {
+        self.expect = match self.stack.last() {
+            None => Expect::Done,
+            Some((Scope::Array, _)) => Expect::Value,
+            Some((Scope::Object, _)) => Expect::Key,
+        };
+    }
+
+    fn unexpected(&self, call: &'static str) -> WriteError {
+        let expected = match (self.expect, self.stack.last()) {
+            (Expect::Done, _) => "nothing after the root value",
+            (Expect::Member, _) => "the key's value",
+            (Expect::Key, _) => "a key or end_object",
+            (Expect::Value, Some((Scope::Array, _))) => "a value or end_array",
+            (Expect::Value, _) => "a value",
+        };
+        WriteError::Unexpected { call, expected }
+    }
+}
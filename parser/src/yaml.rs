@@ -0,0 +1,1010 @@
+// YAML 1.2 for the values JSON has: block-style output, and reading back
+// the subset of YAML that maps onto JSON, without anchors, aliases, tags or
+// complex keys.
+
+use std::fmt::Write;
+
+#[cfg(feature = "yaml")]
+use std::borrow::Cow;
+
+#[cfg(feature = "yaml")]
+use crate::{alloc::Id, error::YamlError, key::Key, map::Map};
+use crate::{
+    doc::{Document, ValueRef},
+    value::{JsonValue, Number},
+};
+
+impl Document {
+    /// The document as block-style YAML, indented by two spaces and ending
+    /// with a newline. Strings are plain unless YAML would read them as
+    /// something else, such as `"true"`, `"1.0"` or `"yes"`, or they hold
+    /// characters a plain scalar can't; then they're double-quoted. NaN and
+    /// the infinities are `.nan`, `.inf` and `-.inf`, and a `Raw` value is a
+    /// string of its JSON text.
+    pub fn to_yaml_string(&self) -> String {
+        self.value().to_yaml_string()
+    }
+}
+
+// Something still to write, at `indent`. A `compact` one goes on the line
+// already started by a `- `.
+enum Task<'doc> {
+    Node(ValueRef<'doc>, usize, bool),
+    Member(&'doc str, ValueRef<'doc>, usize, bool),
+    Item(ValueRef<'doc>, usize, bool),
+}
+
+impl ValueRef<'_> {
+    pub fn to_yaml_string(&self) -> String {
+        let mut out = String::new();
+        let mut stack = vec![Task::Node(*self, 0, false)];
+        while let Some(task) = stack.pop() {
+            let start = stack.len();
+            match task {
+                Task::Node(value, indent, compact) => match value.value() {
+                    JsonValue::Object(obj) if !obj.is_empty() => {
+                        for (i, (key, &id)) in obj.iter().enumerate() {
+                            let compact = compact && i == 0;
+                            stack.push(Task::Member(key, value.child(id), indent, compact));
+                        }
+                    }
+                    JsonValue::List(list) if !list.is_empty() => {
+                        for (i, &id) in list.iter().enumerate() {
+                            stack.push(Task::Item(value.child(id), indent, compact && i == 0));
+                        }
+                    }
+                    scalar => {
+                        self::scalar(scalar, &mut out);
+                        out.push('\n');
+                    }
+                },
+                Task::Member(key, value, indent, compact) => {
+                    if !compact {
+                        pad(indent, &mut out);
+                    }
+                    let key_start = out.len();
+                    string(key, &mut out);
+                    // Only keys up to 1024 characters long can go without a `?`.
+                    if out[key_start..].chars().count() > 1024 {
+                        out.insert_str(key_start, "? ");
+                        out.push('\n');
+                        pad(indent, &mut out);
+                    }
+                    out.push(':');
+                    if nested(value.value()) {
+                        out.push('\n');
+                        stack.push(Task::Node(value, indent + 2, false));
+                    } else {
+                        out.push(' ');
+                        scalar(value.value(), &mut out);
+                        out.push('\n');
+                    }
+                }
+                Task::Item(value, indent, compact) => {
+                    if !compact {
+                        pad(indent, &mut out);
+                    }
+                    out.push_str("- ");
+                    stack.push(Task::Node(value, indent + 2, true));
+                }
+            }
+            stack[start..].reverse();
+        }
+        out
+    }
+}
+
+fn pad(indent: usize, out: &mut String) {
+    out.extend(std::iter::repeat_n(' ', indent));
+}
+
+// Whether `value` takes lines of its own, rather than going after its key.
+fn nested(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Object(obj) => !obj.is_empty(),
+        JsonValue::List(list) => !list.is_empty(),
+        _ => false,
+    }
+}
+
+fn scalar(value: &JsonValue, out: &mut String) {
+    match value {
+        JsonValue::Object(_) => out.push_str("{}"),
+        JsonValue::List(_) => out.push_str("[]"),
+        JsonValue::String(s) | JsonValue::Raw(s) => string(s, out),
+        JsonValue::Number(Number::F64(n)) if n.is_nan() => out.push_str(".nan"),
+        JsonValue::Number(Number::F64(n)) if n.is_infinite() => {
+            out.push_str(if *n > 0.0 { ".inf" } else { "-.inf" })
+        }
+        // The fewest digits that read back as the same float, as `{:?}`
+        // gives, with a fraction or exponent to keep it a float.
+        JsonValue::Number(Number::F64(n)) => {
+            write!(out, "{n:?}").expect("writing to a string can't fail")
+        }
+        JsonValue::Number(n) => write!(out, "{n}").expect("writing to a string can't fail"),
+        JsonValue::Bool(b) => write!(out, "{b}").expect("writing to a string can't fail"),
+        JsonValue::Null => out.push_str("null"),
+    }
+}
+
+// `s` plain if YAML reads it back as the same string, and double-quoted
+// otherwise.
+fn string(s: &str, out: &mut String) {
+    if plain(s) {
+        return out.push_str(s);
+    }
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if printable(c) => out.push(c),
+            // Everything that isn't printable is in the BMP.
+            c => write!(out, "\\u{:04x}", c as u32).expect("writing to a string can't fail"),
+        }
+    }
+    out.push('"');
+}
+
+fn plain(s: &str) -> bool {
+    let Some(first) = s.chars().next() else {
+        return false;
+    };
+    !" -?:,[]{}#&*!|>'\"%@`".contains(first)
+        && !s.ends_with([' ', ':'])
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s.starts_with("...")
+        && s.chars().all(printable)
+        && resolve(s).is_none()
+        // What YAML 1.1 reads as booleans, and its merge key.
+        && !matches!(
+            s,
+            "y" | "Y" | "yes" | "Yes" | "YES" | "n" | "N" | "no" | "No" | "NO" | "on" | "On"
+                | "ON" | "off" | "Off" | "OFF" | "<<"
+        )
+}
+
+// The characters YAML allows as they are, less whitespace other than the
+// space, what YAML 1.1 reads as line breaks, and the byte order mark.
+fn printable(c: char) -> bool {
+    matches!(c, ' '..='~' | '\u{a0}'..='\u{d7ff}' | '\u{e000}'..='\u{fffd}' | '\u{10000}'..)
+        && !matches!(c, '\u{2028}' | '\u{2029}' | '\u{feff}')
+}
+
+// What the YAML 1.2 core schema reads the plain scalar `s` as, if it isn't
+// a string.
+fn resolve(s: &str) -> Option<JsonValue> {
+    let float = |n: f64| Some(JsonValue::Number(Number::F64(n)));
+    match s {
+        "" | "~" | "null" | "Null" | "NULL" => return Some(JsonValue::Null),
+        "true" | "True" | "TRUE" => return Some(JsonValue::Bool(true)),
+        "false" | "False" | "FALSE" => return Some(JsonValue::Bool(false)),
+        ".nan" | ".NaN" | ".NAN" => return float(f64::NAN),
+        _ => {}
+    }
+    let (negative, unsigned) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    if matches!(unsigned, ".inf" | ".Inf" | ".INF") {
+        return float(if negative {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        });
+    }
+    for (prefix, radix) in [("0o", 8), ("0x", 16)] {
+        let Some(digits) = s.strip_prefix(prefix) else {
+            continue;
+        };
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return None;
+        }
+        return Some(JsonValue::Number(
+            match u64::from_str_radix(digits, radix) {
+                Ok(n) => n.into(),
+                Err(_) => Number::F64(digits.chars().fold(0.0, |n, c| {
+                    n * radix as f64 + c.to_digit(radix).unwrap_or_default() as f64
+                })),
+            },
+        ));
+    }
+    // `[-+]?(\.[0-9]+|[0-9]+(\.[0-9]*)?)([eE][-+]?[0-9]+)?`, spelled again as
+    // JSON to be read as it would be.
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (unsigned, None),
+    };
+    let (int, fraction) = match mantissa.split_once('.') {
+        Some((int, fraction)) => (int, Some(fraction)),
+        None => (mantissa, None),
+    };
+    let exponent_digits = exponent.map(|e| e.strip_prefix(['-', '+']).unwrap_or(e));
+    if !digits(int)
+        || !fraction.is_none_or(digits)
+        || (int.is_empty() && fraction.is_none_or(str::is_empty))
+        || !exponent_digits.is_none_or(|e| !e.is_empty() && digits(e))
+    {
+        return None;
+    }
+    let int = int.trim_start_matches('0');
+    let mut json = format!(
+        "{}{}",
+        if negative { "-" } else { "" },
+        if int.is_empty() { "0" } else { int }
+    );
+    if let Some(fraction) = fraction {
+        json.push('.');
+        json.push_str(if fraction.is_empty() { "0" } else { fraction });
+    }
+    if let Some(exponent) = exponent {
+        json.push('e');
+        json.push_str(exponent);
+    }
+    match crate::parse_value(&json).map(|doc| doc.root().clone()) {
+        Ok(value @ JsonValue::Number(_)) => Some(value),
+        _ => float(json.parse().ok()?),
+    }
+}
+
+#[cfg(feature = "yaml")]
+pub(crate) fn parse(src: &str) -> Result<Document, YamlError> {
+    let src = src.strip_prefix('\u{feff}').unwrap_or(src);
+    let src: Cow<'_, str> = match src.contains('\r') {
+        true => src.replace("\r\n", "\n").into(),
+        false => src.into(),
+    };
+    Parser {
+        src: &src,
+        pos: 0,
+        line: 1,
+        line_start: 0,
+        doc: Document::default(),
+        stack: Vec::new(),
+        root: None,
+    }
+    .document()
+}
+
+// A line and a column, both 1-based.
+#[cfg(feature = "yaml")]
+type Position = (usize, usize);
+
+#[cfg(feature = "yaml")]
+fn fail((line, column): Position, message: &'static str) -> YamlError {
+    YamlError {
+        line,
+        column,
+        message,
+    }
+}
+
+// Whether `rest` starts with the document marker `marker`, `---` or `...`.
+#[cfg(feature = "yaml")]
+fn marker(rest: &str, marker: &str) -> bool {
+    rest.strip_prefix(marker)
+        .is_some_and(|after| after.is_empty() || after.starts_with([' ', '\t', '\n']))
+}
+
+#[cfg(feature = "yaml")]
+fn insert(
+    doc: &mut Document,
+    map: &mut Map<Key, Id<JsonValue>>,
+    (key, at): (String, Position),
+    value: JsonValue,
+) -> Result<(), YamlError> {
+    match map.insert(key.into(), doc.alloc(value)) {
+        Some(_) => Err(fail(at, "a duplicate key")),
+        None => Ok(()),
+    }
+}
+
+// A block collection being read, at the column of its keys or `- `s.
+#[cfg(feature = "yaml")]
+struct Block {
+    indent: usize,
+    kind: Kind,
+}
+
+// Each with the entry waiting for its value, if there is one: the key and
+// where it was, or that a `- ` was read.
+#[cfg(feature = "yaml")]
+enum Kind {
+    Map(Map<Key, Id<JsonValue>>, Option<(String, Position)>),
+    List(Vec<Id<JsonValue>>, bool),
+}
+
+#[cfg(feature = "yaml")]
+impl Block {
+    fn pending(&self) -> bool {
+        match &self.kind {
+            Kind::Map(_, key) => key.is_some(),
+            Kind::List(_, pending) => *pending,
+        }
+    }
+}
+
+// A flow collection being read, with whether an entry was just read, so a
+// comma or the end can come next. A map has the key waiting for its value,
+// and whether the colon after it was read.
+#[cfg(feature = "yaml")]
+enum Flow {
+    List(Vec<Id<JsonValue>>),
+    Map(Map<Key, Id<JsonValue>>, Option<(String, Position)>, bool),
+}
+
+#[cfg(feature = "yaml")]
+struct Parser<'s> {
+    src: &'s str,
+    pos: usize,
+    line: usize,
+    line_start: usize,
+    doc: Document,
+    // The block collections the line is in, outermost first.
+    stack: Vec<Block>,
+    root: Option<JsonValue>,
+}
+
+#[cfg(feature = "yaml")]
+impl<'s> Parser<'s> {
+    fn document(mut self) -> Result<Document, YamlError> {
+        self.skip_blank_lines();
+        if self.rest().starts_with('%') {
+            return Err(fail(self.at(), "directives aren't supported"));
+        }
+        if marker(self.rest(), "---") {
+            self.pos += 3;
+            self.skip_spaces();
+            match self.at_line_end() {
+                true => self.line_end()?,
+                false => self.line_rest()?,
+            }
+        }
+        loop {
+            self.skip_blank_lines();
+            if self.pos == self.src.len() {
+                break;
+            }
+            let indent = self.rest().len() - self.rest().trim_start_matches(' ').len();
+            self.pos += indent;
+            if self.peek() == Some('\t') {
+                return Err(fail(self.at(), "a tab in indentation"));
+            }
+            if indent == 0 && marker(self.rest(), "...") {
+                self.pos += 3;
+                self.line_end()?;
+                self.skip_blank_lines();
+                if self.pos < self.src.len() {
+                    return Err(fail(self.at(), "more than one document"));
+                }
+                break;
+            }
+            if indent == 0 && marker(self.rest(), "---") {
+                return Err(fail(self.at(), "more than one document"));
+            }
+            let item = self.peek() == Some('-') && self.separated(1, false);
+            self.close(indent, item)?;
+            let top = self.stack.last().map(|block| {
+                let map = matches!(block.kind, Kind::Map(..));
+                (block.indent, block.pending(), map)
+            });
+            match top {
+                None if self.root.is_some() => {
+                    return Err(fail(self.at(), "more than one value at the top level"))
+                }
+                Some((block, false, _)) if block < indent => {
+                    return Err(fail(self.at(), "unexpected indentation"))
+                }
+                Some((block, false, true)) if block == indent && item => {
+                    return Err(fail(self.at(), "a list item among an object's members"))
+                }
+                // A list can be the value of a key at the same indentation.
+                Some((block, true, map)) if block == indent && !(map && item) => {
+                    self.deliver(JsonValue::Null, self.at())?
+                }
+                _ => {}
+            }
+            self.line_rest()?;
+        }
+        while !self.stack.is_empty() {
+            self.pop()?;
+        }
+        *self.doc.root_mut() = self.root.unwrap_or_default();
+        Ok(self.doc)
+    }
+
+    fn rest(&self) -> &'s str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn at(&self) -> Position {
+        let column = self.src[self.line_start..self.pos].chars().count() + 1;
+        (self.line, column)
+    }
+
+    // Moves past the next character, counting lines.
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.line_start = self.pos;
+        }
+        Some(c)
+    }
+
+    // Whether what's `offset` bytes on ends a token: whitespace or the end,
+    // or in a flow collection one of its indicators.
+    fn separated(&self, offset: usize, flow: bool) -> bool {
+        match self
+            .rest()
+            .get(offset..)
+            .and_then(|rest| rest.chars().next())
+        {
+            None | Some(' ' | '\t' | '\n') => true,
+            Some(',' | '[' | ']' | '{' | '}') => flow,
+            _ => false,
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.pos += 1;
+        }
+    }
+
+    // Past blank lines and comments, to the start of a line with something
+    // on it.
+    fn skip_blank_lines(&mut self) {
+        loop {
+            let rest = self.rest();
+            let text = rest.trim_start_matches([' ', '\t']);
+            if !text.is_empty() && !text.starts_with(['#', '\n']) {
+                return;
+            }
+            match rest.find('\n') {
+                Some(end) => {
+                    self.pos += end;
+                    self.bump();
+                }
+                None => {
+                    self.pos = self.src.len();
+                    return;
+                }
+            }
+        }
+    }
+
+    // Whether only a comment is left on the line.
+    fn at_line_end(&self) -> bool {
+        let text = self.rest().trim_start_matches([' ', '\t']);
+        text.is_empty() || text.starts_with(['#', '\n'])
+    }
+
+    // Past the end of the line, with only a comment left on it.
+    fn line_end(&mut self) -> Result<(), YamlError> {
+        if !self.at_line_end() {
+            self.skip_spaces();
+            return Err(fail(self.at(), "more on the line after a value"));
+        }
+        while let Some(c) = self.bump() {
+            if c == '\n' {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    // Ends the blocks that a line at `indent` is past, and a list at it
+    // unless the line is another `item`.
+    fn close(&mut self, indent: usize, item: bool) -> Result<(), YamlError> {
+        while let Some(block) = self.stack.last() {
+            let list = matches!(block.kind, Kind::List(..));
+            if block.indent < indent || block.indent == indent && (item || !list) {
+                break;
+            }
+            self.pop()?;
+        }
+        Ok(())
+    }
+
+    // Ends the innermost block, as the value of the entry it's in.
+    fn pop(&mut self) -> Result<(), YamlError> {
+        let at = self.at();
+        if self.stack.last().is_some_and(Block::pending) {
+            self.deliver(JsonValue::Null, at)?;
+        }
+        let value = match self.stack.pop().map(|block| block.kind) {
+            Some(Kind::Map(map, _)) => JsonValue::Object(map),
+            Some(Kind::List(list, _)) => JsonValue::List(list),
+            None => return Ok(()),
+        };
+        self.deliver(value, at)
+    }
+
+    // Gives `value`, read at `at`, to the entry waiting for it.
+    fn deliver(&mut self, value: JsonValue, at: Position) -> Result<(), YamlError> {
+        let Some(block) = self.stack.last_mut() else {
+            self.root = Some(value);
+            return Ok(());
+        };
+        match &mut block.kind {
+            Kind::Map(map, key) => match key.take() {
+                Some(key) => insert(&mut self.doc, map, key, value),
+                None => Err(fail(at, "a value where a key was expected")),
+            },
+            Kind::List(list, pending) => match std::mem::take(pending) {
+                true => {
+                    list.push(self.doc.alloc(value));
+                    Ok(())
+                }
+                false => Err(fail(at, "a value where a `- ` was expected")),
+            },
+        }
+    }
+
+    // Reads what's left of the line from where a node starts: the `- `s
+    // and key of block collections, and then a value, unless that's on the
+    // lines after.
+    fn line_rest(&mut self) -> Result<(), YamlError> {
+        let mut after_key = false;
+        loop {
+            let at = self.at();
+            let column = at.1 - 1;
+            match self.peek() {
+                Some('-') if self.separated(1, false) => {
+                    if after_key {
+                        return Err(fail(at, "a list on the same line as its key"));
+                    }
+                    match self.stack.last_mut() {
+                        Some(Block {
+                            indent,
+                            kind: Kind::List(_, pending @ false),
+                        }) if *indent == column => *pending = true,
+                        _ => self.stack.push(Block {
+                            indent: column,
+                            kind: Kind::List(Vec::new(), true),
+                        }),
+                    }
+                    self.pos += 1;
+                    self.skip_spaces();
+                    if self.at_line_end() {
+                        return self.line_end();
+                    }
+                    continue;
+                }
+                Some('|' | '>') => {
+                    let value = self.block_scalar()?;
+                    return self.deliver(JsonValue::String(value), at);
+                }
+                Some('[' | '{') => {
+                    let value = self.flow()?;
+                    self.deliver(value, at)?;
+                    return self.line_end();
+                }
+                _ => {}
+            }
+            let line = self.line;
+            let (text, plain) = self.scalar(false)?;
+            self.skip_spaces();
+            if self.peek() != Some(':') || !self.separated(1, false) {
+                let value = match plain {
+                    true => resolve(&text).unwrap_or(JsonValue::String(text)),
+                    false => JsonValue::String(text),
+                };
+                self.deliver(value, at)?;
+                return self.line_end();
+            }
+            if after_key {
+                return Err(fail(at, "a key on the same line as another"));
+            }
+            if self.line != line {
+                return Err(fail(at, "a key that spans lines"));
+            }
+            self.pos += 1;
+            match self.stack.last_mut() {
+                Some(Block {
+                    indent,
+                    kind: Kind::Map(_, key @ None),
+                }) if *indent == column => *key = Some((text, at)),
+                _ => self.stack.push(Block {
+                    indent: column,
+                    kind: Kind::Map(Map::new(), Some((text, at))),
+                }),
+            }
+            self.skip_spaces();
+            if self.at_line_end() {
+                return self.line_end();
+            }
+            after_key = true;
+        }
+    }
+
+    // A quoted or plain scalar's text, and whether it was plain.
+    fn scalar(&mut self, flow: bool) -> Result<(String, bool), YamlError> {
+        match self.peek() {
+            Some('"') => Ok((self.double_quoted()?, false)),
+            Some('\'') => Ok((self.single_quoted()?, false)),
+            _ => Ok((self.plain(flow)?, true)),
+        }
+    }
+
+    // A plain scalar, which ends at the line, a comment or a `: `, or in a
+    // flow collection at one of its indicators.
+    fn plain(&mut self, flow: bool) -> Result<String, YamlError> {
+        let message = match self.peek() {
+            Some('&') => "anchors aren't supported",
+            Some('*') => "aliases aren't supported",
+            Some('!') => "tags aren't supported",
+            Some('?') if self.separated(1, flow) => "explicit keys aren't supported",
+            Some(':') if self.separated(1, flow) => "an empty key",
+            Some('-') if self.separated(1, flow) => "a list item inside a flow collection",
+            Some(',' | '[' | ']' | '{' | '}' | '#' | '|' | '>' | '%' | '@' | '`') => {
+                "a character that can't start a plain scalar"
+            }
+            _ => "",
+        };
+        if !message.is_empty() {
+            return Err(fail(self.at(), message));
+        }
+        let rest = self.rest();
+        let mut end = 0;
+        let mut prev = ' ';
+        for (i, c) in rest.char_indices() {
+            let next = rest[i + c.len_utf8()..].chars().next();
+            let stop = match c {
+                '\n' => true,
+                ':' => match next {
+                    None | Some(' ' | '\t' | '\n') => true,
+                    Some(',' | '[' | ']' | '{' | '}') => flow,
+                    _ => false,
+                },
+                '#' => matches!(prev, ' ' | '\t'),
+                ',' | '[' | ']' | '{' | '}' => flow,
+                _ => false,
+            };
+            if stop {
+                break;
+            }
+            end = i + c.len_utf8();
+            prev = c;
+        }
+        let text = rest[..end].trim_end_matches([' ', '\t']);
+        self.pos += text.len();
+        Ok(text.to_owned())
+    }
+
+    fn double_quoted(&mut self) -> Result<String, YamlError> {
+        let start = self.at();
+        self.pos += 1;
+        let mut text = String::new();
+        // How much of `text` came from escapes, which folding keeps.
+        let mut kept = 0;
+        loop {
+            let at = self.at();
+            let c = match self.bump() {
+                None => return Err(fail(start, "an unterminated quoted scalar")),
+                Some('"') => return Ok(text),
+                Some('\n') => {
+                    self.fold(&mut text, kept);
+                    kept = text.len();
+                    continue;
+                }
+                Some('\\') => match self.bump() {
+                    Some('0') => '\0',
+                    Some('a') => '\x07',
+                    Some('b') => '\x08',
+                    Some('t' | '\t') => '\t',
+                    Some('n') => '\n',
+                    Some('v') => '\x0b',
+                    Some('f') => '\x0c',
+                    Some('r') => '\r',
+                    Some('e') => '\x1b',
+                    Some(c @ (' ' | '"' | '/' | '\\')) => c,
+                    Some('N') => '\u{85}',
+                    Some('_') => '\u{a0}',
+                    Some('L') => '\u{2028}',
+                    Some('P') => '\u{2029}',
+                    Some(c @ ('x' | 'u' | 'U')) => self.code_point(c, at)?,
+                    // An escaped line break joins the lines.
+                    Some('\n') => {
+                        self.skip_spaces();
+                        kept = text.len();
+                        continue;
+                    }
+                    _ => return Err(fail(at, "an unknown escape")),
+                },
+                Some(c) => {
+                    text.push(c);
+                    continue;
+                }
+            };
+            text.push(c);
+            kept = text.len();
+        }
+    }
+
+    // The character of a `\x`, `\u` or `\U` escape at `at`.
+    fn code_point(&mut self, kind: char, at: Position) -> Result<char, YamlError> {
+        let width = match kind {
+            'x' => 2,
+            'u' => 4,
+            _ => 8,
+        };
+        let Some(mut n) = self.hex(width) else {
+            return Err(fail(at, "an escape without enough hex digits"));
+        };
+        // A UTF-16 surrogate pair, as JSON spells characters past the BMP.
+        if (0xd800..0xdc00).contains(&n) && self.rest().starts_with("\\u") {
+            let pos = self.pos;
+            self.pos += 2;
+            match self.hex(4) {
+                Some(low @ 0xdc00..=0xdfff) => n = 0x10000 + ((n - 0xd800) << 10) + (low - 0xdc00),
+                _ => self.pos = pos,
+            }
+        }
+        char::from_u32(n).ok_or(fail(at, "an escape that isn't a character"))
+    }
+
+    fn hex(&mut self, width: usize) -> Option<u32> {
+        let digits = self.rest().get(..width)?;
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        self.pos += width;
+        u32::from_str_radix(digits, 16).ok()
+    }
+
+    fn single_quoted(&mut self) -> Result<String, YamlError> {
+        let start = self.at();
+        self.pos += 1;
+        let mut text = String::new();
+        loop {
+            match self.bump() {
+                None => return Err(fail(start, "an unterminated quoted scalar")),
+                Some('\'') if self.peek() == Some('\'') => {
+                    self.pos += 1;
+                    text.push('\'');
+                }
+                Some('\'') => return Ok(text),
+                Some('\n') => self.fold(&mut text, 0),
+                Some(c) => text.push(c),
+            }
+        }
+    }
+
+    // A line break in a quoted scalar, just read. The whitespace around it
+    // goes, past the first `kept` bytes, and it reads as a space, or as a
+    // newline for each blank line after it.
+    fn fold(&mut self, text: &mut String, kept: usize) {
+        let trimmed = text[kept..].trim_end_matches([' ', '\t']).len();
+        text.truncate(kept + trimmed);
+        let mut breaks = 0;
+        loop {
+            self.skip_spaces();
+            if self.peek() != Some('\n') {
+                break;
+            }
+            self.bump();
+            breaks += 1;
+        }
+        match breaks {
+            0 => text.push(' '),
+            _ => text.extend(std::iter::repeat_n('\n', breaks)),
+        }
+    }
+
+    // A `|` literal or `>` folded block scalar, whose lines go on until one
+    // isn't indented past the block it's in.
+    fn block_scalar(&mut self) -> Result<String, YamlError> {
+        let literal = self.bump() == Some('|');
+        let (mut chomp, mut explicit) = (None, None);
+        for _ in 0..2 {
+            match self.peek() {
+                Some(c @ ('+' | '-')) if chomp.is_none() => chomp = Some(c),
+                Some(c @ '1'..='9') if explicit.is_none() => explicit = c.to_digit(10),
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        self.line_end()?;
+        let parent = self.stack.last().map_or(-1, |block| block.indent as isize);
+        // Without an indentation indicator, the first line with something on
+        // it sets the indentation.
+        let indent = match explicit {
+            Some(m) => parent + m as isize,
+            None => self
+                .rest()
+                .split('\n')
+                .find_map(|line| {
+                    let text = line.trim_start_matches(' ');
+                    (!text.is_empty()).then_some((line.len() - text.len()) as isize)
+                })
+                .unwrap_or_default(),
+        };
+        let mut lines = Vec::new();
+        while self.pos < self.src.len() {
+            let rest = self.rest();
+            let line = &rest[..rest.find('\n').unwrap_or(rest.len())];
+            let text = line.trim_start_matches(' ');
+            let spaces = (line.len() - text.len()) as isize;
+            let document = indent == 0 && (marker(line, "---") || marker(line, "..."));
+            if !text.is_empty() && (indent <= parent || spaces < indent || document) {
+                break;
+            }
+            lines.push(line.get(indent as usize..).unwrap_or_default());
+            self.pos += line.len();
+            self.bump();
+        }
+        let end = lines
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .map_or(0, |i| i + 1);
+        let trailing = lines.len() - end;
+        let lines = &lines[..end];
+        let mut text = String::new();
+        if literal {
+            text = lines.join("\n");
+        } else {
+            // Lines fold into spaces, except around blank lines and lines
+            // indented further.
+            let more = |line: &str| line.starts_with([' ', '\t']);
+            let (mut prev, mut blank) = (None, 0);
+            for &line in lines {
+                if line.is_empty() {
+                    blank += 1;
+                    continue;
+                }
+                match prev {
+                    Some(prev) if !more(prev) && !more(line) && blank == 0 => text.push(' '),
+                    Some(prev) if !more(prev) && !more(line) => {
+                        text.extend(std::iter::repeat_n('\n', blank))
+                    }
+                    Some(_) => text.extend(std::iter::repeat_n('\n', blank + 1)),
+                    None => text.extend(std::iter::repeat_n('\n', blank)),
+                }
+                text.push_str(line);
+                (prev, blank) = (Some(line), 0);
+            }
+        }
+        match chomp {
+            Some('-') => {}
+            Some('+') if lines.is_empty() => text.extend(std::iter::repeat_n('\n', trailing)),
+            Some('+') => text.extend(std::iter::repeat_n('\n', trailing + 1)),
+            _ if !lines.is_empty() => text.push('\n'),
+            _ => {}
+        }
+        Ok(text)
+    }
+
+    // A flow collection, `[...]` or `{...}`, which can span lines.
+    fn flow(&mut self) -> Result<JsonValue, YamlError> {
+        // Each with whether an entry was just read, so a comma or the end
+        // can come next.
+        let mut stack: Vec<(Flow, bool)> = Vec::new();
+        loop {
+            self.skip_flow_space();
+            let at = self.at();
+            let value = match self.peek() {
+                None => return Err(fail(at, "an unterminated flow collection")),
+                Some(c @ ('[' | '{')) => {
+                    match stack.last() {
+                        Some((Flow::List(_), true)) => return Err(fail(at, "a missing comma")),
+                        Some((Flow::Map(_, None, _), _)) => {
+                            return Err(fail(at, "a collection as a key"))
+                        }
+                        Some((Flow::Map(_, Some(_), false), _)) => {
+                            return Err(fail(at, "a missing colon"))
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                    stack.push(match c {
+                        '[' => (Flow::List(Vec::new()), false),
+                        _ => (Flow::Map(Map::new(), None, false), false),
+                    });
+                    continue;
+                }
+                Some(c @ (']' | '}')) => {
+                    let value = match stack.pop() {
+                        Some((Flow::List(list), _)) if c == ']' => JsonValue::List(list),
+                        Some((Flow::Map(mut map, key, _), _)) if c == '}' => {
+                            if let Some(key) = key {
+                                insert(&mut self.doc, &mut map, key, JsonValue::Null)?;
+                            }
+                            JsonValue::Object(map)
+                        }
+                        _ => return Err(fail(at, "a mismatched bracket")),
+                    };
+                    self.pos += 1;
+                    value
+                }
+                Some(',') => {
+                    let Some((flow, ready @ true)) = stack.last_mut() else {
+                        return Err(fail(at, "a comma without an entry before it"));
+                    };
+                    if let Flow::Map(map, key, colon) = flow {
+                        if let Some(key) = key.take() {
+                            insert(&mut self.doc, map, key, JsonValue::Null)?;
+                        }
+                        *colon = false;
+                    }
+                    *ready = false;
+                    self.pos += 1;
+                    continue;
+                }
+                // After a quoted key, the colon doesn't need a space.
+                Some(':')
+                    if self.separated(1, true)
+                        || matches!(stack.last(), Some((Flow::Map(_, Some(_), false), _))) =>
+                {
+                    let Some((Flow::Map(_, Some(_), colon @ false), _)) = stack.last_mut() else {
+                        return Err(fail(at, "an unexpected colon"));
+                    };
+                    *colon = true;
+                    self.pos += 1;
+                    continue;
+                }
+                Some(_) => {
+                    let (text, plain) = self.scalar(true)?;
+                    if let Some((Flow::Map(_, key @ None, _), ready)) = stack.last_mut() {
+                        if *ready {
+                            return Err(fail(at, "a missing comma"));
+                        }
+                        *key = Some((text, at));
+                        *ready = true;
+                        continue;
+                    }
+                    match plain {
+                        true => resolve(&text).unwrap_or(JsonValue::String(text)),
+                        false => JsonValue::String(text),
+                    }
+                }
+            };
+            match stack.last_mut() {
+                None => return Ok(value),
+                Some((Flow::List(list), ready)) => {
+                    if *ready {
+                        return Err(fail(at, "a missing comma"));
+                    }
+                    list.push(self.doc.alloc(value));
+                    *ready = true;
+                }
+                Some((Flow::Map(map, key, colon), ready)) => {
+                    let Some(key) = key.take().filter(|_| *colon) else {
+                        return Err(fail(at, "a missing colon"));
+                    };
+                    insert(&mut self.doc, map, key, value)?;
+                    *colon = false;
+                    *ready = true;
+                }
+            }
+        }
+    }
+
+    // Past whitespace, line breaks and comments in a flow collection.
+    fn skip_flow_space(&mut self) {
+        loop {
+            match self.peek() {
+                Some(' ' | '\t' | '\n') => {
+                    self.bump();
+                }
+                Some('#') => {
+                    while !matches!(self.peek(), None | Some('\n')) {
+                        self.bump();
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use jsonparser::{parse_value, Document, ErrorKind, ParseError};
+
+#[test]
+fn typed_accessors() {
+    let doc = parse_value(
+        r#"{"name": "ann", "age": 41, "ratio": 0.5, "ok": true, "tags": ["a"], "gone": null}"#,
+    )
+    .unwrap();
+    let root = doc.value();
+    assert_eq!(root.get("name").and_then(|v| v.as_str()), Some("ann"));
+    assert_eq!(root.get("age").and_then(|v| v.as_i64()), Some(41));
+    assert_eq!(root.get("age").and_then(|v| v.as_f64()), Some(41.0));
+    assert_eq!(root.get("ratio").and_then(|v| v.as_i64()), None);
+    assert_eq!(root.get("ok").and_then(|v| v.as_bool()), Some(true));
+    assert!(root.get("gone").unwrap().is_null());
+    assert_eq!(root.as_object().map(|obj| obj.len()), Some(6));
+    assert!(root.as_array().is_none());
+    assert!(root.get("missing").is_none());
+}
+
+#[test]
+fn handles_resolve_children() {
+    let doc = parse_value(r#"{"users": [{"name": "ann"}, {"name": "bo"}]}"#).unwrap();
+    let users = doc.value().get("users").unwrap();
+    assert_eq!(users.as_array().unwrap().len(), 2);
+    let name = users.get_index(1).and_then(|u| u.get("name"));
+    assert_eq!(name.and_then(|n| n.as_str()), Some("bo"));
+    assert!(users.get_index(2).is_none());
+    assert!(users.get("name").is_none());
+}
+
+#[test]
+fn indexing() {
+    let doc = parse_value(r#"{"users": [{"name": "ann"}], "n": 1}"#).unwrap();
+    assert_eq!(doc["n"].as_i64(), Some(1));
+    assert!(doc["missing"].is_null());
+    assert!(doc[0].is_null());
+    let users = doc.value().at("users");
+    assert_eq!(users[0].as_object().map(|obj| obj.len()), Some(1));
+    assert_eq!(users.at(0).at("name").as_str(), Some("ann"));
+    assert!(doc.value().at("users").at(3).at("name").is_null());
+    assert!(doc.value().at("n").at("deeper")[0].is_null());
+}
+
+#[test]
+fn from_str_and_try_from() {
+    let doc: Document = r#"{"a": [1]}"#.parse().unwrap();
+    assert_eq!(doc.value().pointer("/a/0").unwrap().as_i64(), Some(1));
+    let err = "[1,".parse::<Document>().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Eof);
+
+    let doc = Document::try_from("\"x\"").unwrap();
+    assert_eq!(doc.value().as_str(), Some("x"));
+    let err = Document::try_from("[1] 2").unwrap_err();
+    assert!(matches!(err, ParseError::TrailingData { .. }), "{err:?}");
+
+    let doc = Document::try_from("[true, \"é\"]".as_bytes()).unwrap();
+    assert_eq!(doc.value().get_index(1).unwrap().as_str(), Some("é"));
+    let err = Document::try_from(&b"[\"\xc3\x28\"]"[..]).unwrap_err();
+    assert!(matches!(err, ParseError::InvalidUtf8 { .. }), "{err:?}");
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "async")]
+
+use std::{
+    future::Future,
+    io,
+    pin::{pin, Pin},
+    task::{Context, Poll, Waker},
+};
+
+use jsonparser::{
+    parse_async, parse_async_with, parse_lines_async, AsyncRead, JsonValue, ParseError,
+    ParseOptions,
+};
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+// Hands out a few bytes at a time, not ready every other call.
+struct Trickle<'a> {
+    src: &'a [u8],
+    ready: bool,
+}
+
+impl AsyncRead for Trickle<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.ready = !self.ready;
+        if !self.ready {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        let n = buf.len().min(self.src.len()).min(3);
+        buf[..n].copy_from_slice(&self.src[..n]);
+        self.src = &self.src[n..];
+        Poll::Ready(Ok(n))
+    }
+}
+
+fn trickle(src: &str) -> Trickle<'_> {
+    Trickle {
+        src: src.as_bytes(),
+        ready: false,
+    }
+}
+
+#[test]
+fn whole_body() {
+    let doc = block_on(parse_async(trickle("{\"items\": [1, 2, 3]}"))).unwrap();
+    assert!(matches!(doc.root(), JsonValue::Object(obj) if obj.len() == 1));
+    assert!(block_on(parse_async(&b"[1,"[..])).is_err());
+}
+
+#[test]
+fn body_limit() {
+    let opts = ParseOptions {
+        max_document_size: Some(4),
+        ..ParseOptions::default()
+    };
+    assert!(matches!(
+        block_on(parse_async_with(trickle("[1, 2, 3]"), opts)),
+        Err(ParseError::DocumentTooLarge { .. })
+    ));
+}
+
+#[test]
+fn lines() {
+    let mut lines = parse_lines_async(trickle("1\n\n[2,\n\"three\""));
+    block_on(async {
+        assert!(lines.next().await.unwrap().is_ok());
+        let err = lines.next().await.unwrap().unwrap_err();
+        assert_eq!(err.span().line, 3);
+        let last = lines.next().await.unwrap().unwrap();
+        assert!(matches!(last.root(), JsonValue::String(s) if s == "three"));
+        assert!(lines.next().await.is_none());
+    });
+}
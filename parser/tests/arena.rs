@@ -0,0 +1,177 @@
+use jsonparser::{
+    parse, parse_value, parse_value_with, Allocator, Document, ErrorKind, Id, JsonValue,
+    ParseError, ParseOptions, Parser, Storage,
+};
+
+#[test]
+fn arena_grows_past_initial_capacity() {
+    let src = format!("[{}]", vec!["1"; 1000].join(","));
+    let docs = parse(&src, 1).unwrap();
+    assert_eq!(docs[0].arena().len(), 1000);
+}
+
+#[test]
+fn max_nodes() {
+    let opts = ParseOptions {
+        max_nodes: Some(3),
+        ..ParseOptions::default()
+    };
+    assert!(parse_value_with("[1, 2, 3]", opts).is_ok());
+    let err = parse_value_with("[1, 2, 3, 4]", opts).unwrap_err();
+    assert!(matches!(err, ParseError::TooManyNodes { max: 3, .. }));
+    assert_eq!(err.kind(), ErrorKind::Limit);
+}
+
+#[test]
+fn parser_reuses_the_arena() {
+    let opts = ParseOptions {
+        max_nodes: Some(3),
+        ..ParseOptions::default()
+    };
+    let mut parser = Parser::new(opts);
+    let mut doc = Document::default();
+    for src in [r#"{"id": 1, "tags": ["a"]}"#, r#"{"id": 2}"#, "[1, 2, 3]"] {
+        parser.parse_into(src, &mut doc).unwrap();
+        let fresh = parse_value(src).unwrap();
+        assert_eq!(
+            doc.value().to_canonical_string().unwrap(),
+            fresh.value().to_canonical_string().unwrap()
+        );
+        // Only this parse's values count against `max_nodes`.
+        assert_eq!(doc.arena().len(), fresh.arena().len());
+    }
+    let err = parser.parse_into("[1, 2", &mut doc).unwrap_err();
+    assert!(matches!(err, ParseError::UnexpectedEof { .. }), "{err:?}");
+    assert!(doc.root().is_null());
+    assert!(doc.arena().is_empty());
+    let first = parser.parse(r#"{"id": 3}"#).unwrap();
+    let second = parser.parse(r#"{"id": 4}"#).unwrap();
+    let key = |doc: &Document| {
+        let (key, _) = doc.root().as_object().unwrap().iter().next().unwrap();
+        key.as_ptr()
+    };
+    // Keys seen by an earlier parse share its allocation.
+    assert_eq!(key(&first), key(&second));
+}
+
+// Fixed-size chunks, so values never move once pushed.
+#[derive(Default)]
+struct Chunks(Vec<Vec<JsonValue>>);
+
+impl Storage<JsonValue> for Chunks {
+    fn push(&mut self, value: JsonValue) {
+        match self.0.last_mut() {
+            Some(chunk) if chunk.len() < 4 => chunk.push(value),
+            _ => self.0.push(vec![value]),
+        }
+    }
+
+    fn get(&self, index: usize) -> Option<&JsonValue> {
+        self.0.get(index / 4)?.get(index % 4)
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut JsonValue> {
+        self.0.get_mut(index / 4)?.get_mut(index % 4)
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(Vec::len).sum()
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+#[test]
+fn custom_storage() {
+    let mut parser = Parser::new(ParseOptions::default());
+    let mut mem = Allocator::with_storage(Chunks::default());
+    let root = parser
+        .parse_in(r#"{"a": [1, 2, 3, 4, 5, 6], "b": "x"}"#, &mut mem)
+        .unwrap();
+    assert_eq!(mem.len(), 8);
+    assert_eq!(mem.storage().0.len(), 2);
+    let a = mem
+        .fetch(root.as_object().unwrap()["a"])
+        .as_array()
+        .unwrap();
+    let numbers: Vec<_> = a
+        .iter()
+        .map(|&id| mem.fetch(id).as_i64().unwrap())
+        .collect();
+    assert_eq!(numbers, [1, 2, 3, 4, 5, 6]);
+    assert!(parser.parse_in("[1, ", &mut mem).is_err());
+    assert!(mem.is_empty());
+}
+
+#[test]
+fn id_size() {
+    use std::mem::size_of;
+    assert_eq!(
+        size_of::<Option<Id<JsonValue>>>(),
+        size_of::<Id<JsonValue>>()
+    );
+    // Debug builds add a tag to catch stale ids.
+    #[cfg(all(feature = "id32", not(debug_assertions)))]
+    assert_eq!(size_of::<Id<JsonValue>>(), 4);
+    assert_eq!(format!("{:?}", Id::<JsonValue>::new(3)), "Id(3)");
+}
+
+#[test]
+fn untagged_ids_skip_the_check() {
+    let mut a = Allocator::make(2);
+    let mut b = Allocator::make(2);
+    a.alloc(JsonValue::Bool(true));
+    b.alloc(JsonValue::Null);
+    assert_eq!(a.fetch(Id::new(0)).as_bool(), Some(true));
+    assert!(b.fetch(Id::new(0)).is_null());
+    // Even once the value it was made for is gone.
+    a.reset();
+    a.alloc(JsonValue::from(1));
+    assert_eq!(a.fetch_mut(Id::new(0)).as_i64(), Some(1));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn stale_ids_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut a = Allocator::make(2);
+    let mut b = Allocator::make(2);
+    let first = a.alloc(JsonValue::Bool(true));
+    b.alloc(JsonValue::Null);
+    let err = catch_unwind(|| b.fetch(first).clone()).unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert_eq!(
+        message,
+        "id from another arena, or from before the arena was reset"
+    );
+    // A clone holds the same ids.
+    assert_eq!(a.clone().fetch(first).as_bool(), Some(true));
+    a.reset();
+    a.alloc(JsonValue::Null);
+    assert!(catch_unwind(AssertUnwindSafe(|| a.fetch(first).clone())).is_err());
+
+    let mut parser = Parser::default();
+    let mut doc = parser.parse(r#"{"a": 1}"#).unwrap();
+    let id = doc.root().as_object().unwrap()["a"];
+    parser.parse_into(r#"{"b": 2}"#, &mut doc).unwrap();
+    assert!(catch_unwind(|| doc.get(id).clone()).is_err());
+}
+
+#[test]
+fn missing_ids_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut a = Allocator::make(2);
+    a.alloc(JsonValue::Null);
+    for err in [
+        catch_unwind(|| a.fetch(Id::new(1)).clone()).unwrap_err(),
+        catch_unwind(AssertUnwindSafe(|| a.clone().fetch_mut(Id::new(1)).clone())).unwrap_err(),
+    ] {
+        let message = err.downcast_ref::<&str>().copied();
+        let message = message.or(err.downcast_ref::<String>().map(String::as_str));
+        assert_eq!(message, Some("id past the end of the arena"));
+    }
+}
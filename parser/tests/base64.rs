@@ -0,0 +1,62 @@
+#![cfg(feature = "base64")]
+
+use jsonparser::JsonValue;
+
+fn bytes(s: &str) -> Option<Vec<u8>> {
+    JsonValue::String(s.to_owned()).as_base64_bytes()
+}
+
+// Vectors from RFC 4648, section 10.
+#[test]
+fn base64() {
+    for (text, expected) in [
+        ("", ""),
+        ("Zg==", "f"),
+        ("Zm8=", "fo"),
+        ("Zm9v", "foo"),
+        ("Zm9vYg==", "foob"),
+        ("Zm9vYmE=", "fooba"),
+        ("Zm9vYmFy", "foobar"),
+    ] {
+        assert_eq!(
+            JsonValue::from_bytes_base64(expected.as_bytes()).as_str(),
+            Some(text)
+        );
+        assert_eq!(bytes(text).as_deref(), Some(expected.as_bytes()));
+        assert_eq!(
+            bytes(text.trim_end_matches('=')).as_deref(),
+            Some(expected.as_bytes())
+        );
+    }
+    assert_eq!(bytes("+/8A"), Some(vec![0xfb, 0xff, 0x00]));
+    assert_eq!(bytes("-_8A"), Some(vec![0xfb, 0xff, 0x00]));
+    let all: Vec<u8> = (0..=255).collect();
+    assert_eq!(
+        JsonValue::from_bytes_base64(&all).as_base64_bytes(),
+        Some(all)
+    );
+}
+
+#[test]
+fn not_base64() {
+    for text in [
+        "Z", "Zg=", "Zg===", "Zm9v=", "Zh==", "Zm9=", "Zm 9v", "Zm9v\n", "Z=g=", "é",
+    ] {
+        assert_eq!(bytes(text), None, "{text:?}");
+    }
+    assert_eq!(JsonValue::Null.as_base64_bytes(), None);
+    assert_eq!(JsonValue::Bool(true).as_hex_bytes(), None);
+}
+
+#[test]
+fn hex() {
+    let value = JsonValue::from_bytes_hex(&[0x00, 0x1f, 0xab, 0xff]);
+    assert_eq!(value.as_str(), Some("001fabff"));
+    assert_eq!(value.as_hex_bytes(), Some(vec![0x00, 0x1f, 0xab, 0xff]));
+    let hex = |s: &str| JsonValue::String(s.to_owned()).as_hex_bytes();
+    assert_eq!(hex("DEADbeef"), Some(vec![0xde, 0xad, 0xbe, 0xef]));
+    assert_eq!(hex(""), Some(Vec::new()));
+    assert_eq!(hex("abc"), None);
+    assert_eq!(hex("zz"), None);
+    assert_eq!(hex("+1"), None);
+}
@@ -0,0 +1,99 @@
+use std::borrow::Cow;
+
+use jsonparser::{
+    parse_borrowed, parse_borrowed_with, parse_value, BorrowedValue, Document, DuplicateKeys,
+    ParseError, ParseOptions,
+};
+
+fn borrowed(value: &BorrowedValue<'_>) -> bool {
+    matches!(value, BorrowedValue::String(Cow::Borrowed(_)))
+}
+
+#[test]
+fn strings_without_escapes_borrow() {
+    let src = r#"{"name": "plain", "quote": "say \"hi\"", "list": ["é", "\u00e9", ""]}"#;
+    let value = parse_borrowed(src).unwrap();
+    let name = value.get("name").unwrap();
+    assert_eq!(name.as_str(), Some("plain"));
+    assert!(borrowed(name));
+    let s = name.as_str().unwrap();
+    assert!(src.as_bytes().as_ptr_range().contains(&s.as_ptr()));
+    let quote = value.get("quote").unwrap();
+    assert_eq!(quote.as_str(), Some(r#"say "hi""#));
+    assert!(!borrowed(quote));
+    let BorrowedValue::List(list) = value.get("list").unwrap() else {
+        panic!("expected a list");
+    };
+    assert_eq!(
+        list.iter()
+            .map(|v| (v.as_str().unwrap(), borrowed(v)))
+            .collect::<Vec<_>>(),
+        [("é", true), ("é", false), ("", true)]
+    );
+    let BorrowedValue::Object(obj) = &value else {
+        panic!("expected an object");
+    };
+    assert!(obj.keys().all(|key| matches!(key, Cow::Borrowed(_))));
+}
+
+#[test]
+fn same_tree_as_parse_value() {
+    let src = "\u{feff} [1, -2.5e3, true, null, {\"a\\/b\": [{}], \"c\": \"\\n\"}] ";
+    let value = parse_borrowed(src).unwrap();
+    let doc = Document::from_owned(value.into_owned());
+    assert!(doc.deep_eq(&parse_value(src).unwrap()));
+}
+
+#[test]
+#[cfg(feature = "json5")]
+fn json5() {
+    let opts = ParseOptions {
+        json5: true,
+        ..ParseOptions::default()
+    };
+    let value = parse_borrowed_with("{key: 'it\\'s', other: 'x',}", opts).unwrap();
+    assert_eq!(value.get("key").unwrap().as_str(), Some("it's"));
+    assert!(borrowed(value.get("other").unwrap()));
+}
+
+#[test]
+fn options() {
+    let with = |duplicate_keys| ParseOptions {
+        duplicate_keys,
+        ..ParseOptions::default()
+    };
+    let src = r#"{"a": 1, "a": 2}"#;
+    let first = parse_borrowed_with(src, with(DuplicateKeys::FirstWins)).unwrap();
+    assert_eq!(first, parse_borrowed(r#"{"a": 1}"#).unwrap());
+    let last = parse_borrowed(src).unwrap();
+    assert_eq!(last, parse_borrowed(r#"{"a": 2}"#).unwrap());
+    let err = parse_borrowed_with(src, with(DuplicateKeys::Error)).unwrap_err();
+    assert!(
+        matches!(err, ParseError::DuplicateKey { ref key, span } if key == "a" && span.start == 9)
+    );
+
+    let opts = ParseOptions {
+        max_string_length: Some(3),
+        ..ParseOptions::default()
+    };
+    assert!(parse_borrowed_with(r#"["abc", "\u0061bc"]"#, opts).is_ok());
+    for src in [r#"["abcd"]"#, r#"["ab\ncd"]"#] {
+        let err = parse_borrowed_with(src, opts).unwrap_err();
+        assert!(matches!(err, ParseError::StringTooLong { max: 3, span } if span.start == 1));
+    }
+    for src in ["", "[1,]", r#"{"a" 1}"#, "[] []", r#""\x""#] {
+        assert!(parse_borrowed(src).is_err(), "{src:?}");
+    }
+}
+
+#[test]
+fn misplaced_strings_are_named() {
+    for src in [r#"[1 "x"]"#, r#"{"a": 1 "b\n": 2}"#, r#"{"a" "b"}"#] {
+        let expected = parse_value(src).unwrap_err();
+        assert!(
+            matches!(expected, ParseError::UnexpectedToken { .. }),
+            "{src}"
+        );
+        assert_eq!(parse_borrowed(src).unwrap_err(), expected, "{src}");
+    }
+}
@@ -0,0 +1,31 @@
+use jsonparser::{parse_value, Document};
+
+#[test]
+fn builds_into_the_arena() {
+    let doc = Document::object(|o| {
+        o.field("name", "ann")
+            .field("age", 41)
+            .field("email", None::<&str>)
+            .array("tags", |a| a.push("x").push(true).array(|a| a))
+            .object("address", |o| o.field("city", "Oslo"))
+    });
+    let expected = parse_value(
+        r#"{"name": "ann", "age": 41, "email": null, "tags": ["x", true, []],
+            "address": {"city": "Oslo"}}"#,
+    )
+    .unwrap();
+    assert!(doc.deep_eq(&expected));
+    assert_eq!(doc.arena().len(), expected.arena().len());
+}
+
+#[test]
+fn into_an_existing_document() {
+    let mut doc = parse_value(r#"{"items": []}"#).unwrap();
+    let item = doc.object_builder().field("id", 1).build();
+    doc.list_mut("/items").unwrap().push(item);
+    let list = doc.array_builder().push(-2).object(|o| o).build();
+    assert!(doc.set_pointer("/more", list));
+    let expected = parse_value(r#"{"items": [{"id": 1}], "more": [-2, {}]}"#).unwrap();
+    assert!(doc.deep_eq(&expected));
+    assert!(Document::array(|a| a).deep_eq(&parse_value("[]").unwrap()));
+}
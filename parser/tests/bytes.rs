@@ -0,0 +1,110 @@
+use jsonparser::{parse_bytes, parse_reader, Document, ErrorKind, JsonValue, ParseError};
+
+#[test]
+fn utf8_strings() {
+    let doc = parse_bytes("[\"naïve\", \"日本\"]".as_bytes()).unwrap();
+    let strings: Vec<_> = doc
+        .children(doc.root())
+        .map(|v| match v {
+            JsonValue::String(s) => s.clone(),
+            other => panic!("{other:?}"),
+        })
+        .collect();
+    assert_eq!(strings, ["naïve", "日本"]);
+}
+
+#[test]
+fn invalid_utf8_in_string() {
+    let err = parse_bytes(b"{\"key\": \"\xc3\x28\"}").unwrap_err();
+    assert!(matches!(err, ParseError::InvalidUtf8 { span } if span.start == 8));
+    assert_eq!(err.kind(), ErrorKind::Encoding);
+}
+
+#[test]
+fn try_from_bytes() {
+    assert!(Document::try_from(&b"[1, 2]"[..]).is_ok());
+    assert!(Document::try_from(&b"[\xff]"[..]).is_err());
+}
+
+#[test]
+fn utf8_bom_is_skipped() {
+    let doc = parse_bytes(b"\xef\xbb\xbf{\"a\": [1]}").unwrap();
+    assert_eq!(doc.arena().len(), 2);
+    assert!(jsonparser::parse_value("\u{feff}[true]").is_ok());
+    let err = parse_bytes(b"\xef\xbb\xbf[1,]").unwrap_err();
+    assert_eq!(err.span().column, 4);
+}
+
+#[test]
+fn utf16_bom_is_rejected_when_streaming() {
+    for (src, encoding) in [
+        (&b"\xfe\xff\x00[\x00]"[..], "UTF-16BE"),
+        (&b"\xff\xfe[\x00]\x00"[..], "UTF-16LE"),
+    ] {
+        let err = parse_reader(src).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Encoding);
+        assert!(
+            matches!(err, ParseError::UnsupportedEncoding { encoding: e, .. } if e == encoding),
+            "{err}"
+        );
+    }
+}
+
+const SAMPLE: &str = r#"{"a": ["é😀", 1]}"#;
+
+fn utf16(be: bool, bom: bool) -> Vec<u8> {
+    let bom = bom.then_some(0xFEFF);
+    bom.into_iter()
+        .chain(SAMPLE.encode_utf16())
+        .flat_map(|u| if be { u.to_be_bytes() } else { u.to_le_bytes() })
+        .collect()
+}
+
+fn utf32(be: bool, bom: bool) -> Vec<u8> {
+    let bom = bom.then_some(0xFEFF);
+    bom.into_iter()
+        .chain(SAMPLE.chars().map(u32::from))
+        .flat_map(|u| if be { u.to_be_bytes() } else { u.to_le_bytes() })
+        .collect()
+}
+
+#[test]
+fn utf16_and_utf32_are_transcoded() {
+    for be in [true, false] {
+        for bom in [true, false] {
+            for src in [utf16(be, bom), utf32(be, bom)] {
+                let doc = parse_bytes(&src).unwrap_or_else(|e| panic!("{e}: {src:x?}"));
+                let strings: Vec<_> = doc
+                    .descendants()
+                    .filter_map(|v| match v {
+                        JsonValue::String(s) => Some(s.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                assert_eq!(strings, ["é😀"]);
+            }
+        }
+    }
+}
+
+#[test]
+fn malformed_utf16() {
+    let mut src = utf16(false, false);
+    // Replace the low half of the emoji's surrogate pair.
+    let at = src.windows(2).position(|w| w == [0x00, 0xDE]).unwrap();
+    src[at + 1] = 0x00;
+    let err = parse_bytes(&src).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ParseError::InvalidEncoding {
+                encoding: "UTF-16LE",
+                ..
+            }
+        ),
+        "{err}"
+    );
+
+    let err = parse_bytes(&utf16(true, false)[1..]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Encoding);
+}
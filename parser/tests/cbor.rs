@@ -0,0 +1,182 @@
+#![cfg(feature = "cbor")]
+
+use jsonparser::{parse_cbor, parse_value, BinaryError, EqOptions};
+
+fn encode(src: &str) -> Vec<u8> {
+    parse_value(src).unwrap().to_cbor()
+}
+
+// Examples from RFC 8949, Appendix A.
+#[test]
+fn encodings() {
+    for (src, bytes) in [
+        ("0", &[0x00][..]),
+        ("23", &[0x17]),
+        ("24", &[0x18, 0x18]),
+        ("1000", &[0x19, 0x03, 0xe8]),
+        ("1000000", &[0x1a, 0x00, 0x0f, 0x42, 0x40]),
+        (
+            "18446744073709551615",
+            &[0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+        ),
+        ("-1", &[0x20]),
+        ("-1000", &[0x39, 0x03, 0xe7]),
+        (
+            "-9223372036854775808",
+            &[0x3b, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+        ),
+        ("100000.0", &[0xfa, 0x47, 0xc3, 0x50, 0x00]),
+        (
+            "1.1",
+            &[0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a],
+        ),
+        ("false", &[0xf4]),
+        ("true", &[0xf5]),
+        ("null", &[0xf6]),
+        (r#""""#, &[0x60]),
+        (r#""IETF""#, b"\x64IETF"),
+        (r#""ü""#, &[0x62, 0xc3, 0xbc]),
+        ("[]", &[0x80]),
+        (
+            "[1, [2, 3], [4, 5]]",
+            &[0x83, 0x01, 0x82, 0x02, 0x03, 0x82, 0x04, 0x05],
+        ),
+        ("{}", &[0xa0]),
+        (r#"{"a": [2]}"#, &[0xa1, 0x61, 0x61, 0x81, 0x02]),
+    ] {
+        assert_eq!(encode(src), bytes, "{src}");
+    }
+    let list = format!("[{}]", vec!["1"; 25].join(","));
+    assert_eq!(encode(&list)[..2], [0x98, 0x19]);
+}
+
+#[test]
+fn round_trips() {
+    let long = "é".repeat(40_000);
+    let many = vec!["[]"; 70_000].join(",");
+    for src in [
+        r#"{"a": [1, -2, 3.25, {"b": null}], "c": "", "d": {}}"#.to_owned(),
+        "[18446744073709551615, -9223372036854775808, 1e300, 0.1]".to_owned(),
+        format!("[{long:?}, [{many}]]"),
+    ] {
+        let doc = parse_value(&src).unwrap();
+        let back = parse_cbor(&doc.to_cbor()).unwrap();
+        assert!(back.deep_eq_with(
+            &doc,
+            EqOptions {
+                numbers_by_value: true
+            }
+        ));
+    }
+}
+
+fn decode(bytes: &[u8]) -> String {
+    parse_cbor(bytes).unwrap().to_string()
+}
+
+#[test]
+fn decodes() {
+    // Half-precision floats.
+    assert_eq!(decode(&[0xf9, 0x3c, 0x00]), "1.0");
+    assert_eq!(decode(&[0xf9, 0xc4, 0x00]), "-4.0");
+    assert_eq!(decode(&[0xf9, 0x00, 0x01]), "5.960464477539063e-8");
+    assert_eq!(decode(&[0xf9, 0x7c, 0x00]), "null");
+    assert_eq!(decode(&[0xfa, 0x7f, 0xc0, 0x00, 0x00]), "null");
+    // undefined and simple values.
+    assert_eq!(decode(&[0x83, 0xf7, 0xf0, 0xf8, 0xff]), "[null,null,null]");
+    // Below `i64::MIN`.
+    assert_eq!(
+        decode(&[0x3b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+        "-1.8446744073709552e19"
+    );
+    // Tags other than those for byte strings are dropped.
+    assert_eq!(
+        decode(b"\xc0\x74\x32\x30\x31\x33\x2d\x30\x33\x2d\x32\x31\x54\x32\x30\x3a\x30\x34\x3a\x30\x30\x5a"),
+        r#""2013-03-21T20:04:00Z""#
+    );
+    assert_eq!(decode(&[0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0]), "1363896240");
+    // Integer keys become their text.
+    assert_eq!(decode(&[0xa1, 0x20, 0x01]), r#"{"-1":1}"#);
+}
+
+#[test]
+fn byte_strings() {
+    assert_eq!(decode(&[0x43, 0xfb, 0xff, 0x00]), r#""-_8A""#);
+    assert_eq!(decode(&[0xd6, 0x43, 0xfb, 0xff, 0x00]), r#""+/8A""#);
+    assert_eq!(decode(&[0xd6, 0x42, 0xfb, 0xff]), r#""+/8=""#);
+    assert_eq!(
+        decode(&[0xd7, 0x44, 0x01, 0x02, 0x03, 0x04]),
+        r#""01020304""#
+    );
+    // A negative bignum.
+    assert_eq!(decode(&[0xc3, 0x42, 0x01, 0x00]), r#""~AQA""#);
+    // The tag is only for the byte string right after it.
+    assert_eq!(
+        decode(&[0x82, 0xd7, 0x41, 0x01, 0x41, 0x01]),
+        r#"["01","AQ"]"#
+    );
+}
+
+#[test]
+fn indefinite_lengths() {
+    assert_eq!(
+        decode(&[0x5f, 0x42, 0x01, 0x02, 0x43, 0x03, 0x04, 0x05, 0xff]),
+        r#""AQIDBAU""#
+    );
+    assert_eq!(decode(b"\x7f\x65strea\x64ming\xff"), r#""streaming""#);
+    assert_eq!(decode(&[0x9f, 0xff]), "[]");
+    assert_eq!(
+        decode(&[0x9f, 0x01, 0x82, 0x02, 0x03, 0x9f, 0x04, 0x05, 0xff, 0xff]),
+        "[1,[2,3],[4,5]]"
+    );
+    assert_eq!(
+        decode(b"\xbf\x63Fun\xf5\x63Amt\x21\xff"),
+        parse_value(r#"{"Fun": true, "Amt": -2}"#)
+            .unwrap()
+            .to_string()
+    );
+}
+
+#[test]
+fn errors() {
+    for (src, offset, message) in [
+        (&b""[..], 0, "unexpected end of input"),
+        (&[0x82, 0x01], 2, "unexpected end of input"),
+        (&[0x1c], 0, "a reserved additional information value"),
+        (&[0x1f], 0, "a length where there can't be one"),
+        (&[0x62, 0xff, 0xfe], 0, "a text string that isn't UTF-8"),
+        (
+            &[0xa1, 0x80, 0x01],
+            1,
+            "a map key that isn't a string or an integer",
+        ),
+        (&[0x01, 0x02], 1, "more after the value"),
+        (
+            &[0xff],
+            0,
+            "a break outside of an indefinite-length container",
+        ),
+        (
+            &[0x82, 0x01, 0xff],
+            2,
+            "a break outside of an indefinite-length container",
+        ),
+        (&[0xbf, 0x61, 0x61, 0xff], 3, "a map key without a value"),
+        (
+            &[0x5f, 0x61, 0x61, 0xff],
+            1,
+            "a chunk of an indefinite-length string that isn't a string of its type with a length",
+        ),
+        (
+            &[0x9b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            9,
+            "unexpected end of input",
+        ),
+    ] {
+        assert_eq!(
+            parse_cbor(src),
+            Err(BinaryError { offset, message }),
+            "{src:?}"
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use std::cmp::Ordering;
+
+use jsonparser::{parse_value, JsonValue};
+
+fn cmp(a: &str, b: &str) -> Ordering {
+    let (a, b) = (parse_value(a).unwrap(), parse_value(b).unwrap());
+    a.value().cmp_with(b.value())
+}
+
+#[test]
+fn sorts_mixed_lists() {
+    let mut doc =
+        parse_value(r#"[{"a": 1}, [2], "b", 3, true, null, "a", 1.5, false, [1, 2], {}]"#).unwrap();
+    let mut ids = doc.root().as_array().unwrap().to_vec();
+    ids.sort_by(|&a, &b| doc.cmp_values(doc.get(a), doc.get(b)));
+    *doc.root_mut() = JsonValue::List(ids);
+    let sorted =
+        parse_value(r#"[null, false, true, 1.5, 3, "a", "b", [1, 2], [2], {}, {"a": 1}]"#).unwrap();
+    assert!(doc.deep_eq(&sorted));
+}
+
+#[test]
+fn numbers() {
+    assert_eq!(cmp("1", "1.0"), Ordering::Less);
+    assert_eq!(cmp("2", "1.5"), Ordering::Greater);
+    assert_eq!(cmp("-1", "-1.5"), Ordering::Greater);
+    assert_eq!(
+        cmp("9007199254740993", "9007199254740992.0"),
+        Ordering::Greater
+    );
+    assert_eq!(cmp("18446744073709551615", "-1"), Ordering::Greater);
+}
+
+#[test]
+fn containers() {
+    assert_eq!(cmp("[1, 2]", "[1, 2, 0]"), Ordering::Less);
+    assert_eq!(cmp("[1, 3]", "[1, 2, 0]"), Ordering::Greater);
+    assert_eq!(
+        cmp(r#"{"b": 1, "a": 2}"#, r#"{"a": 2, "b": 1}"#),
+        Ordering::Equal
+    );
+    assert_eq!(cmp(r#"{"a": 9}"#, r#"{"b": 0}"#), Ordering::Less);
+    assert_eq!(cmp(r#"{"a": 1}"#, r#"{"a": 2}"#), Ordering::Less);
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use jsonparser::{parse_value, DecodeError, Document, FromJson, JsonValue};
+
+fn decode<T: FromJson>(src: &str) -> Result<T, DecodeError> {
+    parse_value(src).unwrap().decode()
+}
+
+#[test]
+fn primitives() {
+    assert_eq!(decode::<bool>("true"), Ok(true));
+    assert_eq!(decode::<String>(r#""hi""#), Ok("hi".to_owned()));
+    assert_eq!(decode::<u8>("255"), Ok(255));
+    assert_eq!(decode::<i32>("-7"), Ok(-7));
+    assert_eq!(decode::<f64>("2"), Ok(2.0));
+    assert_eq!(decode::<Option<i64>>("null"), Ok(None));
+    assert_eq!(decode::<Option<i64>>("3"), Ok(Some(3)));
+}
+
+#[test]
+fn integers_must_fit() {
+    assert!(matches!(
+        decode::<u8>("256"),
+        Err(DecodeError::Type {
+            expected: "a u8",
+            ..
+        })
+    ));
+    assert!(decode::<u32>("-1").is_err());
+    assert_eq!(decode::<u64>("18446744073709551615"), Ok(u64::MAX));
+}
+
+#[test]
+fn containers() {
+    assert_eq!(decode::<Vec<i64>>("[1, 2, 3]"), Ok(vec![1, 2, 3]));
+    let map = decode::<HashMap<String, Vec<bool>>>(r#"{"a": [true], "b": []}"#).unwrap();
+    assert_eq!(map["a"], [true]);
+    assert!(map["b"].is_empty());
+    assert_eq!(
+        decode::<(String, i64, Option<bool>)>(r#"["x", 1, null]"#),
+        Ok(("x".to_owned(), 1, None))
+    );
+    assert!(matches!(
+        decode::<(i64, i64)>("[1]"),
+        Err(DecodeError::Type {
+            expected: "a list of 2",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn errors_point_at_the_value() {
+    let err = decode::<HashMap<String, Vec<i64>>>(r#"{"a/b": [1, "2"]}"#).unwrap_err();
+    assert_eq!(err.pointer(), "/a~1b/1");
+    assert_eq!(
+        err.to_string(),
+        "Expected an i64, found a string at /a~1b/1."
+    );
+    let err = decode::<bool>("1").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Expected a bool, found a number at the root."
+    );
+}
+
+#[test]
+fn custom_impl() {
+    #[derive(Debug, PartialEq)]
+    struct Point {
+        x: i64,
+        y: i64,
+    }
+
+    impl FromJson for Point {
+        fn from_json(
+            value: &JsonValue,
+            arena: &jsonparser::Allocator<JsonValue>,
+        ) -> Result<Self, DecodeError> {
+            let JsonValue::Object(obj) = value else {
+                return Err(DecodeError::custom("a point is an object"));
+            };
+            let field = |name: &'static str| {
+                let id = obj.get(name).ok_or(DecodeError::MissingField {
+                    field: name,
+                    pointer: String::new(),
+                })?;
+                i64::from_json(arena.fetch(*id), arena).map_err(|e| e.within(name))
+            };
+            Ok(Point {
+                x: field("x")?,
+                y: field("y")?,
+            })
+        }
+    }
+
+    assert_eq!(decode(r#"{"x": 1, "y": 2}"#), Ok(Point { x: 1, y: 2 }));
+    assert_eq!(
+        decode::<Vec<Point>>(r#"[{"x": 1}]"#)
+            .unwrap_err()
+            .to_string(),
+        "Missing field \"y\" at /0."
+    );
+}
+
+#[test]
+fn round_trip() {
+    let mut map = HashMap::new();
+    map.insert("k".to_owned(), vec![Some(1u32), None]);
+    let value = (map, "s", true, -2i8);
+    let doc = Document::encode(&value);
+    let back: (HashMap<String, Vec<Option<u32>>>, String, bool, i8) = doc.decode().unwrap();
+    assert_eq!(back.0, value.0);
+    assert_eq!((back.1.as_str(), back.2, back.3), ("s", true, -2));
+    assert_eq!(
+        Document::encode(&[1.5f64][..]).decode::<Vec<f64>>(),
+        Ok(vec![1.5])
+    );
+    assert!(Document::encode(&None::<bool>).root().is_null());
+    assert_eq!(Document::encode(&u64::MAX).decode::<u64>(), Ok(u64::MAX));
+}
+
+#[test]
+fn from_str() {
+    let pairs: Vec<(String, bool)> = jsonparser::from_str(r#"[["a", true]]"#).unwrap();
+    assert_eq!(pairs, [("a".to_owned(), true)]);
+    assert!(matches!(
+        jsonparser::from_str::<bool>("tru"),
+        Err(jsonparser::FromStrError::Parse(_))
+    ));
+    assert!(matches!(
+        jsonparser::from_str::<bool>("1"),
+        Err(jsonparser::FromStrError::Decode(_))
+    ));
+}
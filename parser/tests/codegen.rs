@@ -0,0 +1,126 @@
+use jsonparser::{codegen, parse_many, CodegenOptions};
+
+const SAMPLE: &str = r#"[
+    {"id": 1, "userName": "a", "type": "x", "tags": ["t"], "address": {"zip": "1"}, "scores": [[1.5]]},
+    {"id": 2, "userName": null, "type": "y", "tags": [], "extra": [1, "a"]}
+]"#;
+
+const GENERATED: &str = r#"pub type Users = Vec<User>;
+
+#[derive(Debug, Clone, PartialEq, jsonparser::FromJson, jsonparser::ToJson)]
+pub struct User {
+    pub address: Option<Address>,
+    pub extra: Option<Vec<jsonparser::OwnedValue>>,
+    pub id: i64,
+    pub scores: Option<Vec<Vec<f64>>>,
+    pub tags: Vec<String>,
+    #[json(rename = "type")]
+    pub r#type: String,
+    #[json(rename = "userName")]
+    pub user_name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, jsonparser::FromJson, jsonparser::ToJson)]
+pub struct Address {
+    pub zip: String,
+}
+"#;
+
+fn generate(src: &str, opts: &CodegenOptions) -> String {
+    codegen(&parse_many(src).unwrap(), opts)
+}
+
+#[test]
+fn structs() {
+    let opts = CodegenOptions {
+        root_name: "users".into(),
+        ..CodegenOptions::default()
+    };
+    assert_eq!(generate(SAMPLE, &opts), GENERATED);
+}
+
+#[test]
+fn names() {
+    let out = generate(
+        r#"{"HTTPServer": {"a": 1}, "self": true, "2fa": "x", "a-b": 1, "a_b": 2, "root": {}}"#,
+        &CodegenOptions::default(),
+    );
+    for line in [
+        "pub struct Root {",
+        "    #[json(rename = \"2fa\")]\n    pub _2fa: String,",
+        "    #[json(rename = \"a-b\")]\n    pub a_b: i64,",
+        "    pub a_b_2: i64,",
+        "    #[json(rename = \"HTTPServer\")]\n    pub http_server: HttpServer,",
+        "    pub root: Root2,",
+        "    #[json(rename = \"self\")]\n    pub self_: bool,",
+        "pub struct Root2 {\n}",
+    ] {
+        assert!(out.contains(line), "{line} not in\n{out}");
+    }
+}
+
+#[test]
+fn serde() {
+    let opts = CodegenOptions {
+        serde: true,
+        ..CodegenOptions::default()
+    };
+    assert_eq!(
+        generate(r#"{"a-b": null} {"a-b": [1, 2.5]}"#, &opts),
+        concat!(
+            "#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]\n",
+            "pub struct Root {\n",
+            "    #[serde(rename = \"a-b\")]\n",
+            "    pub a_b: Option<Vec<f64>>,\n",
+            "}\n"
+        )
+    );
+    assert_eq!(
+        generate("[1, null]", &opts),
+        "pub type Root = Vec<Option<i64>>;\n"
+    );
+    assert_eq!(
+        generate("[]", &opts),
+        "pub type Root = Vec<serde_json::Value>;\n"
+    );
+}
+
+// `GENERATED`, compiled.
+#[cfg(feature = "derive")]
+mod generated {
+    use jsonparser::{parse_value, Document, OwnedValue};
+
+    pub type Users = Vec<User>;
+
+    #[derive(Debug, Clone, PartialEq, jsonparser::FromJson, jsonparser::ToJson)]
+    pub struct User {
+        pub address: Option<Address>,
+        pub extra: Option<Vec<jsonparser::OwnedValue>>,
+        pub id: i64,
+        pub scores: Option<Vec<Vec<f64>>>,
+        pub tags: Vec<String>,
+        #[json(rename = "type")]
+        pub r#type: String,
+        #[json(rename = "userName")]
+        pub user_name: Option<String>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, jsonparser::FromJson, jsonparser::ToJson)]
+    pub struct Address {
+        pub zip: String,
+    }
+
+    #[test]
+    fn decodes_the_sample() {
+        let users: Users = parse_value(super::SAMPLE).unwrap().decode().unwrap();
+        assert_eq!(users[0].r#type, "x");
+        assert_eq!(users[0].address.as_ref().unwrap().zip, "1");
+        assert_eq!(users[1].user_name, None);
+        assert_eq!(
+            users[1].extra.as_ref().unwrap()[1],
+            OwnedValue::String("a".into())
+        );
+        let again: Users = Document::encode(&users).decode().unwrap();
+        assert_eq!(again, users);
+    }
+}
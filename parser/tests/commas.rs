@@ -0,0 +1,34 @@
+use jsonparser::{parse_value, parse_value_with, ParseError, ParseOptions};
+
+fn lenient() -> ParseOptions {
+    ParseOptions {
+        allow_trailing_commas: true,
+        ..ParseOptions::default()
+    }
+}
+
+#[test]
+fn trailing_commas() {
+    let doc = parse_value_with(r#"{"a": [1, 2, 3,], "b": {"c": 1,},}"#, lenient()).unwrap();
+    assert_eq!(doc.arena().len(), 6);
+    assert!(parse_value("[1, 2, 3,]").is_err());
+}
+
+#[test]
+fn still_needs_a_value() {
+    for src in ["[,]", "{,}", "[1,,]", r#"{"a": 1,,}"#] {
+        assert!(parse_value_with(src, lenient()).is_err(), "{src}");
+    }
+}
+
+#[test]
+fn strict_rejects() {
+    let opts = ParseOptions {
+        strict: true,
+        ..lenient()
+    };
+    assert!(matches!(
+        parse_value_with("[1,]", opts),
+        Err(ParseError::TrailingComma { .. })
+    ));
+}
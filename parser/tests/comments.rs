@@ -0,0 +1,45 @@
+use jsonparser::{parse_value, parse_value_with, ErrorKind, ParseError, ParseOptions};
+
+fn jsonc() -> ParseOptions {
+    ParseOptions {
+        allow_comments: true,
+        ..ParseOptions::default()
+    }
+}
+
+#[test]
+fn line_and_block_comments() {
+    let src = r#"
+        // compiler settings
+        {
+            "target": "es2020", /* keep in sync with CI */
+            "strict": true // trailing
+            /* multi
+               line */
+        }
+        // done"#;
+    let doc = parse_value_with(src, jsonc()).unwrap();
+    assert_eq!(doc.arena().len(), 2);
+    assert!(parse_value(src).is_err());
+}
+
+#[test]
+fn comment_markers_inside_strings() {
+    let doc = parse_value_with(r#"["http://x", "/* no */"] // real"#, jsonc()).unwrap();
+    assert_eq!(doc.arena().len(), 2);
+}
+
+#[test]
+fn unterminated_block_comment() {
+    let err = parse_value_with("[1, /* oops\n 2]", jsonc()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Eof);
+    assert!(matches!(
+        err,
+        ParseError::UnterminatedComment { span } if span.start == 4 && span.end == 6
+    ));
+}
+
+#[test]
+fn lone_slash() {
+    assert!(parse_value_with("[1, / 2]", jsonc()).is_err());
+}
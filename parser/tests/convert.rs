@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use jsonparser::{JsonValue, Number, OwnedValue, TypeError};
+
+#[test]
+fn build_values() {
+    assert_eq!(OwnedValue::from("a"), OwnedValue::String("a".into()));
+    assert_eq!(OwnedValue::from(1.5), OwnedValue::Number(Number::F64(1.5)));
+    assert_eq!(OwnedValue::from(None::<i64>), OwnedValue::Null);
+    assert_eq!(
+        OwnedValue::from(vec![Some(1), None]),
+        OwnedValue::List(vec![OwnedValue::Number(Number::I64(1)), OwnedValue::Null])
+    );
+    let map = HashMap::from([("k".to_string(), vec![true])]);
+    let OwnedValue::Object(obj) = OwnedValue::from(map) else {
+        panic!("expected an object");
+    };
+    assert_eq!(obj["k"], OwnedValue::List(vec![OwnedValue::Bool(true)]));
+    assert!(matches!(JsonValue::from(false), JsonValue::Bool(false)));
+    assert!(matches!(JsonValue::from(String::from("s")), JsonValue::String(s) if s == "s"));
+}
+
+#[test]
+fn back_to_rust() {
+    assert_eq!(String::try_from(OwnedValue::from("a")), Ok("a".to_string()));
+    assert_eq!(i64::try_from(JsonValue::from(7_i64)), Ok(7));
+    assert_eq!(u64::try_from(JsonValue::from(-7_i64)).ok(), None);
+    assert_eq!(f64::try_from(OwnedValue::from(7_i64)), Ok(7.0));
+    assert_eq!(
+        Vec::<i64>::try_from(OwnedValue::from(vec![1_i64, 2])),
+        Ok(vec![1, 2])
+    );
+    let map: HashMap<String, bool> =
+        HashMap::try_from(OwnedValue::from(HashMap::from([("x".to_string(), true)]))).unwrap();
+    assert!(map["x"]);
+}
+
+#[test]
+fn type_errors() {
+    let err = bool::try_from(OwnedValue::from("yes")).unwrap_err();
+    assert_eq!(
+        err,
+        TypeError {
+            expected: "a bool",
+            found: "a string"
+        }
+    );
+    assert_eq!(err.to_string(), "Expected a bool, found a string.");
+    assert!(Vec::<String>::try_from(OwnedValue::from(vec![1_i64])).is_err());
+    assert!(i64::try_from(JsonValue::from(1.5)).is_err());
+}
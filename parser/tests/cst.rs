@@ -0,0 +1,204 @@
+use std::rc::Rc;
+
+use jsonparser::{
+    parse_cst, parse_cst_with, CstKind, GreenElement, GreenNode, GreenToken, ParseError,
+    ParseOptions, SyntaxElement, SyntaxNode,
+};
+
+fn lenient() -> ParseOptions {
+    ParseOptions {
+        allow_comments: true,
+        allow_trailing_commas: true,
+        ..ParseOptions::default()
+    }
+}
+
+fn kinds(node: &SyntaxNode) -> Vec<CstKind> {
+    node.children().map(|child| child.kind()).collect()
+}
+
+#[test]
+fn lossless() {
+    let sources = [
+        "  {\"a\" : [1.50, -2E3, \"\\u0041\"] ,\n\t\"b\":{} }\n",
+        "// head\n{ /* one */ \"k\": 1, // tail\n \"l\": [2,],\n}",
+        "\u{feff} null ",
+        "[]",
+    ];
+    for src in sources {
+        let cst = parse_cst_with(src, lenient()).unwrap();
+        assert_eq!(cst.to_string(), src);
+        assert_eq!(cst.root().text_range(), 0..src.len());
+    }
+}
+
+#[test]
+fn structure() {
+    let src = "// c\n{\"a\" : [1, true], \"b\": null}";
+    let cst = parse_cst_with(src, lenient()).unwrap();
+    let root = cst.root();
+    assert_eq!(root.kind(), CstKind::Root);
+    assert_eq!(
+        kinds(root),
+        [CstKind::Comment, CstKind::Whitespace, CstKind::Object]
+    );
+    let object = root.children().last().unwrap();
+    let object = object.as_node().unwrap();
+    assert_eq!(object.text_range(), 5..src.len());
+    assert_eq!(
+        kinds(object),
+        [
+            CstKind::LBrace,
+            CstKind::Member,
+            CstKind::Comma,
+            CstKind::Whitespace,
+            CstKind::Member,
+            CstKind::RBrace
+        ]
+    );
+    let member = object.children().nth(1).unwrap();
+    let member = member.as_node().unwrap();
+    assert_eq!(member.to_string(), "\"a\" : [1, true]");
+    let parts: Vec<_> = member.significant_children().map(|c| c.kind()).collect();
+    assert_eq!(parts, [CstKind::String, CstKind::Colon, CstKind::Array]);
+    let key = member.children().next().unwrap();
+    let key = key.as_token().unwrap();
+    assert_eq!((key.text(), key.text_range()), ("\"a\"", 6..9));
+    assert_eq!(key.parent().kind(), CstKind::Member);
+    assert_eq!(member.parent().unwrap().kind(), CstKind::Object);
+    assert!(cst.document().value().at("a").at(1).as_bool().unwrap());
+}
+
+#[test]
+fn edits_keep_the_rest() {
+    let src = "{\n  // port\n  \"port\": 80, \"host\": \"x\"\n}";
+    let cst = parse_cst_with(src, lenient()).unwrap();
+    let object = cst.root().children().next().unwrap();
+    let mut members = object.as_node().unwrap().children();
+    let member = members.find(|c| c.kind() == CstKind::Member).unwrap();
+    let Some(SyntaxElement::Token(value)) = member.as_node().unwrap().children().last() else {
+        panic!("expected a token");
+    };
+    assert_eq!(value.text(), "80");
+    let root = value.replace_with(GreenToken::new(CstKind::Number, "8080"));
+    assert_eq!(
+        root.to_string(),
+        "{\n  // port\n  \"port\": 8080, \"host\": \"x\"\n}"
+    );
+    // The members that didn't change are shared with the old tree.
+    let (GreenElement::Node(old), GreenElement::Node(new)) =
+        (&cst.root().green().children()[0], &root.children()[0])
+    else {
+        panic!("expected objects");
+    };
+    let (GreenElement::Node(a), GreenElement::Node(b)) = (&old.children()[7], &new.children()[7])
+    else {
+        panic!("expected members");
+    };
+    assert!(Rc::ptr_eq(a, b));
+
+    let new = GreenNode::new(CstKind::Array, vec![]);
+    assert_eq!(cst.root().replace_with(new).kind(), CstKind::Array);
+}
+
+#[test]
+fn errors_come_from_the_parser() {
+    assert!(parse_cst("[1,]").is_err());
+    assert!(matches!(
+        parse_cst("[1"),
+        Err(ParseError::UnexpectedEof { .. })
+    ));
+    assert!(parse_cst("// no\n1").is_err());
+    assert!(parse_cst("1 2").is_err());
+}
+
+#[test]
+fn deep() {
+    let src = "[".repeat(100_000) + &"]".repeat(100_000);
+    let cst = parse_cst(&src).unwrap();
+    assert_eq!(cst.to_string(), src);
+    let mut node = cst
+        .root()
+        .children()
+        .next()
+        .unwrap()
+        .as_node()
+        .unwrap()
+        .clone();
+    for _ in 0..1000 {
+        node = node.children().nth(1).unwrap().as_node().unwrap().clone();
+    }
+    assert_eq!(node.text_range().start, 1000);
+}
+
+const CONFIG: &str = "// Server settings
+{
+  \"host\": \"localhost\", // where to listen
+  /* the port */
+  \"port\": 80,
+  \"tags\": [1, 2]
+}
+";
+
+#[test]
+fn set_pointer_keeps_comments() {
+    let mut cst = parse_cst_with(CONFIG, lenient()).unwrap();
+    assert!(cst.set_pointer("/port", 8080));
+    assert_eq!(cst.to_string(), CONFIG.replace("80,", "8080,"));
+    assert_eq!(cst.document().value().at("port").as_i64(), Some(8080));
+
+    assert!(cst.set_pointer("/tags/0", "one"));
+    assert!(cst.set_pointer("/tags/-", vec![true]));
+    assert!(cst.set_pointer("/tags/3", None::<i64>));
+    assert!(cst.set_pointer("/debug", false));
+    assert_eq!(
+        cst.to_string(),
+        "// Server settings
+{
+  \"host\": \"localhost\", // where to listen
+  /* the port */
+  \"port\": 8080,
+  \"tags\": [\"one\", 2, [true], null],
+  \"debug\": false
+}
+"
+    );
+    assert_eq!(cst.document().value().at("debug").as_bool(), Some(false));
+}
+
+#[test]
+fn set_pointer_edge_cases() {
+    let mut cst = parse_cst_with("{} ", lenient()).unwrap();
+    assert!(cst.set_pointer("/a", 1));
+    assert!(cst.set_pointer("/b", Vec::<i64>::new()));
+    assert!(cst.set_pointer("/b/0", 2));
+    assert_eq!(cst.to_string(), "{\"a\": 1,\"b\": [2]} ");
+
+    let mut cst = parse_cst_with("{\"a\":[1,],}", lenient()).unwrap();
+    assert!(cst.set_pointer("/a/-", 2));
+    assert!(cst.set_pointer("/c~1d", "x"));
+    assert_eq!(cst.to_string(), "{\"a\":[1,2,],\"c/d\":\"x\",}");
+
+    assert!(!cst.set_pointer("/missing/x", 1));
+    assert!(!cst.set_pointer("/a/9", 1));
+    assert!(!cst.set_pointer("/a/x", 1));
+    assert!(!cst.set_pointer("a", 1));
+    assert!(cst.set_pointer("", 7));
+    assert_eq!(cst.to_string(), "7");
+    assert!(cst.pointer("").is_some());
+}
+
+#[test]
+fn pointer_lookup() {
+    let cst = parse_cst_with(CONFIG, lenient()).unwrap();
+    let port = cst.pointer("/port").unwrap();
+    assert_eq!(port.as_token().unwrap().text(), "80");
+    assert_eq!(&CONFIG[port.text_range()], "80");
+    assert_eq!(cst.pointer("/tags").unwrap().kind(), CstKind::Array);
+    assert_eq!(
+        cst.pointer("/tags/1").unwrap().as_token().unwrap().text(),
+        "2"
+    );
+    assert!(cst.pointer("/tags/2").is_none());
+    assert!(cst.pointer("/port/0").is_none());
+}
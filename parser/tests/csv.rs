@@ -0,0 +1,104 @@
+use jsonparser::{parse_value, CsvError, CsvOptions, Document};
+
+fn csv(src: &str, opts: &CsvOptions) -> String {
+    let mut out = Vec::new();
+    parse_value(src).unwrap().to_csv(&mut out, opts).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn columns(names: &[&str]) -> CsvOptions {
+    CsvOptions {
+        columns: Some(names.iter().map(|&name| name.into()).collect()),
+        ..CsvOptions::default()
+    }
+}
+
+const RECORDS: &str = r#"[
+    {"name": "Ada, Countess", "age": 36, "tags": ["a", "b"]},
+    {"name": "Say \"hi\"\nthen go", "age": null, "id": "7", "ok": true}
+]"#;
+
+#[test]
+fn writes() {
+    assert_eq!(
+        csv(RECORDS, &columns(&["name", "age", "id", "ok", "tags"])),
+        concat!(
+            "name,age,id,ok,tags\n",
+            "\"Ada, Countess\",36,,,\"[\"\"a\"\",\"\"b\"\"]\"\n",
+            "\"Say \"\"hi\"\"\nthen go\",,\"7\",true,\n",
+        )
+    );
+    let opts = CsvOptions {
+        delimiter: ';',
+        null: "NULL".into(),
+        ..columns(&["a", "b"])
+    };
+    assert_eq!(
+        csv(
+            r#"[{"a": null, "b": "NULL"}, {"a": "x;y", "b": ""}]"#,
+            &opts
+        ),
+        "a;b\nNULL;\"NULL\"\n\"x;y\";\n"
+    );
+    // Without columns, the header has every key once.
+    let out = csv(r#"[{"a": 1}, {"b": 2, "a": 3}]"#, &CsvOptions::default());
+    assert!(out == "a,b\n1,\n3,2\n" || out == "b,a\n,1\n2,3\n", "{out}");
+    assert_eq!(csv("[]", &CsvOptions::default()), "\n");
+}
+
+#[test]
+fn reads() {
+    let doc = Document::from_csv(
+        "name,age,ok,note\r\n\"Ada, C\",36,true,\"1\"\r\n\nBob,,FALSE,\"a \"\"b\"\"\nc\"\nEve\n",
+        &CsvOptions::default(),
+    )
+    .unwrap();
+    let expected = parse_value(
+        r#"[
+            {"name": "Ada, C", "age": 36, "ok": true, "note": "1"},
+            {"name": "Bob", "age": null, "ok": "FALSE", "note": "a \"b\"\nc"},
+            {"name": "Eve"}
+        ]"#,
+    )
+    .unwrap();
+    assert!(doc.deep_eq(&expected), "{doc}");
+    let untyped = CsvOptions {
+        typed: false,
+        ..CsvOptions::default()
+    };
+    let doc = Document::from_csv("n\n1.5\n", &untyped).unwrap();
+    assert_eq!(doc.to_string(), r#"[{"n":"1.5"}]"#);
+}
+
+#[test]
+fn round_trips() {
+    let opts = columns(&["name", "age", "id", "ok"]);
+    let src = r#"[{"name": "x,\"y\"", "age": 1.5, "id": "007", "ok": false},
+                 {"name": "true", "age": -2, "id": "", "ok": null}]"#;
+    let back = Document::from_csv(&csv(src, &opts), &opts).unwrap();
+    assert!(back.deep_eq(&parse_value(src).unwrap()), "{back}");
+}
+
+#[test]
+fn errors() {
+    let mut out = Vec::new();
+    let e = parse_value("[{}, 1]")
+        .unwrap()
+        .to_csv(&mut out, &CsvOptions::default());
+    assert!(matches!(e, Err(CsvError::NotRecords { index: Some(1) })));
+    let e = parse_value("{}")
+        .unwrap()
+        .to_csv(&mut out, &CsvOptions::default());
+    assert_eq!(e.unwrap_err().to_string(), "CSV needs a list of objects.");
+    for (src, line) in [
+        ("a\n\"x\n", 2),
+        ("a\nx\"y\n", 2),
+        ("a,b\n1,2\n\n1,2,3\n", 4),
+        ("a\n\"x\"y\n", 2),
+    ] {
+        assert!(
+            matches!(Document::from_csv(src, &CsvOptions::default()), Err(CsvError::Syntax { line: l, .. }) if l == line),
+            "{src:?}"
+        );
+    }
+}
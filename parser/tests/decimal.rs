@@ -0,0 +1,62 @@
+#![cfg(feature = "arbitrary-precision")]
+
+use jsonparser::{parse_value, parse_value_with, JsonValue, Number, ParseOptions};
+
+fn exact(src: &str) -> Number {
+    match parse_value(src).unwrap().into_parts().0 {
+        JsonValue::Number(n) => n,
+        other => panic!("expected a number, got {other:?}"),
+    }
+}
+
+#[test]
+fn decimals_keep_their_digits() {
+    let n = exact("0.1000000000000000000001");
+    assert_eq!(n.as_str(), Some("0.1000000000000000000001"));
+    assert_eq!(n.to_string(), "0.1000000000000000000001");
+    assert_eq!(n.as_f64(), 0.1);
+    assert_eq!(n.as_i64(), None);
+    assert_eq!(
+        exact("123456789012345678901234567890").as_str(),
+        Some("123456789012345678901234567890")
+    );
+    assert_eq!(exact("1e400").as_str(), Some("1e400"));
+}
+
+#[test]
+fn integers_stay_integers() {
+    assert_eq!(exact("42"), Number::I64(42));
+    assert_eq!(exact("42").as_str(), None);
+}
+
+// Decimals are written as kept, so what the lenient lexer lets through is
+// kept as RFC 8259 spells it.
+#[test]
+fn lenient_decimals_write_as_json() {
+    let doc = parse_value("[1., 01.5, -00.25e3, 0012345678901234567890123]").unwrap();
+    assert_eq!(doc.to_string(), "[1,1.5,-0.25e3,12345678901234567890123]");
+    let strict = ParseOptions {
+        strict: true,
+        ..ParseOptions::default()
+    };
+    assert!(parse_value_with(&doc.to_string(), strict).is_ok());
+}
+
+#[test]
+#[cfg(feature = "json5")]
+fn json5_decimals_round_trip() {
+    let json5 = ParseOptions {
+        json5: true,
+        ..ParseOptions::default()
+    };
+    let src = "[+1.5, .5, 5., -.25, +.5e10, 0.1000000000000000000001]";
+    let doc = parse_value_with(src, json5).unwrap();
+    let text = doc.to_string();
+    assert_eq!(text, "[1.5,0.5,5,-0.25,0.5e10,0.1000000000000000000001]");
+    let strict = ParseOptions {
+        strict: true,
+        ..ParseOptions::default()
+    };
+    let again = parse_value_with(&text, strict).unwrap();
+    assert_eq!(again.to_string(), text);
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "derive")]
+
+use jsonparser::{parse_value, DecodeError, Document, FromJson, ToJson};
+
+#[derive(Debug, PartialEq, FromJson, ToJson)]
+struct User {
+    #[json(rename = "userId")]
+    id: u64,
+    name: String,
+    email: Option<String>,
+    #[json(default)]
+    tags: Vec<String>,
+    #[json(default = "one")]
+    r#type: i64,
+    #[json(flatten)]
+    audit: Audit,
+}
+
+#[derive(Debug, PartialEq, FromJson, ToJson)]
+pub struct Audit {
+    /// When it was made.
+    pub created: i64,
+    pub(crate) updated: Option<i64>,
+}
+
+fn one() -> i64 {
+    1
+}
+
+#[test]
+fn decode_fields() {
+    let doc =
+        parse_value(r#"{"userId": 7, "name": "ann", "tags": ["a"], "type": 2, "created": 3}"#)
+            .unwrap();
+    assert_eq!(
+        doc.decode::<User>(),
+        Ok(User {
+            id: 7,
+            name: "ann".to_owned(),
+            email: None,
+            tags: vec!["a".to_owned()],
+            r#type: 2,
+            audit: Audit {
+                created: 3,
+                updated: None,
+            },
+        })
+    );
+}
+
+#[test]
+fn defaults() {
+    let doc = parse_value(r#"{"userId": 7, "name": "ann", "created": 3}"#).unwrap();
+    let user: User = doc.decode().unwrap();
+    assert!(user.tags.is_empty());
+    assert_eq!(user.r#type, 1);
+}
+
+#[test]
+fn errors() {
+    let doc = parse_value(r#"{"name": "ann", "created": 3}"#).unwrap();
+    assert!(matches!(
+        doc.decode::<User>(),
+        Err(DecodeError::MissingField {
+            field: "userId",
+            ..
+        })
+    ));
+    let doc = parse_value(r#"{"userId": 7, "name": 1, "created": 3}"#).unwrap();
+    assert_eq!(doc.decode::<User>().unwrap_err().pointer(), "/name");
+    let doc = parse_value("[1]").unwrap();
+    assert!(doc.decode::<Audit>().is_err());
+}
+
+#[test]
+fn round_trip() {
+    let user = User {
+        id: 1,
+        name: "bo".to_owned(),
+        email: Some("bo@example.com".to_owned()),
+        tags: Vec::new(),
+        r#type: 5,
+        audit: Audit {
+            created: 10,
+            updated: Some(11),
+        },
+    };
+    let doc = Document::encode(&user);
+    let obj = doc.root().as_object().unwrap();
+    assert!(obj.contains_key("userId") && obj.contains_key("type") && obj.contains_key("created"));
+    assert!(!obj.contains_key("audit"));
+    assert_eq!(doc.decode::<User>(), Ok(user));
+}
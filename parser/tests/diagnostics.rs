@@ -0,0 +1,102 @@
+use jsonparser::{
+    parse_with_diagnostics, Diagnostic, Document, JsonValue, ParseError, ParseOptions, Severity,
+};
+
+fn render(doc: &Document, value: &JsonValue) -> String {
+    match value {
+        JsonValue::List(list) => {
+            let items: Vec<_> = list.iter().map(|id| render(doc, doc.get(*id))).collect();
+            format!("[{}]", items.join(","))
+        }
+        JsonValue::Object(obj) => {
+            let mut entries: Vec<_> = obj
+                .iter()
+                .map(|(k, id)| format!("{k}:{}", render(doc, doc.get(*id))))
+                .collect();
+            entries.sort();
+            format!("{{{}}}", entries.join(","))
+        }
+        JsonValue::Number(n) => n.to_string(),
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Null => "null".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+fn check(src: &str) -> (String, Vec<String>) {
+    let (doc, diagnostics) = parse_with_diagnostics(src, ParseOptions::default());
+    let messages = diagnostics.iter().map(Diagnostic::to_string).collect();
+    (render(&doc, doc.root()), messages)
+}
+
+#[test]
+fn valid_input_has_no_diagnostics() {
+    assert_eq!(check(r#"{"a": [1, 2]}"#), ("{a:[1,2]}".to_string(), vec![]));
+}
+
+#[test]
+fn recovers_at_commas_and_brackets() {
+    let (doc, messages) = check(r#"{"a": [1, 2 3], "b" 4, "c": }"#);
+    assert_eq!(doc, "{a:[1,2,3],b:4,c:null}");
+    assert_eq!(
+        messages,
+        [
+            "error: Expected ',' or ']', found '3' at line 1, column 13.",
+            "error: Expected ':', found '4' at line 1, column 21.",
+            "error: Expected a value, found '}' at line 1, column 29.",
+        ]
+    );
+}
+
+#[test]
+fn bad_tokens_become_null() {
+    assert_eq!(
+        check("[1, tru, 3]"),
+        (
+            "[1,null,3]".to_string(),
+            vec!["error: Unexpected 'tru'; did you mean 'true'? at line 1, column 5.".to_string()]
+        )
+    );
+    let (doc, messages) = check(r#"{1: 2, "b": 3}"#);
+    assert_eq!(doc, "{:2,b:3}");
+    assert_eq!(messages.len(), 1);
+}
+
+#[test]
+fn end_of_input_is_reported_once() {
+    let (doc, messages) = check(r#"[1, {"a": ["x"#);
+    assert_eq!(doc, "[1,{a:[null]}]");
+    assert_eq!(
+        messages,
+        ["error: Unterminated string at line 1, column 12."]
+    );
+}
+
+#[test]
+fn duplicate_keys_are_warnings() {
+    let src = r#"{"a": 1, "a": 2}"#;
+    let (_, diagnostics) = parse_with_diagnostics(src, ParseOptions::default());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert!(matches!(
+        diagnostics[0].error,
+        ParseError::DuplicateKey { .. }
+    ));
+}
+
+#[test]
+fn limits_still_stop_the_parse() {
+    let opts = ParseOptions {
+        max_depth: Some(1),
+        ..ParseOptions::default()
+    };
+    let (doc, diagnostics) = parse_with_diagnostics("[[1, 2 3]]", opts);
+    assert!(matches!(doc.root(), JsonValue::Null));
+    assert!(matches!(
+        diagnostics[..],
+        [Diagnostic {
+            severity: Severity::Error,
+            error: ParseError::DepthExceeded { .. },
+        }]
+    ));
+}
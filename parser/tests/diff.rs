@@ -0,0 +1,136 @@
+use jsonparser::{diff, parse_value, Document};
+
+// The patch from `a` to `b`, checked to work, with each operation as
+// "op path" or "move from path".
+fn ops(a: &str, b: &str) -> Vec<String> {
+    let (a, b) = (parse_value(a).unwrap(), parse_value(b).unwrap());
+    let patch = diff(&a, &b);
+    let mut patched = a.clone();
+    patched.apply_patch(&patch).unwrap();
+    assert!(patched.deep_eq(&b), "{patch} gave {patched}");
+    let op = patch.value();
+    (0..op.as_array().unwrap().len())
+        .map(|i| {
+            let op = op.at(i);
+            let name = op.at("op").as_str().unwrap();
+            let path = op.at("path").as_str().unwrap();
+            match op.at("from").as_str() {
+                Some(from) => format!("{name} {from} {path}"),
+                None => format!("{name} {path}"),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn equal_documents() {
+    assert!(ops(r#"{"a": [1, {"b": null}]}"#, r#"{"a": [1, {"b": null}]}"#).is_empty());
+    assert_eq!(ops("1", "1.0"), ["replace "]);
+}
+
+#[test]
+fn objects() {
+    assert_eq!(
+        ops(r#"{"a": 1, "b": 2, "c": 3}"#, r#"{"a": 1, "c": 4, "d": 5}"#),
+        ["remove /b", "add /d", "replace /c"]
+    );
+    assert_eq!(
+        ops(r#"{"a": {"big": [1, 2]}}"#, r#"{"b": {"big": [1, 2]}}"#),
+        ["move /a /b"]
+    );
+    assert_eq!(
+        ops(r#"{"a/b": {"~": 1}}"#, r#"{"a/b": {"~": 2}}"#),
+        ["replace /a~1b/~0"]
+    );
+    assert_eq!(ops(r#"{"a": 1}"#, "[1]"), ["replace "]);
+}
+
+#[test]
+fn lists() {
+    assert_eq!(
+        ops(r#"["a", "b", "c"]"#, r#"["a", "x", "b", "c"]"#),
+        ["add /1"]
+    );
+    assert_eq!(ops("[1, 2, 3, 4]", "[1, 3, 4]"), ["remove /1"]);
+    assert_eq!(ops("[1, 2, 3]", "[3, 1, 2]"), ["move /2 /0"]);
+    assert_eq!(ops("[3, 1, 2]", "[1, 2, 3]"), ["move /0 /2"]);
+    assert_eq!(ops("[1, 2, 3, 4]", "[4, 3, 2, 1]").len(), 3);
+    assert_eq!(ops("[1, 2, 3]", "[1, 5, 3]"), ["replace /1"]);
+    assert_eq!(
+        ops(
+            r#"[{"id": 1, "n": "a"}, 2]"#,
+            r#"[0, {"id": 1, "n": "b"}, 2]"#
+        ),
+        ["add /0", "replace /1/n"]
+    );
+}
+
+#[test]
+fn changes_inside_lists() {
+    assert_eq!(
+        ops(
+            r#"{"x": [1, {"y": 2, "z": [true]}]}"#,
+            r#"{"x": [1, {"y": 3, "z": [true, false]}]}"#
+        ),
+        ["replace /x/1/y", "add /x/1/z/1"]
+    );
+    // Across an element that stayed, a change is a removal and an addition.
+    assert_eq!(ops("[[1, 2], 9]", "[8, [1, 2]]"), ["remove /1", "add /0"]);
+}
+
+// A small generator, so runs are repeatable without a dependency.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % n
+    }
+
+    // Values from a small alphabet, so two of them share a lot.
+    fn value(&mut self, depth: u32, out: &mut String) {
+        match self.next(if depth == 0 { 3 } else { 5 }) {
+            0 => out.push_str(["null", "true", "1", "2"][self.next(4) as usize]),
+            1 | 2 => out.push_str(["\"a\"", "\"b\"", "3"][self.next(3) as usize]),
+            3 => {
+                out.push('[');
+                for i in 0..self.next(5) {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    self.value(depth - 1, out);
+                }
+                out.push(']');
+            }
+            _ => {
+                out.push('{');
+                for i in 0..self.next(4) {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    out.push_str(["\"k\":", "\"l\":", "\"m\":", "\"n\":"][i as usize]);
+                    self.value(depth - 1, out);
+                }
+                out.push('}');
+            }
+        }
+    }
+}
+
+#[test]
+fn patches_always_apply() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..2000 {
+        let (mut a, mut b) = (String::new(), String::new());
+        rng.value(3, &mut a);
+        rng.value(3, &mut b);
+        let a: Document = a.parse().unwrap();
+        let b: Document = b.parse().unwrap();
+        let mut patched = a.clone();
+        patched.apply_patch(&diff(&a, &b)).unwrap();
+        assert!(patched.deep_eq(&b), "{a} to {b} gave {patched}");
+        assert!(diff(&b, &b).root().as_array().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,53 @@
+use jsonparser::{
+    parse_value, parse_value_with, DuplicateKeys, JsonValue, ParseError, ParseOptions,
+};
+
+fn alg(src: &str, duplicate_keys: DuplicateKeys) -> Result<String, ParseError> {
+    let opts = ParseOptions {
+        duplicate_keys,
+        ..ParseOptions::default()
+    };
+    let doc = parse_value_with(src, opts)?;
+    let JsonValue::Object(obj) = doc.root() else {
+        panic!("expected an object");
+    };
+    match doc.get(obj["alg"]) {
+        JsonValue::String(s) => Ok(s.clone()),
+        other => panic!("expected a string, got {other:?}"),
+    }
+}
+
+const TOKEN: &str = r#"{"alg": "HS256", "typ": "JWT", "alg": "none"}"#;
+
+#[test]
+fn last_wins_by_default() {
+    assert_eq!(alg(TOKEN, DuplicateKeys::default()).unwrap(), "none");
+    assert_eq!(parse_value(TOKEN).unwrap().arena().len(), 3);
+}
+
+#[test]
+fn first_wins() {
+    assert_eq!(alg(TOKEN, DuplicateKeys::FirstWins).unwrap(), "HS256");
+    let opts = ParseOptions {
+        duplicate_keys: DuplicateKeys::FirstWins,
+        ..ParseOptions::default()
+    };
+    assert_eq!(parse_value_with(TOKEN, opts).unwrap().arena().len(), 2);
+}
+
+#[test]
+fn duplicates_are_errors() {
+    let err = alg(TOKEN, DuplicateKeys::Error).unwrap_err();
+    assert_eq!(
+        err,
+        ParseError::DuplicateKey {
+            key: "alg".to_string(),
+            span: err.span(),
+        }
+    );
+    assert_eq!(err.span().start, 31);
+    assert_eq!(
+        err.to_string(),
+        "Duplicate key \"alg\" at line 1, column 32."
+    );
+}
@@ -0,0 +1,173 @@
+use jsonparser::{parse_value, JsonValue};
+
+fn int(n: i64) -> JsonValue {
+    JsonValue::Number(n.into())
+}
+
+fn text(doc: &jsonparser::Document, pointer: &str) -> String {
+    doc.value_ref(doc.pointer(pointer).unwrap()).to_string()
+}
+
+fn ints(doc: &jsonparser::Document, pointer: &str) -> Vec<i64> {
+    doc.pointer(pointer)
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|&id| doc.get(id).as_i64().unwrap())
+        .collect()
+}
+
+#[test]
+fn edit_objects() {
+    let mut doc = parse_value(r#"{"a": 1, "b": {"c": 2}}"#).unwrap();
+    let mut root = doc.object_mut("").unwrap();
+    assert!(root.remove("a").is_some());
+    assert!(root.insert("d", JsonValue::Bool(true)).is_none());
+    assert_eq!(root.len(), 2);
+    let mut b = doc.object_mut("/b").unwrap();
+    assert!(b.insert("c", JsonValue::Null).is_some());
+    assert!(doc.pointer("/b/c").unwrap().is_null());
+    assert!(doc.pointer("/a").is_none());
+    assert_eq!(doc["d"].as_bool(), Some(true));
+    assert!(doc.object_mut("/d").is_none());
+}
+
+#[test]
+fn edit_lists() {
+    let mut doc = parse_value(r#"{"xs": [1, 2, 3]}"#).unwrap();
+    let mut xs = doc.list_mut("/xs").unwrap();
+    xs.push(int(4));
+    xs.insert(0, int(0));
+    xs.remove(2);
+    assert_eq!(ints(&doc, "/xs"), [0, 1, 3, 4]);
+    doc.list_mut("/xs").unwrap().truncate(1);
+    assert_eq!(ints(&doc, "/xs"), [0]);
+}
+
+#[test]
+fn nested_values_go_through_the_arena() {
+    let mut doc = parse_value("[]").unwrap();
+    let inner = doc.alloc(JsonValue::String("deep".into()));
+    doc.list_mut("").unwrap().push(JsonValue::List(vec![inner]));
+    assert_eq!(doc.pointer("/0/0").and_then(|v| v.as_str()), Some("deep"));
+}
+
+#[test]
+fn set_pointer() {
+    let mut doc = parse_value(r#"{"a": {"b": [1, 2]}, "c": 3}"#).unwrap();
+    assert!(doc.set_pointer("/c", JsonValue::Bool(false)));
+    assert!(doc.set_pointer("/a/new", JsonValue::Null));
+    assert!(doc.set_pointer("/a/b/0", int(10)));
+    assert!(doc.set_pointer("/a/b/-", int(30)));
+    assert!(doc.set_pointer("/a/b/3", int(40)));
+    assert_eq!(ints(&doc, "/a/b"), [10, 2, 30, 40]);
+    assert_eq!(doc.pointer("/c").and_then(|v| v.as_bool()), Some(false));
+    assert!(doc.pointer("/a/new").unwrap().is_null());
+
+    assert!(!doc.set_pointer("/a/b/9", JsonValue::Null));
+    assert!(!doc.set_pointer("/x/y", JsonValue::Null));
+    assert!(!doc.set_pointer("/c/d", JsonValue::Null));
+    assert!(!doc.set_pointer("c", JsonValue::Null));
+    assert!(doc.set_pointer("", JsonValue::Null));
+    assert!(doc.root().is_null());
+}
+
+#[test]
+fn pointer_set_creates_missing_containers() {
+    let mut doc = parse_value(r#"{"a": [1]}"#).unwrap();
+    assert!(!doc.pointer_set("/x/y", JsonValue::Null, false));
+    assert!(doc.pointer_set("/x/y/0/z", int(1), true));
+    assert_eq!(text(&doc, "/x"), r#"{"y":[{"z":1}]}"#);
+    assert!(doc.pointer_set("/a/-/b/-", int(2), true));
+    assert_eq!(text(&doc, "/a"), r#"[1,{"b":[2]}]"#);
+
+    // Nothing changes when the pointer runs into a scalar or past a list.
+    assert!(!doc.pointer_set("/a/0/b", int(3), true));
+    assert!(!doc.pointer_set("/a/5/b", int(3), true));
+    assert_eq!(text(&doc, "/a"), r#"[1,{"b":[2]}]"#);
+}
+
+#[test]
+fn pointer_remove() {
+    let mut doc = parse_value(r#"{"a": [1, 2, 3], "b": {"c": 4}}"#).unwrap();
+    let removed = doc.pointer_remove("/a/1").unwrap();
+    assert_eq!(doc.get(removed).as_i64(), Some(2));
+    assert_eq!(ints(&doc, "/a"), [1, 3]);
+    assert!(doc.pointer_remove("/b/c").is_some());
+    assert_eq!(text(&doc, "/b"), "{}");
+
+    assert!(doc.pointer_remove("/a/2").is_none());
+    assert!(doc.pointer_remove("/b/c").is_none());
+    assert!(doc.pointer_remove("").is_none());
+}
+
+#[test]
+fn sort_by_pointer() {
+    let mut doc = parse_value(
+        r#"{"people": [{"n": "a", "age": 40}, {"n": "b"}, {"n": "c", "age": 3.5}, {"n": "d", "age": 40}, {"n": "e", "age": "x"}]}"#,
+    )
+    .unwrap();
+    doc.list_mut("/people").unwrap().sort_by_pointer("/age");
+    let names: Vec<_> = doc
+        .value()
+        .at("people")
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|&id| doc.value_ref(doc.get(id)).at("n").as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["b", "c", "a", "d", "e"]);
+    let mut doc = parse_value("[3, [1], 1, 2]").unwrap();
+    doc.list_mut("").unwrap().sort_by_pointer("");
+    assert_eq!(doc.to_string(), "[1,2,3,[1]]");
+    let mut doc = parse_value("[[2, 9], [1, 8], [3]]").unwrap();
+    doc.list_mut("").unwrap().sort_by_pointer("/1");
+    assert_eq!(doc.to_string(), "[[3],[1,8],[2,9]]");
+}
+
+#[test]
+fn dedupe_by_pointer() {
+    let mut doc = parse_value(
+        r#"[{"id": 1, "v": "a"}, {"id": 2}, {"id": 1.0, "v": "b"}, {"v": "c"}, {"id": null}, {"id": {"x": [1]}}, {"id": {"x": [1]}}]"#,
+    )
+    .unwrap();
+    doc.list_mut("").unwrap().dedupe_by_pointer("/id");
+    assert_eq!(
+        doc.to_canonical_string().unwrap(),
+        r#"[{"id":1,"v":"a"},{"id":2},{"v":"c"},{"id":{"x":[1]}}]"#
+    );
+}
+
+#[test]
+fn group_by_pointer() {
+    let mut doc = parse_value(
+        r#"{"events": [{"type": "click", "n": 1}, {"type": "view", "n": 2}, {"type": "click", "n": 3}, {"type": 7, "n": 4}, {"n": 5}]}"#,
+    )
+    .unwrap();
+    let mut groups = doc.list_mut("/events").unwrap().group_by_pointer("/type");
+    assert_eq!(groups.len(), 4);
+    let expected = r#"{"events": {"click": [{"type": "click", "n": 1}, {"type": "click", "n": 3}],
+        "view": [{"type": "view", "n": 2}], "7": [{"type": 7, "n": 4}], "null": [{"n": 5}]}}"#;
+    assert_eq!(
+        doc.to_canonical_string().unwrap(),
+        parse_value(expected)
+            .unwrap()
+            .to_canonical_string()
+            .unwrap()
+    );
+    let mut doc = parse_value("[]").unwrap();
+    doc.list_mut("").unwrap().group_by_pointer("/x");
+    assert_eq!(doc.to_string(), "{}");
+}
+
+#[test]
+fn value_ref_pointer() {
+    let doc = parse_value(r#"{"a": [{"b/c": 1}]}"#).unwrap();
+    let a = doc.value().at("a");
+    assert_eq!(a.pointer("/0/b~1c").unwrap().as_i64(), Some(1));
+    assert_eq!(a.pointer("").unwrap().as_array().unwrap().len(), 1);
+    assert!(a.pointer("/1").is_none());
+    assert!(a.pointer("/01").is_none());
+    assert!(a.pointer("0").is_none());
+}
@@ -0,0 +1,46 @@
+use jsonparser::{parse_value, EqOptions};
+
+fn eq(a: &str, b: &str) -> bool {
+    parse_value(a).unwrap() == parse_value(b).unwrap()
+}
+
+fn eq_by_value(a: &str, b: &str) -> bool {
+    let opts = EqOptions {
+        numbers_by_value: true,
+    };
+    parse_value(a)
+        .unwrap()
+        .deep_eq_with(&parse_value(b).unwrap(), opts)
+}
+
+#[test]
+fn structural() {
+    assert!(eq(
+        r#"{"a": [1, {"b": null}], "c": "x"}"#,
+        r#"{"c": "x", "a": [1, {"b": null}]}"#
+    ));
+    assert!(!eq("[1, 2]", "[2, 1]"));
+    assert!(!eq(r#"{"a": 1}"#, r#"{"a": 1, "b": 2}"#));
+    assert!(!eq(r#"{"a": 1}"#, r#"{"b": 1}"#));
+    assert!(!eq("[[]]", "[{}]"));
+    assert!(!eq("\"1\"", "1"));
+}
+
+#[test]
+fn arenas_dont_matter() {
+    let padded = parse_value(r#"[[1, 2, 3], {"k": [true]}]"#).unwrap();
+    let plain = parse_value(r#"[ [1,2,3],{"k":[true]} ]"#).unwrap();
+    assert!(padded.deep_eq(&plain));
+    assert_eq!(padded.value().at(1), plain.value().at(1));
+    assert_ne!(padded.value().at(0), plain.value().at(1));
+}
+
+#[test]
+fn numbers() {
+    assert!(!eq("1", "1.0"));
+    assert!(eq_by_value("1", "1.0"));
+    assert!(eq_by_value("[100]", "[1e2]"));
+    assert!(!eq_by_value("1", "1.5"));
+    assert!(!eq_by_value("9007199254740993", "9007199254740992.0"));
+    assert!(eq_by_value("18446744073709551615", "18446744073709551615"));
+}
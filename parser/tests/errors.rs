@@ -0,0 +1,72 @@
+use jsonparser::{parse_value, parse_value_with, ErrorKind, ParseError, ParseOptions};
+
+type Variant = fn(&ParseError) -> bool;
+
+#[test]
+fn kinds() {
+    let cases: [(&str, ErrorKind, Variant); 8] = [
+        (
+            "[1, }",
+            ErrorKind::Syntax,
+            |e| matches!(e, ParseError::UnexpectedToken { found, .. } if found == "'}'"),
+        ),
+        ("[1, 2", ErrorKind::Eof, |e| {
+            matches!(e, ParseError::UnexpectedEof { .. })
+        }),
+        (r#"{"a": "b"#, ErrorKind::Eof, |e| {
+            matches!(e, ParseError::UnterminatedString { .. })
+        }),
+        (
+            "[1e]",
+            ErrorKind::Syntax,
+            |e| matches!(e, ParseError::InvalidNumber { lexeme, .. } if lexeme == "1e"),
+        ),
+        (r#""\x""#, ErrorKind::Syntax, |e| {
+            matches!(e, ParseError::InvalidEscape { .. })
+        }),
+        ("\"a\tb\"", ErrorKind::Syntax, |e| {
+            matches!(e, ParseError::ControlCharacter { chr: '\t', .. })
+        }),
+        ("{} []", ErrorKind::Syntax, |e| {
+            matches!(e, ParseError::TrailingData { .. })
+        }),
+        ("[[[]]]", ErrorKind::Limit, |e| {
+            matches!(e, ParseError::DepthExceeded { max: 2, .. })
+        }),
+    ];
+    let opts = ParseOptions {
+        max_depth: Some(2),
+        ..ParseOptions::default()
+    };
+    for (src, kind, variant) in cases {
+        let err = parse_value_with(src, opts).unwrap_err();
+        assert_eq!(err.kind(), kind, "{src}: {err:?}");
+        assert!(variant(&err), "{src}: {err:?}");
+        // The message is for people; matching goes by the variant.
+        assert!(!err.to_string().is_empty());
+    }
+    assert!(parse_value("[[[]]]").is_ok());
+}
+
+#[test]
+fn spans() {
+    let long = "значение ".repeat(8);
+    let third = format!("{{\"ключ\": \"{long}\",\n \"é\" 1}}");
+    let cases = [
+        ("{\n  \"a\": 1,\n  \"b\": tru\n}", "tru", 3, 8),
+        ("[\"日本語\", x]", "x", 1, 9),
+        // Past a run long enough for the block scanner.
+        (third.as_str(), "1", 2, 6),
+        ("[1,\n\"naïve 😀", "\"", 2, 1),
+        ("[\r\n  1,\r\n  2,,\r\n]", ",\r", 3, 5),
+    ];
+    for (src, at, line, column) in cases {
+        let span = parse_value(src).unwrap_err().span();
+        assert_eq!(span.start, src.rfind(at).unwrap(), "{src}");
+        assert_eq!((span.line, span.column), (line, column), "{src}");
+    }
+    // Lexing errors point into the token, here at the backslash.
+    let err = parse_value("[\"ö\", \"\\q\"]").unwrap_err();
+    assert!(matches!(err, ParseError::InvalidEscape { .. }), "{err:?}");
+    assert_eq!((err.span().line, err.span().column), (1, 8));
+}
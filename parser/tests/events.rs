@@ -0,0 +1,104 @@
+use std::ops::ControlFlow;
+
+use jsonparser::{parse_events, JsonHandler, Number, ParseError, Visit};
+
+#[derive(Default)]
+struct Log(Vec<String>);
+
+impl JsonHandler for Log {
+    fn start_object(&mut self) -> ControlFlow<(), Visit> {
+        self.0.push("{".into());
+        ControlFlow::Continue(Visit::Enter)
+    }
+
+    fn end_object(&mut self) -> ControlFlow<()> {
+        self.0.push("}".into());
+        ControlFlow::Continue(())
+    }
+
+    fn start_array(&mut self) -> ControlFlow<(), Visit> {
+        self.0.push("[".into());
+        ControlFlow::Continue(Visit::Enter)
+    }
+
+    fn end_array(&mut self) -> ControlFlow<()> {
+        self.0.push("]".into());
+        ControlFlow::Continue(())
+    }
+
+    fn key(&mut self, key: &str) -> ControlFlow<(), Visit> {
+        self.0.push(format!("{key}:"));
+        if key == "secret" {
+            ControlFlow::Continue(Visit::Skip)
+        } else {
+            ControlFlow::Continue(Visit::Enter)
+        }
+    }
+
+    fn string(&mut self, s: &str) -> ControlFlow<()> {
+        self.0.push(s.into());
+        ControlFlow::Continue(())
+    }
+
+    fn number(&mut self, n: Number) -> ControlFlow<()> {
+        self.0.push(n.to_string());
+        if n == Number::I64(99) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+fn log(src: &str) -> (Result<ControlFlow<()>, ParseError>, String) {
+    let mut log = Log::default();
+    let flow = parse_events(src, &mut log);
+    (flow, log.0.join(" "))
+}
+
+#[test]
+fn every_event() {
+    assert_eq!(
+        log(r#"{"a": [1, "x"], "b": {}}"#),
+        (
+            Ok(ControlFlow::Continue(())),
+            "{ a: [ 1 x ] b: { } }".to_string()
+        )
+    );
+}
+
+#[test]
+fn skipping_a_key() {
+    let (flow, log) = log(r#"{"secret": {"k": [1, {"x": 2}]}, "n": 3, "secret": 4, "m": 5}"#);
+    assert_eq!(flow, Ok(ControlFlow::Continue(())));
+    assert_eq!(log, "{ secret: n: 3 secret: m: 5 }");
+}
+
+#[test]
+fn breaking_stops_early() {
+    let (flow, log) = log("[1, 99, 3, ]]]");
+    assert_eq!(flow, Ok(ControlFlow::Break(())));
+    assert_eq!(log, "[ 1 99");
+}
+
+#[test]
+fn errors() {
+    let (flow, log) = log("[1, 2 3]");
+    assert!(matches!(flow, Err(ParseError::UnexpectedToken { .. })));
+    assert_eq!(log, "[ 1 2");
+}
+
+#[test]
+fn defaults_count_nothing() {
+    struct Count(usize);
+    impl JsonHandler for Count {
+        fn start_array(&mut self) -> ControlFlow<(), Visit> {
+            self.0 += 1;
+            ControlFlow::Continue(Visit::Skip)
+        }
+    }
+    let mut count = Count(0);
+    let flow = parse_events("{\"a\": [[1], [2]], \"b\": [3]}", &mut count);
+    assert_eq!(flow, Ok(ControlFlow::Continue(())));
+    assert_eq!(count.0, 2);
+}
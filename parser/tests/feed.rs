@@ -0,0 +1,116 @@
+use jsonparser::{Feeder, JsonValue, ParseError, ParseOptions, Poll};
+
+fn feed_all(chunks: &[&[u8]]) -> Vec<Result<Poll, ParseError>> {
+    let mut feeder = Feeder::default();
+    let mut polls = Vec::new();
+    for chunk in chunks {
+        feeder.feed(chunk);
+        loop {
+            let poll = feeder.poll();
+            let more = matches!(poll, Ok(Poll::Document(_)) | Err(_));
+            polls.push(poll);
+            if !more {
+                break;
+            }
+        }
+    }
+    feeder.finish();
+    loop {
+        let poll = feeder.poll();
+        let done = matches!(poll, Ok(Poll::Finished));
+        polls.push(poll);
+        if done {
+            return polls;
+        }
+    }
+}
+
+fn documents(polls: &[Result<Poll, ParseError>]) -> usize {
+    polls
+        .iter()
+        .filter(|p| matches!(p, Ok(Poll::Document(_))))
+        .count()
+}
+
+#[test]
+fn documents_split_across_chunks() {
+    let polls = feed_all(&[b"{\"a\": [1, ", b"\"]}\"", b"]}\n{", b"}  \"x\\", b"\"\""]);
+    assert!(matches!(polls[0], Ok(Poll::NeedMoreData)));
+    assert!(
+        matches!(&polls[2], Ok(Poll::Document(doc)) if matches!(doc.root(), JsonValue::Object(_)))
+    );
+    assert_eq!(documents(&polls), 3);
+    assert!(polls.iter().all(|p| p.is_ok()));
+}
+
+#[test]
+fn byte_at_a_time() {
+    let src = "[\"a\", {\"b\": null}] 12 true \"s\"";
+    let chunks: Vec<&[u8]> = src.as_bytes().chunks(1).collect();
+    let polls = feed_all(&chunks);
+    assert_eq!(documents(&polls), 4);
+}
+
+#[test]
+fn trailing_scalar_needs_finish() {
+    let mut feeder = Feeder::default();
+    feeder.feed(b"12");
+    assert!(matches!(feeder.poll(), Ok(Poll::NeedMoreData)));
+    feeder.feed(b"3");
+    feeder.finish();
+    match feeder.poll() {
+        Ok(Poll::Document(doc)) => {
+            assert!(matches!(doc.root(), JsonValue::Number(n) if n.as_i64() == Some(123)))
+        }
+        other => panic!("expected a document, got {other:?}"),
+    }
+    assert!(matches!(feeder.poll(), Ok(Poll::Finished)));
+}
+
+#[test]
+fn errors_keep_their_place() {
+    let mut feeder = Feeder::default();
+    feeder.feed(b"{}\n[1 2]\n[3]");
+    assert!(matches!(feeder.poll(), Ok(Poll::Document(_))));
+    let err = feeder.poll().unwrap_err();
+    assert_eq!(err.span().line, 2);
+    assert_eq!(err.span().start, 6);
+    assert!(matches!(feeder.poll(), Ok(Poll::Document(_))));
+    assert!(matches!(feeder.poll(), Ok(Poll::NeedMoreData)));
+}
+
+#[test]
+fn unfinished_document() {
+    let mut feeder = Feeder::default();
+    feeder.feed(b"[1, ");
+    feeder.finish();
+    assert!(matches!(
+        feeder.poll(),
+        Err(ParseError::UnexpectedEof { .. })
+    ));
+}
+
+#[test]
+fn comments_hide_brackets() {
+    let mut feeder = Feeder::new(ParseOptions {
+        allow_comments: true,
+        ..ParseOptions::default()
+    });
+    feeder.feed(b"[1 /* ] */, 2 /");
+    assert!(matches!(feeder.poll(), Ok(Poll::NeedMoreData)));
+    feeder.feed(b"/ ]\n]");
+    assert!(matches!(feeder.poll(), Ok(Poll::Document(_))));
+}
+
+#[test]
+fn buffer_limit() {
+    let mut feeder = Feeder::new(ParseOptions {
+        max_document_size: Some(8),
+        ..ParseOptions::default()
+    });
+    feeder.feed(b"[1, 2, 3, 4");
+    assert!(matches!(
+        feeder.poll(),
+        Err(ParseError::DocumentTooLarge { .. })
+    ));
+}
@@ -0,0 +1,87 @@
+use jsonparser::{parse_value, Document, FlattenError, FlattenOptions, IndexStyle};
+
+const NESTED: &str = r#"{"a": {"b": [{"c": 1}, 2, []]}, "d": null, "e": {}}"#;
+
+fn flat(src: &str, opts: FlattenOptions) -> String {
+    parse_value(src)
+        .unwrap()
+        .flatten_with(opts)
+        .to_canonical_string()
+        .unwrap()
+}
+
+#[test]
+fn flattens() {
+    assert_eq!(
+        flat(NESTED, FlattenOptions::default()),
+        r#"{"a.b[0].c":1,"a.b[1]":2,"a.b[2]":[],"d":null,"e":{}}"#
+    );
+    let opts = FlattenOptions {
+        separator: "/".into(),
+        indices: IndexStyle::Separated,
+    };
+    assert_eq!(
+        flat(NESTED, opts),
+        r#"{"a/b/0/c":1,"a/b/1":2,"a/b/2":[],"d":null,"e":{}}"#
+    );
+    assert_eq!(
+        flat("[[1], 2]", FlattenOptions::default()),
+        r#"{"[0][0]":1,"[1]":2}"#
+    );
+    assert_eq!(flat("3", FlattenOptions::default()), r#"{"":3}"#);
+}
+
+#[test]
+fn round_trips() {
+    for opts in [
+        FlattenOptions::default(),
+        FlattenOptions {
+            separator: "__".into(),
+            indices: IndexStyle::Separated,
+        },
+    ] {
+        for src in [NESTED, "[[1, [2]], {\"x\": [true]}]", "\"s\"", "{}", "[]"] {
+            let doc = parse_value(src).unwrap();
+            let back = Document::from_flat_map_with(&doc.flatten_with(opts.clone()), opts.clone());
+            assert!(back.unwrap().deep_eq(&doc), "{src}");
+        }
+    }
+}
+
+#[test]
+fn unflattens() {
+    let back = |src: &str| {
+        Document::from_flat_map(&parse_value(src).unwrap())
+            .map(|doc| doc.to_canonical_string().unwrap())
+    };
+    assert_eq!(
+        back(r#"{"a[1]": 2, "a[0].b": 1, "c.d": "x"}"#).unwrap(),
+        r#"{"a":[{"b":1},2],"c":{"d":"x"}}"#
+    );
+    // Gaps are null.
+    assert_eq!(
+        back(r#"{"a[2]": 1, "b": 0, "c": 0}"#).unwrap(),
+        r#"{"a":[null,null,1],"b":0,"c":0}"#
+    );
+    // Brackets that aren't an index are part of the key.
+    assert_eq!(
+        back(r#"{"a[x]": 1, "b[]": 2}"#).unwrap(),
+        r#"{"a[x]":1,"b[]":2}"#
+    );
+    assert_eq!(
+        back(r#"{"a": 1, "a.b": 2}"#).unwrap_err().message,
+        "conflicts with another key"
+    );
+    assert!(back(r#"{"a[0]": 1, "a.b": 2}"#).is_err());
+    assert_eq!(
+        back(r#"{"a[5]": 1}"#).unwrap_err(),
+        FlattenError {
+            key: "a[5]".into(),
+            message: "invalid index"
+        }
+    );
+    assert_eq!(
+        back("[1]").unwrap_err().to_string(),
+        "Can't unflatten \"\": the flat map must be an object."
+    );
+}
@@ -0,0 +1,81 @@
+use jsonparser::{parse_value, parse_value_with, CanonicalError, ParseOptions};
+
+fn hash(src: &str) -> u64 {
+    parse_value(src).unwrap().canonical_hash()
+}
+
+#[test]
+fn ignores_formatting_and_key_order() {
+    let a = hash(r#"{"b": null, "a": [1.0, "x\n"]}"#);
+    assert_eq!(a, hash(r#"{"a":[1,"x\u000a"],"b":null}"#));
+    assert_ne!(a, hash(r#"{"a":[1,"x"],"b":null}"#));
+    assert_ne!(hash("[1, 2]"), hash("[2, 1]"));
+    assert_ne!(hash(r#"{"a": {}}"#), hash(r#"{"a": []}"#));
+}
+
+// FNV-1a of the JCS text, so the digest stays the same across releases.
+#[test]
+fn digest_of_canonical_text() {
+    // {"a":[1,"x\n"],"b":null}
+    assert_eq!(hash(r#"{"b": null, "a": [1, "x\n"]}"#), 0xf05b0b380e732904);
+    // [1e+21,1e-7,0.000001,123.456,0,"\u001f"]
+    assert_eq!(
+        hash(r#"[1e21, 0.0000001, 1e-6, 123.4560, -0, "\u001F"]"#),
+        0xde7aedc32049bc05
+    );
+}
+
+fn canonical(src: &str) -> String {
+    parse_value(src).unwrap().to_canonical_string().unwrap()
+}
+
+// The example from RFC 8785, section 3.2.2.
+#[test]
+fn canonical_text() {
+    let src = r#"{
+        "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+        "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+        "literals": [null, true, false]
+    }"#;
+    assert_eq!(
+        canonical(src),
+        r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+    );
+    assert_eq!(
+        canonical(r#"{"b": null, "a": [1, "x\n"]}"#),
+        r#"{"a":[1,"x\n"],"b":null}"#
+    );
+    // Keys sort by UTF-16 code units, which puts U+FF61 after U+1F600.
+    assert_eq!(
+        canonical(r#"{"｡": 1, "😀": 2, "a": 3}"#),
+        "{\"a\":3,\"\u{1f600}\":2,\"\u{ff61}\":1}"
+    );
+}
+
+#[test]
+fn non_finite_has_no_canonical_form() {
+    let opts = ParseOptions {
+        allow_non_finite_numbers: true,
+        ..ParseOptions::default()
+    };
+    for (src, pointer) in [
+        ("NaN", ""),
+        (r#"{"a": [1, Infinity]}"#, "/a/1"),
+        (r#"[{"x/y": -Infinity}]"#, "/0/x~1y"),
+    ] {
+        let doc = parse_value_with(src, opts).unwrap();
+        let err = doc.to_canonical_string().unwrap_err();
+        assert_eq!(
+            err,
+            CanonicalError {
+                pointer: pointer.into(),
+                message: "a non-finite number"
+            },
+            "{src}"
+        );
+    }
+    // Hashing still tells them apart.
+    let hash = |src| parse_value_with(src, opts).unwrap().canonical_hash();
+    assert_ne!(hash("[Infinity]"), hash("[-Infinity]"));
+    assert_ne!(hash("[NaN]"), hash("[null]"));
+}
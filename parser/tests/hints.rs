@@ -0,0 +1,42 @@
+use jsonparser::{parse_value, ParseError};
+
+fn hint(src: &str) -> Option<&'static str> {
+    match parse_value(src).unwrap_err() {
+        ParseError::UnexpectedToken { hint, .. } => hint,
+        other => panic!("expected an unexpected token, got {other:?}"),
+    }
+}
+
+#[test]
+fn near_misses() {
+    assert_eq!(hint("ture"), Some("did you mean 'true'?"));
+    assert_eq!(hint("[1, fasle]"), Some("did you mean 'false'?"));
+    assert_eq!(hint("nul"), Some("did you mean 'null'?"));
+    assert_eq!(hint("True"), Some("did you mean 'true'?"));
+    assert_eq!(hint("NULL"), Some("did you mean 'null'?"));
+    assert_eq!(hint("None"), Some("did you mean 'null'?"));
+    assert_eq!(hint("undefined"), Some("did you mean 'null'?"));
+    assert_eq!(hint("'single'"), Some("strings need double quotes"));
+    assert_eq!(hint("banana"), None);
+}
+
+#[test]
+fn hint_in_message() {
+    assert_eq!(
+        parse_value(r#"{"ok": ture}"#).unwrap_err().to_string(),
+        "Unexpected 'ture'; did you mean 'true'? at line 1, column 8."
+    );
+}
+
+// The word is compared against the literals, which once took time and
+// memory in proportion to its length.
+#[test]
+fn long_words_are_not_compared() {
+    let word = "t".repeat(4 << 20);
+    let start = std::time::Instant::now();
+    assert_eq!(hint(&word), None);
+    assert_eq!(hint(&format!("[{word}]")), None);
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    assert_eq!(hint("nulll"), Some("did you mean 'null'?"));
+    assert_eq!(hint("truest"), None);
+}
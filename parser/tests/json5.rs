@@ -0,0 +1,102 @@
+#![cfg(feature = "json5")]
+
+use jsonparser::{parse_value, parse_value_with, JsonValue, ParseError, ParseOptions};
+
+fn json5() -> ParseOptions {
+    ParseOptions {
+        json5: true,
+        ..ParseOptions::default()
+    }
+}
+
+fn number(src: &str) -> f64 {
+    match parse_value_with(src, json5()).unwrap().root() {
+        JsonValue::Number(n) => n.as_f64(),
+        other => panic!("expected a number, got {other:?}"),
+    }
+}
+
+fn string(src: &str) -> String {
+    match parse_value_with(src, json5()).unwrap().root() {
+        JsonValue::String(s) => s.clone(),
+        other => panic!("expected a string, got {other:?}"),
+    }
+}
+
+#[test]
+fn config_file() {
+    let src = "
+        // JSON5 allows comments
+        {
+            unquoted: 'and you can quote me on that',
+            $dollar_key: 1,
+            lineBreaks: \"Look, Mom! \\
+No \\\\n's!\",
+            hexadecimal: 0xdecaf,
+            leadingDecimalPoint: .8675309, andTrailing: 8675309.,
+            positiveSign: +1,
+            trailingComma: 'in objects', andIn: ['arrays',],
+        }";
+    let doc = parse_value_with(src, json5()).unwrap();
+    let JsonValue::Object(obj) = doc.root() else {
+        panic!("expected an object");
+    };
+    assert_eq!(obj.len(), 9);
+    match doc.get(obj["lineBreaks"]) {
+        JsonValue::String(s) => assert_eq!(s, "Look, Mom! No \\n's!"),
+        other => panic!("expected a string, got {other:?}"),
+    }
+    assert!(parse_value(src).is_err());
+}
+
+#[test]
+fn numbers() {
+    assert_eq!(number("0xFF"), 255.0);
+    assert_eq!(number("-0x10"), -16.0);
+    assert_eq!(number("+.5"), 0.5);
+    assert_eq!(number("5."), 5.0);
+    assert_eq!(number("Infinity"), f64::INFINITY);
+    assert_eq!(number("-Infinity"), f64::NEG_INFINITY);
+    assert!(number("+NaN").is_nan());
+    assert!(number("-NaN").is_nan());
+    assert!(matches!(
+        parse_value_with("0x", json5()),
+        Err(ParseError::InvalidNumber { .. })
+    ));
+    assert!(matches!(
+        parse_value_with("-Inf", json5()),
+        Err(ParseError::InvalidNumber { .. })
+    ));
+}
+
+#[test]
+fn string_escapes() {
+    assert_eq!(string(r"'it\'s \x41\v\0'"), "it's A\u{b}\0");
+    assert_eq!(string("'a\\\r\nb'"), "ab");
+    assert_eq!(string(r#"'say "hi"'"#), "say \"hi\"");
+    assert!(matches!(
+        parse_value_with(r"'\01'", json5()),
+        Err(ParseError::InvalidEscape { .. })
+    ));
+}
+
+#[test]
+fn identifiers_are_only_keys() {
+    assert!(matches!(
+        parse_value_with("[undefined]", json5()),
+        Err(ParseError::UnexpectedToken { .. })
+    ));
+}
+
+#[test]
+fn strict_wins() {
+    let opts = ParseOptions {
+        strict: true,
+        ..json5()
+    };
+    assert!(parse_value_with("{a: 1}", opts).is_err());
+    assert!(matches!(
+        parse_value_with("[1,]", opts),
+        Err(ParseError::TrailingComma { .. })
+    ));
+}
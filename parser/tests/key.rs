@@ -0,0 +1,55 @@
+use jsonparser::{parse_value, Document, JsonValue, Key};
+
+fn keys(doc: &Document, pointer: &str) -> Vec<Key> {
+    let JsonValue::Object(obj) = doc.pointer(pointer).unwrap() else {
+        panic!("expected an object at {pointer:?}");
+    };
+    obj.keys().cloned().collect()
+}
+
+#[test]
+fn repeated_keys_share_their_text() {
+    let doc = parse_value(
+        r#"[{"timestamp": 1, "value": 2}, {"timestamp": 3, "value": 4}, {"nested": {"value": 5}}]"#,
+    )
+    .unwrap();
+    let find = |pointer, name| {
+        keys(&doc, pointer)
+            .into_iter()
+            .find(|key| key == name)
+            .unwrap()
+    };
+    let first = find("/0", "value");
+    for pointer in ["/1", "/2/nested"] {
+        assert_eq!(find(pointer, "value").as_ptr(), first.as_ptr());
+    }
+    assert_eq!(
+        find("/1", "timestamp").as_ptr(),
+        find("/0", "timestamp").as_ptr()
+    );
+    let other = parse_value(r#"{"value": 0}"#).unwrap();
+    assert_ne!(keys(&other, "")[0].as_ptr(), first.as_ptr());
+}
+
+#[test]
+fn keys_read_as_str() {
+    let key = Key::from("name");
+    assert_eq!(key, "name");
+    assert_eq!("name", key);
+    assert_eq!(key, "name".to_owned());
+    assert_eq!(key.len(), 4);
+    assert_eq!(format!("{key} {key:?}"), r#"name "name""#);
+    assert_eq!(String::from(key.clone()), "name");
+
+    let mut doc = parse_value("{}").unwrap();
+    let mut obj = doc.object_mut("").unwrap();
+    obj.insert("a", JsonValue::Null);
+    obj.insert(String::from("b"), JsonValue::Null);
+    obj.insert(key, JsonValue::Bool(true));
+    assert_eq!(
+        doc.to_canonical_string().unwrap(),
+        r#"{"a":null,"b":null,"name":true}"#
+    );
+    let id = doc.root().as_object().unwrap()["name"];
+    assert_eq!(doc.get(id).as_bool(), Some(true));
+}
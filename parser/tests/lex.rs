@@ -0,0 +1,182 @@
+use jsonparser::{Lex, LexError, Number, ParseOptions, ReadSource, Source, Span, Token};
+
+#[test]
+fn tokens_with_spans() {
+    let tokens: Vec<_> = Lex::new("{\"a\":\n [1, true]}")
+        .map(|t| t.unwrap())
+        .collect();
+    let kinds: Vec<_> = tokens.iter().map(|t| t.token.clone()).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            Token::LBrace,
+            Token::Str("a".to_string()),
+            Token::Colon,
+            Token::LBracket,
+            Token::Num(Number::I64(1)),
+            Token::Comma,
+            Token::True,
+            Token::RBracket,
+            Token::RBrace,
+        ]
+    );
+    assert_eq!(
+        tokens[3].span,
+        Span {
+            start: 7,
+            end: 8,
+            line: 2,
+            column: 2
+        }
+    );
+}
+
+#[test]
+fn strings_with_escapes_span_the_whole_literal() {
+    let tok = Lex::new(r#" "a\nb\u0041" "#).next().unwrap().unwrap();
+    assert_eq!(tok.token, Token::Str("a\nbA".to_string()));
+    assert_eq!(
+        tok.span,
+        Span {
+            start: 1,
+            end: 13,
+            line: 1,
+            column: 2
+        }
+    );
+}
+
+#[test]
+fn stops_after_an_error() {
+    let mut lex = Lex::new("[nope, 1]");
+    assert_eq!(lex.next().unwrap().unwrap().token, Token::LBracket);
+    assert!(matches!(
+        lex.next(),
+        Some(Err(LexError::InvalidLiteral { ref text, .. })) if text == "nope"
+    ));
+    assert!(lex.next().is_none());
+}
+
+#[test]
+fn unterminated_string() {
+    let err = jsonparser::parse_value("{\"key\": \"unterminated").unwrap_err();
+    assert_eq!(err.kind(), jsonparser::ErrorKind::Eof);
+    assert!(matches!(
+        err,
+        jsonparser::ParseError::UnterminatedString {
+            span: Span {
+                start: 8,
+                end: 9,
+                line: 1,
+                column: 9
+            }
+        }
+    ));
+    assert!(matches!(
+        Lex::new("\"abc\\\"").last(),
+        Some(Err(LexError::UnterminatedString { .. }))
+    ));
+}
+
+#[test]
+fn control_characters() {
+    use jsonparser::{parse_value_with, ParseError, ParseOptions};
+
+    let src = "[\"tab\there\"]";
+    let err = jsonparser::parse_value(src).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::ControlCharacter { chr: '\t', span } if span.start == 5 && span.end == 6
+    ));
+
+    let lenient = ParseOptions {
+        allow_control_characters: true,
+        ..ParseOptions::default()
+    };
+    assert!(parse_value_with(src, lenient).is_ok());
+    let strict = ParseOptions {
+        strict: true,
+        ..lenient
+    };
+    assert!(parse_value_with(src, strict).is_err());
+}
+
+// A source that only goes byte by byte, without `buffered`.
+struct Bytes<'a>(&'a [u8]);
+
+impl Source for Bytes<'_> {
+    fn peek(&mut self) -> Option<u8> {
+        self.0.first().copied()
+    }
+
+    fn bump(&mut self) -> Option<u8> {
+        let (&byte, rest) = self.0.split_first()?;
+        self.0 = rest;
+        Some(byte)
+    }
+}
+
+#[test]
+fn runs_match_single_bytes() {
+    let src = "{\n  \"naïve ☕\": [12, -345, 0, -0, 007, 1.5e3, true, false, null],\n\t\"b\": \"x\\ty\"  \r\n}  ";
+    let slice: Vec<_> = Lex::new(src).collect();
+    let bytes: Vec<_> = Lex::from_source(Bytes(src.as_bytes()), ParseOptions::default()).collect();
+    assert_eq!(slice, bytes);
+    for capacity in [1, 2, 3, 7] {
+        let source = ReadSource::with_capacity(capacity, src.as_bytes());
+        let read: Vec<_> = Lex::from_source(source, ParseOptions::default()).collect();
+        assert_eq!(slice, read, "capacity {capacity}");
+    }
+    let last = slice.last().unwrap().as_ref().unwrap();
+    assert_eq!((last.span.line, last.span.column), (4, 1));
+    assert!(slice.iter().all(Result::is_ok));
+    let key = slice[1].as_ref().unwrap();
+    assert_eq!(key.token, Token::Str("naïve ☕".to_owned()));
+    assert_eq!((key.span.start, key.span.line, key.span.column), (4, 2, 3));
+    assert_eq!(slice[4].as_ref().unwrap().span.column, 15);
+}
+
+// Long enough to cross the blocks `simd` scans strings in, with the quote,
+// escape or control character that ends each run at every offset.
+#[test]
+fn long_strings_match_single_bytes() {
+    let text: String = "abcé☕xyz".chars().cycle().take(80).collect();
+    for (at, _) in text.char_indices() {
+        for stop in ["\"", "\\n", "\n"] {
+            let src = format!("[\"{}{stop}{}\", 1]", &text[..at], &text[at..]);
+            let slice: Vec<_> = Lex::new(&src).collect();
+            let bytes: Vec<_> =
+                Lex::from_source(Bytes(src.as_bytes()), ParseOptions::default()).collect();
+            assert_eq!(slice, bytes, "{src:?}");
+        }
+    }
+}
+
+#[test]
+fn escapes() {
+    let string = |src: &str| Lex::new(src).next().unwrap();
+    assert_eq!(
+        string(r#""\ud83d\ude00 \u00e9\/""#).unwrap().token,
+        Token::Str("\u{1f600} é/".to_string())
+    );
+    for (src, bad) in [
+        // A high surrogate with nothing after it,
+        (r#""a\ud83d""#, r"\ud83d"),
+        // a low one on its own,
+        (r#""\ude00x""#, r"\ude00"),
+        // a high one followed by another kind of escape,
+        (r#""\ud83d\n""#, r"\ud83d\"),
+        // or by a second high one.
+        (r#""\ud83d\ud83d""#, r"\ud83d\ud83d"),
+        (r#""x\q""#, r"\q"),
+        (r#""\u12g4""#, r"\u12"),
+    ] {
+        match string(src) {
+            Err(LexError::InvalidEscape { sequence, span }) => {
+                assert_eq!(sequence, bad, "{src}");
+                assert_eq!(&src[span.start..span.end], bad, "{src}");
+            }
+            other => panic!("{src}: {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use jsonparser::{parse_reader, parse_value_with, ErrorKind, ParseError, ParseOptions};
+
+#[test]
+fn max_string_length() {
+    let opts = ParseOptions {
+        max_string_length: Some(4),
+        ..ParseOptions::default()
+    };
+    assert!(parse_value_with(r#"["abcd", "éé"]"#, opts).is_ok());
+    let err = parse_value_with(r#"["abcd", "abcde"]"#, opts).unwrap_err();
+    assert!(matches!(err, ParseError::StringTooLong { max: 4, span } if span.start == 9));
+    assert_eq!(err.kind(), ErrorKind::Limit);
+    assert!(matches!(
+        parse_value_with(r#""ééé""#, opts),
+        Err(ParseError::StringTooLong { .. })
+    ));
+}
+
+#[test]
+fn max_document_size() {
+    let opts = ParseOptions {
+        max_document_size: Some(8),
+        ..ParseOptions::default()
+    };
+    assert!(parse_value_with("[1, 2] ", opts).is_ok());
+    let err = parse_value_with("[1, 2, 3]", opts).unwrap_err();
+    assert!(matches!(err, ParseError::DocumentTooLarge { max: 8, .. }));
+    assert_eq!(err.kind(), ErrorKind::Limit);
+}
+
+#[test]
+fn limits_apply_to_readers() {
+    let opts = ParseOptions {
+        max_document_size: Some(1 << 20),
+        max_string_length: Some(1024),
+        ..ParseOptions::default()
+    };
+    // An endless string must stop at the limit rather than exhaust memory.
+    let endless = std::io::repeat(b'a');
+    let src = std::io::Read::chain(&b"\""[..], endless);
+    assert!(matches!(
+        jsonparser::Par::parse_reader(src, opts),
+        Err(ParseError::StringTooLong { .. })
+    ));
+    let src = std::io::Read::chain(&b"["[..], std::io::repeat(b' '));
+    assert!(matches!(
+        jsonparser::Par::parse_reader(src, opts),
+        Err(ParseError::DocumentTooLarge { .. })
+    ));
+    assert!(parse_reader(&b"[1]"[..]).is_ok());
+}
@@ -0,0 +1,47 @@
+use std::io::{BufReader, Read};
+
+use jsonparser::{parse_lines, JsonValue, ParseError};
+
+#[test]
+fn one_document_per_line() {
+    let src = "{\"a\": 1}\n\n  \r\n[true]\r\n\"last\"";
+    let docs: Vec<_> = parse_lines(src.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(docs.len(), 3);
+    assert!(matches!(docs[1].root(), JsonValue::List(l) if l.len() == 1));
+    assert!(matches!(docs[2].root(), JsonValue::String(s) if s == "last"));
+}
+
+#[test]
+fn errors_report_the_stream_line() {
+    let src = "1\n\n[1,\n2\n";
+    let results: Vec<_> = parse_lines(src.as_bytes()).collect();
+    assert_eq!(results.len(), 3);
+    let err = results[1].as_ref().unwrap_err();
+    assert_eq!(err.span().line, 3);
+    assert_eq!(err.span().start, 6);
+    assert!(results[2].is_ok());
+}
+
+#[test]
+fn one_value_per_line() {
+    let results: Vec<_> = parse_lines("1 2\n".as_bytes()).collect();
+    assert!(matches!(results[0], Err(ParseError::TrailingData { .. })));
+}
+
+struct Broken;
+
+impl Read for Broken {
+    fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::other("unplugged"))
+    }
+}
+
+#[test]
+fn read_errors_end_the_stream() {
+    let mut lines = parse_lines(BufReader::new("1\n".as_bytes().chain(Broken)));
+    assert!(lines.next().unwrap().is_ok());
+    assert!(matches!(lines.next(), Some(Err(ParseError::Io { .. }))));
+    assert!(lines.next().is_none());
+}
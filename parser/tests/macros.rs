@@ -0,0 +1,32 @@
+use jsonparser::{json, parse_value, Document, OwnedValue};
+
+#[test]
+fn builds_nested_values() {
+    let name = "ann";
+    let key = String::from("dynamic");
+    let value = json!({
+        "name": name,
+        "tags": [1, 2, 3],
+        "nested": {"list": [[], {}, null, true], "n": 1 + 2},
+        (key): null,
+        "opt": Some("x"),
+    });
+    let expected = parse_value(
+        r#"{"name": "ann", "tags": [1, 2, 3],
+            "nested": {"list": [[], {}, null, true], "n": 3},
+            "dynamic": null, "opt": "x"}"#,
+    )
+    .unwrap();
+    assert!(Document::from(value).deep_eq(&expected));
+}
+
+#[test]
+fn scalars_and_trailing_commas() {
+    assert_eq!(json!(null), OwnedValue::Null);
+    assert_eq!(json!("s"), OwnedValue::String("s".into()));
+    assert_eq!(json!([]), OwnedValue::List(Vec::new()));
+    assert_eq!(json!([1, null,]), json!([1, null]));
+    assert_eq!(json!({"a": [],}), json!({"a": []}));
+    let xs = [10, 20];
+    assert_eq!(json!([xs[0], xs[1] + 1]), json!([10, 21]));
+}
@@ -0,0 +1,95 @@
+use jsonparser::{parse_value, ArrayMerge, MergeStrategy, NullMerge};
+
+fn merge(base: &str, other: &str, strategy: MergeStrategy) -> String {
+    let mut doc = parse_value(base).unwrap();
+    doc.deep_merge(&parse_value(other).unwrap(), strategy);
+    doc.to_canonical_string().unwrap()
+}
+
+fn arrays(arrays: ArrayMerge) -> MergeStrategy {
+    MergeStrategy {
+        arrays,
+        ..MergeStrategy::default()
+    }
+}
+
+#[test]
+fn objects_merge_member_by_member() {
+    assert_eq!(
+        merge(
+            r#"{"db": {"host": "localhost", "port": 5432}, "debug": true}"#,
+            r#"{"db": {"host": "prod.example"}, "debug": false, "workers": 8}"#,
+            MergeStrategy::default(),
+        ),
+        r#"{"db":{"host":"prod.example","port":5432},"debug":false,"workers":8}"#
+    );
+    // Anything but two objects or two lists is replaced.
+    assert_eq!(
+        merge(
+            r#"{"a": {"b": 1}}"#,
+            r#"{"a": [1]}"#,
+            MergeStrategy::default()
+        ),
+        r#"{"a":[1]}"#
+    );
+    assert_eq!(merge(r#"{"a": 1}"#, "2", MergeStrategy::default()), "2");
+}
+
+#[test]
+fn nulls() {
+    let base = r#"{"a": 1, "b": {"c": 2}}"#;
+    let other = r#"{"a": null, "b": {"c": null}, "d": null}"#;
+    let nulls = |nulls| MergeStrategy {
+        nulls,
+        ..MergeStrategy::default()
+    };
+    assert_eq!(
+        merge(base, other, nulls(NullMerge::Set)),
+        r#"{"a":null,"b":{"c":null},"d":null}"#
+    );
+    assert_eq!(merge(base, other, nulls(NullMerge::Remove)), r#"{"b":{}}"#);
+    assert_eq!(
+        merge(base, other, nulls(NullMerge::Ignore)),
+        r#"{"a":1,"b":{"c":2}}"#
+    );
+}
+
+#[test]
+fn lists() {
+    let base = r#"{"tags": ["a", "b"], "n": [[1]]}"#;
+    let other = r#"{"tags": ["b", "c"], "n": [[1], [2]]}"#;
+    assert_eq!(
+        merge(base, other, arrays(ArrayMerge::Replace)),
+        r#"{"n":[[1],[2]],"tags":["b","c"]}"#
+    );
+    assert_eq!(
+        merge(base, other, arrays(ArrayMerge::Append)),
+        r#"{"n":[[1],[1],[2]],"tags":["a","b","b","c"]}"#
+    );
+    assert_eq!(
+        merge(base, other, arrays(ArrayMerge::Union)),
+        r#"{"n":[[1],[2]],"tags":["a","b","c"]}"#
+    );
+}
+
+#[test]
+fn lists_by_key() {
+    let base = r#"[{"id": 1, "name": "a", "on": true}, {"id": 2, "name": "b"}, 3]"#;
+    let other = r#"[{"id": 2, "name": "B"}, {"id": 4}, {"name": "x"}, 3]"#;
+    assert_eq!(
+        merge(base, other, arrays(ArrayMerge::ByKey("id".into()))),
+        concat!(
+            r#"[{"id":1,"name":"a","on":true},{"id":2,"name":"B"},3,"#,
+            r#"{"id":4},{"name":"x"},3]"#
+        )
+    );
+}
+
+#[test]
+fn merged_values_are_copies() {
+    let mut doc = parse_value(r#"{"a": {}}"#).unwrap();
+    let other = parse_value(r#"{"a": {"b": [1, {"c": 2}]}}"#).unwrap();
+    doc.deep_merge(&other, MergeStrategy::default());
+    drop(other);
+    assert_eq!(doc.pointer("/a/b/1/c").and_then(|v| v.as_i64()), Some(2));
+}
@@ -0,0 +1,38 @@
+use jsonparser::{minify, minify_with, ParseError, ParseOptions};
+
+#[test]
+fn strips_whitespace() {
+    let src = "{\n  \"a b\" : [ 1.50 , -2e3 ,\ttrue ],\r\n  \"c\": \"\\u0041 \\n\"\n}\n";
+    assert_eq!(
+        minify(src).unwrap(),
+        r#"{"a b":[1.50,-2e3,true],"c":"\u0041 \n"}"#
+    );
+    assert_eq!(minify("  null  ").unwrap(), "null");
+    assert_eq!(minify("").unwrap(), "");
+}
+
+#[test]
+fn keeps_tokens_apart() {
+    assert_eq!(minify("1 2 true\n\"x\" null").unwrap(), "1 2 true\"x\"null");
+}
+
+#[test]
+fn comments() {
+    let src = "// config\n{\"a\": /* one */ 1, /* */ \"b\": [2 /**/, 3]}";
+    assert!(matches!(
+        minify(src),
+        Err(ParseError::UnexpectedToken { .. })
+    ));
+    let opts = ParseOptions {
+        allow_comments: true,
+        ..ParseOptions::default()
+    };
+    assert_eq!(minify_with(src, opts).unwrap(), r#"{"a":1,"b":[2,3]}"#);
+}
+
+#[test]
+fn lexical_errors() {
+    assert!(minify(r#"["\x"]"#).is_err());
+    assert!(minify(r#"["open"#).is_err());
+    assert!(minify("[tru]").is_err());
+}
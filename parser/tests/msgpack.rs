@@ -0,0 +1,107 @@
+#![cfg(feature = "msgpack")]
+
+use jsonparser::{parse_msgpack, parse_value, parse_value_with, BinaryError, ParseOptions};
+
+fn encode(src: &str) -> Vec<u8> {
+    parse_value(src).unwrap().to_msgpack()
+}
+
+#[test]
+fn encodings() {
+    assert_eq!(encode(r#"{"compact": true}"#), b"\x81\xa7compact\xc3");
+    assert_eq!(
+        encode("[null, false, 1, -1]"),
+        [0x94, 0xc0, 0xc2, 0x01, 0xff]
+    );
+    for (src, bytes) in [
+        ("127", &[0x7f][..]),
+        ("128", &[0xcc, 0x80]),
+        ("65535", &[0xcd, 0xff, 0xff]),
+        ("65536", &[0xce, 0, 1, 0, 0]),
+        (
+            "18446744073709551615",
+            &[0xcf, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+        ),
+        ("-32", &[0xe0]),
+        ("-33", &[0xd0, 0xdf]),
+        ("-129", &[0xd1, 0xff, 0x7f]),
+        ("-9223372036854775808", &[0xd3, 0x80, 0, 0, 0, 0, 0, 0, 0]),
+        ("1.5", &[0xcb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0]),
+    ] {
+        assert_eq!(encode(src), bytes, "{src}");
+    }
+    assert_eq!(encode(&format!("{:?}", "x".repeat(32)))[..2], [0xd9, 32]);
+    assert_eq!(encode(&format!("{:?}", "x".repeat(256)))[..3], [0xda, 1, 0]);
+    let list = format!("[{}]", vec!["0"; 16].join(","));
+    assert_eq!(encode(&list)[..3], [0xdc, 0, 16]);
+}
+
+#[test]
+fn round_trips() {
+    let long = "é".repeat(40_000);
+    let many = vec!["[]"; 70_000].join(",");
+    for src in [
+        r#"{"a": [1, -2, 3.25, {"b": null}], "c": "", "d": {}}"#.to_owned(),
+        "[18446744073709551615, -9223372036854775808, 1e300, 1.0]".to_owned(),
+        format!("[{long:?}, [{many}]]"),
+    ] {
+        let doc = parse_value(&src).unwrap();
+        let back = parse_msgpack(&doc.to_msgpack()).unwrap();
+        assert!(back.deep_eq_with(
+            &doc,
+            jsonparser::EqOptions {
+                numbers_by_value: true
+            }
+        ));
+    }
+}
+
+#[test]
+fn decodes() {
+    let doc = parse_msgpack(
+        b"\x83\xa1a\xca\x3f\xc0\x00\x00\x01\xc4\x03\xfb\xff\x00\xd0\x80\x92\xd1\xff\x7f\xc3",
+    )
+    .unwrap();
+    assert_eq!(doc.value().at("a").as_f64(), Some(1.5));
+    // Binary is base64url, and integer keys their text.
+    assert_eq!(doc.value().at("1").as_str(), Some("-_8A"));
+    assert_eq!(doc.value().at("-128").to_string(), "[-129,true]");
+}
+
+#[test]
+fn errors() {
+    for (src, offset, message) in [
+        (&b""[..], 0, "unexpected end of input"),
+        (b"\x92\x01", 2, "unexpected end of input"),
+        (b"\xc1", 0, "a reserved byte"),
+        (
+            b"\xd4\x01\x00",
+            0,
+            "an extension type, which JSON has no value for",
+        ),
+        (b"\xa2\xff\xfe", 0, "a string that isn't UTF-8"),
+        (
+            b"\x81\x90\x01",
+            1,
+            "a map key that isn't a string or an integer",
+        ),
+        (b"\x01\x02", 1, "more after the value"),
+        (b"\xdd\xff\xff\xff\xff", 5, "unexpected end of input"),
+    ] {
+        assert_eq!(
+            parse_msgpack(src),
+            Err(BinaryError { offset, message }),
+            "{src:?}"
+        );
+    }
+}
+
+#[test]
+fn raw_values_are_strings_of_their_text() {
+    let opts = ParseOptions {
+        raw_keys: &["p"],
+        ..ParseOptions::default()
+    };
+    let doc = parse_value_with(r#"{"p": [1,  2]}"#, opts).unwrap();
+    assert_eq!(doc.to_msgpack(), b"\x81\xa1p\xa7[1,  2]");
+}
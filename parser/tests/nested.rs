@@ -0,0 +1,103 @@
+use jsonparser::{
+    parse_many, parse_value, parse_value_with, Document, ErrorKind, JsonValue, ParseError,
+    ParseOptions,
+};
+
+// Compact rendering with sorted keys, so assertions don't depend on map order.
+fn render(doc: &Document, value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) => format!("{s:?}"),
+        JsonValue::Number(n) => n.to_string(),
+        JsonValue::Bool(b) => b.to_string(),
+        JsonValue::Null => "null".to_string(),
+        JsonValue::Raw(range) => format!("raw{range:?}"),
+        JsonValue::List(list) => {
+            let items: Vec<_> = list.iter().map(|id| render(doc, doc.get(*id))).collect();
+            format!("[{}]", items.join(","))
+        }
+        JsonValue::Object(obj) => {
+            let mut entries: Vec<_> = obj
+                .iter()
+                .map(|(k, id)| format!("{k:?}:{}", render(doc, doc.get(*id))))
+                .collect();
+            entries.sort();
+            format!("{{{}}}", entries.join(","))
+        }
+    }
+}
+
+fn roundtrip(src: &str) -> String {
+    let doc = parse_value(src).unwrap();
+    render(&doc, doc.root())
+}
+
+#[test]
+fn nested_lists() {
+    assert_eq!(roundtrip("[[1,2],[3]]"), "[[1,2],[3]]");
+    assert_eq!(roundtrip("[[[]], [], [[1], 2]]"), "[[[]],[],[[1],2]]");
+}
+
+#[test]
+fn nested_objects() {
+    assert_eq!(
+        roundtrip(r#"{"a": {"b": {"c": 1}}, "d": {"e": 2}}"#),
+        r#"{"a":{"b":{"c":1}},"d":{"e":2}}"#
+    );
+}
+
+#[test]
+fn mixed_nesting() {
+    assert_eq!(
+        roundtrip(r#"[{"a": [1, {"b": []}]}, {}, [{"c": null}]]"#),
+        r#"[{"a":[1,{"b":[]}]},{},[{"c":null}]]"#
+    );
+}
+
+#[test]
+fn sibling_containers_after_nested_ones() {
+    assert_eq!(
+        roundtrip(r#"{"x": [1, [2, 3]], "y": [4], "z": {"w": [5]}}"#),
+        r#"{"x":[1,[2,3]],"y":[4],"z":{"w":[5]}}"#
+    );
+}
+
+#[test]
+fn unbalanced_containers() {
+    for src in [
+        "[[1, 2]",
+        "[1, 2]]",
+        r#"{"a": {"b": 1}"#,
+        "[1 2]",
+        r#"{"a" 1}"#,
+    ] {
+        assert!(parse_value(src).is_err(), "{src}");
+    }
+}
+
+#[test]
+fn deep_nesting_does_not_overflow() {
+    let depth = 100_000;
+    let src = "[".repeat(depth) + &"]".repeat(depth);
+    let doc = parse_value(&src).unwrap();
+    assert_eq!(doc.descendants().count(), depth);
+}
+
+#[test]
+fn max_depth() {
+    let opts = ParseOptions {
+        max_depth: Some(3),
+        ..ParseOptions::default()
+    };
+    assert!(parse_value_with("[[{}]]", opts).is_ok());
+    let err = parse_value_with(r#"[[{"a": []}]]"#, opts).unwrap_err();
+    assert!(matches!(err, ParseError::DepthExceeded { max: 3, .. }));
+    assert_eq!(err.kind(), ErrorKind::Limit);
+}
+
+#[test]
+fn trailing_content() {
+    let err = parse_value("[1] [2]").unwrap_err();
+    assert!(matches!(err, ParseError::TrailingData { .. }));
+    assert_eq!(parse_many("[1] [2]").unwrap().len(), 2);
+    assert_eq!(parse_many("[1], {}, 3").unwrap().len(), 3);
+}
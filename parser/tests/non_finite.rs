@@ -0,0 +1,45 @@
+use jsonparser::{parse_value, parse_value_with, JsonValue, ParseError, ParseOptions};
+
+fn python() -> ParseOptions {
+    ParseOptions {
+        allow_non_finite_numbers: true,
+        ..ParseOptions::default()
+    }
+}
+
+#[test]
+fn non_finite_literals() {
+    let doc = parse_value_with("[NaN, Infinity, -Infinity]", python()).unwrap();
+    let JsonValue::List(list) = doc.root() else {
+        panic!("expected a list");
+    };
+    let numbers: Vec<f64> = list
+        .iter()
+        .map(|id| match doc.get(*id) {
+            JsonValue::Number(n) => n.as_f64(),
+            other => panic!("expected a number, got {other:?}"),
+        })
+        .collect();
+    assert!(numbers[0].is_nan());
+    assert_eq!(numbers[1..], [f64::INFINITY, f64::NEG_INFINITY]);
+}
+
+#[test]
+fn off_by_default() {
+    assert!(matches!(
+        parse_value("NaN"),
+        Err(ParseError::UnexpectedToken { .. })
+    ));
+    assert!(matches!(
+        parse_value("-Infinity"),
+        Err(ParseError::InvalidNumber { .. })
+    ));
+    assert!(matches!(
+        parse_value_with("-Inf", python()),
+        Err(ParseError::InvalidNumber { .. })
+    ));
+    assert!(matches!(
+        parse_value_with("[-NaN]", python()),
+        Err(ParseError::InvalidNumber { lexeme, .. }) if lexeme == "-NaN"
+    ));
+}
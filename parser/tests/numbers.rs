@@ -0,0 +1,118 @@
+use jsonparser::{parse_value, parse_value_with, JsonValue, Number, ParseOptions};
+
+fn number(src: &str) -> f64 {
+    let doc = parse_value(src).unwrap();
+    match doc.root() {
+        JsonValue::Number(n) => n.as_f64(),
+        other => panic!("expected a number, got {other:?}"),
+    }
+}
+
+#[test]
+fn negative_zero() {
+    let n = number("-0");
+    assert_eq!(n, 0.0);
+    assert!(n.is_sign_negative());
+}
+
+#[test]
+fn negative_integer() {
+    assert_eq!(number("-12"), -12.0);
+}
+
+#[test]
+fn negative_float() {
+    assert_eq!(number("-3.25"), -3.25);
+}
+
+#[test]
+fn negative_object_value() {
+    let doc = parse_value(r#"{"temp": -4.5}"#).unwrap();
+    let temp = doc.children(doc.root()).next().unwrap();
+    assert!(matches!(temp, JsonValue::Number(n) if n.as_f64() == -4.5));
+}
+
+#[test]
+fn lone_minus_is_invalid() {
+    assert!(parse_value("-").is_err());
+}
+
+#[test]
+fn exponents() {
+    assert_eq!(number("1e10"), 1e10);
+    assert_eq!(number("6.022e23"), 6.022e23);
+    assert_eq!(number("2E-5"), 2e-5);
+    assert_eq!(number("-1.5e+3"), -1500.0);
+}
+
+#[test]
+fn malformed_exponents() {
+    for src in ["1e", "1e+", "1E-", "2.5e.1"] {
+        let err = parse_value(src).unwrap_err();
+        assert!(
+            matches!(err, jsonparser::ParseError::InvalidNumber { .. }),
+            "{src}: {err}"
+        );
+    }
+}
+
+fn exact(src: &str) -> Number {
+    match parse_value(src).unwrap().into_parts().0 {
+        JsonValue::Number(n) => n,
+        other => panic!("expected a number, got {other:?}"),
+    }
+}
+
+#[test]
+fn integers_are_exact() {
+    assert_eq!(exact("9007199254740993"), Number::I64(9007199254740993));
+    assert_eq!(exact("-9223372036854775808").as_i64(), Some(i64::MIN));
+    assert_eq!(exact("18446744073709551615"), Number::U64(u64::MAX));
+    assert_eq!(exact("-0").as_i64(), None);
+    assert_eq!(exact("42").as_u64(), Some(42));
+    assert_eq!(exact("-1").as_u64(), None);
+    assert_eq!(exact("9007199254740993").to_string(), "9007199254740993");
+}
+
+#[test]
+#[cfg(not(feature = "arbitrary-precision"))]
+fn fractions_and_huge_integers_are_floats() {
+    assert_eq!(
+        exact("18446744073709551616"),
+        Number::F64(18446744073709551616.0)
+    );
+    assert_eq!(exact("2.0"), Number::F64(2.0));
+}
+
+// Too large for a float, so only non-finite numbers let them in; with
+// `arbitrary-precision` the text is kept instead.
+#[test]
+fn overflowing_literals() {
+    let strict = ParseOptions {
+        strict: true,
+        ..ParseOptions::default()
+    };
+    for src in ["1e400", "-1e999", "[1.5e309]"] {
+        for opts in [ParseOptions::default(), strict] {
+            let parsed = parse_value_with(src, opts);
+            #[cfg(not(feature = "arbitrary-precision"))]
+            assert!(
+                matches!(parsed, Err(jsonparser::ParseError::InvalidNumber { .. })),
+                "{src}: {parsed:?}"
+            );
+            #[cfg(feature = "arbitrary-precision")]
+            assert_eq!(parsed.unwrap().to_string(), src);
+        }
+    }
+}
+
+#[test]
+#[cfg(not(feature = "arbitrary-precision"))]
+fn overflowing_literals_as_non_finite() {
+    let opts = ParseOptions {
+        allow_non_finite_numbers: true,
+        ..ParseOptions::default()
+    };
+    let doc = parse_value_with("-1e999", opts).unwrap();
+    assert_eq!(doc.root().as_f64(), Some(f64::NEG_INFINITY));
+}
@@ -0,0 +1,28 @@
+use jsonparser::IndexMap;
+
+#[test]
+fn index_map_keeps_insertion_order() {
+    let mut map: IndexMap<String, i32> = [("z", 1), ("a", 2), ("m", 3)]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+    assert_eq!(map.insert("a".to_string(), 20), Some(2));
+    assert_eq!(map.remove("z"), Some(1));
+    map.insert("b".to_string(), 4);
+    let entries: Vec<_> = map.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+    assert_eq!(entries, [("a", 20), ("m", 3), ("b", 4)]);
+    assert_eq!(map["m"], 3);
+    assert!(!map.contains_key("z"));
+}
+
+#[test]
+#[cfg(feature = "preserve-order")]
+fn objects_keep_source_order() {
+    let doc =
+        jsonparser::parse_value(r#"{"zeta": 1, "alpha": 2, "mid": {"y": 0, "b": 0}}"#).unwrap();
+    let jsonparser::JsonValue::Object(obj) = doc.root() else {
+        panic!("expected an object");
+    };
+    let keys: Vec<_> = obj.keys().map(jsonparser::Key::as_str).collect();
+    assert_eq!(keys, ["zeta", "alpha", "mid"]);
+}
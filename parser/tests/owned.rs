@@ -0,0 +1,46 @@
+use jsonparser::{parse_value, Document, Map, Number, OwnedValue};
+
+#[test]
+fn round_trip() {
+    let src = r#"{"name": "ann", "tags": [1, -3, [], {}], "extra": {"ok": true, "none": null}}"#;
+    let doc = parse_value(src).unwrap();
+    let tree = doc.to_owned_tree();
+    let OwnedValue::Object(obj) = &tree else {
+        panic!("expected an object");
+    };
+    assert_eq!(obj["name"], OwnedValue::String("ann".into()));
+    assert_eq!(
+        obj["tags"],
+        OwnedValue::List(vec![
+            OwnedValue::Number(Number::I64(1)),
+            OwnedValue::Number(Number::I64(-3)),
+            OwnedValue::List(Vec::new()),
+            OwnedValue::Object(Map::new()),
+        ])
+    );
+    let back = Document::from_owned(tree.clone());
+    assert!(back.deep_eq(&doc));
+    assert_eq!(back.to_owned_tree(), tree);
+    assert_eq!(back.arena().len(), doc.arena().len());
+}
+
+#[test]
+fn subtrees_and_scalars() {
+    let doc = parse_value(r#"{"a": {"b": [null]}}"#).unwrap();
+    let sub = doc.value().at("a").to_owned_tree();
+    assert_eq!(
+        Document::from(sub).to_owned_tree(),
+        parse_value(r#"{"b": [null]}"#).unwrap().to_owned_tree()
+    );
+    let scalar = Document::from_owned(OwnedValue::Bool(true));
+    assert_eq!(scalar.root().as_bool(), Some(true));
+    assert!(scalar.arena().is_empty());
+}
+
+#[test]
+fn deep_nesting() {
+    let src = format!("{}{}", "[".repeat(1000), "]".repeat(1000));
+    let doc = parse_value(&src).unwrap();
+    let back = Document::from_owned(OwnedValue::from(&doc));
+    assert!(back.deep_eq(&doc));
+}
@@ -0,0 +1,174 @@
+use jsonparser::{parse_value, PatchError};
+
+// Applies `patch` to `src` and checks the result against `expected`.
+fn check(src: &str, patch: &str, expected: &str) {
+    let mut doc = parse_value(src).unwrap();
+    doc.apply_patch(&parse_value(patch).unwrap()).unwrap();
+    assert!(
+        doc.deep_eq(&parse_value(expected).unwrap()),
+        "{patch} gave {doc}"
+    );
+}
+
+fn error(src: &str, patch: &str) -> PatchError {
+    let mut doc = parse_value(src).unwrap();
+    let before = doc.to_string();
+    let e = doc.apply_patch(&parse_value(patch).unwrap()).unwrap_err();
+    assert_eq!(doc.to_string(), before, "{patch} changed the document");
+    e
+}
+
+// The examples from RFC 6902's appendix A.
+#[test]
+fn rfc_6902_examples() {
+    check(
+        r#"{"foo": "bar"}"#,
+        r#"[{"op": "add", "path": "/baz", "value": "qux"}]"#,
+        r#"{"baz": "qux", "foo": "bar"}"#,
+    );
+    check(
+        r#"{"foo": ["bar", "baz"]}"#,
+        r#"[{"op": "add", "path": "/foo/1", "value": "qux"}]"#,
+        r#"{"foo": ["bar", "qux", "baz"]}"#,
+    );
+    check(
+        r#"{"baz": "qux", "foo": "bar"}"#,
+        r#"[{"op": "remove", "path": "/baz"}]"#,
+        r#"{"foo": "bar"}"#,
+    );
+    check(
+        r#"{"foo": ["bar", "qux", "baz"]}"#,
+        r#"[{"op": "remove", "path": "/foo/1"}]"#,
+        r#"{"foo": ["bar", "baz"]}"#,
+    );
+    check(
+        r#"{"baz": "qux", "foo": "bar"}"#,
+        r#"[{"op": "replace", "path": "/baz", "value": "boo"}]"#,
+        r#"{"baz": "boo", "foo": "bar"}"#,
+    );
+    check(
+        r#"{"foo": {"bar": "baz", "waldo": "fred"}, "qux": {"corge": "grault"}}"#,
+        r#"[{"op": "move", "from": "/foo/waldo", "path": "/qux/thud"}]"#,
+        r#"{"foo": {"bar": "baz"}, "qux": {"corge": "grault", "thud": "fred"}}"#,
+    );
+    check(
+        r#"{"foo": ["all", "grass", "cows", "eat"]}"#,
+        r#"[{"op": "move", "from": "/foo/1", "path": "/foo/3"}]"#,
+        r#"{"foo": ["all", "cows", "eat", "grass"]}"#,
+    );
+    check(
+        r#"{"baz": "qux", "foo": ["a", 2, "c"]}"#,
+        r#"[{"op": "test", "path": "/baz", "value": "qux"},
+            {"op": "test", "path": "/foo/1", "value": 2}]"#,
+        r#"{"baz": "qux", "foo": ["a", 2, "c"]}"#,
+    );
+    check(
+        r#"{"foo": "bar"}"#,
+        r#"[{"op": "add", "path": "/child", "value": {"grandchild": {}}}]"#,
+        r#"{"foo": "bar", "child": {"grandchild": {}}}"#,
+    );
+    check(
+        r#"{"foo": "bar"}"#,
+        r#"[{"op": "add", "path": "/baz", "value": "qux", "xyz": 123}]"#,
+        r#"{"foo": "bar", "baz": "qux"}"#,
+    );
+    check(
+        r#"{"foo": ["bar"]}"#,
+        r#"[{"op": "add", "path": "/foo/-", "value": ["abc", "def"]}]"#,
+        r#"{"foo": ["bar", ["abc", "def"]]}"#,
+    );
+    check(
+        r#"{"/": 9, "~1": 10}"#,
+        r#"[{"op": "test", "path": "/~01", "value": 10}]"#,
+        r#"{"/": 9, "~1": 10}"#,
+    );
+}
+
+#[test]
+fn copies_are_independent() {
+    let mut doc = parse_value(r#"{"a": {"b": [1, 2]}}"#).unwrap();
+    let patch = r#"[
+        {"op": "copy", "from": "/a", "path": "/c"},
+        {"op": "add", "path": "/c/b/0", "value": 0},
+        {"op": "replace", "path": "/a/b/1", "value": 3}
+    ]"#;
+    doc.apply_patch(&parse_value(patch).unwrap()).unwrap();
+    let ints = |pointer: &str| -> Vec<i64> {
+        let list = doc.pointer(pointer).unwrap().as_array().unwrap();
+        list.iter()
+            .map(|&id| doc.get(id).as_i64().unwrap())
+            .collect()
+    };
+    assert_eq!(ints("/a/b"), [1, 3]);
+    assert_eq!(ints("/c/b"), [0, 1, 2]);
+}
+
+#[test]
+fn root_operations() {
+    check(
+        r#"{"a": 1}"#,
+        r#"[{"op": "replace", "path": "", "value": [1]}]"#,
+        "[1]",
+    );
+    check(
+        "null",
+        r#"[{"op": "add", "path": "", "value": {"a": 1}},
+            {"op": "move", "from": "/a", "path": "/b"}]"#,
+        r#"{"b": 1}"#,
+    );
+    check("1", r#"[{"op": "test", "path": "", "value": 1.0}]"#, "1");
+}
+
+#[test]
+fn failures_leave_the_document_alone() {
+    let src = r#"{"a": {"b": 1}, "c": [1, 2]}"#;
+    assert_eq!(
+        error(
+            src,
+            r#"[{"op": "remove", "path": "/c/0"},
+                {"op": "test", "path": "/a/b", "value": 2}]"#
+        ),
+        PatchError::Test {
+            index: 1,
+            pointer: "/a/b".into()
+        }
+    );
+    for (patch, pointer) in [
+        (r#"[{"op": "remove", "path": "/x"}]"#, "/x"),
+        (r#"[{"op": "add", "path": "/x/y", "value": 1}]"#, "/x/y"),
+        (r#"[{"op": "add", "path": "/c/3", "value": 1}]"#, "/c/3"),
+        (r#"[{"op": "replace", "path": "/x", "value": 1}]"#, "/x"),
+        (r#"[{"op": "remove", "path": ""}]"#, ""),
+        (
+            r#"[{"op": "move", "from": "/a", "path": "/a/b/c"}]"#,
+            "/a/b/c",
+        ),
+        (r#"[{"op": "copy", "from": "/x", "path": "/y"}]"#, "/x"),
+        (r#"[{"op": "test", "path": "/x", "value": null}]"#, "/x"),
+    ] {
+        assert_eq!(
+            error(src, patch),
+            PatchError::Path {
+                index: 0,
+                pointer: pointer.into()
+            },
+            "{patch}"
+        );
+    }
+    for (patch, index) in [
+        ("{}", None),
+        (r#"[{"op": "add", "path": "/x", "value": 1}, 1]"#, Some(1)),
+        (r#"[{"op": "frob", "path": "/a"}]"#, Some(0)),
+        (r#"[{"op": "add", "path": "/x"}]"#, Some(0)),
+        (r#"[{"op": "move", "path": "/x"}]"#, Some(0)),
+    ] {
+        assert!(
+            matches!(error(src, patch), PatchError::Invalid { index: i, .. } if i == index),
+            "{patch}"
+        );
+    }
+    assert_eq!(
+        error(src, r#"[{"op": "add"}]"#).to_string(),
+        "Invalid patch operation 0: \"path\" must be a string."
+    );
+}
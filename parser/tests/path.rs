@@ -0,0 +1,216 @@
+use jsonparser::{parse_value, Document, JsonPath};
+
+// The bookstore from Goessner's article and RFC 9535.
+const STORE: &str = r#"{ "store": {
+    "book": [
+      { "category": "reference", "author": "Nigel Rees",
+        "title": "Sayings of the Century", "price": 8.95 },
+      { "category": "fiction", "author": "Evelyn Waugh",
+        "title": "Sword of Honour", "price": 12.99 },
+      { "category": "fiction", "author": "Herman Melville",
+        "title": "Moby Dick", "isbn": "0-553-21311-3", "price": 8.99 },
+      { "category": "fiction", "author": "J. R. R. Tolkien",
+        "title": "The Lord of the Rings", "isbn": "0-395-19395-8",
+        "price": 22.99 }
+    ],
+    "bicycle": { "color": "red", "price": 399 }
+  }
+}"#;
+
+// The matched strings, and the paths of every match, sorted where map
+// order would show.
+fn strings(doc: &Document, expr: &str) -> Vec<String> {
+    let matches = doc.query(expr).unwrap();
+    matches
+        .iter()
+        .map(|(_, v)| v.as_str().unwrap().to_owned())
+        .collect()
+}
+
+fn paths(doc: &Document, expr: &str) -> Vec<String> {
+    let mut paths: Vec<_> = doc
+        .query(expr)
+        .unwrap()
+        .into_iter()
+        .map(|(p, _)| p)
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn child_and_wildcard_segments() {
+    let doc = parse_value(STORE).unwrap();
+    let authors = [
+        "Nigel Rees",
+        "Evelyn Waugh",
+        "Herman Melville",
+        "J. R. R. Tolkien",
+    ];
+    assert_eq!(strings(&doc, "$.store.book[*].author"), authors);
+    assert_eq!(strings(&doc, "$['store']['book'][*]['author']"), authors);
+    assert_eq!(
+        paths(&doc, "$.store.book[0].author"),
+        ["$['store']['book'][0]['author']"]
+    );
+    assert_eq!(paths(&doc, "$.store.*").len(), 2);
+    assert_eq!(paths(&doc, "$").len(), 1);
+    assert!(doc.query("$.nope.book").unwrap().is_empty());
+}
+
+#[test]
+fn descendants() {
+    let doc = parse_value(STORE).unwrap();
+    let mut authors = strings(&doc, "$..author");
+    authors.sort();
+    assert_eq!(authors.len(), 4);
+    assert_eq!(authors[0], "Evelyn Waugh");
+    assert_eq!(paths(&doc, "$..price").len(), 5);
+    assert_eq!(
+        paths(&doc, "$..book[2].isbn"),
+        ["$['store']['book'][2]['isbn']"]
+    );
+    // The value itself counts, before what's under it.
+    let doc = parse_value(r#"[[1, [2]], 3]"#).unwrap();
+    assert_eq!(
+        doc.query("$..*")
+            .unwrap()
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect::<Vec<_>>(),
+        ["$[0]", "$[1]", "$[0][0]", "$[0][1]", "$[0][1][0]"]
+    );
+}
+
+#[test]
+fn indices_and_slices() {
+    let doc = parse_value("[0, 1, 2, 3, 4, 5, 6]").unwrap();
+    let ints = |expr: &str| -> Vec<i64> {
+        doc.query(expr)
+            .unwrap()
+            .iter()
+            .map(|(_, v)| v.as_i64().unwrap())
+            .collect()
+    };
+    assert_eq!(ints("$[0, -1, 9]"), [0, 6]);
+    assert_eq!(ints("$[1:3]"), [1, 2]);
+    assert_eq!(ints("$[:2]"), [0, 1]);
+    assert_eq!(ints("$[5:]"), [5, 6]);
+    assert_eq!(ints("$[-2:]"), [5, 6]);
+    assert_eq!(ints("$[::3]"), [0, 3, 6]);
+    assert_eq!(ints("$[5:1:-2]"), [5, 3]);
+    assert_eq!(ints("$[::-1]"), [6, 5, 4, 3, 2, 1, 0]);
+    assert_eq!(ints("$[1:5:0]"), [] as [i64; 0]);
+    assert_eq!(ints("$[ 1 : 3 ]"), [1, 2]);
+}
+
+#[test]
+fn filters() {
+    let doc = parse_value(STORE).unwrap();
+    assert_eq!(
+        strings(&doc, "$.store.book[?(@.price < 10)].title"),
+        ["Sayings of the Century", "Moby Dick"]
+    );
+    assert_eq!(
+        strings(&doc, "$..book[?@.isbn].title"),
+        ["Moby Dick", "The Lord of the Rings"]
+    );
+    assert_eq!(
+        strings(&doc, "$..book[?!@.isbn && @.category == 'fiction'].author"),
+        ["Evelyn Waugh"]
+    );
+    assert_eq!(
+        strings(
+            &doc,
+            r#"$..book[?@.price > 20 || @.author == "Nigel Rees"].title"#
+        ),
+        ["Sayings of the Century", "The Lord of the Rings"]
+    );
+    assert_eq!(
+        strings(&doc, "$..book[?@.price >= $.store.bicycle.price].title"),
+        [] as [&str; 0]
+    );
+    assert_eq!(
+        paths(&doc, "$..[?@.price == 399]"),
+        ["$['store']['bicycle']"]
+    );
+    // Missing values equal each other, and nothing else.
+    assert_eq!(paths(&doc, "$.store.book[?@.isbn == @.nope]").len(), 2);
+    assert_eq!(paths(&doc, "$.store.book[?@.isbn != null]").len(), 4);
+}
+
+#[test]
+fn filter_comparisons() {
+    let doc = parse_value(r#"[1, 2.0, "2", true, null, [2], {"a": 2}]"#).unwrap();
+    let paths = |expr: &str| -> Vec<String> {
+        doc.query(expr)
+            .unwrap()
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect()
+    };
+    assert_eq!(paths("$[?@ == 2]"), ["$[1]"]);
+    assert_eq!(paths("$[?@ < 2]"), ["$[0]"]);
+    assert_eq!(paths("$[?@ <= 2]"), ["$[0]", "$[1]"]);
+    assert_eq!(paths("$[?@ > '10']"), ["$[2]"]);
+    assert_eq!(paths("$[?@ == true]"), ["$[3]"]);
+    assert_eq!(paths("$[?@ == null]"), ["$[4]"]);
+    assert_eq!(paths("$[?@[0] == 2]"), ["$[5]"]);
+    assert_eq!(paths("$[?@.a]"), ["$[6]"]);
+    assert_eq!(paths("$[?@ == $[1]]"), ["$[1]"]);
+    assert_eq!(paths("$[?@ < true]"), [] as [&str; 0]);
+}
+
+#[test]
+fn normalized_paths_escape_names() {
+    let doc = parse_value(r#"{"it's": {"a\\b": {"\n": 1}}}"#).unwrap();
+    assert_eq!(
+        paths(&doc, "$..*"),
+        [
+            r"$['it\'s']",
+            r"$['it\'s']['a\\b']",
+            r"$['it\'s']['a\\b']['\n']",
+        ]
+    );
+    assert_eq!(paths(&doc, r"$['it\'s']['a\\b']['\u000a']").len(), 1);
+    assert_eq!(paths(&doc, r#"$["it's"].*"#).len(), 1);
+}
+
+#[test]
+fn syntax_errors() {
+    for (expr, offset) in [
+        ("", 0),
+        ("store", 0),
+        ("$.", 2),
+        ("$.1a", 2),
+        ("$[", 2),
+        ("$[0", 3),
+        ("$['a", 2),
+        ("$[01]", 2),
+        ("$[-0]", 2),
+        ("$[?@.a ==]", 9),
+        ("$[?1]", 4),
+        ("$[?@..a == 1]", 3),
+        ("$[?@.* == 1]", 3),
+        ("$[?(@.a]", 7),
+        ("$.a b", 3),
+        ("$ ", 1),
+    ] {
+        let e = JsonPath::parse(expr).unwrap_err();
+        assert_eq!(e.offset, offset, "{expr}: {e}");
+    }
+    assert_eq!(
+        JsonPath::parse("$[0").unwrap_err().to_string(),
+        "Expected ',' or ']' at offset 3."
+    );
+    let deep = format!("$[?{}@{}]", "(".repeat(100), ")".repeat(100));
+    assert!(JsonPath::parse(&deep).is_err());
+}
+
+#[test]
+fn compiled_paths_are_reusable() {
+    let path: JsonPath = "$.a[*]".parse().unwrap();
+    for (src, len) in [(r#"{"a": [1, 2]}"#, 2), (r#"{"a": {}}"#, 0), ("[]", 0)] {
+        assert_eq!(path.query(&parse_value(src).unwrap()).len(), len);
+    }
+}
@@ -0,0 +1,43 @@
+use jsonparser::{parse_value, JsonValue};
+
+const SRC: &str = r#"{
+    "data": {"items": [{"id": 1}, {"id": 2}, {"id": 3}, {"id": 4}]},
+    "a/b": 1, "m~n": 2, "": 3, "01": 4
+}"#;
+
+#[test]
+fn rfc_6901() {
+    let doc = parse_value(SRC).unwrap();
+    assert_eq!(
+        doc.pointer("/data/items/3/id").and_then(|v| v.as_i64()),
+        Some(4)
+    );
+    assert!(matches!(doc.pointer(""), Some(JsonValue::Object(_))));
+    assert_eq!(doc.pointer("/a~1b").and_then(|v| v.as_i64()), Some(1));
+    assert_eq!(doc.pointer("/m~0n").and_then(|v| v.as_i64()), Some(2));
+    assert_eq!(doc.pointer("/").and_then(|v| v.as_i64()), Some(3));
+    assert_eq!(doc.pointer("/01").and_then(|v| v.as_i64()), Some(4));
+}
+
+#[test]
+fn missing_paths() {
+    let doc = parse_value(SRC).unwrap();
+    assert!(doc.pointer("data").is_none());
+    assert!(doc.pointer("/data/items/4").is_none());
+    assert!(doc.pointer("/data/items/01").is_none());
+    assert!(doc.pointer("/data/items/-").is_none());
+    assert!(doc.pointer("/data/items/0/id/x").is_none());
+    assert!(doc.pointer("/nope").is_none());
+}
+
+#[test]
+fn pointer_mut() {
+    let mut doc = parse_value(SRC).unwrap();
+    *doc.pointer_mut("/data/items/0/id").unwrap() = JsonValue::Bool(true);
+    assert_eq!(
+        doc.pointer("/data/items/0/id").and_then(|v| v.as_bool()),
+        Some(true)
+    );
+    *doc.pointer_mut("").unwrap() = JsonValue::Null;
+    assert!(doc.root().is_null());
+}
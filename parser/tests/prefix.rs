@@ -0,0 +1,29 @@
+use jsonparser::{parse_prefix, JsonValue, ParseError};
+
+#[test]
+fn returns_the_remainder() {
+    let (doc, rest) = parse_prefix("{\"len\": 3}\r\nabc").unwrap();
+    assert!(matches!(doc.root(), JsonValue::Object(obj) if obj.len() == 1));
+    assert_eq!(rest, "\r\nabc");
+
+    let (_, rest) = parse_prefix("  [1, [2]]]]").unwrap();
+    assert_eq!(rest, "]]");
+    let (_, rest) = parse_prefix("\"é\"").unwrap();
+    assert_eq!(rest, "");
+}
+
+#[test]
+fn scalars_end_at_their_last_byte() {
+    let (doc, rest) = parse_prefix("12 garbage").unwrap();
+    assert!(matches!(doc.root(), JsonValue::Number(n) if n.as_i64() == Some(12)));
+    assert_eq!(rest, " garbage");
+}
+
+#[test]
+fn needs_a_whole_value() {
+    assert!(matches!(
+        parse_prefix("  "),
+        Err(ParseError::UnexpectedEof { .. })
+    ));
+    assert!(parse_prefix("[1, 2").is_err());
+}
@@ -0,0 +1,71 @@
+use jsonparser::parse_value;
+
+const SRC: &str = r#"{"name": "Ann", "password": "hunter2",
+    "address": {"city": "Oslo", "zip": "0150"},
+    "orders": [{"id": 1, "items": [{"sku": "a", "price": 3}, {"sku": "b"}]}, {"id": 2}, 7],
+    "tags": "x"}"#;
+
+fn canonical(src: &str) -> String {
+    parse_value(src).unwrap().to_canonical_string().unwrap()
+}
+
+#[test]
+fn project() {
+    let doc = parse_value(SRC).unwrap();
+    assert_eq!(
+        doc.project(&["name", "address.city"])
+            .to_canonical_string()
+            .unwrap(),
+        canonical(r#"{"name": "Ann", "address": {"city": "Oslo"}}"#)
+    );
+    assert_eq!(
+        doc.project(&["orders.id", "orders.items.price"])
+            .to_canonical_string()
+            .unwrap(),
+        canonical(r#"{"orders": [{"id": 1, "items": [{"price": 3}, {}]}, {"id": 2}]}"#)
+    );
+    assert_eq!(
+        doc.project(&["address", "address.city", "tags.deeper", "missing"])
+            .to_canonical_string()
+            .unwrap(),
+        canonical(r#"{"address": {"city": "Oslo", "zip": "0150"}}"#)
+    );
+    assert_eq!(doc.project(&[]).to_string(), "{}");
+    let list = parse_value(r#"[{"a": 1, "b": 2}, 3, [{"a": 4}]]"#).unwrap();
+    assert_eq!(list.project(&["a"]).to_string(), r#"[{"a":1},[{"a":4}]]"#);
+    assert_eq!(
+        parse_value("3").unwrap().project(&["a"]).to_string(),
+        "null"
+    );
+}
+
+#[test]
+fn strip() {
+    let doc = parse_value(SRC).unwrap();
+    assert_eq!(
+        doc.strip(&["password", "address.zip", "orders.items", "tags.deeper"])
+            .to_canonical_string()
+            .unwrap(),
+        canonical(
+            r#"{"name": "Ann", "address": {"city": "Oslo"}, "orders": [{"id": 1}, {"id": 2}, 7], "tags": "x"}"#
+        )
+    );
+    let stripped = doc.strip(&["orders.items.sku"]);
+    assert_eq!(
+        stripped.value().at("orders").at(0).at("items").to_string(),
+        r#"[{"price":3},{}]"#
+    );
+    assert_eq!(
+        doc.strip(&[]).to_canonical_string().unwrap(),
+        canonical(SRC)
+    );
+    assert_eq!(parse_value("3").unwrap().strip(&["a"]).to_string(), "3");
+}
+
+#[test]
+fn new_arena() {
+    let doc = parse_value(SRC).unwrap();
+    let projected = doc.project(&["name"]);
+    assert_eq!(projected.arena().len(), 1);
+    assert_eq!(doc.strip(&["orders", "address"]).arena().len(), 3);
+}
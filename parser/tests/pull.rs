@@ -0,0 +1,167 @@
+use jsonparser::{parse_value, Event, JsonPullParser, Number, ParseError, ParseOptions};
+
+fn events(src: &str) -> Result<Vec<String>, ParseError> {
+    let mut parser = JsonPullParser::new(src);
+    let mut out = Vec::new();
+    while let Some(event) = parser.next_event()? {
+        out.push(format!("{event:?}"));
+    }
+    Ok(out)
+}
+
+#[test]
+fn events_in_source_order() {
+    assert_eq!(
+        events(r#"{"a": [1, "x", true, null], "b": {}}"#).unwrap(),
+        [
+            "StartObject",
+            "Key(\"a\")",
+            "StartArray",
+            "Number(I64(1))",
+            "String(\"x\")",
+            "Bool(true)",
+            "Null",
+            "EndArray",
+            "Key(\"b\")",
+            "StartObject",
+            "EndObject",
+            "EndObject",
+        ]
+    );
+    assert_eq!(events("3").unwrap(), ["Number(I64(3))"]);
+}
+
+#[test]
+fn only_the_needed_fields() {
+    let src = r#"[{"id": 1, "name": "a"}, {"id": 2, "name": "b"}]"#;
+    let mut parser = JsonPullParser::new(src);
+    let mut ids = Vec::new();
+    let mut in_id = false;
+    while let Some(event) = parser.next_event().unwrap() {
+        match event {
+            Event::Key(key) => in_id = key == "id",
+            Event::Number(n) if in_id => ids.push(n),
+            _ => {}
+        }
+    }
+    assert_eq!(ids, [Number::I64(1), Number::I64(2)]);
+}
+
+#[test]
+fn errors_match_the_parser() {
+    for src in ["[1 2]", "[1,]", r#"{"a" 1}"#, "{,}", "[", "1 2", "]", ""] {
+        assert_eq!(
+            events(src).unwrap_err(),
+            parse_value(src).unwrap_err(),
+            "{src}"
+        );
+    }
+}
+
+#[test]
+fn stays_finished_after_an_error() {
+    let mut parser = JsonPullParser::new("[1 2]");
+    assert_eq!(parser.next_event(), Ok(Some(Event::StartArray)));
+    assert_eq!(parser.next_event(), Ok(Some(Event::Number(Number::I64(1)))));
+    assert!(parser.next_event().is_err());
+    assert_eq!(parser.next_event(), Ok(None));
+}
+
+#[test]
+fn options() {
+    let opts = ParseOptions {
+        allow_trailing_commas: true,
+        max_depth: Some(2),
+        ..ParseOptions::default()
+    };
+    let mut parser = JsonPullParser::with_options("[[1,],]", opts);
+    let mut depth = 0;
+    while parser.next_event().unwrap().is_some() {
+        depth = depth.max(parser.depth());
+    }
+    assert_eq!(depth, 2);
+    let mut parser = JsonPullParser::with_options("[[[]]]", opts);
+    let err = loop {
+        match parser.next_event() {
+            Ok(Some(_)) => {}
+            Ok(None) => panic!("expected an error"),
+            Err(e) => break e,
+        }
+    };
+    assert!(matches!(err, ParseError::DepthExceeded { max: 2, .. }));
+}
+
+#[test]
+fn reader_input() {
+    let mut parser = JsonPullParser::from_reader(&br#"{"k": "v"}"#[..], ParseOptions::default());
+    let mut keys = Vec::new();
+    while let Some(event) = parser.next_event().unwrap() {
+        if let Event::Key(k) = event {
+            keys.push(k.to_string());
+        }
+    }
+    assert_eq!(keys, ["k"]);
+}
+
+#[test]
+fn skip_value() {
+    let src = r#"{"big": {"a": [1, "]", {"b": "\"}"}]}, "keep": [1, 2, 3], "n": 4}"#;
+    let mut parser = JsonPullParser::new(src);
+    let mut out = Vec::new();
+    while let Some(event) = parser.next_event().unwrap() {
+        let skip = event == Event::Key("big");
+        out.push(format!("{event:?}"));
+        if skip {
+            assert!(parser.skip_value().unwrap());
+        }
+    }
+    assert_eq!(
+        out.join(" "),
+        "StartObject Key(\"big\") Key(\"keep\") StartArray Number(I64(1)) Number(I64(2)) \
+         Number(I64(3)) EndArray Key(\"n\") Number(I64(4)) EndObject"
+    );
+}
+
+#[test]
+fn skip_array_elements() {
+    let mut parser = JsonPullParser::new("[[1], 2, {\"x\": 3}]");
+    assert_eq!(parser.next_event(), Ok(Some(Event::StartArray)));
+    assert_eq!(parser.skip_value(), Ok(true));
+    assert_eq!(parser.skip_value(), Ok(true));
+    assert_eq!(parser.skip_value(), Ok(true));
+    assert_eq!(parser.skip_value(), Ok(false));
+    assert_eq!(parser.next_event(), Ok(Some(Event::EndArray)));
+    assert_eq!(parser.next_event(), Ok(None));
+}
+
+#[test]
+fn skip_rest() {
+    let mut parser = JsonPullParser::new(r#"[{"a": 1, "b": [2]}, 3]"#);
+    assert_eq!(parser.next_event(), Ok(Some(Event::StartArray)));
+    assert_eq!(parser.next_event(), Ok(Some(Event::StartObject)));
+    assert_eq!(parser.next_event(), Ok(Some(Event::Key("a"))));
+    assert_eq!(parser.skip_rest(), Ok(true));
+    assert_eq!(parser.depth(), 1);
+    assert_eq!(parser.next_event(), Ok(Some(Event::Number(Number::I64(3)))));
+    assert_eq!(parser.next_event(), Ok(Some(Event::EndArray)));
+}
+
+#[test]
+fn skipping_still_balances() {
+    let mut parser = JsonPullParser::new(r#"{"k": [1, {"x": 2]}"#);
+    parser.next_event().unwrap();
+    parser.next_event().unwrap();
+    assert!(matches!(
+        parser.skip_value(),
+        Err(ParseError::UnexpectedToken {
+            expected: Some("'}'"),
+            ..
+        })
+    ));
+    let mut parser = JsonPullParser::new(r#"["unterminated]"#);
+    parser.next_event().unwrap();
+    assert!(matches!(
+        parser.skip_value(),
+        Err(ParseError::UnterminatedString { .. })
+    ));
+}
@@ -0,0 +1,94 @@
+use jsonparser::{
+    parse_bytes_with, parse_value, parse_value_with, CanonicalError, JsonValue, Par, ParseError,
+    ParseOptions,
+};
+
+fn envelope() -> ParseOptions {
+    ParseOptions {
+        raw_keys: &["payload"],
+        ..ParseOptions::default()
+    }
+}
+
+#[test]
+fn payload_is_left_unparsed() {
+    let src = r#"{"header": {"to": "b"}, "payload": {"items": [1, [2], {"x": null}]}}"#;
+    let doc = parse_value_with(src, envelope()).unwrap();
+    let JsonValue::Object(obj) = doc.root() else {
+        panic!("expected an object");
+    };
+    let JsonValue::Raw(text) = doc.get(obj["payload"]).clone() else {
+        panic!("expected a raw value");
+    };
+    assert_eq!(text, r#"{"items": [1, [2], {"x": null}]}"#);
+    assert_eq!(doc.arena().len(), 3);
+    assert_eq!(parse_value(&text).unwrap().arena().len(), 6);
+}
+
+#[test]
+fn raw_scalars() {
+    let src = r#"{"payload": "abc", "n": 1}"#;
+    let doc = parse_value_with(src, envelope()).unwrap();
+    let raw = doc.descendants().find_map(|v| match v {
+        JsonValue::Raw(text) => Some(text.clone()),
+        _ => None,
+    });
+    assert_eq!(raw.as_deref(), Some(r#""abc""#));
+}
+
+fn payload(doc: &jsonparser::Document) -> String {
+    doc.descendants()
+        .find_map(|v| match v {
+            JsonValue::Raw(text) => Some(text.clone()),
+            _ => None,
+        })
+        .expect("a raw value")
+}
+
+#[test]
+fn text_is_kept_from_any_source() {
+    let src = r#"{"payload": [1, "\u00e9", {"x": null}]}"#;
+    let read = Par::parse_reader(src.as_bytes(), envelope()).unwrap();
+    assert_eq!(payload(&read), r#"[1, "\u00e9", {"x": null}]"#);
+    let utf16: Vec<u8> = src.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    let bytes = parse_bytes_with(&utf16, envelope()).unwrap();
+    assert_eq!(payload(&bytes), r#"[1, "\u00e9", {"x": null}]"#);
+}
+
+#[test]
+fn raw_values_are_written_verbatim() {
+    let doc = parse_value_with(r#"{"payload": [1,  2]}"#, envelope()).unwrap();
+    assert_eq!(doc.to_string(), r#"{"payload":[1,  2]}"#);
+}
+
+#[test]
+fn raw_values_compare_by_text() {
+    let parse = |src| parse_value_with(src, envelope()).unwrap();
+    let (a, b) = (parse(r#"{"payload": [1]}"#), parse(r#"{"payload": [2]}"#));
+    assert!(a.deep_eq(&parse(r#"{"payload": [1]}"#)));
+    assert!(!a.deep_eq(&b));
+    assert_ne!(a.canonical_hash(), b.canonical_hash());
+    assert_eq!(
+        a.to_canonical_string(),
+        Err(CanonicalError {
+            pointer: "/payload".into(),
+            message: "a raw value"
+        })
+    );
+}
+
+#[test]
+fn unbalanced_raw_values() {
+    assert!(matches!(
+        parse_value_with(r#"{"payload": [1, 2}"#, envelope()),
+        Err(ParseError::UnexpectedToken {
+            expected: Some("']'"),
+            ..
+        })
+    ));
+    assert!(matches!(
+        parse_value_with(r#"{"payload": [1, 2"#, envelope()),
+        Err(ParseError::UnexpectedEof { .. })
+    ));
+    assert!(parse_value_with(r#"{"payload": }"#, envelope()).is_err());
+}
@@ -0,0 +1,54 @@
+use std::io::{self, Read};
+
+use jsonparser::{parse_reader, parse_value, ErrorKind, JsonValue, ParseError};
+
+// Hands out at most one byte per `read`, then optionally fails.
+struct Trickle<'a> {
+    bytes: &'a [u8],
+    fail_at_end: bool,
+}
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.bytes.split_first() {
+            Some((&b, rest)) if !buf.is_empty() => {
+                buf[0] = b;
+                self.bytes = rest;
+                Ok(1)
+            }
+            _ if self.fail_at_end => Err(io::Error::other("connection reset")),
+            _ => Ok(0),
+        }
+    }
+}
+
+#[test]
+fn parses_across_reads() {
+    let src = r#"{"name": "café ☕", "tags": [1, -2.5e1, true, null]}"#;
+    let doc = parse_reader(Trickle {
+        bytes: src.as_bytes(),
+        fail_at_end: false,
+    })
+    .unwrap();
+    let expected = parse_value(src).unwrap();
+    assert_eq!(doc.arena().len(), expected.arena().len());
+    let strings: Vec<_> = doc
+        .descendants()
+        .filter_map(|v| match v {
+            JsonValue::String(s) => Some(s.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(strings, ["café ☕"]);
+}
+
+#[test]
+fn reports_read_errors() {
+    let err = parse_reader(Trickle {
+        bytes: b"[1, 2",
+        fail_at_end: true,
+    })
+    .unwrap_err();
+    assert!(matches!(err, ParseError::Io { .. }), "{err}");
+    assert_eq!(err.kind(), ErrorKind::Io);
+}
@@ -0,0 +1,84 @@
+use jsonparser::{parse_value, Mask, Redactor};
+
+#[test]
+fn paths_and_keys() {
+    let doc = parse_value(
+        r#"{"user": {"name": "Ann", "ssn": "123-45-6789", "auth": {"password": "hunter2"}},
+        "friends": [{"name": "Bob", "ssn": "987-65-4321"}, {"name": "Cy"}],
+        "ssn": "top", "password": {"old": "a", "new": "b"}}"#,
+    )
+    .unwrap();
+    let redacted = Redactor::new().path("*.ssn").key("password").redact(&doc);
+    assert_eq!(
+        redacted.to_canonical_string().unwrap(),
+        r#"{"friends":[{"name":"Bob","ssn":"***"},{"name":"Cy"}],"password":"***","ssn":"top","user":{"auth":{"password":"***"},"name":"Ann","ssn":"***"}}"#
+    );
+    let redacted = Redactor::new()
+        .path("user.auth")
+        .path("friends.name")
+        .path("missing.x")
+        .redact(&doc);
+    let value = redacted.value();
+    assert_eq!(value.pointer("/user/auth").unwrap().as_str(), Some("***"));
+    assert_eq!(
+        value.pointer("/friends/1/name").unwrap().as_str(),
+        Some("***")
+    );
+    assert_eq!(
+        value.pointer("/user/ssn").unwrap().as_str(),
+        Some("123-45-6789")
+    );
+}
+
+#[test]
+fn masked_values_are_not_copied() {
+    let doc = parse_value(r#"{"a": 1, "secret": {"deep": [1, 2, 3]}}"#).unwrap();
+    let redacted = Redactor::new().key("secret").redact(&doc);
+    assert_eq!(redacted.arena().len(), 2);
+    assert_eq!(
+        Redactor::new().redact(&doc).to_canonical_string().unwrap(),
+        doc.to_canonical_string().unwrap()
+    );
+}
+
+#[test]
+fn hash() {
+    let doc = parse_value(r#"[{"email": "a@x.org"}, {"email": "a@x.org"}, {"email": "b@x.org"}]"#)
+        .unwrap();
+    let hashed = |salt: &str| {
+        let redactor = Redactor::new().key("email").mask(Mask::Hash {
+            salt: salt.to_owned(),
+        });
+        let redacted = redactor.redact(&doc);
+        (0..3)
+            .map(|i| {
+                let pointer = format!("/{i}/email");
+                redacted
+                    .value()
+                    .pointer(&pointer)
+                    .unwrap()
+                    .as_str()
+                    .unwrap()
+                    .to_owned()
+            })
+            .collect::<Vec<_>>()
+    };
+    let plain = hashed("");
+    assert_eq!(plain[0], plain[1]);
+    assert_ne!(plain[0], plain[2]);
+    assert_eq!(plain[0].len(), 16);
+    let email = parse_value(r#""a@x.org""#).unwrap().canonical_hash();
+    assert_eq!(plain[0], format!("{email:016x}"));
+    let salted = hashed("pepper");
+    assert_eq!(salted[0], salted[1]);
+    assert_ne!(salted[0], plain[0]);
+
+    let redactor = Redactor::new()
+        .key("email")
+        .mask(Mask::Text("[gone]".to_owned()));
+    let redacted = redactor.redact(&doc);
+    assert_eq!(
+        redacted.value().pointer("/2/email").unwrap().as_str(),
+        Some("[gone]")
+    );
+}
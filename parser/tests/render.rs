@@ -0,0 +1,28 @@
+use jsonparser::parse_value;
+
+#[test]
+fn caret_under_the_token() {
+    let src = "{\n  \"a\": [1, 2 3]\n}";
+    let out = parse_value(src).unwrap_err().render(src);
+    assert_eq!(
+        out,
+        "error: Expected ',' or ']', found '3'\n --> line 2, column 14\n  |\n2 |   \"a\": [1, 2 3]\n  |              ^\n"
+    );
+}
+
+#[test]
+fn underline_spans_the_token() {
+    let src = "[\ttrue, nope]";
+    let out = parse_value(src).unwrap_err().render(src);
+    assert!(
+        out.ends_with("1 | [\ttrue, nope]\n  |  \t      ^~~~\n"),
+        "{out}"
+    );
+}
+
+#[test]
+fn end_of_input() {
+    let src = "[1,";
+    let out = parse_value(src).unwrap_err().render(src);
+    assert!(out.ends_with("1 | [1,\n  |    ^\n"), "{out}");
+}
@@ -0,0 +1,228 @@
+use jsonparser::{infer_schema, parse_value, Schema, SchemaError};
+
+fn schema(src: &str) -> Schema {
+    Schema::compile(&parse_value(src).unwrap()).unwrap()
+}
+
+// Each violation of `instance` as "path keyword".
+fn violations(schema: &Schema, instance: &str) -> Vec<String> {
+    match schema.validate(&parse_value(instance).unwrap()) {
+        Ok(()) => Vec::new(),
+        Err(violations) => violations
+            .iter()
+            .map(|v| format!("{} {}", v.instance_path, v.keyword))
+            .collect(),
+    }
+}
+
+#[test]
+fn objects() {
+    let person = schema(
+        r#"{
+            "type": "object",
+            "required": ["name", "age"],
+            "properties": {
+                "name": {"type": "string", "minLength": 1},
+                "age": {"type": "integer", "minimum": 0, "maximum": 150},
+                "email": {"type": "string", "pattern": "^[^@\\s]+@[^@\\s]+\\.[a-z]{2,}$"}
+            },
+            "additionalProperties": false
+        }"#,
+    );
+    assert!(person.is_valid(&parse_value(r#"{"name": "Ada", "age": 36}"#).unwrap()));
+    assert!(violations(
+        &person,
+        r#"{"name": "Ada", "age": 36.0, "email": "ada@example.org"}"#
+    )
+    .is_empty());
+    assert_eq!(
+        violations(
+            &person,
+            r#"{"name": "", "age": -1, "email": "ada@example", "x": 1}"#
+        ),
+        [
+            "/age minimum",
+            "/email pattern",
+            "/name minLength",
+            "/x false"
+        ]
+    );
+    assert_eq!(
+        violations(&person, r#"{"age": 1.5}"#),
+        [" required", "/age type"]
+    );
+    assert_eq!(violations(&person, "[]"), [" type"]);
+}
+
+#[test]
+fn lists_and_values() {
+    let tags =
+        schema(r#"{"type": "array", "maxItems": 3, "items": {"enum": ["a", "b", 1, null]}}"#);
+    assert!(violations(&tags, r#"["a", 1.0, null]"#).is_empty());
+    assert_eq!(
+        violations(&tags, r#"["a", "c", 2, "b"]"#),
+        [" maxItems", "/1 enum", "/2 enum"]
+    );
+    let exclusive = schema(r#"{"exclusiveMinimum": 0, "exclusiveMaximum": 1e1, "const": 5}"#);
+    assert!(violations(&exclusive, "5").is_empty());
+    assert_eq!(
+        violations(&exclusive, "10"),
+        [" const", " exclusiveMaximum"]
+    );
+    assert_eq!(violations(&exclusive, "0"), [" const", " exclusiveMinimum"]);
+    // Keywords for other types don't apply.
+    assert!(violations(&exclusive, r#""five""#).len() == 1);
+    let either = schema(r#"{"type": ["string", "null"], "maxLength": 2}"#);
+    assert!(violations(&either, r#""éé""#).is_empty());
+    assert_eq!(violations(&either, r#""abc""#), [" maxLength"]);
+    assert_eq!(violations(&either, "true"), [" type"]);
+}
+
+#[test]
+fn references() {
+    let tree = schema(
+        r##"{
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "required": ["value"],
+                    "properties": {
+                        "value": {"type": "number"},
+                        "children": {"type": "array", "items": {"$ref": "#/$defs/node"}}
+                    }
+                }
+            },
+            "$ref": "#/$defs/node"
+        }"##,
+    );
+    assert!(violations(
+        &tree,
+        r#"{"value": 1, "children": [{"value": 2, "children": []}]}"#
+    )
+    .is_empty());
+    assert_eq!(
+        violations(
+            &tree,
+            r#"{"value": 1, "children": [{"value": "2"}, {"children": [{}]}]}"#
+        ),
+        [
+            "/children/0/value type",
+            "/children/1 required",
+            "/children/1/children/0 required"
+        ]
+    );
+    // A schema that refers to itself on the same value doesn't loop.
+    let itself = schema(r##"{"$ref": "#", "type": "string"}"##);
+    assert_eq!(violations(&itself, "1"), [" type"]);
+}
+
+#[test]
+fn messages() {
+    let s = schema(r#"{"properties": {"a/b": {"type": ["string", "null"]}}}"#);
+    let e = s
+        .validate(&parse_value(r#"{"a/b": 1}"#).unwrap())
+        .unwrap_err();
+    assert_eq!(
+        e[0].to_string(),
+        r#""/a~1b" must be string or null, not number."#
+    );
+    let e = schema(r#"{"minimum": 2.5}"#)
+        .validate(&parse_value("1").unwrap())
+        .unwrap_err();
+    assert_eq!(e[0].message, "is less than 2.5");
+}
+
+#[test]
+fn patterns() {
+    for (pattern, yes, no) in [
+        ("abc", "xabcx", "abx"),
+        ("^a.c$", "abc", "a\nc"),
+        ("^(ab|cd)+$", "abcdab", "abc"),
+        ("^[a-f0-9]{4,6}$", "beef01", "beef0123"),
+        ("^[^x-z]*$", "abc", "axb"),
+        ("^\\d{3}-\\d{4}$", "555-1234", "555-12345"),
+        ("\\bcat\\b", "a cat sat", "concatenate"),
+        ("^(?:a|b)?c*$", "bccc", "abc"),
+        ("^[\\w.-]+$", "a_b.c-d", "a b"),
+        ("^\\u00e9\\x41$", "éA", "eA"),
+        ("^a{2}$", "aa", "aaa"),
+        ("a{,2}", "a{,2}", "aa"),
+    ] {
+        let s = schema(&format!("{{\"pattern\": {pattern:?}}}"));
+        assert!(
+            violations(&s, &format!("{yes:?}")).is_empty(),
+            "{pattern} {yes}"
+        );
+        assert_eq!(
+            violations(&s, &format!("{no:?}")),
+            [" pattern"],
+            "{pattern} {no}"
+        );
+    }
+    // Matching takes linear time even for patterns that trip up
+    // backtracking engines.
+    let s = schema(r#"{"pattern": "^(a+)+$"}"#);
+    let long = format!("\"{}b\"", "a".repeat(10_000));
+    assert_eq!(violations(&s, &long), [" pattern"]);
+}
+
+#[test]
+fn bad_schemas() {
+    let error = |src: &str| Schema::compile(&parse_value(src).unwrap()).unwrap_err();
+    assert_eq!(
+        error(r#"{"properties": {"a": {"pattern": "(a"}}}"#),
+        SchemaError {
+            pointer: "/properties/a/pattern".into(),
+            message: "unterminated group"
+        }
+    );
+    assert_eq!(error(r#"{"type": "text"}"#).pointer, "/type");
+    assert_eq!(error(r##"{"$ref": "#/$defs/x"}"##).pointer, "/$ref");
+    assert_eq!(error(r#"{"$ref": "other.json"}"#).pointer, "/$ref");
+    assert_eq!(error(r#"{"items": 1}"#).pointer, "/items");
+    assert_eq!(error(r#"{"minLength": -1}"#).pointer, "/minLength");
+    assert_eq!(
+        error("[]").to_string(),
+        "Invalid schema at \"\": a schema must be an object or a boolean."
+    );
+    assert!(Schema::compile(&parse_value("true").unwrap()).is_ok());
+}
+
+#[test]
+fn inferred_schemas() {
+    let samples: Vec<_> = [
+        r#"{"id": 1, "name": "a", "tags": ["x"], "score": 1.5, "owner": {"id": 7}}"#,
+        r#"{"id": 2, "name": null, "tags": [], "score": 2}"#,
+        r#"{"id": 3, "name": "c", "tags": ["y", "z"], "score": null, "extra": true}"#,
+    ]
+    .iter()
+    .map(|src| parse_value(src).unwrap())
+    .collect();
+    let inferred = infer_schema(&samples);
+    assert_eq!(
+        inferred.to_canonical_string().unwrap(),
+        concat!(
+            r#"{"$schema":"https://json-schema.org/draft/2020-12/schema","#,
+            r#""properties":{"extra":{"type":"boolean"},"id":{"type":"integer"},"#,
+            r#""name":{"type":["string","null"]},"#,
+            r#""owner":{"properties":{"id":{"type":"integer"}},"required":["id"],"type":"object"},"#,
+            r#""score":{"type":["number","null"]},"#,
+            r#""tags":{"items":{"type":"string"},"type":"array"}},"#,
+            r#""required":["id","name","score","tags"],"type":"object"}"#
+        )
+    );
+    let inferred = Schema::compile(&inferred).unwrap();
+    assert!(samples.iter().all(|sample| inferred.is_valid(sample)));
+    assert!(!inferred
+        .is_valid(&parse_value(r#"{"id": "1", "name": "a", "tags": [], "score": 1}"#).unwrap()));
+
+    let mixed = infer_schema(&[parse_value(r#"[1, "a", [], {}]"#).unwrap()]);
+    assert_eq!(
+        mixed.value().at("items").at("type").to_string(),
+        r#"["integer","string","array","object"]"#
+    );
+    assert_eq!(
+        infer_schema(&[]).to_canonical_string().unwrap(),
+        r#"{"$schema":"https://json-schema.org/draft/2020-12/schema"}"#
+    );
+}
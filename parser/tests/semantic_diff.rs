@@ -0,0 +1,56 @@
+use jsonparser::{parse_value, Change, DiffOptions, Document};
+
+// Each change as "+path value", "-path value" or "~path old new".
+fn changes(a: &Document, b: &Document, opts: DiffOptions) -> Vec<String> {
+    a.semantic_diff_with(b, opts)
+        .into_iter()
+        .map(|(path, change)| match change {
+            Change::Added(new) => format!("+{path} {new}"),
+            Change::Removed(old) => format!("-{path} {old}"),
+            Change::Modified { old, new } => format!("~{path} {old} {new}"),
+        })
+        .collect()
+}
+
+#[test]
+fn changes_by_path() {
+    let a = parse_value(r#"{"name": "svc", "port": 80, "tags": ["a", "b"], "tls": {"on": false}}"#)
+        .unwrap();
+    let b = parse_value(
+        r#"{"tls": {"on": true, "cert": "x"}, "tags": ["a"], "port": 8080, "name": "svc"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        changes(&a, &b, DiffOptions::default()),
+        [
+            "~/port 80 8080",
+            "-/tags/1 \"b\"",
+            "+/tls/cert \"x\"",
+            "~/tls/on false true",
+        ]
+    );
+    assert!(a.semantic_diff(&a).is_empty());
+}
+
+#[test]
+fn values_of_other_kinds_are_modified() {
+    let a = parse_value(r#"{"a": [1], "b": {"c": 1}, "c/d": null}"#).unwrap();
+    let b = parse_value(r#"{"a": {"0": 1}, "b": {"c": 1.0}, "c/d": 0}"#).unwrap();
+    assert_eq!(
+        changes(&a, &b, DiffOptions::default()),
+        ["~/a [1] {\"0\":1}", "~/c~1d null 0"]
+    );
+}
+
+#[test]
+fn epsilon() {
+    let a = Document::encode(&[0.1 + 0.2, 1.0, 3.0][..]);
+    let b = Document::encode(&[0.3, 1.05, 3.5][..]);
+    assert_eq!(changes(&a, &b, DiffOptions::default()).len(), 3);
+    let opts = DiffOptions { epsilon: 0.1 };
+    assert_eq!(changes(&a, &b, opts), ["~/2 3.0 3.5"]);
+    // Integers past where floats are exact still differ.
+    let a = parse_value("9007199254740993").unwrap();
+    let b = parse_value("9007199254740992").unwrap();
+    assert_eq!(changes(&a, &b, DiffOptions::default()).len(), 1);
+}
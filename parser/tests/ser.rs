@@ -0,0 +1,225 @@
+use jsonparser::{
+    json, parse_value, parse_value_with, Document, FormatError, FormatOptions, JsonValue,
+    ParseOptions,
+};
+
+fn round_trip(src: &str) -> String {
+    parse_value(src).unwrap().to_string()
+}
+
+#[test]
+fn compact() {
+    assert_eq!(
+        round_trip(" [ 1 , true, null, \"a\" , [ ] , { } ] "),
+        r#"[1,true,null,"a",[],{}]"#
+    );
+    assert_eq!(
+        round_trip(r#"{ "a" : { "b" : [ -2 ] } }"#),
+        r#"{"a":{"b":[-2]}}"#
+    );
+    assert_eq!(round_trip("18446744073709551615"), "18446744073709551615");
+}
+
+#[test]
+fn strings() {
+    assert_eq!(
+        round_trip(r#""q\" s\\ \/ \b\f\n\r\t \u0001 \u001F é""#),
+        r#""q\" s\\ / \b\f\n\r\t \u0001 \u001f é""#
+    );
+}
+
+#[test]
+fn floats() {
+    let doc = Document::from_owned(json!([0.1, 3.0, -0.0, 1e300, 5e-324]));
+    assert_eq!(doc.to_string(), "[0.1,3.0,-0.0,1e300,5e-324]");
+    for n in [0.1, 1.0 / 3.0, 123456.789e10, f64::MIN_POSITIVE] {
+        let text = Document::from_owned(json!(n)).to_string();
+        assert_eq!(parse_value(&text).unwrap().root().as_f64(), Some(n));
+    }
+}
+
+#[test]
+fn non_finite() {
+    let doc = Document::from_owned(json!([f64::NAN, f64::INFINITY, f64::NEG_INFINITY]));
+    // `Display` can't fail, so it spells them out.
+    assert_eq!(doc.to_string(), "[NaN,Infinity,-Infinity]");
+    assert_eq!(format!("{}", doc.value().at(2)), "-Infinity");
+    let err = doc.try_to_string().unwrap_err();
+    assert_eq!(
+        err,
+        FormatError {
+            pointer: "/0".into()
+        }
+    );
+    let err = doc.write_to(Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    let opts = FormatOptions::default();
+    assert!(doc.to_string_pretty(&opts).is_err());
+    assert!(doc.write_pretty_to(Vec::new(), &opts).is_err());
+    let doc = Document::from_owned(json!({"a": [1, f64::INFINITY]}));
+    let err = doc.value().at("a").try_to_string().unwrap_err();
+    assert_eq!(err.pointer, "/1");
+    let doc = Document::from_owned(json!([f64::NAN, f64::INFINITY, f64::NEG_INFINITY]));
+    let opts = FormatOptions {
+        indent: 0,
+        array_width: 80,
+        space_after_colon: false,
+        allow_non_finite_numbers: true,
+        ..opts
+    };
+    let text = doc.to_string_pretty(&opts).unwrap();
+    assert_eq!(text, "[NaN, Infinity, -Infinity]");
+    let read = ParseOptions {
+        allow_non_finite_numbers: true,
+        ..ParseOptions::default()
+    };
+    let back = parse_value_with(&text, read).unwrap();
+    assert!(back
+        .value()
+        .get_index(0)
+        .unwrap()
+        .as_f64()
+        .unwrap()
+        .is_nan());
+    assert_eq!(
+        back.value().get_index(2).unwrap().as_f64(),
+        Some(f64::NEG_INFINITY)
+    );
+}
+
+#[test]
+fn write_to() {
+    let doc = parse_value(r#"{"k": [1, 2]}"#).unwrap();
+    let mut out = Vec::new();
+    doc.write_to(&mut out).unwrap();
+    assert_eq!(out, br#"{"k":[1,2]}"#);
+    assert_eq!(doc.value().at("k").to_string(), "[1,2]");
+    let mut full = [0u8; 4];
+    assert!(doc.write_to(&mut full[..]).is_err());
+}
+
+#[test]
+fn deep() {
+    let src = "[".repeat(100_000) + &"]".repeat(100_000);
+    let mut doc = Document::default();
+    let mut value = JsonValue::List(Vec::new());
+    for _ in 1..100_000 {
+        let id = doc.alloc(value);
+        value = JsonValue::List(vec![id]);
+    }
+    *doc.root_mut() = value;
+    assert_eq!(doc.to_string(), src);
+}
+
+#[test]
+fn pretty() {
+    let doc = parse_value(r#"{"a": [1, {"b": null}], "c": {}, "d": []}"#).unwrap();
+    let text = doc.to_string_pretty(&FormatOptions::default()).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.first(), Some(&"{"));
+    assert_eq!(lines.last(), Some(&"}"));
+    // Members are in the map's order, so any of them may come last.
+    assert!(lines.iter().any(|l| l.starts_with("  \"c\": {}")));
+    assert!(lines.iter().any(|l| l.starts_with("  \"d\": []")));
+    assert!(lines.contains(&"    {"));
+    assert!(lines.contains(&"      \"b\": null"));
+    assert!(!text.ends_with('\n'));
+    assert!(parse_value(&text).unwrap().deep_eq(&doc));
+}
+
+#[test]
+fn pretty_options() {
+    let doc = parse_value(r#"[[1, 2, 3], [1, 2, 3, 4], [[]], "x"]"#).unwrap();
+    let opts = FormatOptions {
+        indent: 1,
+        indent_char: '\t',
+        trailing_newline: true,
+        array_width: 9,
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        doc.to_string_pretty(&opts).unwrap(),
+        "[\n\t[1, 2, 3],\n\t[\n\t\t1,\n\t\t2,\n\t\t3,\n\t\t4\n\t],\n\t[\n\t\t[]\n\t],\n\t\"x\"\n]\n"
+    );
+    let doc = parse_value(r#"{"k": 1}"#).unwrap();
+    let opts = FormatOptions {
+        space_after_colon: false,
+        ..FormatOptions::default()
+    };
+    assert_eq!(doc.to_string_pretty(&opts).unwrap(), "{\n  \"k\":1\n}");
+    let mut out = Vec::new();
+    doc.write_pretty_to(&mut out, &opts).unwrap();
+    assert_eq!(out, doc.to_string_pretty(&opts).unwrap().as_bytes());
+    assert_eq!(
+        parse_value("[]").unwrap().to_string_pretty(&opts).unwrap(),
+        "[]"
+    );
+}
+
+#[test]
+fn sort_keys_and_ensure_ascii() {
+    let doc =
+        parse_value(r#"{"b": 1, "a": ["é", "😀", "\u0001"], "é": {"z": 0, "y": 0}}"#).unwrap();
+    let opts = FormatOptions {
+        sort_keys: true,
+        ensure_ascii: true,
+        ..FormatOptions::default()
+    };
+    // What Python's `json.dumps(value, indent=2, sort_keys=True)` writes.
+    assert_eq!(
+        doc.to_string_pretty(&opts).unwrap(),
+        r#"{
+  "a": [
+    "\u00e9",
+    "\ud83d\ude00",
+    "\u0001"
+  ],
+  "b": 1,
+  "\u00e9": {
+    "y": 0,
+    "z": 0
+  }
+}"#
+    );
+    let opts = FormatOptions {
+        array_width: 80,
+        ..opts
+    };
+    let doc = parse_value(r#"["ü"]"#).unwrap();
+    assert_eq!(doc.to_string_pretty(&opts).unwrap(), r#"["\u00fc"]"#);
+    assert_eq!(doc.to_string(), r#"["ü"]"#);
+}
+
+#[test]
+fn shortest_floats() {
+    let doc = Document::from_owned(json!([0.1, 0.2 + 0.1, 100.0, 1e16, 2.5e-8]));
+    assert_eq!(
+        doc.to_string(),
+        "[0.1,0.30000000000000004,100.0,1e16,2.5e-8]"
+    );
+    let opts = FormatOptions {
+        trim_float_fraction: true,
+        array_width: 80,
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        doc.to_string_pretty(&opts).unwrap(),
+        "[0.1, 0.30000000000000004, 100, 1e16, 2.5e-8]"
+    );
+    let doc = Document::from_owned(json!(-0.0));
+    assert_eq!(doc.to_string_pretty(&opts).unwrap(), "-0");
+    // Every bit pattern reads back as itself.
+    let mut bits = 0x9e37_79b9_7f4a_7c15u64;
+    for _ in 0..10_000 {
+        bits ^= bits << 13;
+        bits ^= bits >> 7;
+        bits ^= bits << 17;
+        let n = f64::from_bits(bits);
+        if !n.is_finite() {
+            continue;
+        }
+        let text = Document::from_owned(json!(n)).to_string();
+        let back = parse_value(&text).unwrap().root().as_f64().unwrap();
+        assert_eq!(back.to_bits(), bits, "{text}");
+    }
+}
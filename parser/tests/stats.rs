@@ -0,0 +1,100 @@
+use jsonparser::{parse_value, parse_value_with, ParseOptions, Stats};
+
+#[test]
+fn counts() {
+    let doc = parse_value(
+        r#"{"id": 7, "name": "héllo", "tags": ["a", "bc", null], "nested": {"list": [[], [true, 1.5, false]]}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        doc.stats(),
+        Stats {
+            nulls: 1,
+            bools: 2,
+            numbers: 2,
+            strings: 3,
+            lists: 4,
+            objects: 2,
+            raws: 0,
+            max_depth: 4,
+            string_bytes: 9,
+            key_bytes: 20,
+            max_list_len: 3,
+            nodes: 13,
+        }
+    );
+}
+
+#[test]
+fn scalars_and_raw() {
+    let stats = parse_value("\"text\"").unwrap().stats();
+    assert_eq!((stats.strings, stats.max_depth, stats.nodes), (1, 0, 0));
+    assert_eq!(parse_value("[]").unwrap().stats().max_depth, 1);
+    let opts = ParseOptions {
+        raw_keys: &["payload"],
+        ..ParseOptions::default()
+    };
+    let doc = parse_value_with(r#"{"payload": [1, [2]]}"#, opts).unwrap();
+    let stats = doc.stats();
+    assert_eq!((stats.raws, stats.lists, stats.max_depth), (1, 0, 1));
+}
+
+#[test]
+fn depth_matches_max_depth() {
+    let src = r#"[{"a": [[{}]]}, 1]"#;
+    let depth = parse_value(src).unwrap().stats().max_depth;
+    assert_eq!(depth, 5);
+    let opts = |max| ParseOptions {
+        max_depth: Some(max),
+        ..ParseOptions::default()
+    };
+    assert!(parse_value_with(src, opts(depth)).is_ok());
+    assert!(parse_value_with(src, opts(depth - 1)).is_err());
+}
+
+#[test]
+fn deep() {
+    let src = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+    let stats = parse_value(&src).unwrap().stats();
+    assert_eq!(
+        (stats.lists, stats.max_depth, stats.max_list_len),
+        (100_000, 100_000, 1)
+    );
+}
+
+#[test]
+fn path_profile() {
+    let doc = parse_value(
+        r#"{"items": [{"price": 1.5, "tags": ["a"]}, {"price": null}, {"price": 2, "tags": []}],
+        "next page": null, "total": 3}"#,
+    )
+    .unwrap();
+    let profile: Vec<_> = doc
+        .path_profile()
+        .into_iter()
+        .map(|p| (p.path, p.count, p.types.into_iter().collect::<Vec<_>>()))
+        .collect();
+    assert_eq!(
+        profile,
+        [
+            ("$".to_owned(), 1, vec![("object", 1)]),
+            ("$.items".to_owned(), 1, vec![("array", 1)]),
+            ("$.items[*]".to_owned(), 3, vec![("object", 3)]),
+            (
+                "$.items[*].price".to_owned(),
+                3,
+                vec![("null", 1), ("number", 2)]
+            ),
+            ("$.items[*].tags".to_owned(), 2, vec![("array", 2)]),
+            ("$.items[*].tags[*]".to_owned(), 1, vec![("string", 1)]),
+            ("$.total".to_owned(), 1, vec![("number", 1)]),
+            ("$['next page']".to_owned(), 1, vec![("null", 1)]),
+        ]
+    );
+    let doc = parse_value(r#"[[1], {"_x1": {"1a": "it's"}}]"#).unwrap();
+    let paths: Vec<_> = doc.path_profile().into_iter().map(|p| p.path).collect();
+    assert_eq!(
+        paths,
+        ["$", "$[*]", "$[*]._x1", "$[*]._x1['1a']", "$[*][*]"]
+    );
+}
@@ -0,0 +1,48 @@
+use jsonparser::{parse_stream, JsonValue, ParseError, ParseOptions, Stream};
+
+#[test]
+fn concatenated_values() {
+    let mut stream = parse_stream("{\"a\":1}{\"b\":2}[3]\n\"x\" 4 true");
+    let mut ends = Vec::new();
+    let mut count = 0;
+    while let Some(doc) = stream.next() {
+        doc.unwrap();
+        count += 1;
+        ends.push(stream.byte_offset());
+    }
+    assert_eq!(count, 6);
+    assert_eq!(ends, [7, 14, 17, 21, 23, 28]);
+}
+
+#[test]
+fn documents_have_their_own_arena() {
+    let docs: Vec<_> = parse_stream("[1, 2] [3]")
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let JsonValue::List(list) = docs[1].root() else {
+        panic!("expected a list");
+    };
+    assert!(matches!(docs[1].get(list[0]), JsonValue::Number(n) if n.as_i64() == Some(3)));
+}
+
+#[test]
+fn empty_stream() {
+    assert!(parse_stream("  \n ").next().is_none());
+}
+
+#[test]
+fn stops_after_an_error() {
+    let mut stream = parse_stream("1 ] 2");
+    assert!(stream.next().unwrap().is_ok());
+    assert!(matches!(
+        stream.next(),
+        Some(Err(ParseError::UnexpectedToken { .. }))
+    ));
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn from_reader() {
+    let stream = Stream::from_reader("{}{}{}".as_bytes(), ParseOptions::default());
+    assert_eq!(stream.count(), 3);
+}
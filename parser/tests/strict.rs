@@ -0,0 +1,36 @@
+use jsonparser::{parse_value, parse_value_with, ParseError, ParseOptions};
+
+fn strict() -> ParseOptions {
+    ParseOptions {
+        strict: true,
+        ..ParseOptions::default()
+    }
+}
+
+#[test]
+fn numbers_rfc_8259_rejects() {
+    // Lenient mode reads these as the number they look like.
+    for src in ["01", "-01", "007", "00.5", "1.", "-2.", "1.e5", "-.5"] {
+        let err = parse_value_with(src, strict()).unwrap_err();
+        assert!(
+            matches!(err, ParseError::InvalidNumber { .. }),
+            "{src}: {err:?}"
+        );
+        assert!(parse_value(src).is_ok(), "{src}");
+    }
+    // Without JSON5 these don't start a number at all.
+    for src in [".5", "+1"] {
+        let err = parse_value_with(src, strict()).unwrap_err();
+        assert!(
+            matches!(err, ParseError::UnexpectedToken { .. }),
+            "{src}: {err:?}"
+        );
+    }
+}
+
+#[test]
+fn numbers_rfc_8259_allows() {
+    for src in ["0", "-0", "0.5", "-0.5", "10", "1e5", "1.5E-3", "-0e0"] {
+        assert!(parse_value_with(src, strict()).is_ok(), "{src}");
+    }
+}
@@ -0,0 +1,132 @@
+use jsonparser::{
+    parse_tape, parse_tape_with, parse_value, parse_value_with, DuplicateKeys, ParseError,
+    ParseOptions,
+};
+
+#[test]
+fn cursors() {
+    let src = r#"{"users": [{"name": "ada", "tags": []}, {"name": "bob", "age": 36}], "ok": true, "none": null}"#;
+    let tape = parse_tape(src).unwrap();
+    let root = tape.root();
+    assert!(root.is_object());
+    assert_eq!(root.len(), Some(3));
+    let users = root.get("users").unwrap();
+    assert_eq!(users.len(), Some(2));
+    assert_eq!(
+        users
+            .get_index(1)
+            .unwrap()
+            .get("age")
+            .unwrap()
+            .as_number()
+            .unwrap()
+            .as_i64(),
+        Some(36)
+    );
+    assert!(users.get_index(2).is_none());
+    assert_eq!(root.pointer("/users/0/name").unwrap().as_str(), Some("ada"));
+    assert_eq!(
+        root.pointer("/users/0/tags").unwrap().is_empty(),
+        Some(true)
+    );
+    assert_eq!(root.get("ok").unwrap().as_bool(), Some(true));
+    assert!(root.get("none").unwrap().is_null());
+    assert!(root.get("missing").is_none());
+    assert!(root.get_index(0).is_none());
+    let keys: Vec<_> = root.members().unwrap().map(|(k, _)| k).collect();
+    assert_eq!(keys, ["users", "ok", "none"]);
+    let names: Vec<_> = users
+        .elements()
+        .unwrap()
+        .map(|user| user.get("name").unwrap().as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["ada", "bob"]);
+    // One entry per value and key.
+    assert_eq!(tape.len(), 17);
+}
+
+#[test]
+fn same_tree_as_parse_value() {
+    for src in [
+        r#"{"a": [1, -2.5, "x\ny", {"b": {}}, [[]]], "c": {"d": null}, "e": "é"}"#,
+        "[]",
+        "\"top\"",
+        "7",
+        r#"[{"a": [{"b": [1]}]}, 2]"#,
+    ] {
+        let tape = parse_tape(src).unwrap();
+        let doc = parse_value(src).unwrap();
+        assert_eq!(
+            tape.root().to_owned_tree(),
+            doc.value().to_owned_tree(),
+            "{src}"
+        );
+        let inner = tape.root().get_index(0).or(tape.root().get("a"));
+        if let Some(inner) = inner {
+            let expect = doc.value().get_index(0).or(doc.value().get("a")).unwrap();
+            assert_eq!(inner.to_owned_tree(), expect.to_owned_tree(), "{src}");
+        }
+    }
+}
+
+#[test]
+fn duplicate_keys() {
+    let with = |duplicate_keys| ParseOptions {
+        duplicate_keys,
+        ..ParseOptions::default()
+    };
+    let small = r#"{"a": 1, "b": {"x": [1, 2]}, "c": 3, "b": [4], "d": 5}"#;
+    // Past the members searched on the tape, into those found by a map.
+    let members: Vec<_> = (0..40)
+        .map(|i| format!("\"k{i}\": {{\"v\": {i}}}"))
+        .collect();
+    let large = format!(
+        "{{{}, \"k3\": \"again\", \"k39\": 0, \"end\": 1}}",
+        members.join(", ")
+    );
+    for src in [small, &large] {
+        for policy in [DuplicateKeys::FirstWins, DuplicateKeys::LastWins] {
+            let tape = parse_tape_with(src, with(policy)).unwrap();
+            let doc = parse_value_with(src, with(policy)).unwrap();
+            assert_eq!(
+                tape.root().to_owned_tree(),
+                doc.value().to_owned_tree(),
+                "{policy:?}"
+            );
+            assert_eq!(tape.root().len(), doc.value().as_object().map(|o| o.len()));
+        }
+        let err = parse_tape_with(src, with(DuplicateKeys::Error)).unwrap_err();
+        assert!(matches!(err, ParseError::DuplicateKey { .. }), "{err:?}");
+    }
+    let tape = parse_tape_with(small, with(DuplicateKeys::LastWins)).unwrap();
+    let keys: Vec<_> = tape.root().members().unwrap().map(|(k, _)| k).collect();
+    assert_eq!(keys, ["a", "c", "b", "d"]);
+    assert_eq!(
+        tape.root()
+            .pointer("/b/0")
+            .unwrap()
+            .as_number()
+            .unwrap()
+            .as_i64(),
+        Some(4)
+    );
+}
+
+#[test]
+fn errors() {
+    assert!(parse_tape(r#"{"a": [1, 2}"#).is_err());
+    assert!(parse_tape("[1] [2]").is_err());
+    assert!(parse_tape("").is_err());
+}
+
+#[test]
+fn misplaced_strings_are_named() {
+    for src in [r#"[1 "x"]"#, r#"{"a": 1 "b\n": 2}"#, r#"{"a" "b"}"#] {
+        let expected = parse_value(src).unwrap_err();
+        assert!(
+            matches!(expected, ParseError::UnexpectedToken { .. }),
+            "{src}"
+        );
+        assert_eq!(parse_tape(src).unwrap_err(), expected, "{src}");
+    }
+}
@@ -0,0 +1,326 @@
+use jsonparser::{parse_value, Document, EqOptions, TomlError};
+
+fn toml(src: &str) -> Result<String, TomlError> {
+    parse_value(src).unwrap().to_toml_string()
+}
+
+fn json(src: &str) -> String {
+    Document::from_toml(src)
+        .unwrap()
+        .to_canonical_string()
+        .unwrap()
+}
+
+fn canonical(src: &str) -> String {
+    parse_value(src).unwrap().to_canonical_string().unwrap()
+}
+
+#[test]
+fn writes_tables() {
+    assert_eq!(toml(r#"{"title": "x"}"#).unwrap(), "title = \"x\"\n");
+    assert_eq!(
+        toml(r#"{"server": {"limits": {"max": 10}}}"#).unwrap(),
+        "[server]\n\n[server.limits]\nmax = 10\n"
+    );
+    assert_eq!(
+        toml(r#"{"fruit": [{"name": "apple"}, {"name": "pear"}]}"#).unwrap(),
+        "[[fruit]]\nname = \"apple\"\n\n[[fruit]]\nname = \"pear\"\n"
+    );
+    assert_eq!(
+        toml(r#"{"fruit": [{"variety": [{"name": "red"}]}]}"#).unwrap(),
+        "[[fruit]]\n\n[[fruit.variety]]\nname = \"red\"\n"
+    );
+    assert_eq!(toml("{}").unwrap(), "");
+    assert_eq!(toml(r#"{"empty": {}}"#).unwrap(), "[empty]\n");
+}
+
+#[test]
+fn writes_inline_values() {
+    assert_eq!(
+        toml(r#"{"a": [1, [2.5, "x"], [], {"b": true}, {}]}"#).unwrap(),
+        "a = [1, [2.5, \"x\"], [], { b = true }, {}]\n"
+    );
+    assert_eq!(
+        toml(r#"{"a": [{"b": [{"c": 1}]}, 2]}"#).unwrap(),
+        "a = [{ b = [{ c = 1 }] }, 2]\n"
+    );
+    assert_eq!(
+        toml(r#"{"a": [1.0, -0.5, 1e300, 9223372036854775807]}"#).unwrap(),
+        "a = [1.0, -0.5, 1e300, 9223372036854775807]\n"
+    );
+    assert_eq!(
+        toml(r#"{"s": "q\"b\\n\n\t\u0001\u007fé"}"#).unwrap(),
+        "s = \"q\\\"b\\\\n\\n\\t\\u0001\\u007fé\"\n"
+    );
+    assert_eq!(
+        toml(r#"{"": {"a.b": 1, "x y": {"é": 2}}}"#)
+            .unwrap()
+            .lines()
+            .filter(|line| line.starts_with('['))
+            .collect::<Vec<_>>(),
+        ["[\"\"]", "[\"\".\"x y\"]"]
+    );
+    assert_eq!(
+        toml(r#"{"a-b_9": {"\"": 1}}"#).unwrap(),
+        "[a-b_9]\n\"\\\"\" = 1\n"
+    );
+}
+
+#[test]
+fn unrepresentable() {
+    for (src, pointer, message) in [
+        ("[1]", "", "a document that isn't an object"),
+        ("null", "", "a document that isn't an object"),
+        (r#"{"a": null}"#, "/a", "null, which TOML has no value for"),
+        (
+            r#"{"a": {"b/c": [1, [null]]}}"#,
+            "/a/b~1c/1/0",
+            "null, which TOML has no value for",
+        ),
+        (
+            r#"{"a": [{"b": 1}, {"c": null}]}"#,
+            "/a/1/c",
+            "null, which TOML has no value for",
+        ),
+        (
+            r#"{"a": [{"b": null}, 1]}"#,
+            "/a/0/b",
+            "null, which TOML has no value for",
+        ),
+        (
+            r#"{"a": 9223372036854775808}"#,
+            "/a",
+            "an integer past TOML's 64 bits",
+        ),
+    ] {
+        assert_eq!(
+            toml(src),
+            Err(TomlError::Unrepresentable {
+                pointer: pointer.to_owned(),
+                message
+            }),
+            "{src}"
+        );
+    }
+    assert_eq!(
+        toml(r#"{"a": null}"#).unwrap_err().to_string(),
+        r#"Can't write "/a" as TOML: null, which TOML has no value for."#
+    );
+}
+
+#[test]
+fn reads() {
+    let src = r#"
+# This is a TOML document.
+title = "TOML Example"
+
+[owner]
+name = "Tom Preston-Werner"
+dob = 1979-05-27T07:32:00-08:00 # First class dates
+
+[database]
+enabled = true
+ports = [ 8000, 8001, 8002 ]
+data = [ ["delta", "phi"], [3.14] ]
+temp_targets = { cpu = 79.5, case = 72.0 }
+
+[servers]
+
+  [servers.alpha]
+  ip = "10.0.0.1"
+  role = "frontend"
+
+[[products]]
+name = "Hammer"
+sku = 738594937
+
+[[products]]  # empty table within the array
+
+[[products]]
+name = "Nail"
+color = "gray"
+"#;
+    assert_eq!(
+        json(src),
+        canonical(
+            r#"{"title": "TOML Example",
+            "owner": {"name": "Tom Preston-Werner", "dob": "1979-05-27T07:32:00-08:00"},
+            "database": {"enabled": true, "ports": [8000, 8001, 8002],
+                "data": [["delta", "phi"], [3.14]], "temp_targets": {"cpu": 79.5, "case": 72.0}},
+            "servers": {"alpha": {"ip": "10.0.0.1", "role": "frontend"}},
+            "products": [{"name": "Hammer", "sku": 738594937}, {}, {"name": "Nail", "color": "gray"}]}"#
+        )
+    );
+    assert_eq!(json(""), "{}");
+    assert_eq!(json("a = 1\r\nb = 2\r\n"), r#"{"a":1,"b":2}"#);
+}
+
+#[test]
+fn reads_keys_and_tables() {
+    assert_eq!(
+        json("a.b.c = 1\na.d = 2\n\"x.y\" = 3\n'z' = 4\n1234 = 5\n[a.e]\nf = 6"),
+        canonical(r#"{"a": {"b": {"c": 1}, "d": 2, "e": {"f": 6}}, "x.y": 3, "z": 4, "1234": 5}"#)
+    );
+    assert_eq!(
+        json("[x.y.z]\na = 1\n[x]\nb = 2"),
+        canonical(r#"{"x": {"y": {"z": {"a": 1}}, "b": 2}}"#)
+    );
+    assert_eq!(
+        json("[[a]]\n[a.b]\nc = 1\n[[a]]\n[[a.d]]\ne = 2\n[[a.d]]"),
+        canonical(r#"{"a": [{"b": {"c": 1}}, {"d": [{"e": 2}, {}]}]}"#)
+    );
+    assert_eq!(
+        json("a = { b.c = 1, d = [ 1,\n  2, # two\n] }"),
+        canonical(r#"{"a": {"b": {"c": 1}, "d": [1, 2]}}"#)
+    );
+    assert_eq!(json("[ a . 'b' ]\n"), r#"{"a":{"b":{}}}"#);
+}
+
+#[test]
+fn reads_values() {
+    let doc = Document::from_toml(
+        "ints = [+99, -17, 0, 1_000, 0xDEAD_beef, 0o755, 0b1101]\n\
+         floats = [+1.0, 3.1415, -0.01, 5e+22, 1e06, -2E-2, 6.626e-34, 224_617.445_991]\n\
+         special = [inf, -inf, nan, +nan]\n\
+         dates = [1979-05-27T07:32:00Z, 1979-05-27 00:32:00.999999-07:00, 1979-05-27T07:32:00, \
+         1979-05-27, 07:32:00, 00:32:00.999]",
+    )
+    .unwrap();
+    let value = doc.value();
+    assert_eq!(
+        value.at("ints").to_string(),
+        "[99,-17,0,1000,3735928559,493,13]"
+    );
+    assert_eq!(
+        value.at("floats").to_string(),
+        "[1.0,3.1415,-0.01,5e22,1000000.0,-0.02,6.626e-34,224617.445991]"
+    );
+    let special = value.at("special");
+    assert_eq!(special.at(0).as_f64(), Some(f64::INFINITY));
+    assert_eq!(special.at(1).as_f64(), Some(f64::NEG_INFINITY));
+    assert!(special.at(3).as_f64().is_some_and(f64::is_nan));
+    assert_eq!(
+        value.at("dates").to_string(),
+        r#"["1979-05-27T07:32:00Z","1979-05-27 00:32:00.999999-07:00","1979-05-27T07:32:00","1979-05-27","07:32:00","00:32:00.999"]"#
+    );
+}
+
+#[test]
+fn reads_strings() {
+    assert_eq!(
+        json(r#"s = "tab\there \"q\" \\ \u00e9 \U0001F600 \b\f\r""#),
+        canonical(r#"{"s": "tab\there \"q\" \\ é 😀 \b\f\r"}"#)
+    );
+    assert_eq!(
+        json(r"s = 'C:\Users\x'"),
+        canonical(r#"{"s": "C:\\Users\\x"}"#)
+    );
+    assert_eq!(
+        json("s = \"\"\"\nRoses\nare \\\n    red\"\"\"\nt = '''\n\\n ''stays'' '''"),
+        canonical(r#"{"s": "Roses\nare red", "t": "\\n ''stays'' "}"#)
+    );
+    assert_eq!(
+        json("s = \"\"\"\"quoted\"\"\"\"\"\nt = ''''''"),
+        canonical(r#"{"s": "\"quoted\"\"", "t": ""}"#)
+    );
+}
+
+#[test]
+fn round_trips() {
+    let src = r#"{"name": "api", "ports": [80, 443], "nested": {"deep": {"x": [[1, {"a": {}}], []]}},
+        "servers": [{"host": "one", "tags": [{"t": 1}]}, {"host": "two", "on": false}],
+        "tricky": ["", "line\nbreak", "\"", "\u0001", "a = b"], "numbers": [0, -1, 1.5, 1e100],
+        "keys": {"": 1, "a.b": 2, "é": 3, "[x]": {"y": 4}}}"#;
+    let doc = parse_value(src).unwrap();
+    let back = Document::from_toml(&doc.to_toml_string().unwrap()).unwrap();
+    assert!(back.deep_eq_with(
+        &doc,
+        EqOptions {
+            numbers_by_value: true
+        }
+    ));
+}
+
+#[test]
+fn errors() {
+    for (src, line, column, message) in [
+        ("a = 1\na = 2", 2, 1, "a key that is already defined"),
+        ("a.b = 1\na.b.c = 2", 2, 3, "a key that is already defined"),
+        ("a = {}\na.b = 1", 2, 1, "a key that is already defined"),
+        ("a = {}\n[a]", 2, 2, "a key that is already defined"),
+        ("a = 1\n[a.b]", 2, 2, "a key that is already defined"),
+        ("[a]\n[a]", 2, 2, "a table that is already defined"),
+        (
+            "[a]\nb.c = 1\n[a.b]",
+            3,
+            4,
+            "a table that is already defined",
+        ),
+        ("[[a]]\n[a]", 2, 2, "a table that is already defined"),
+        ("[a]\n[[a]]", 2, 3, "a table that is already defined"),
+        ("a = [{}]\n[[a]]", 2, 3, "a key that is already defined"),
+        ("[a.b]\n[a]\nb.c = 1", 3, 1, "a key that is already defined"),
+        ("a = {b = 1, b = 2}", 1, 13, "a key that is already defined"),
+        ("a = {b = 1,}", 1, 12, "a trailing comma in an inline table"),
+        (
+            "a = {b = 1\n}",
+            1,
+            11,
+            "an inline table that doesn't end on its line",
+        ),
+        ("a = {b = 1 c = 2}", 1, 12, "a missing comma"),
+        ("a = [1 2]", 1, 8, "a missing comma"),
+        ("a = [1,,2]", 1, 8, "a comma without a value before it"),
+        ("a = [1", 1, 7, "an unterminated array"),
+        ("a =", 1, 4, "a missing value"),
+        ("a = # none", 1, 5, "a missing value"),
+        ("a", 1, 2, "a key without `=` after it"),
+        ("= 1", 1, 1, "a missing key"),
+        ("a = 1 b = 2", 1, 7, "more on the line after its end"),
+        ("[a] b", 1, 5, "more on the line after its end"),
+        ("[a", 1, 3, "a header without its closing bracket"),
+        ("[[a]", 1, 5, "a header without its closing bracket"),
+        ("a = \"open", 1, 5, "an unterminated string"),
+        ("a = 'open\n'", 1, 5, "an unterminated string"),
+        ("a = \"\"\"open", 1, 5, "an unterminated string"),
+        ("a = \"\\q\"", 1, 6, "an unknown escape"),
+        ("a = \"\\u12\"", 1, 6, "an escape without enough hex digits"),
+        ("a = \"\\ud800\"", 1, 6, "an escape that isn't a character"),
+        ("a = \"\u{1}\"", 1, 6, "a control character in a string"),
+        ("# \u{7f}", 1, 3, "a control character in a comment"),
+        (
+            "a = \"\"\"x\"\"\"\"\"\"",
+            1,
+            9,
+            "too many quotes at the end of a string",
+        ),
+        ("a = 01", 1, 5, "a number with a leading zero"),
+        ("a = 1__0", 1, 5, "an invalid value"),
+        ("a = 9223372036854775808", 1, 5, "an integer past 64 bits"),
+        ("a = 0xffffffffffffffff", 1, 5, "an integer past 64 bits"),
+        ("a = 1.", 1, 5, "an invalid value"),
+        ("a = TRUE", 1, 5, "an invalid value"),
+        ("a = 1979-02-29", 1, 5, "an invalid value"),
+        ("a = 07:32:00Z", 1, 5, "an invalid value"),
+        ("a = @", 1, 5, "an unexpected character"),
+    ] {
+        assert_eq!(
+            Document::from_toml(src).map(|doc| doc.to_string()),
+            Err(TomlError::Syntax {
+                line,
+                column,
+                message
+            }),
+            "{src:?}"
+        );
+    }
+    assert_eq!(
+        TomlError::Syntax {
+            line: 2,
+            column: 1,
+            message: "a key that is already defined"
+        }
+        .to_string(),
+        "Invalid TOML at line 2, column 1: a key that is already defined."
+    );
+}
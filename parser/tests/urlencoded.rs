@@ -0,0 +1,117 @@
+use jsonparser::{parse_value, Document, UrlencodedError};
+
+fn json(src: &str) -> String {
+    Document::from_urlencoded(src)
+        .unwrap()
+        .to_canonical_string()
+        .unwrap()
+}
+
+fn canonical(src: &str) -> String {
+    parse_value(src).unwrap().to_canonical_string().unwrap()
+}
+
+fn form(src: &str) -> Result<String, UrlencodedError> {
+    parse_value(src).unwrap().to_urlencoded()
+}
+
+#[test]
+fn reads() {
+    assert_eq!(
+        json("a=1&b[]=2&b[]=3"),
+        canonical(r#"{"a": "1", "b": ["2", "3"]}"#)
+    );
+    assert_eq!(
+        json("user[name]=Ann+Lee&user[langs][]=en&user[langs][]=fr&user[addr][city]=Paris"),
+        canonical(
+            r#"{"user": {"name": "Ann Lee", "langs": ["en", "fr"], "addr": {"city": "Paris"}}}"#
+        )
+    );
+    assert_eq!(
+        json("e[][f]=5&e[][g]=6&e[][f]=7&e[][h][]=8&e[][h][]=9"),
+        canonical(r#"{"e": [{"f": "5", "g": "6"}, {"f": "7", "h": ["8", "9"]}]}"#)
+    );
+    assert_eq!(json("m[][]=1&m[][]=2"), r#"{"m":[["1"],["2"]]}"#);
+    assert_eq!(json("&&flag&empty=&=x"), r#"{"":"x","empty":"","flag":""}"#);
+    assert_eq!(json(""), "{}");
+}
+
+#[test]
+fn decodes() {
+    assert_eq!(
+        json("q=caf%C3%A9+%26+cr%c3%a8me&b%5B%5D=1&b%5B%5D=2"),
+        canonical(r#"{"q": "café & crème", "b": ["1", "2"]}"#)
+    );
+    // Bad escapes are kept, and bytes that aren't UTF-8 replaced.
+    assert_eq!(
+        json("a=100%&b=%zz&c=%+1&d=%ff"),
+        canonical(r#"{"a": "100%", "b": "%zz", "c": "% 1", "d": "�"}"#)
+    );
+    // Names that aren't in bracket notation are taken as they are.
+    assert_eq!(
+        json("a[b=1&[c]=2&d]=3&e[f]g=4&h[i[j]]=5"),
+        canonical(r#"{"a[b": "1", "[c]": "2", "d]": "3", "e[f]g": "4", "h[i[j]]": "5"}"#)
+    );
+}
+
+#[test]
+fn conflicts() {
+    for (src, key) in [
+        ("a=1&a=2", "a"),
+        ("a=1&a[b]=2", "a[b]"),
+        ("a[b]=1&a=2", "a"),
+        ("a[]=1&a[b]=2", "a[b]"),
+        ("a[b]=1&a[]=2", "a[]"),
+        ("a[b]=1&a[b]=2", "a[b]"),
+        ("a[b]=1&a[b][c]=2", "a[b][c]"),
+    ] {
+        assert_eq!(
+            Document::from_urlencoded(src).map(|doc| doc.to_string()),
+            Err(UrlencodedError::Conflict {
+                key: key.to_owned()
+            }),
+            "{src}"
+        );
+    }
+    assert_eq!(
+        UrlencodedError::Conflict {
+            key: "a[b]".to_owned()
+        }
+        .to_string(),
+        r#"The form field "a[b]" conflicts with an earlier one."#
+    );
+}
+
+#[test]
+fn writes() {
+    assert_eq!(
+        form(r#"{"b": [2, "3", true, null]}"#).unwrap(),
+        "b[]=2&b[]=3&b[]=true&b[]="
+    );
+    assert_eq!(
+        form(r#"{"user": {"addr": {"city": "São Paulo"}}}"#).unwrap(),
+        "user[addr][city]=S%C3%A3o+Paulo"
+    );
+    assert_eq!(
+        form(r#"{"e": [{"f": 1}, {"g": [1.5]}]}"#).unwrap(),
+        "e[][f]=1&e[][g][]=1.5"
+    );
+    assert_eq!(
+        form(r#"{"a&b=[c]": "x=y&z", "empty": [], "none": {}}"#).unwrap(),
+        "a%26b%3D%5Bc%5D=x%3Dy%26z"
+    );
+    assert_eq!(form("{}").unwrap(), "");
+    assert_eq!(form("[1]"), Err(UrlencodedError::NotObject));
+}
+
+#[test]
+fn round_trips() {
+    let src = r#"{"name": "a b+c", "tags": ["x", "y"], "nested": {"deep": {"list": ["1", "2"]}},
+        "rows": [{"id": "1", "v": "a"}, {"id": "2"}], "odd": "%&=?#"}"#;
+    let doc = parse_value(src).unwrap();
+    let back = Document::from_urlencoded(&doc.to_urlencoded().unwrap()).unwrap();
+    assert_eq!(
+        back.to_canonical_string().unwrap(),
+        doc.to_canonical_string().unwrap()
+    );
+}
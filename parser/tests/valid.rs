@@ -0,0 +1,93 @@
+use jsonparser::{
+    is_valid, is_valid_with, parse_value, parse_value_with, DuplicateKeys, ParseOptions,
+};
+
+#[test]
+fn agrees_with_the_parser() {
+    for src in [
+        r#"{"a": [1, 2, {"b": null}], "c": "d"}"#,
+        "[]",
+        "{}",
+        "[[[]]]",
+        "1",
+        "[1, 2,]",
+        r#"{"a" 1}"#,
+        r#"{"a": 1,}"#,
+        "[1 2]",
+        "[1, 2",
+        "1 2",
+        r#"{"a": }"#,
+        r#"{1: 2}"#,
+        "",
+        "]",
+    ] {
+        assert_eq!(is_valid(src), parse_value(src).map(|_| ()), "{src}");
+    }
+}
+
+#[test]
+fn respects_options() {
+    let opts = ParseOptions {
+        allow_trailing_commas: true,
+        ..ParseOptions::default()
+    };
+    assert!(is_valid_with("[1, {\"a\": 2,},]", opts).is_ok());
+    let opts = ParseOptions {
+        max_depth: Some(2),
+        ..ParseOptions::default()
+    };
+    assert!(is_valid_with("[[1]]", opts).is_ok());
+    assert!(is_valid_with("[[[1]]]", opts).is_err());
+}
+
+#[test]
+fn agrees_on_limits_and_keys() {
+    let srcs = [
+        r#"{"a": 1, "b": 2, "a": 3}"#,
+        r#"{"a": {"a": 1}, "b": [{"c": 1, "c": 2}]}"#,
+        r#"[{"a": [1, 2, 3]}, {"a": [4], "a": 5}]"#,
+        r#"{"raw": [1 2 {], "x": 1}"#,
+        r#"{"raw": [1 2 {]}"#,
+        "[[], [[1, 2]], {}, 3]",
+        "7",
+    ];
+    for duplicate_keys in [
+        DuplicateKeys::FirstWins,
+        DuplicateKeys::LastWins,
+        DuplicateKeys::Error,
+    ] {
+        for max_nodes in [None, Some(0), Some(1), Some(3), Some(5)] {
+            for raw_keys in [&[][..], &["raw"][..]] {
+                let opts = ParseOptions {
+                    duplicate_keys,
+                    max_nodes,
+                    raw_keys,
+                    ..ParseOptions::default()
+                };
+                for src in srcs {
+                    assert_eq!(
+                        is_valid_with(src, opts),
+                        parse_value_with(src, opts).map(|_| ()),
+                        "{src} {opts:?}"
+                    );
+                }
+            }
+        }
+    }
+    let opts = ParseOptions {
+        duplicate_keys: DuplicateKeys::Error,
+        ..ParseOptions::default()
+    };
+    assert!(is_valid_with(srcs[1], opts).is_err());
+    let opts = ParseOptions {
+        max_nodes: Some(3),
+        ..ParseOptions::default()
+    };
+    assert!(is_valid_with(srcs[5], opts).is_err());
+}
+
+#[test]
+fn deep_nesting() {
+    let src = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+    assert!(is_valid(&src).is_ok());
+}
@@ -0,0 +1,112 @@
+use std::ops::ControlFlow;
+
+use jsonparser::{parse_value, WalkOrder};
+
+const SRC: &str = r#"[{"a": [1, 2]}, "b/c", [[3]]]"#;
+
+fn visits(order: WalkOrder) -> Vec<String> {
+    let doc = parse_value(SRC).unwrap();
+    let mut seen = Vec::new();
+    let flow: ControlFlow<()> = doc.walk_with(order, |path, value| {
+        seen.push(format!("{path} {value}"));
+        ControlFlow::Continue(())
+    });
+    assert_eq!(flow, ControlFlow::Continue(()));
+    seen
+}
+
+#[test]
+fn depth_first() {
+    assert_eq!(
+        visits(WalkOrder::DepthFirst),
+        [
+            r#" [{"a":[1,2]},"b/c",[[3]]]"#,
+            r#"/0 {"a":[1,2]}"#,
+            "/0/a [1,2]",
+            "/0/a/0 1",
+            "/0/a/1 2",
+            r#"/1 "b/c""#,
+            "/2 [[3]]",
+            "/2/0 [3]",
+            "/2/0/0 3",
+        ]
+    );
+}
+
+#[test]
+fn breadth_first() {
+    assert_eq!(
+        visits(WalkOrder::BreadthFirst),
+        [
+            r#" [{"a":[1,2]},"b/c",[[3]]]"#,
+            r#"/0 {"a":[1,2]}"#,
+            r#"/1 "b/c""#,
+            "/2 [[3]]",
+            "/0/a [1,2]",
+            "/2/0 [3]",
+            "/0/a/0 1",
+            "/0/a/1 2",
+            "/2/0/0 3",
+        ]
+    );
+}
+
+#[test]
+fn early_exit() {
+    let doc = parse_value(r#"{"x": {"a~b": [true, "found", "later"]}}"#).unwrap();
+    let mut visited = 0;
+    let found = doc.walk(|path, value| {
+        visited += 1;
+        match value.as_str() {
+            Some(s) => ControlFlow::Break((path.to_owned(), s.to_owned())),
+            None => ControlFlow::Continue(()),
+        }
+    });
+    assert_eq!(
+        found,
+        ControlFlow::Break(("/x/a~0b/1".to_owned(), "found".to_owned()))
+    );
+    assert_eq!(visited, 5);
+}
+
+#[test]
+fn deep() {
+    let src = format!("{}{}", "[".repeat(10_000), "]".repeat(10_000));
+    let doc = parse_value(&src).unwrap();
+    for order in [WalkOrder::DepthFirst, WalkOrder::BreadthFirst] {
+        let mut count = 0;
+        let _: ControlFlow<()> = doc.walk_with(order, |_, _| {
+            count += 1;
+            ControlFlow::Continue(())
+        });
+        assert_eq!(count, 10_000);
+    }
+}
+
+#[test]
+fn find_key() {
+    let doc = parse_value(r#"[{"id": 1, "more": {"id": {"id": 2}}}, {"x": [{"id": null}]}, "id"]"#)
+        .unwrap();
+    let found: Vec<_> = doc
+        .find_key("id")
+        .into_iter()
+        .map(|(path, value)| format!("{path} {value}"))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            "/0/id 1",
+            r#"/0/more/id {"id":2}"#,
+            "/0/more/id/id 2",
+            "/1/x/0/id null",
+        ]
+    );
+    let doc = parse_value(r#"{"a/b": {"a/b": true}}"#).unwrap();
+    let paths: Vec<_> = doc
+        .find_key("a/b")
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+    assert_eq!(paths, ["/a~1b", "/a~1b/a~1b"]);
+    assert!(doc.find_key("missing").is_empty());
+}
@@ -0,0 +1,89 @@
+use jsonparser::{parse_value, JsonWriter, WriteError};
+
+fn written(f: impl FnOnce(&mut JsonWriter<Vec<u8>>) -> Result<(), WriteError>) -> String {
+    let mut w = JsonWriter::new(Vec::new());
+    f(&mut w).unwrap();
+    String::from_utf8(w.finish().unwrap()).unwrap()
+}
+
+#[test]
+fn events() {
+    let text = written(|w| {
+        w.begin_object()?;
+        w.key("name")?;
+        w.value("a \"b\"")?;
+        w.key("list")?;
+        w.begin_array()?;
+        w.value(&1)?;
+        w.value(&vec![true, false])?;
+        w.begin_object()?;
+        w.end_object()?;
+        w.value(&None::<i64>)?;
+        w.end_array()?;
+        w.end_object()
+    });
+    assert_eq!(
+        text,
+        r#"{"name":"a \"b\"","list":[1,[true,false],{},null]}"#
+    );
+    assert_eq!(written(|w| w.value(&2.5)), "2.5");
+}
+
+#[test]
+fn splices_parsed_values() {
+    let doc = parse_value(r#"{"k": [1, {"x": null}]}"#).unwrap();
+    let text = written(|w| {
+        w.begin_array()?;
+        w.value_ref(doc.value().at("k"))?;
+        w.value_ref(doc.value().at("k").at(1))?;
+        w.end_array()
+    });
+    assert_eq!(text, r#"[[1,{"x":null}],{"x":null}]"#);
+}
+
+#[test]
+fn rejects_malformed_calls() {
+    let mut w = JsonWriter::new(Vec::new());
+    assert!(matches!(
+        w.key("k"),
+        Err(WriteError::Unexpected { call: "key", .. })
+    ));
+    w.begin_object().unwrap();
+    let err = w.value(&1).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unexpected value, expected a key or end_object."
+    );
+    assert!(w.end_array().is_err());
+    w.key("k").unwrap();
+    assert!(w.end_object().is_err());
+    assert!(w.key("again").is_err());
+    w.value(&1).unwrap();
+    w.end_object().unwrap();
+    assert!(w.begin_array().is_err());
+    assert_eq!(w.depth(), 0);
+    assert_eq!(w.finish().unwrap(), br#"{"k":1}"#);
+
+    let mut w = JsonWriter::new(Vec::new());
+    w.begin_array().unwrap();
+    assert!(matches!(
+        w.finish(),
+        Err(WriteError::Unexpected {
+            call: "finish",
+            expected: "a value or end_array"
+        })
+    ));
+}
+
+#[test]
+fn many_values() {
+    let text = written(|w| {
+        w.begin_array()?;
+        for i in 0..10_000 {
+            w.value(&i)?;
+        }
+        w.end_array()
+    });
+    let doc = parse_value(&text).unwrap();
+    assert_eq!(doc.root().as_array().unwrap().len(), 10_000);
+}
@@ -0,0 +1,330 @@
+use jsonparser::parse_value;
+
+fn yaml(src: &str) -> String {
+    parse_value(src).unwrap().to_yaml_string()
+}
+
+#[test]
+fn blocks() {
+    assert_eq!(
+        yaml(r#"{"a": {"b": {"c": null}}}"#),
+        "a:\n  b:\n    c: null\n"
+    );
+    assert_eq!(yaml("[1, [2, 3], []]"), "- 1\n- - 2\n  - 3\n- []\n");
+    assert_eq!(
+        yaml(r#"[{"a": 1}, {}, {"b": [true]}]"#),
+        "- a: 1\n- {}\n- b:\n    - true\n"
+    );
+    assert_eq!(yaml(r#"{"a": []}"#), "a: []\n");
+    assert_eq!(yaml("3"), "3\n");
+    assert_eq!(yaml("{}"), "{}\n");
+}
+
+#[test]
+fn scalars() {
+    assert_eq!(
+        yaml("[1.0, -0.5, 1e300, 18446744073709551615]"),
+        "- 1.0\n- -0.5\n- 1e300\n- 18446744073709551615\n"
+    );
+    assert_eq!(
+        yaml(r#"["plain text", "https://example.com/a#b", "a:b"]"#),
+        "- plain text\n- https://example.com/a#b\n- a:b\n"
+    );
+    // Strings that would read back as something else, or can't be plain.
+    for s in [
+        "",
+        "true",
+        "False",
+        "null",
+        "~",
+        "12",
+        "-3",
+        "1.5",
+        ".5",
+        "1e3",
+        "0x1F",
+        "0o17",
+        ".inf",
+        "-.Inf",
+        ".nan",
+        "yes",
+        "off",
+        "<<",
+        "- item",
+        "-x",
+        ":x",
+        "key: value",
+        "a #b",
+        " pad",
+        "pad ",
+        "end:",
+        "[1]",
+        "{}",
+        "#tag",
+        "&a",
+        "*a",
+        "!x",
+        "|",
+        ">",
+        "'q'",
+        "\"q\"",
+        "%x",
+        "@x",
+        "`x",
+        "...",
+        "a\nb",
+        "tab\t",
+    ] {
+        let out = yaml(&format!("{s:?}"));
+        assert!(out.starts_with('"'), "{s:?} gave {out:?}");
+    }
+    assert_eq!(
+        yaml(r#""line\nbreak \"quoted\" \\ \u0007""#),
+        "\"line\\nbreak \\\"quoted\\\" \\\\ \\u0007\"\n"
+    );
+    assert_eq!(yaml(r#""\u2028""#), "\"\\u2028\"\n");
+    assert_eq!(yaml(r#"{"": 1}"#), "\"\": 1\n");
+}
+
+#[test]
+fn long_keys() {
+    let key = "k".repeat(1025);
+    let out = yaml(&format!(r#"{{"{key}": [1]}}"#));
+    assert_eq!(out, format!("? {key}\n:\n  - 1\n"));
+    let key = "k".repeat(1024);
+    assert_eq!(yaml(&format!(r#"{{"{key}": 1}}"#)), format!("{key}: 1\n"));
+}
+
+#[test]
+fn deep() {
+    let src = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+    let out = yaml(&src);
+    assert!(out.ends_with("- []\n"));
+    assert_eq!(out.matches("- ").count(), 99_999);
+}
+
+#[cfg(feature = "yaml")]
+mod parse {
+    use jsonparser::{parse_value, parse_yaml, EqOptions, YamlError};
+
+    fn json(src: &str) -> String {
+        parse_yaml(src).unwrap().to_canonical_string().unwrap()
+    }
+
+    fn canonical(src: &str) -> String {
+        parse_value(src).unwrap().to_canonical_string().unwrap()
+    }
+
+    #[test]
+    fn blocks() {
+        let src = "\
+# A service.
+name: api
+ports:
+  - 80
+  - 443
+env:
+  debug: false   # for now
+  level:
+tags:
+- a
+- b
+servers:
+  - host: one
+    weight: 2
+  - host: two
+matrix:
+  - - 1
+    - 2
+  -
+    - 3
+";
+        assert_eq!(
+            json(src),
+            canonical(
+                r#"{"name": "api", "ports": [80, 443], "env": {"debug": false, "level": null},
+                "tags": ["a", "b"], "servers": [{"host": "one", "weight": 2}, {"host": "two"}],
+                "matrix": [[1, 2], [3]]}"#
+            )
+        );
+        assert_eq!(json("- a\n-\n- c\n"), r#"["a",null,"c"]"#);
+        assert_eq!(json("---\nkey: value\n...\n"), r#"{"key":"value"}"#);
+        assert_eq!(json("--- [1, 2]\n"), "[1,2]");
+        assert_eq!(json(""), "null");
+        assert_eq!(json("# nothing\n\n"), "null");
+        assert_eq!(json("a: 1\r\nb: 2\r\n"), r#"{"a":1,"b":2}"#);
+        assert_eq!(json("\u{feff}just text"), r#""just text""#);
+    }
+
+    #[test]
+    fn scalars() {
+        assert_eq!(
+            json("[~, null, Null, true, FALSE, 12, -3, +4, 007, 0x1F, 0o17, 1.5, .5, 1., 1e3, -1E-2]"),
+            "[null,null,null,true,false,12,-3,4,7,31,15,1.5,0.5,1,1000,-0.01]"
+        );
+        let doc = parse_yaml("[.nan, -.Inf, 9223372036854775808, 0xffffffffffffffffff]").unwrap();
+        let list = doc.value();
+        assert!(list.at(0).as_f64().is_some_and(f64::is_nan));
+        assert_eq!(list.at(1).as_f64(), Some(f64::NEG_INFINITY));
+        assert_eq!(list.at(2).as_u64(), Some(9223372036854775808));
+        assert_eq!(list.at(3).as_f64(), Some(4722366482869645213696.0));
+        assert_eq!(
+            json("[yes, 1.2.3, 0x, 12abc, a b, 'single ''quoted''', \"double\"]"),
+            r#"["yes","1.2.3","0x","12abc","a b","single 'quoted'","double"]"#
+        );
+    }
+
+    #[test]
+    fn quoted() {
+        assert_eq!(
+            json(r#""\t\n\\\"\/\x41\u00e9\U0001F600\ud83d\ude00\N\_\L\P\0\e""#),
+            canonical(r#""\t\n\\\"/Aé😀😀\u0085\u00a0\u2028\u2029\u0000\u001b""#)
+        );
+        assert_eq!(
+            json("\"folded  \n  line\n\n  end\""),
+            r#""folded line\nend""#
+        );
+        assert_eq!(json("\"joined\\\n   line\""), r#""joinedline""#);
+        assert_eq!(json("'it''s\n  folded'"), r#""it's folded""#);
+        assert_eq!(json("\"true\": '1'"), r#"{"true":"1"}"#);
+        assert_eq!(json("1: one\ntrue: yes"), r#"{"1":"one","true":"yes"}"#);
+    }
+
+    #[test]
+    fn block_scalars() {
+        let src = "\
+literal: |
+  one
+    two
+
+  three
+folded: >
+  one
+  two
+
+  three
+    indented
+  four
+strip: |-
+  text
+
+keep: |+
+  text
+
+clip: >
+
+  after blank
+explicit: |2
+   space
+empty: |
+last: end
+";
+        let doc = parse_yaml(src).unwrap();
+        let at = |key| doc.value().at(key).as_str().unwrap().to_owned();
+        assert_eq!(at("literal"), "one\n  two\n\nthree\n");
+        assert_eq!(at("folded"), "one two\nthree\n  indented\nfour\n");
+        assert_eq!(at("strip"), "text");
+        assert_eq!(at("keep"), "text\n\n");
+        assert_eq!(at("clip"), "\nafter blank\n");
+        assert_eq!(at("explicit"), " space\n");
+        assert_eq!(at("empty"), "");
+        assert_eq!(at("last"), "end");
+        assert_eq!(json("- |\n  a\n  b\n- c\n"), r#"["a\nb\n","c"]"#);
+        assert_eq!(json("--- >\nroot text\n"), r#""root text\n""#);
+    }
+
+    #[test]
+    fn flow() {
+        assert_eq!(
+            json("{a: [1, 2, {b: c}], \"d\":4, e, f: , 'g': [ ] , h: {},}"),
+            canonical(
+                r#"{"a": [1, 2, {"b": "c"}], "d": 4, "e": null, "f": null, "g": [], "h": {}}"#
+            )
+        );
+        assert_eq!(
+            json("key: [one, # comment\n  two,\n  three]\nnext: 1"),
+            canonical(r#"{"key": ["one", "two", "three"], "next": 1}"#)
+        );
+        assert_eq!(
+            json("[a:b, http://x.y/z?q=1#f]"),
+            r#"["a:b","http://x.y/z?q=1#f"]"#
+        );
+        let src = r#"{"a": [1, 2.5, {"b": null}], "c": "é\n", "d": true}"#;
+        assert_eq!(json(src), canonical(src));
+    }
+
+    #[test]
+    fn round_trips() {
+        let src = r##"{"name": "api", "ports": [80, 443], "nested": [[1, [2]], {"a": {}}, [], ""],
+            "tricky": ["yes", "1.0", "- x", "a: b", "#c", "line\nbreak", " pad", "\u0007", "null"],
+            "numbers": [0, -1, 1.5, 1e100, 18446744073709551615], "": {"": ""}}"##;
+        let doc = parse_value(src).unwrap();
+        let back = parse_yaml(&doc.to_yaml_string()).unwrap();
+        assert!(back.deep_eq_with(
+            &doc,
+            EqOptions {
+                numbers_by_value: true
+            }
+        ));
+        let long = format!(r#"{{"{}": [1]}}"#, "k".repeat(2000));
+        let doc = parse_value(&long).unwrap();
+        assert!(parse_yaml(&doc.to_yaml_string()).is_err());
+    }
+
+    #[test]
+    fn errors() {
+        for (src, line, column, message) in [
+            ("a: &x 1", 1, 4, "anchors aren't supported"),
+            ("a: *x", 1, 4, "aliases aren't supported"),
+            ("a: !!str 1", 1, 4, "tags aren't supported"),
+            ("? a\n: b", 1, 1, "explicit keys aren't supported"),
+            ("%YAML 1.2\n---\na", 1, 1, "directives aren't supported"),
+            ("a: 1\n---\nb: 2", 2, 1, "more than one document"),
+            ("a: 1\na: 2", 2, 1, "a duplicate key"),
+            ("{a: 1, a: 2}", 1, 8, "a duplicate key"),
+            ("a: 1\n  b: 2", 2, 3, "unexpected indentation"),
+            ("a: b\n  c", 2, 3, "unexpected indentation"),
+            ("a: 1\n- b", 2, 1, "a list item among an object's members"),
+            ("- a\nb: 1", 2, 1, "more than one value at the top level"),
+            ("a: b: c", 1, 4, "a key on the same line as another"),
+            ("a: - b", 1, 4, "a list on the same line as its key"),
+            ("a:\n\tb: 1", 2, 1, "a tab in indentation"),
+            ("\"a\" b", 1, 5, "more on the line after a value"),
+            ("\"open", 1, 1, "an unterminated quoted scalar"),
+            ("'open", 1, 1, "an unterminated quoted scalar"),
+            ("\"\\q\"", 1, 2, "an unknown escape"),
+            ("\"\\x4\"", 1, 2, "an escape without enough hex digits"),
+            ("\"\\ud800\"", 1, 2, "an escape that isn't a character"),
+            ("[1, 2", 1, 6, "an unterminated flow collection"),
+            ("[\"a\" b]", 1, 6, "a missing comma"),
+            ("[1,, 2]", 1, 4, "a comma without an entry before it"),
+            ("{a: 1]", 1, 6, "a mismatched bracket"),
+            ("{[a]: 1}", 1, 2, "a collection as a key"),
+            ("{\"a\" 1}", 1, 6, "a missing colon"),
+            ("[a: 1]", 1, 3, "an unexpected colon"),
+            ("[- a]", 1, 2, "a list item inside a flow collection"),
+            ("a: 1\n: 2", 2, 1, "an empty key"),
+            ("\"multi\nline\": 1", 1, 1, "a key that spans lines"),
+            ("a: @x", 1, 4, "a character that can't start a plain scalar"),
+        ] {
+            assert_eq!(
+                parse_yaml(src).map(|doc| doc.to_string()),
+                Err(YamlError {
+                    line,
+                    column,
+                    message
+                }),
+                "{src:?}"
+            );
+        }
+        assert_eq!(
+            YamlError {
+                line: 2,
+                column: 3,
+                message: "a duplicate key"
+            }
+            .to_string(),
+            "Invalid YAML at line 2, column 3: a duplicate key."
+        );
+    }
+}